@@ -2705,6 +2705,95 @@ async fn test_git_diff_base_change(
     });
 }
 
+#[gpui::test]
+async fn test_stage_and_unstage_hunks(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/dir",
+            json!({
+                ".git": {},
+                "a.txt": "one\nTWO\nthree\nfour\n",
+            }),
+        )
+        .await;
+    let committed_text = "one\ntwo\nthree\n".to_string();
+    client_a.fs().set_head_for_repo(
+        Path::new("/dir/.git"),
+        &[(Path::new("a.txt"), committed_text.clone())],
+    );
+    client_a.fs().set_index_for_repo(
+        Path::new("/dir/.git"),
+        &[(Path::new("a.txt"), committed_text.clone())],
+    );
+
+    let (project_a, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+
+    let buffer_a = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // The guest stages the modified row, leaving the added one unstaged.
+    project_b
+        .update(cx_b, |project, cx| {
+            project.stage_hunks(&buffer_b, vec![1..2], cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    let staged_text = "one\nTWO\nthree\n";
+    buffer_a.read_with(cx_a, |buffer, _| {
+        assert_eq!(buffer.diff_base(), Some(staged_text));
+    });
+    buffer_b.read_with(cx_b, |buffer, _| {
+        assert_eq!(buffer.diff_base(), Some(staged_text));
+        git::diff::assert_hunks(
+            buffer.snapshot().git_diff_hunks_in_row_range(0..4),
+            &buffer,
+            staged_text,
+            &[(3..4, "", "four\n")],
+        );
+    });
+
+    // Unstaging the row restores the committed text in the index.
+    project_b
+        .update(cx_b, |project, cx| {
+            project.unstage_hunks(&buffer_b, vec![1..2], cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    buffer_a.read_with(cx_a, |buffer, _| {
+        assert_eq!(buffer.diff_base(), Some(committed_text.as_ref()));
+    });
+    buffer_b.read_with(cx_b, |buffer, _| {
+        assert_eq!(buffer.diff_base(), Some(committed_text.as_ref()));
+    });
+}
+
 #[gpui::test]
 async fn test_git_branch_name(
     executor: BackgroundExecutor,
@@ -161,7 +161,14 @@ gpui::actions!(
         DisplayCursorNames,
         ExpandMacroRecursively,
         FindAllReferences,
+        FindIncomingCalls,
+        FindOutgoingCalls,
+        FindSubtypes,
+        FindSupertypes,
         Fold,
+        FoldAllComments,
+        FoldAllImports,
+        FoldAllRegions,
         FoldSelectedRanges,
         Format,
         GoToDefinition,
@@ -239,12 +246,19 @@ gpui::actions!(
         SelectUp,
         ShowCharacterPalette,
         ShowCompletions,
+        ShowSignatureHelp,
         ShuffleLines,
+        SignatureHelpNext,
+        SignatureHelpPrevious,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
         SplitSelectionIntoLines,
+        StageSelectedHunks,
         Tab,
         TabPrev,
+        ToggleGitBlame,
+        ToggleGitBlameInline,
+        ToggleHunkDiff,
         ToggleInlayHints,
         ToggleSoftWrap,
         ToggleLineNumbers,
@@ -253,6 +267,7 @@ gpui::actions!(
         UndoSelection,
         UnfoldLines,
         UniqueLinesCaseSensitive,
-        UniqueLinesCaseInsensitive
+        UniqueLinesCaseInsensitive,
+        UnstageSelectedHunks
     ]
 );
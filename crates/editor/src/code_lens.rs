@@ -0,0 +1,293 @@
+use crate::{
+    display_map::{
+        BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle, RenderBlock,
+    },
+    Anchor, Editor, EditorMode,
+};
+use collections::{BTreeMap, HashSet};
+use futures::future;
+use gpui::{Model, Task, WeakView};
+use language::{language_settings::language_settings, Buffer, Point, ToPoint};
+use multi_buffer::ExcerptId;
+use project::CodeLens;
+use std::{sync::Arc, time::Duration};
+use ui::prelude::*;
+use util::ResultExt;
+
+pub const CODE_LENS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
+
+#[derive(Default)]
+pub(crate) struct CodeLensState {
+    task: Option<Task<()>>,
+    blocks: HashSet<BlockId>,
+}
+
+/// Requests code lenses for every buffer in the editor whose language has them enabled,
+/// and replaces the lens blocks shown above the lines they refer to.
+pub(crate) fn refresh_code_lens(editor: &mut Editor, debounce: bool, cx: &mut ViewContext<Editor>) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let buffers = editor
+        .buffer
+        .read(cx)
+        .all_buffers()
+        .into_iter()
+        .filter(|buffer| {
+            let buffer = buffer.read(cx);
+            language_settings(buffer.language(), buffer.file(), cx).code_lens
+        })
+        .collect::<Vec<_>>();
+    if buffers.is_empty() {
+        editor.code_lens.task = None;
+        let blocks = std::mem::take(&mut editor.code_lens.blocks);
+        if !blocks.is_empty() {
+            editor.remove_blocks(blocks, None, cx);
+        }
+        return;
+    }
+
+    editor.code_lens.task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor()
+                .timer(CODE_LENS_DEBOUNCE_TIMEOUT)
+                .await;
+        }
+
+        let Some(requests) = project
+            .update(&mut cx, |project, cx| {
+                buffers
+                    .into_iter()
+                    .map(|buffer| {
+                        let request = project.code_lens(&buffer, cx);
+                        async move { (buffer, request.await) }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .log_err()
+        else {
+            return;
+        };
+        let responses = future::join_all(requests).await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                let weak_editor = cx.view().downgrade();
+                let multi_buffer = editor.buffer.read(cx);
+                let mut lenses_by_line = BTreeMap::<(ExcerptId, u32), LensLine>::default();
+                for (buffer, lenses) in responses {
+                    let Some(lenses) = lenses.log_err() else {
+                        continue;
+                    };
+                    let buffer_id = buffer.read(cx).remote_id();
+                    let buffer_snapshot = buffer.read(cx).snapshot();
+                    for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(&buffer, cx)
+                    {
+                        let context_start = excerpt_range.context.start.to_point(&buffer_snapshot);
+                        let context_end = excerpt_range.context.end.to_point(&buffer_snapshot);
+                        for lens in &lenses {
+                            let row = lens.range.start.to_point(&buffer_snapshot).row;
+                            if row < context_start.row || row > context_end.row {
+                                continue;
+                            }
+
+                            lenses_by_line
+                                .entry((excerpt_id, row))
+                                .or_insert_with(|| LensLine {
+                                    position: Anchor {
+                                        buffer_id: Some(buffer_id),
+                                        excerpt_id,
+                                        text_anchor: buffer_snapshot
+                                            .anchor_before(Point::new(row, 0)),
+                                    },
+                                    indent: buffer_snapshot.indent_size_for_line(row).len,
+                                    buffer: buffer.clone(),
+                                    lenses: Vec::new(),
+                                })
+                                .lenses
+                                .push(lens.clone());
+                        }
+                    }
+                }
+
+                let old_blocks = std::mem::take(&mut editor.code_lens.blocks);
+                if !old_blocks.is_empty() {
+                    editor.remove_blocks(old_blocks, None, cx);
+                }
+                let blocks = lenses_by_line
+                    .into_values()
+                    .map(|line| BlockProperties {
+                        position: line.position,
+                        height: 1,
+                        style: BlockStyle::Flex,
+                        render: render_lens_line(
+                            weak_editor.clone(),
+                            line.buffer,
+                            line.indent,
+                            line.lenses,
+                        ),
+                        disposition: BlockDisposition::Above,
+                    })
+                    .collect::<Vec<_>>();
+                editor.code_lens.blocks =
+                    editor.insert_blocks(blocks, None, cx).into_iter().collect();
+            })
+            .log_err();
+    }));
+}
+
+struct LensLine {
+    position: Anchor,
+    indent: u32,
+    buffer: Model<Buffer>,
+    lenses: Vec<CodeLens>,
+}
+
+fn render_lens_line(
+    editor: WeakView<Editor>,
+    buffer: Model<Buffer>,
+    indent: u32,
+    lenses: Vec<CodeLens>,
+) -> RenderBlock {
+    Arc::new(move |cx: &mut BlockContext| {
+        let mut line = h_flex()
+            .id(cx.block_id)
+            .size_full()
+            .pl(cx.gutter_dimensions.width + cx.em_width * indent as f32)
+            .gap_1();
+        for (ix, lens) in lenses.iter().enumerate() {
+            if ix > 0 {
+                line = line.child(Label::new("|").size(LabelSize::Small).color(Color::Muted));
+            }
+            line = line.child(
+                div()
+                    .id(ix)
+                    .cursor_pointer()
+                    .child(
+                        Label::new(lens.title().to_owned())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .on_click({
+                        let editor = editor.clone();
+                        let buffer = buffer.clone();
+                        let lens = lens.clone();
+                        move |_, cx| {
+                            editor
+                                .update(cx, |editor, cx| {
+                                    editor.run_code_lens(buffer.clone(), lens.clone(), cx)
+                                })
+                                .ok();
+                        }
+                    }),
+            );
+        }
+        line.into_any_element()
+    })
+}
+
+impl Editor {
+    /// Runs the command of a code lens. Lenses that run tests or binaries are spawned
+    /// as tasks, all others are executed by their language server.
+    pub fn run_code_lens(
+        &mut self,
+        buffer: Model<Buffer>,
+        lens: CodeLens,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        if let Some(spawn_in_terminal) = lens.runnable() {
+            workspace.update(cx, |_, cx| {
+                cx.emit(workspace::Event::SpawnTask(spawn_in_terminal));
+            });
+            return;
+        }
+
+        let title = lens.title().to_owned();
+        let apply_code_lens = workspace
+            .read(cx)
+            .project()
+            .clone()
+            .update(cx, |project, cx| {
+                project.apply_code_lens(buffer, lens, true, cx)
+            });
+        let workspace = workspace.downgrade();
+        cx.spawn(|editor, cx| async move {
+            let project_transaction = apply_code_lens.await?;
+            Self::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use indoc::indoc;
+    use language::language_settings::AllLanguageSettings;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_code_lens_blocks(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |settings| settings.defaults.code_lens = Some(true));
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(false),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.handle_request::<lsp::request::CodeLensRequest, _, _>(|_, _, _| async move {
+            let lens = |line, title: &str| lsp::CodeLens {
+                range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, 2)),
+                command: Some(lsp::Command {
+                    title: title.to_string(),
+                    command: "rust-analyzer.runSingle".to_string(),
+                    arguments: None,
+                }),
+                data: None,
+            };
+            Ok(Some(vec![
+                lens(1, "Run test"),
+                lens(1, "Debug"),
+                lens(4, "Run test"),
+            ]))
+        });
+        cx.set_state(indoc! {"
+            #[test]
+            fn one() {}
+
+            #[test]
+            fn two() {}
+            ˇ
+        "});
+        cx.executor().advance_clock(CODE_LENS_DEBOUNCE_TIMEOUT);
+        cx.run_until_parked();
+
+        cx.update_editor(|editor, _| {
+            // Lenses on the same line share a block.
+            assert_eq!(editor.code_lens.blocks.len(), 2);
+        });
+
+        cx.update(|cx| {
+            cx.update_global(|store: &mut SettingsStore, cx| {
+                store.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                    settings.defaults.code_lens = Some(false)
+                });
+            });
+        });
+        cx.update_editor(|editor, _| assert!(editor.code_lens.blocks.is_empty()));
+    }
+}
@@ -31,13 +31,15 @@ use fold_map::FoldMap;
 use gpui::{Font, HighlightStyle, Hsla, LineLayout, Model, ModelContext, Pixels, UnderlineStyle};
 use inlay_map::InlayMap;
 use language::{
-    language_settings::language_settings, OffsetUtf16, Point, Subscription as BufferSubscription,
+    language_settings::language_settings, FoldRangeKind, OffsetUtf16, Point,
+    Subscription as BufferSubscription,
 };
 use lsp::DiagnosticSeverity;
 use multi_buffer::{Anchor, AnchorRangeExt, MultiBuffer, MultiBufferSnapshot, ToOffset, ToPoint};
 use std::{any::TypeId, borrow::Cow, fmt::Debug, num::NonZeroU32, ops::Range, sync::Arc};
 use sum_tree::{Bias, TreeMap};
 use tab_map::TabMap;
+use text::BufferId;
 
 use wrap_map::WrapMap;
 
@@ -64,6 +66,8 @@ pub trait ToDisplayPoint {
 
 type TextHighlights = TreeMap<Option<TypeId>, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = BTreeMap<TypeId, HashMap<InlayId, (HighlightStyle, InlayHighlight)>>;
+type SemanticHighlights = Arc<[(Range<Anchor>, HighlightStyle)]>;
+type FoldRanges = Arc<[(Range<Anchor>, Option<FoldRangeKind>)]>;
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
 /// folding, hard tabs, soft wrapping, custom blocks (like diagnostics), and highlighting.
@@ -87,6 +91,12 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Styles derived from language server semantic tokens, layered over the syntax highlighting.
+    semantic_highlights: SemanticHighlights,
+    /// Foldable ranges reported by language servers or syntax queries.
+    fold_ranges: FoldRanges,
+    /// The buffers whose rows are folded using [`Self::fold_ranges`] instead of indentation.
+    fold_range_buffers: Arc<HashSet<BufferId>>,
     pub clip_at_line_ends: bool,
 }
 
@@ -119,6 +129,9 @@ impl DisplayMap {
             block_map,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_highlights: Vec::new().into(),
+            fold_ranges: Vec::new().into(),
+            fold_range_buffers: Default::default(),
             clip_at_line_ends: false,
         }
     }
@@ -144,6 +157,9 @@ impl DisplayMap {
             block_snapshot,
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_highlights: self.semantic_highlights.clone(),
+            fold_ranges: self.fold_ranges.clone(),
+            fold_range_buffers: self.fold_range_buffers.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
         }
     }
@@ -264,6 +280,22 @@ impl DisplayMap {
         }
     }
 
+    /// Replaces the semantic token highlights. The ranges must be sorted and must not overlap.
+    pub fn set_semantic_highlights(&mut self, highlights: Vec<(Range<Anchor>, HighlightStyle)>) {
+        self.semantic_highlights = highlights.into();
+    }
+
+    /// Replaces the foldable ranges of the given buffers, which are no longer folded by
+    /// indentation. The ranges must be sorted by their start.
+    pub fn set_fold_ranges(
+        &mut self,
+        ranges: Vec<(Range<Anchor>, Option<FoldRangeKind>)>,
+        buffer_ids: HashSet<BufferId>,
+    ) {
+        self.fold_ranges = ranges.into();
+        self.fold_range_buffers = Arc::new(buffer_ids);
+    }
+
     pub fn text_highlights(&self, type_id: TypeId) -> Option<(HighlightStyle, &[Range<Anchor>])> {
         let highlights = self.text_highlights.get(&Some(type_id))?;
         Some((highlights.0, &highlights.1))
@@ -339,6 +371,7 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_highlights: Option<&'a SemanticHighlights>,
     pub styles: HighlightStyles,
 }
 
@@ -363,6 +396,9 @@ pub struct DisplaySnapshot {
     block_snapshot: block_map::BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_highlights: SemanticHighlights,
+    fold_ranges: FoldRanges,
+    fold_range_buffers: Arc<HashSet<BufferId>>,
     clip_at_line_ends: bool,
 }
 
@@ -529,6 +565,7 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_highlights: Some(&self.semantic_highlights),
                 styles: highlight_styles,
             },
         )
@@ -868,6 +905,10 @@ impl DisplaySnapshot {
             return false;
         }
 
+        if let Some(end) = self.reported_fold_end(buffer_row) {
+            return end.is_some();
+        }
+
         let (indent_size, is_blank) = self.line_indent_for_buffer_row(buffer_row);
         if is_blank {
             return false;
@@ -887,6 +928,12 @@ impl DisplaySnapshot {
 
     pub fn foldable_range(&self, buffer_row: u32) -> Option<Range<Point>> {
         let start = Point::new(buffer_row, self.buffer_snapshot.line_len(buffer_row));
+        if let Some(end) = self.reported_fold_end(buffer_row) {
+            return end
+                .filter(|_| !self.is_line_folded(buffer_row))
+                .map(|end| start..end);
+        }
+
         if self.is_foldable(start.row) && !self.is_line_folded(start.row) {
             let (start_indent, _) = self.line_indent_for_buffer_row(buffer_row);
             let max_point = self.buffer_snapshot.max_point();
@@ -910,6 +957,39 @@ impl DisplaySnapshot {
         }
     }
 
+    /// Returns the end of the largest reported fold range starting at the given row, or `None`
+    /// if the row's buffer has no reported ranges and is folded by indentation instead.
+    fn reported_fold_end(&self, buffer_row: u32) -> Option<Option<Point>> {
+        let (buffer, _) = self.buffer_snapshot.buffer_line_for_row(buffer_row)?;
+        if !self.fold_range_buffers.contains(&buffer.remote_id()) {
+            return None;
+        }
+
+        let start_row = |range: &Range<Anchor>| range.start.to_point(&self.buffer_snapshot).row;
+        let start_ix = self
+            .fold_ranges
+            .partition_point(|(range, _)| start_row(range) < buffer_row);
+        Some(
+            self.fold_ranges[start_ix..]
+                .iter()
+                .take_while(|(range, _)| start_row(range) == buffer_row)
+                .map(|(range, _)| range.end.to_point(&self.buffer_snapshot))
+                .filter(|end| end.row > buffer_row)
+                .max(),
+        )
+    }
+
+    /// Returns the reported fold ranges of the given kind, such as all comments or imports.
+    pub fn fold_ranges_of_kind(
+        &self,
+        kind: FoldRangeKind,
+    ) -> impl Iterator<Item = Range<Anchor>> + '_ {
+        self.fold_ranges
+            .iter()
+            .filter(move |(_, range_kind)| *range_kind == Some(kind))
+            .map(|(range, _)| range.clone())
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn text_highlight_ranges<Tag: ?Sized + 'static>(
         &self,
@@ -14,7 +14,7 @@ use std::{
 use sum_tree::{Bias, Cursor, SumTree, TreeMap};
 use text::{Patch, Rope};
 
-use super::Highlights;
+use super::{Highlights, SemanticHighlights};
 
 /// Decides where the [`Inlay`]s should be displayed.
 ///
@@ -1039,6 +1039,9 @@ impl InlaySnapshot {
                 cursor.seek(&range.start, Bias::Right, &());
             }
         }
+        if let Some(semantic_highlights) = highlights.semantic_highlights {
+            self.apply_semantic_highlights(&range, semantic_highlights, &mut highlight_endpoints);
+        }
         highlight_endpoints.sort();
         let buffer_range = self.to_buffer_offset(range.start)..self.to_buffer_offset(range.end);
         let buffer_chunks = self.buffer.chunks(buffer_range, language_aware);
@@ -1117,6 +1120,55 @@ impl InlaySnapshot {
         }
     }
 
+    /// Semantic highlights use the `None` tag, so they have the lowest priority among the
+    /// active highlights and never hide the ones set through [`super::DisplayMap::highlight_text`].
+    fn apply_semantic_highlights(
+        &self,
+        range: &Range<InlayOffset>,
+        semantic_highlights: &SemanticHighlights,
+        highlight_endpoints: &mut Vec<HighlightEndpoint>,
+    ) {
+        let range_start = self.buffer.anchor_after(self.to_buffer_offset(range.start));
+        let range_end = self.buffer.anchor_before(self.to_buffer_offset(range.end));
+        let start_ix = match semantic_highlights.binary_search_by(|(probe, _)| {
+            if probe.end.cmp(&range_start, &self.buffer).is_gt() {
+                cmp::Ordering::Greater
+            } else {
+                cmp::Ordering::Less
+            }
+        }) {
+            Ok(i) | Err(i) => i,
+        };
+
+        let mut highlights = semantic_highlights[start_ix..]
+            .iter()
+            .take_while(|(range, _)| range.start.cmp(&range_end, &self.buffer).is_lt())
+            .peekable();
+        while let Some((range, style)) = highlights.next() {
+            let end = range.end.to_offset(&self.buffer);
+            highlight_endpoints.push(HighlightEndpoint {
+                offset: self.to_inlay_offset(range.start.to_offset(&self.buffer)),
+                is_start: true,
+                tag: None,
+                style: *style,
+            });
+
+            // All semantic highlights share a tag, so ending this one where the next one
+            // starts would also end the next one. Its start replaces this style instead.
+            let next_start = highlights
+                .peek()
+                .map(|(next_range, _)| next_range.start.to_offset(&self.buffer));
+            if next_start.map_or(true, |next_start| next_start > end) {
+                highlight_endpoints.push(HighlightEndpoint {
+                    offset: self.to_inlay_offset(end),
+                    is_start: false,
+                    tag: None,
+                    style: *style,
+                });
+            }
+        }
+    }
+
     #[cfg(test)]
     pub fn text(&self) -> String {
         self.chunks(Default::default()..self.len(), false, Highlights::default())
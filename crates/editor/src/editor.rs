@@ -14,10 +14,14 @@
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behaviour.
 pub mod actions;
 mod blink_manager;
+mod code_lens;
 pub mod display_map;
 mod editor_settings;
 mod element;
+mod folding_ranges;
+mod hierarchy_view;
 mod inlay_hint_cache;
+mod linked_editing;
 
 mod debounced_delay;
 mod git;
@@ -28,9 +32,13 @@ pub mod items;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
+mod runnables;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod semantic_tokens;
+mod signature_help;
+mod snippets;
 
 #[cfg(test)]
 mod editor_tests;
@@ -43,6 +51,7 @@ use anyhow::{anyhow, Context as _, Result};
 use blink_manager::BlinkManager;
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
+use code_lens::{refresh_code_lens, CodeLensState};
 use collections::{hash_map, BTreeMap, Bound, HashMap, HashSet, VecDeque};
 use convert_case::{Case, Casing};
 use copilot::Copilot;
@@ -54,18 +63,20 @@ use element::LineWithInvisibles;
 pub use element::{
     CursorLayout, EditorElement, HighlightedRange, HighlightedRangeLine, PointForPosition,
 };
+use folding_ranges::{refresh_folding_ranges, FoldingRangesState};
 use futures::FutureExt;
 use fuzzy::{StringMatch, StringMatchCandidate};
-use git::diff_hunk_to_display;
+use git::{blame::GitBlame, diff_hunk_to_display};
 use gpui::{
     div, impl_actions, point, prelude::*, px, relative, rems, size, uniform_list, Action,
     AnyElement, AppContext, AsyncWindowContext, BackgroundExecutor, Bounds, ClipboardItem, Context,
     DispatchPhase, ElementId, EventEmitter, FocusHandle, FocusableView, FontId, FontStyle,
-    FontWeight, HighlightStyle, Hsla, InteractiveText, KeyContext, Model, MouseButton,
-    ParentElement, Pixels, Render, SharedString, StrikethroughStyle, Styled, StyledText,
-    Subscription, Task, TextStyle, UnderlineStyle, UniformListScrollHandle, View, ViewContext,
-    ViewInputHandler, VisualContext, WeakView, WhiteSpace, WindowContext,
+    FontWeight, HighlightStyle, Hsla, InteractiveText, KeyContext, Model, ModelContext,
+    MouseButton, ParentElement, Pixels, Render, SharedString, StrikethroughStyle, Styled,
+    StyledText, Subscription, Task, TextStyle, UnderlineStyle, UniformListScrollHandle, View,
+    ViewContext, ViewInputHandler, VisualContext, WeakView, WhiteSpace, WindowContext,
 };
+pub use hierarchy_view::HierarchyView;
 use highlight_matching_bracket::refresh_matching_bracket_highlights;
 use hover_popover::{hide_hover, HoverState};
 use inlay_hint_cache::{InlayHintCache, InlaySplice, InvalidationStrategy};
@@ -75,11 +86,18 @@ use language::{char_kind, CharKind};
 use language::{
     language_settings::{self, all_language_settings, InlayHintSettings},
     markdown, point_from_lsp, AutoindentMode, BracketPair, Buffer, Capability, CodeAction,
-    CodeLabel, Completion, CursorShape, Diagnostic, Documentation, IndentKind, IndentSize,
-    Language, OffsetRangeExt, Point, Selection, SelectionGoal, TransactionId,
+    CodeLabel, Completion, CursorShape, Diagnostic, Documentation, FoldRangeKind, IndentKind,
+    IndentSize, Language, OffsetRangeExt, Point, Selection, SelectionGoal, TransactionId,
+};
+use linked_editing::{refresh_linked_editing_ranges, sync_linked_edits, LinkedEditingState};
+use runnables::{refresh_runnables, RunnablesState};
+use semantic_tokens::refresh_semantic_tokens;
+use signature_help::{
+    hide_signature_help, refresh_signature_help, trigger_signature_help_on_input,
+    SignatureHelpState,
 };
 
-use hover_links::{HoverLink, HoveredLinkState, InlayHighlight};
+use hover_links::{DocumentLinksCache, HoverLink, HoveredLinkState, InlayHighlight};
 use lsp::{DiagnosticSeverity, LanguageServerId};
 use mouse_context_menu::MouseContextMenu;
 use movement::TextLayoutDetails;
@@ -92,7 +110,10 @@ use ordered_float::OrderedFloat;
 use parking_lot::{Mutex, RwLock};
 use project::project_settings::{GitGutterSetting, ProjectSettings};
 use project::Item;
-use project::{FormatTrigger, Location, Project, ProjectPath, ProjectTransaction};
+use project::{
+    CallHierarchyDirection, FormatTrigger, HierarchyItem, Location, Project, ProjectPath,
+    ProjectTransaction, TypeHierarchyDirection,
+};
 use rand::prelude::*;
 use rpc::proto::*;
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
@@ -141,6 +162,8 @@ pub const CODE_ACTIONS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
 pub const DOCUMENT_HIGHLIGHTS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(75);
 
 pub(crate) const FORMAT_TIMEOUT: Duration = Duration::from_secs(2);
+/// The width of the git blame column of the gutter, in characters.
+pub(crate) const GIT_BLAME_GUTTER_WIDTH_CHARS: f32 = 40.;
 
 pub fn render_parsed_markdown(
     element_id: impl Into<ElementId>,
@@ -415,14 +438,26 @@ pub struct Editor {
     leader_peer_id: Option<PeerId>,
     remote_id: Option<ViewId>,
     hover_state: HoverState,
+    signature_help_state: SignatureHelpState,
+    semantic_tokens_task: Option<Task<()>>,
+    code_lens: CodeLensState,
+    runnables: RunnablesState,
+    folding_ranges: FoldingRangesState,
+    linked_editing: LinkedEditingState,
     gutter_hovered: bool,
     hovered_link_state: Option<HoveredLinkState>,
+    document_links_cache: Option<DocumentLinksCache>,
     copilot_state: CopilotState,
     inlay_hint_cache: InlayHintCache,
     next_inlay_id: usize,
     _subscriptions: Vec<Subscription>,
     pixel_position_of_newest_cursor: Option<gpui::Point<Pixels>>,
     gutter_width: Pixels,
+    blame: Option<Model<GitBlame>>,
+    blame_subscription: Option<Subscription>,
+    show_git_blame_gutter: bool,
+    show_git_blame_inline: bool,
+    expanded_hunks: Vec<ExpandedHunk>,
     pub vim_replace_map: HashMap<Range<usize>, String>,
     style: Option<EditorStyle>,
     editor_actions: Vec<Box<dyn Fn(&mut ViewContext<Self>)>>,
@@ -440,6 +475,7 @@ pub struct Editor {
 pub struct EditorSnapshot {
     pub mode: EditorMode,
     show_gutter: bool,
+    show_git_blame_gutter: bool,
     pub display_snapshot: DisplaySnapshot,
     pub placeholder_text: Option<Arc<str>>,
     is_focused: bool,
@@ -452,6 +488,8 @@ pub struct GutterDimensions {
     pub right_padding: Pixels,
     pub width: Pixels,
     pub margin: Pixels,
+    /// The width of the git blame column, on the left of the gutter, if it is shown.
+    pub git_blame_entries_width: Option<Pixels>,
 }
 
 impl Default for GutterDimensions {
@@ -461,6 +499,7 @@ impl Default for GutterDimensions {
             right_padding: Pixels::ZERO,
             width: Pixels::ZERO,
             margin: Pixels::ZERO,
+            git_blame_entries_width: None,
         }
     }
 }
@@ -611,6 +650,18 @@ struct AutocloseRegion {
 struct SnippetState {
     ranges: Vec<Vec<Range<Anchor>>>,
     active_index: usize,
+    /// The values to choose from at the tabstops with choices, by tabstop index.
+    choices: BTreeMap<usize, Vec<String>>,
+    transforms: Vec<SnippetTransformState>,
+}
+
+/// A transformed mirror of a tabstop, updated when leaving the tabstop.
+#[derive(Debug)]
+struct SnippetTransformState {
+    tabstop: usize,
+    source: Range<Anchor>,
+    target: Range<Anchor>,
+    transform: snippet::Transform,
 }
 
 #[doc(hidden)]
@@ -1338,6 +1389,14 @@ impl CopilotState {
     }
 }
 
+/// A diff hunk whose deleted lines are shown in a block above it.
+#[derive(Debug)]
+struct ExpandedHunk {
+    buffer_id: BufferId,
+    diff_base_byte_range: Range<usize>,
+    block: BlockId,
+}
+
 #[derive(Debug)]
 struct ActiveDiagnosticGroup {
     primary_range: Range<Anchor>,
@@ -1485,11 +1544,27 @@ impl Editor {
                         cx.emit(EditorEvent::TitleChanged);
                     }));
                 }
-                project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
-                    if let project::Event::RefreshInlayHints = event {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
-                    };
-                }));
+                project_subscriptions.push(cx.subscribe(
+                    project,
+                    |editor, _, event, cx| match event {
+                        project::Event::RefreshInlayHints => {
+                            editor
+                                .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                        }
+                        project::Event::RefreshSemanticTokens => {
+                            refresh_semantic_tokens(editor, false, cx);
+                        }
+                        project::Event::RefreshCodeLens => {
+                            refresh_code_lens(editor, false, cx);
+                        }
+                        project::Event::LanguageServerAdded(_) => {
+                            refresh_semantic_tokens(editor, false, cx);
+                            refresh_code_lens(editor, false, cx);
+                            refresh_folding_ranges(editor, false, cx);
+                        }
+                        _ => {}
+                    },
+                ));
             }
         }
 
@@ -1559,12 +1634,28 @@ impl Editor {
             leader_peer_id: None,
             remote_id: None,
             hover_state: Default::default(),
+            signature_help_state: Default::default(),
+            semantic_tokens_task: None,
+            code_lens: Default::default(),
+            runnables: Default::default(),
+            folding_ranges: Default::default(),
+            linked_editing: Default::default(),
             hovered_link_state: Default::default(),
+            document_links_cache: None,
             copilot_state: Default::default(),
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
             gutter_hovered: false,
             pixel_position_of_newest_cursor: None,
             gutter_width: Default::default(),
+            blame: None,
+            blame_subscription: None,
+            show_git_blame_gutter: false,
+            show_git_blame_inline: mode == EditorMode::Full
+                && ProjectSettings::get_global(cx)
+                    .git
+                    .inline_blame
+                    .map_or(false, |inline_blame| inline_blame.enabled),
+            expanded_hunks: Vec::new(),
             style: None,
             show_cursor_names: false,
             hovered_cursors: Default::default(),
@@ -1596,6 +1687,12 @@ impl Editor {
         this._subscriptions.extend(project_subscriptions);
 
         this.end_selection(cx);
+        if this.show_git_blame_inline {
+            this.start_git_blame(cx);
+        }
+        refresh_semantic_tokens(&mut this, false, cx);
+        refresh_code_lens(&mut this, false, cx);
+        refresh_runnables(&mut this, cx);
         this.scroll_manager.show_scrollbar(cx);
 
         if mode == EditorMode::Full {
@@ -1619,6 +1716,9 @@ impl Editor {
         if self.pending_rename.is_some() {
             key_context.add("renaming");
         }
+        if self.signature_help_state.is_visible() {
+            key_context.add("showing_signature_help");
+        }
         if self.context_menu_visible() {
             match self.context_menu.read().as_ref() {
                 Some(ContextMenu::Completions(_)) => {
@@ -1716,6 +1816,7 @@ impl Editor {
         EditorSnapshot {
             mode: self.mode,
             show_gutter: self.show_gutter,
+            show_git_blame_gutter: self.render_git_blame_gutter(cx),
             display_snapshot: self.display_map.update(cx, |map, cx| map.snapshot(cx)),
             scroll_anchor: self.scroll_manager.anchor(),
             ongoing_scroll: self.scroll_manager.ongoing_scroll(),
@@ -1964,6 +2065,8 @@ impl Editor {
             self.refresh_code_actions(cx);
             self.refresh_document_highlights(cx);
             refresh_matching_bracket_highlights(self, cx);
+            refresh_signature_help(self, cx);
+            refresh_linked_editing_ranges(self, cx);
             self.discard_copilot_suggestion(cx);
         }
 
@@ -2386,6 +2489,10 @@ impl Editor {
             return true;
         }
 
+        if hide_signature_help(self, cx) {
+            return true;
+        }
+
         if self.hide_context_menu(cx).is_some() {
             return true;
         }
@@ -2648,6 +2755,7 @@ impl Editor {
                 this.trigger_completion_on_input(&text, cx);
                 this.refresh_copilot_suggestions(true, cx);
             }
+            trigger_signature_help_on_input(this, &text, cx);
         });
     }
 
@@ -2996,7 +3104,7 @@ impl Editor {
 
             this.change_selections(Some(Autoscroll::fit()), cx, |s| {
                 s.select_anchors(selection_anchors);
-            })
+            });
         });
     }
 
@@ -3490,7 +3598,12 @@ impl Editor {
         let snippet;
         let text;
         if completion.is_snippet() {
-            snippet = Some(Snippet::parse(&completion.new_text).log_err()?);
+            snippet = Some(
+                Snippet::parse_with_variables(&completion.new_text, &|name| {
+                    snippets::snippet_variable(self, &buffer_handle, name, cx)
+                })
+                .log_err()?,
+            );
             text = snippet.as_ref().unwrap().text.clone();
         } else {
             snippet = None;
@@ -3567,6 +3680,10 @@ impl Editor {
                     tabstop.start -= common_prefix_len as isize;
                     tabstop.end -= common_prefix_len as isize;
                 }
+                for transform in &mut snippet.transforms {
+                    transform.range.start -= common_prefix_len as isize;
+                    transform.range.end -= common_prefix_len as isize;
+                }
 
                 this.insert_snippet(&ranges, snippet, cx).log_err();
             } else {
@@ -4306,7 +4423,7 @@ impl Editor {
         snippet: Snippet,
         cx: &mut ViewContext<Self>,
     ) -> Result<()> {
-        let tabstops = self.buffer.update(cx, |buffer, cx| {
+        let (tabstops, transforms) = self.buffer.update(cx, |buffer, cx| {
             let snippet_text: Arc<str> = snippet.text.clone().into();
             buffer.edit(
                 insertion_ranges
@@ -4319,7 +4436,7 @@ impl Editor {
 
             let snapshot = &*buffer.read(cx);
             let snippet = &snippet;
-            snippet
+            let tabstops = snippet
                 .tabstops
                 .iter()
                 .map(|tabstop| {
@@ -4344,17 +4461,48 @@ impl Editor {
                     tabstop_ranges.sort_unstable_by(|a, b| a.start.cmp(&b.start, snapshot));
                     tabstop_ranges
                 })
-                .collect::<Vec<_>>()
+                .collect::<Vec<_>>();
+
+            let transforms = snippet
+                .transforms
+                .iter()
+                .filter_map(|transform| {
+                    let source = snippet.tabstops.get(transform.tabstop)?.first()?;
+                    let sources =
+                        snippets::inserted_ranges(insertion_ranges, snippet.text.len(), source);
+                    let targets = snippets::inserted_ranges(
+                        insertion_ranges,
+                        snippet.text.len(),
+                        &transform.range,
+                    );
+                    Some(sources.into_iter().zip(targets).map(|(source, target)| {
+                        SnippetTransformState {
+                            tabstop: transform.tabstop,
+                            source: snapshot.anchor_before(source.start)
+                                ..snapshot.anchor_after(source.end),
+                            target: snapshot.anchor_after(target.start)
+                                ..snapshot.anchor_before(target.end),
+                            transform: transform.transform.clone(),
+                        }
+                    }))
+                })
+                .flatten()
+                .collect::<Vec<_>>();
+            (tabstops, transforms)
         });
 
         if let Some(tabstop) = tabstops.first() {
             self.change_selections(Some(Autoscroll::fit()), cx, |s| {
                 s.select_ranges(tabstop.iter().cloned());
             });
-            self.snippet_stack.push(SnippetState {
+            let snippet = SnippetState {
                 active_index: 0,
                 ranges: tabstops,
-            });
+                choices: snippet.choices,
+                transforms,
+            };
+            snippets::show_snippet_choices(self, &snippet, cx);
+            self.snippet_stack.push(snippet);
 
             // Check whether the just-entered snippet ends with an auto-closable bracket.
             if self.autoclose_regions.is_empty() {
@@ -4405,6 +4553,7 @@ impl Editor {
 
     pub fn move_to_snippet_tabstop(&mut self, bias: Bias, cx: &mut ViewContext<Self>) -> bool {
         if let Some(mut snippet) = self.snippet_stack.pop() {
+            snippets::apply_snippet_transforms(self, &mut snippet, cx);
             match bias {
                 Bias::Left => {
                     if snippet.active_index > 0 {
@@ -4427,6 +4576,7 @@ impl Editor {
                 self.change_selections(Some(Autoscroll::fit()), cx, |s| {
                     s.select_anchor_ranges(current_ranges.iter().cloned())
                 });
+                snippets::show_snippet_choices(self, &snippet, cx);
                 // If snippet state is not at the last tabstop, push it back on the stack
                 if snippet.active_index + 1 < snippet.ranges.len() {
                     self.snippet_stack.push(snippet);
@@ -5038,6 +5188,147 @@ impl Editor {
         }
     }
 
+    pub fn stage_selected_hunks(&mut self, _: &StageSelectedHunks, cx: &mut ViewContext<Self>) {
+        self.update_index_for_selected_hunks(true, cx);
+    }
+
+    pub fn unstage_selected_hunks(&mut self, _: &UnstageSelectedHunks, cx: &mut ViewContext<Self>) {
+        self.update_index_for_selected_hunks(false, cx);
+    }
+
+    fn update_index_for_selected_hunks(&mut self, stage: bool, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        // Like when reverting hunks, the rows of each selection include the row of its head.
+        let mut rows_by_buffer = HashMap::<BufferId, (Model<Buffer>, Vec<Range<u32>>)>::default();
+        let multi_buffer = self.buffer.read(cx);
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        for selection in self.selections.all::<Point>(cx) {
+            let end_row = selection.end.row;
+            let end = Point::new(end_row, multi_buffer_snapshot.line_len(end_row));
+            for (buffer_handle, range, _) in
+                multi_buffer.range_to_buffer_ranges(selection.start..end, cx)
+            {
+                let buffer = buffer_handle.read(cx);
+                let start_row = buffer.offset_to_point(range.start).row;
+                let end_row = buffer.offset_to_point(range.end).row;
+                rows_by_buffer
+                    .entry(buffer.remote_id())
+                    .or_insert_with(|| (buffer_handle.clone(), Vec::new()))
+                    .1
+                    .push(start_row..end_row + 1);
+            }
+        }
+
+        for (buffer, rows) in rows_by_buffer.into_values() {
+            project
+                .update(cx, |project, cx| {
+                    if stage {
+                        project.stage_hunks(&buffer, rows, cx)
+                    } else {
+                        project.unstage_hunks(&buffer, rows, cx)
+                    }
+                })
+                .detach_and_log_err(cx);
+        }
+    }
+
+    /// Shows or hides the deleted lines of the diff hunks intersecting the selections,
+    /// above the lines that replaced them.
+    pub fn toggle_hunk_diff(&mut self, _: &ToggleHunkDiff, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut processed_hunks = HashSet::default();
+        let mut blocks_to_remove = HashSet::default();
+        let mut hunks_to_expand = Vec::new();
+        let selections = self.selections.disjoint_anchors();
+        for selection in selections.iter() {
+            let start_row = selection.start.to_point(&snapshot).row;
+            let end_row = selection.end.to_point(&snapshot).row;
+            for hunk in snapshot.git_diff_hunks_in_range(start_row..end_row + 1) {
+                if hunk.diff_base_byte_range.is_empty()
+                    || !processed_hunks.insert((hunk.buffer_id, hunk.diff_base_byte_range.clone()))
+                {
+                    continue;
+                }
+                if let Some(ix) = self.expanded_hunks.iter().position(|expanded_hunk| {
+                    expanded_hunk.buffer_id == hunk.buffer_id
+                        && expanded_hunk.diff_base_byte_range == hunk.diff_base_byte_range
+                }) {
+                    blocks_to_remove.insert(self.expanded_hunks.remove(ix).block);
+                } else {
+                    hunks_to_expand.push(hunk);
+                }
+            }
+        }
+
+        let mut blocks = Vec::new();
+        let mut expanded_hunks = Vec::new();
+        for hunk in hunks_to_expand {
+            let Some(buffer) = self.buffer.read(cx).buffer(hunk.buffer_id) else {
+                continue;
+            };
+            let Some(deleted_text) = buffer
+                .read(cx)
+                .diff_base()
+                .and_then(|diff_base| diff_base.get(hunk.diff_base_byte_range.clone()))
+            else {
+                continue;
+            };
+            let deleted_text = deleted_text.strip_suffix('\n').unwrap_or(deleted_text);
+            blocks.push(BlockProperties {
+                position: snapshot.anchor_before(Point::new(hunk.associated_range.start, 0)),
+                height: deleted_text.lines().count().clamp(1, u8::MAX as usize) as u8,
+                style: BlockStyle::Flex,
+                render: deleted_hunk_block_renderer(deleted_text.to_string().into()),
+                disposition: BlockDisposition::Above,
+            });
+            expanded_hunks.push((hunk.buffer_id, hunk.diff_base_byte_range));
+        }
+
+        if !blocks_to_remove.is_empty() {
+            self.remove_blocks(blocks_to_remove, None, cx);
+        }
+        if !blocks.is_empty() {
+            let block_ids = self.insert_blocks(blocks, None, cx);
+            self.expanded_hunks
+                .extend(expanded_hunks.into_iter().zip(block_ids).map(
+                    |((buffer_id, diff_base_byte_range), block)| ExpandedHunk {
+                        buffer_id,
+                        diff_base_byte_range,
+                        block,
+                    },
+                ));
+        }
+    }
+
+    /// Hides the deleted lines of the expanded hunks that are no longer in the diff.
+    fn refresh_expanded_hunks(&mut self, cx: &mut ViewContext<Self>) {
+        if self.expanded_hunks.is_empty() {
+            return;
+        }
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let hunks = snapshot
+            .git_diff_hunks_in_range(0..u32::MAX)
+            .map(|hunk| (hunk.buffer_id, hunk.diff_base_byte_range))
+            .collect::<HashSet<_>>();
+        let mut blocks_to_remove = HashSet::default();
+        self.expanded_hunks.retain(|expanded_hunk| {
+            let retain = hunks.contains(&(
+                expanded_hunk.buffer_id,
+                expanded_hunk.diff_base_byte_range.clone(),
+            ));
+            if !retain {
+                blocks_to_remove.insert(expanded_hunk.block);
+            }
+            retain
+        });
+        if !blocks_to_remove.is_empty() {
+            self.remove_blocks(blocks_to_remove, None, cx);
+        }
+    }
+
     pub fn reverse_lines(&mut self, _: &ReverseLines, cx: &mut ViewContext<Self>) {
         self.manipulate_lines(cx, |lines| lines.reverse())
     }
@@ -7738,7 +8029,8 @@ impl Editor {
             let definition = definitions.pop().unwrap();
             let target_task = match definition {
                 HoverLink::Text(link) => Task::Ready(Some(Ok(Some(link.target)))),
-                HoverLink::InlayHint(lsp_location, server_id) => {
+                HoverLink::InlayHint(lsp_location, server_id)
+                | HoverLink::File(lsp_location, server_id) => {
                     self.compute_target_location(lsp_location, server_id, cx)
                 }
                 HoverLink::Url(url) => {
@@ -7817,6 +8109,7 @@ impl Editor {
                                     )
                                 }),
                                 HoverLink::InlayHint(_, _) => None,
+                                HoverLink::File(_, _) => None,
                                 HoverLink::Url(_) => None,
                             })
                             .unwrap_or(tab_kind.to_string());
@@ -7824,7 +8117,8 @@ impl Editor {
                             .into_iter()
                             .map(|definition| match definition {
                                 HoverLink::Text(link) => Task::Ready(Some(Ok(Some(link.target)))),
-                                HoverLink::InlayHint(lsp_location, server_id) => {
+                                HoverLink::InlayHint(lsp_location, server_id)
+                                | HoverLink::File(lsp_location, server_id) => {
                                     editor.compute_target_location(lsp_location, server_id, cx)
                                 }
                                 HoverLink::Url(_) => Task::ready(Ok(None)),
@@ -8109,6 +8403,109 @@ impl Editor {
         }
     }
 
+    pub fn find_incoming_calls(
+        &mut self,
+        _: &FindIncomingCalls,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        self.open_hierarchy(
+            |name| format!("Incoming calls to `{name}`"),
+            |project, buffer, position, cx| {
+                project.call_hierarchy(buffer, position, CallHierarchyDirection::Incoming, cx)
+            },
+            cx,
+        )
+    }
+
+    pub fn find_outgoing_calls(
+        &mut self,
+        _: &FindOutgoingCalls,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        self.open_hierarchy(
+            |name| format!("Outgoing calls from `{name}`"),
+            |project, buffer, position, cx| {
+                project.call_hierarchy(buffer, position, CallHierarchyDirection::Outgoing, cx)
+            },
+            cx,
+        )
+    }
+
+    pub fn find_supertypes(
+        &mut self,
+        _: &FindSupertypes,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        self.open_hierarchy(
+            |name| format!("Supertypes of `{name}`"),
+            |project, buffer, position, cx| {
+                project.type_hierarchy(buffer, position, TypeHierarchyDirection::Supertypes, cx)
+            },
+            cx,
+        )
+    }
+
+    pub fn find_subtypes(
+        &mut self,
+        _: &FindSubtypes,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        self.open_hierarchy(
+            |name| format!("Subtypes of `{name}`"),
+            |project, buffer, position, cx| {
+                project.type_hierarchy(buffer, position, TypeHierarchyDirection::Subtypes, cx)
+            },
+            cx,
+        )
+    }
+
+    /// Opens the call or type hierarchy of the symbol under the newest cursor in a tree, whose
+    /// nodes can be expanded to walk the hierarchy further.
+    fn open_hierarchy(
+        &mut self,
+        title: impl FnOnce(&str) -> String,
+        request: impl 'static
+            + Fn(
+                &mut Project,
+                &Model<Buffer>,
+                text::Anchor,
+                &mut ModelContext<Project>,
+            ) -> Task<Result<Vec<HierarchyItem>>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let multi_buffer = self.buffer.read(cx);
+        let head = self.selections.newest::<usize>(cx).head();
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let (word_range, _) = multi_buffer_snapshot.surrounding_word(head);
+        let title = title(
+            &multi_buffer_snapshot
+                .text_for_range(word_range)
+                .collect::<String>(),
+        );
+
+        let (buffer, head) = multi_buffer.text_anchor_for_position(head, cx)?;
+        let workspace = self.workspace()?;
+        let project = workspace.read(cx).project().clone();
+        let request: Arc<hierarchy_view::HierarchyRequest> = Arc::new(request);
+        let items = project.update(cx, |project, cx| request(project, &buffer, head, cx));
+        Some(cx.spawn(|_, mut cx| async move {
+            let items = items.await?;
+            if items.is_empty() {
+                return Ok(());
+            }
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let weak_workspace = workspace.weak_handle();
+                let hierarchy = cx.new_view(|cx| {
+                    HierarchyView::new(title, items, request, weak_workspace, project, cx)
+                });
+                workspace.add_item_to_active_pane(Box::new(hierarchy), cx);
+            })?;
+
+            Ok(())
+        }))
+    }
+
     pub fn rename(&mut self, _: &Rename, cx: &mut ViewContext<Self>) -> Option<Task<Result<()>>> {
         use language::ToOffset as _;
 
@@ -8548,6 +8945,7 @@ impl Editor {
     ) -> Option<TransactionId> {
         self.start_transaction_at(Instant::now(), cx);
         update(self, cx);
+        sync_linked_edits(self, cx);
         self.end_transaction_at(Instant::now(), cx)
     }
 
@@ -8629,6 +9027,24 @@ impl Editor {
         }
     }
 
+    pub fn fold_all_comments(&mut self, _: &FoldAllComments, cx: &mut ViewContext<Self>) {
+        self.fold_all_of_kind(FoldRangeKind::Comment, cx);
+    }
+
+    pub fn fold_all_imports(&mut self, _: &FoldAllImports, cx: &mut ViewContext<Self>) {
+        self.fold_all_of_kind(FoldRangeKind::Imports, cx);
+    }
+
+    pub fn fold_all_regions(&mut self, _: &FoldAllRegions, cx: &mut ViewContext<Self>) {
+        self.fold_all_of_kind(FoldRangeKind::Region, cx);
+    }
+
+    fn fold_all_of_kind(&mut self, kind: FoldRangeKind, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let fold_ranges = display_map.fold_ranges_of_kind(kind).collect::<Vec<_>>();
+        self.fold_ranges(fold_ranges, true, cx);
+    }
+
     pub fn unfold_lines(&mut self, _: &UnfoldLines, cx: &mut ViewContext<Self>) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = &display_map.buffer_snapshot;
@@ -8889,6 +9305,63 @@ impl Editor {
         cx.notify();
     }
 
+    pub fn toggle_git_blame(&mut self, _: &ToggleGitBlame, cx: &mut ViewContext<Self>) {
+        self.show_git_blame_gutter = !self.show_git_blame_gutter;
+        if self.show_git_blame_gutter {
+            self.start_git_blame(cx);
+        }
+        cx.notify();
+    }
+
+    pub fn toggle_git_blame_inline(
+        &mut self,
+        _: &ToggleGitBlameInline,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.show_git_blame_inline = !self.show_git_blame_inline;
+        if self.show_git_blame_inline {
+            self.start_git_blame(cx);
+        }
+        cx.notify();
+    }
+
+    fn start_git_blame(&mut self, cx: &mut ViewContext<Self>) {
+        if self.blame.is_some() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+
+        let blame = cx.new_model(|cx| GitBlame::new(buffer, project, cx));
+        self.blame_subscription = Some(cx.observe(&blame, |_, _, cx| cx.notify()));
+        self.blame = Some(blame);
+    }
+
+    pub fn blame(&self) -> Option<&Model<GitBlame>> {
+        self.blame.as_ref()
+    }
+
+    fn render_git_blame_gutter(&self, cx: &WindowContext) -> bool {
+        self.show_git_blame_gutter
+            && self
+                .blame
+                .as_ref()
+                .map_or(false, |blame| blame.read(cx).has_generated_entries())
+    }
+
+    fn render_git_blame_inline(&self, cx: &WindowContext) -> bool {
+        self.show_git_blame_inline
+            && self.focus_handle.is_focused(cx)
+            && self
+                .blame
+                .as_ref()
+                .map_or(false, |blame| blame.read(cx).has_generated_entries())
+    }
+
     pub fn toggle_line_numbers(&mut self, _: &ToggleLineNumbers, cx: &mut ViewContext<Self>) {
         let mut editor_settings = EditorSettings::get_global(cx).clone();
         editor_settings.gutter.line_numbers = !editor_settings.gutter.line_numbers;
@@ -9391,6 +9864,9 @@ impl Editor {
                 }
                 cx.emit(EditorEvent::BufferEdited);
                 cx.emit(SearchEvent::MatchesInvalidated);
+                refresh_semantic_tokens(self, true, cx);
+                refresh_code_lens(self, true, cx);
+                refresh_folding_ranges(self, true, cx);
 
                 if *singleton_buffer_edited {
                     if let Some(project) = &self.project {
@@ -9436,13 +9912,24 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                refresh_semantic_tokens(self, true, cx);
+                refresh_code_lens(self, true, cx);
+                refresh_folding_ranges(self, true, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
-            multi_buffer::Event::Reparsed => cx.emit(EditorEvent::Reparsed),
+            multi_buffer::Event::Reparsed => {
+                refresh_folding_ranges(self, true, cx);
+                refresh_runnables(self, cx);
+                cx.emit(EditorEvent::Reparsed);
+            }
             multi_buffer::Event::LanguageChanged => {
+                refresh_semantic_tokens(self, false, cx);
+                refresh_code_lens(self, false, cx);
+                refresh_folding_ranges(self, false, cx);
+                refresh_runnables(self, cx);
                 cx.emit(EditorEvent::Reparsed);
                 cx.notify();
             }
@@ -9451,7 +9938,10 @@ impl Editor {
             multi_buffer::Event::FileHandleChanged | multi_buffer::Event::Reloaded => {
                 cx.emit(EditorEvent::TitleChanged)
             }
-            multi_buffer::Event::DiffBaseChanged => cx.emit(EditorEvent::DiffBaseChanged),
+            multi_buffer::Event::DiffBaseChanged => {
+                self.refresh_expanded_hunks(cx);
+                cx.emit(EditorEvent::DiffBaseChanged)
+            }
             multi_buffer::Event::Closed => cx.emit(EditorEvent::Closed),
             multi_buffer::Event::DiagnosticsUpdated => {
                 self.refresh_active_diagnostics(cx);
@@ -9466,6 +9956,10 @@ impl Editor {
 
     fn settings_changed(&mut self, cx: &mut ViewContext<Self>) {
         self.refresh_copilot_suggestions(true, cx);
+        refresh_semantic_tokens(self, false, cx);
+        refresh_code_lens(self, false, cx);
+        refresh_folding_ranges(self, false, cx);
+        refresh_runnables(self, cx);
         self.refresh_inlay_hints(
             InlayHintRefreshReason::SettingsChange(inlay_hint_settings(
                 self.selections.newest_anchor().head(),
@@ -9956,8 +10450,14 @@ impl CompletionProvider for Model<Project> {
         buffer_position: text::Anchor,
         cx: &mut ViewContext<Editor>,
     ) -> Task<Result<Vec<Completion>>> {
-        self.update(cx, |project, cx| {
+        let snippets = snippets::snippet_completions(self, buffer, buffer_position, cx);
+        let completions = self.update(cx, |project, cx| {
             project.completions(&buffer, buffer_position, cx)
+        });
+        cx.background_executor().spawn(async move {
+            let mut completions = completions.await.log_err().unwrap_or_default();
+            completions.extend(snippets);
+            Ok(completions)
         })
     }
 
@@ -9979,6 +10479,9 @@ impl CompletionProvider for Model<Project> {
         push_to_history: bool,
         cx: &mut ViewContext<Editor>,
     ) -> Task<Result<Option<language::Transaction>>> {
+        if completion.server_id == snippets::SNIPPET_COMPLETION_SERVER_ID {
+            return Task::ready(Ok(None));
+        }
         self.update(cx, |project, cx| {
             project.apply_additional_edits_for_completion(buffer, completion, push_to_history, cx)
         })
@@ -10102,7 +10605,7 @@ impl EditorSnapshot {
             0.0.into()
         };
 
-        let left_padding = if gutter_settings.code_actions {
+        let left_padding = if gutter_settings.code_actions || gutter_settings.runnables {
             em_width * 3.0
         } else if show_git_gutter && gutter_settings.line_numbers {
             em_width * 2.0
@@ -10122,11 +10625,19 @@ impl EditorSnapshot {
             px(0.)
         };
 
+        let git_blame_entries_width = self
+            .show_git_blame_gutter
+            .then(|| em_width * GIT_BLAME_GUTTER_WIDTH_CHARS);
+
         GutterDimensions {
             left_padding,
             right_padding,
-            width: line_gutter_width + left_padding + right_padding,
+            width: line_gutter_width
+                + left_padding
+                + right_padding
+                + git_blame_entries_width.unwrap_or_default(),
             margin: -descent,
+            git_blame_entries_width,
         }
     }
 }
@@ -10676,6 +11187,21 @@ pub fn diagnostic_block_renderer(diagnostic: Diagnostic, _is_valid: bool) -> Ren
     })
 }
 
+fn deleted_hunk_block_renderer(deleted_text: SharedString) -> RenderBlock {
+    Arc::new(move |cx: &mut BlockContext| {
+        let mut text_style = cx.editor_style.text.clone();
+        text_style.color = cx.theme().status().deleted;
+        div()
+            .id(cx.block_id)
+            .size_full()
+            .pl(cx.gutter_dimensions.width)
+            .w(cx.max_width + cx.gutter_dimensions.width)
+            .bg(cx.theme().status().deleted_background.opacity(0.2))
+            .child(StyledText::new(deleted_text.clone()).with_highlights(&text_style, []))
+            .into_any_element()
+    })
+}
+
 pub fn highlight_diagnostic_message(diagnostic: &Diagnostic) -> (SharedString, Vec<Range<usize>>) {
     let mut text_without_backticks = String::new();
     let mut code_ranges = Vec::new();
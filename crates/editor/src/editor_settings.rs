@@ -7,6 +7,8 @@ pub struct EditorSettings {
     pub cursor_blink: bool,
     pub hover_popover_enabled: bool,
     pub show_completions_on_input: bool,
+    pub show_signature_help_on_input: bool,
+    pub linked_edits: bool,
     pub show_completion_documentation: bool,
     pub completion_documentation_secondary_query_debounce: u64,
     pub use_on_type_format: bool,
@@ -65,6 +67,7 @@ pub struct Scrollbar {
 pub struct Gutter {
     pub line_numbers: bool,
     pub code_actions: bool,
+    pub runnables: bool,
     pub folds: bool,
 }
 
@@ -111,6 +114,16 @@ pub struct EditorSettingsContent {
     ///
     /// Default: true
     pub show_completions_on_input: Option<bool>,
+    /// Whether to pop the signature help popover when typing a character that
+    /// the language server reports as a signature help trigger, e.g. `(`.
+    ///
+    /// Default: true
+    pub show_signature_help_on_input: Option<bool>,
+    /// Whether editing one of a group of linked ranges, such as the name of an
+    /// opening tag, also edits the others, such as the name of its closing tag.
+    ///
+    /// Default: true
+    pub linked_edits: Option<bool>,
     /// Whether to display inline and alongside documentation for items in the
     /// completions menu.
     ///
@@ -211,6 +224,10 @@ pub struct GutterContent {
     ///
     /// Default: true
     pub code_actions: Option<bool>,
+    /// Whether to show buttons running the tests and binaries detected in the code in the gutter.
+    ///
+    /// Default: true
+    pub runnables: Option<bool>,
     /// Whether to show fold buttons in the gutter.
     ///
     /// Default: true
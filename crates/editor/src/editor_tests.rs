@@ -5343,6 +5343,47 @@ async fn test_snippets(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_snippets_with_choices_and_transforms(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let (text, insertion_ranges) = marked_text_ranges("ˇ", false);
+    let buffer = cx.update(|cx| MultiBuffer::build_simple(&text, cx));
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+
+    _ = editor.update(cx, |editor, cx| {
+        let snippet =
+            Snippet::parse("struct ${1:name} { kind: ${2|A,B|} } // ${1/(.*)/${1:/upcase}/}$0")
+                .unwrap();
+        editor
+            .insert_snippet(&insertion_ranges, snippet, cx)
+            .unwrap();
+        assert_eq!(editor.text(cx), "struct name { kind: A } // NAME");
+        assert_eq!(editor.selections.ranges::<usize>(cx), [7..11]);
+
+        // The mirror of the tabstop is updated when leaving it.
+        editor.handle_input("point", cx);
+        assert!(editor.move_to_next_snippet_tabstop(cx));
+        assert_eq!(editor.text(cx), "struct point { kind: A } // POINT");
+        assert_eq!(editor.selections.ranges::<usize>(cx), [21..22]);
+
+        // The values of a choice are offered as completions.
+        if let Some(ContextMenu::Completions(menu)) = editor.context_menu.read().as_ref() {
+            assert_eq!(
+                menu.matches.iter().map(|m| &m.string).collect::<Vec<_>>(),
+                &["A", "B"]
+            );
+        } else {
+            panic!("expected completion menu to be open");
+        }
+        editor.confirm_completion(&ConfirmCompletion { item_ix: Some(1) }, cx);
+        assert_eq!(editor.text(cx), "struct point { kind: B } // POINT");
+
+        assert!(editor.move_to_next_snippet_tabstop(cx));
+        assert_eq!(editor.selections.ranges::<usize>(cx), [33..33]);
+    });
+}
+
 #[gpui::test]
 async fn test_document_format_during_save(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -8755,6 +8796,110 @@ async fn test_find_all_references(cx: &mut gpui::TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_find_incoming_calls(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state(indoc! {"
+        fn fooˇ() {}
+
+        fn bar() {
+            foo();
+        }
+
+        fn baz() {
+            bar();
+        }
+    "});
+
+    let uri = cx.buffer_lsp_url.clone();
+    let item = |name: &str, line| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: uri.clone(),
+        range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line + 1, 0)),
+        selection_range: lsp::Range::new(lsp::Position::new(line, 3), lsp::Position::new(line, 6)),
+        data: None,
+    };
+    let items = [item("foo", 0), item("bar", 2), item("baz", 6)];
+    cx.lsp
+        .handle_request::<lsp::request::CallHierarchyPrepare, _, _>({
+            let items = items.clone();
+            move |params, _| {
+                let line = params.text_document_position_params.position.line;
+                let item = items
+                    .iter()
+                    .find(|item| item.range.start.line == line)
+                    .cloned();
+                async move { Ok(item.map(|item| vec![item])) }
+            }
+        });
+    cx.lsp
+        .handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>(move |params, _| {
+            // `bar` calls `foo`, and `baz` calls `bar`, each on the row below their name.
+            let caller_ix = items
+                .iter()
+                .position(|item| item.name == params.item.name)
+                .unwrap()
+                + 1;
+            let calls = items
+                .get(caller_ix)
+                .map(|caller| lsp::CallHierarchyIncomingCall {
+                    from: caller.clone(),
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(caller.range.start.line + 1, 4),
+                        lsp::Position::new(caller.range.start.line + 1, 7),
+                    )],
+                })
+                .into_iter()
+                .collect();
+            async move { Ok(Some(calls)) }
+        });
+
+    let calls = cx
+        .update_editor(|editor, cx| editor.find_incoming_calls(&FindIncomingCalls, cx))
+        .unwrap();
+    cx.executor().run_until_parked();
+    calls.await.unwrap();
+
+    let hierarchy =
+        cx.update_workspace(|workspace, cx| workspace.active_item_as::<HierarchyView>(cx).unwrap());
+    cx.update_workspace(|_, cx| assert_eq!(hierarchy.read(cx).visible_items(), ["bar"]));
+
+    // Expanding a node loads the next level of the hierarchy in place.
+    cx.update_workspace(|_, cx| hierarchy.update(cx, |hierarchy, cx| hierarchy.toggle(0, cx)));
+    cx.executor().run_until_parked();
+    cx.update_workspace(|_, cx| assert_eq!(hierarchy.read(cx).visible_items(), ["bar", "  baz"]));
+
+    cx.update_workspace(|_, cx| hierarchy.update(cx, |hierarchy, cx| hierarchy.toggle(0, cx)));
+    cx.update_workspace(|_, cx| assert_eq!(hierarchy.read(cx).visible_items(), ["bar"]));
+
+    // Opening a node selects where it calls its parent.
+    cx.update_workspace(|_, cx| hierarchy.update(cx, |hierarchy, cx| hierarchy.open(0, cx)));
+    cx.assert_editor_state(indoc! {"
+        fn foo() {}
+
+        fn bar() {
+            «fooˇ»();
+        }
+
+        fn baz() {
+            bar();
+        }
+    "});
+}
+
 #[gpui::test]
 async fn test_addition_reverts(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -9124,6 +9269,33 @@ struct Row10;"#};
     );
 }
 
+#[gpui::test]
+async fn test_toggle_hunk_diff(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+    let diff_base = "one\ntwo\nthree\nfour\n";
+    cx.set_state("one\nTWˇO\nthree\nfour\n");
+    cx.set_diff_base(Some(diff_base));
+    cx.executor().run_until_parked();
+
+    // The deleted row is shown above the row that replaced it.
+    cx.update_editor(|editor, cx| {
+        editor.toggle_hunk_diff(&ToggleHunkDiff, cx);
+        assert_eq!(editor.display_text(cx), "one\n\nTWO\nthree\nfour\n");
+        editor.toggle_hunk_diff(&ToggleHunkDiff, cx);
+        assert_eq!(editor.display_text(cx), "one\nTWO\nthree\nfour\n");
+        editor.toggle_hunk_diff(&ToggleHunkDiff, cx);
+        assert_eq!(editor.display_text(cx), "one\n\nTWO\nthree\nfour\n");
+    });
+
+    // Once the hunk leaves the diff, its deleted rows are hidden.
+    cx.set_diff_base(Some("one\nTWO\nthree\nfour\n"));
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.display_text(cx), "one\nTWO\nthree\nfour\n");
+    });
+}
+
 #[gpui::test]
 async fn test_multibuffer_reverts(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
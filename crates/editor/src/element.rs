@@ -11,17 +11,17 @@ use crate::{
     items::BufferSearchHighlights,
     mouse_context_menu,
     scroll::scroll_amount::ScrollAmount,
-    CursorShape, DisplayPoint, DocumentHighlightRead, DocumentHighlightWrite, Editor, EditorMode,
-    EditorSettings, EditorSnapshot, EditorStyle, GutterDimensions, HalfPageDown, HalfPageUp,
-    HoveredCursor, LineDown, LineUp, OpenExcerpts, PageDown, PageUp, Point, SelectPhase, Selection,
-    SoftWrap, ToPoint, CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
+    signature_help, CursorShape, DisplayPoint, DocumentHighlightRead, DocumentHighlightWrite,
+    Editor, EditorMode, EditorSettings, EditorSnapshot, EditorStyle, GutterDimensions,
+    HalfPageDown, HalfPageUp, HoveredCursor, LineDown, LineUp, OpenExcerpts, PageDown, PageUp,
+    Point, SelectPhase, Selection, SoftWrap, ToPoint, CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
 };
 use anyhow::Result;
 use collections::{BTreeMap, HashMap};
 use git::diff::DiffHunkStatus;
 use gpui::{
     div, fill, outline, overlay, point, px, quad, relative, size, transparent_black, Action,
-    AnchorCorner, AnyElement, AvailableSpace, Bounds, ContentMask, Corners, CursorStyle,
+    AnchorCorner, AnyElement, AnyView, AvailableSpace, Bounds, ContentMask, Corners, CursorStyle,
     DispatchPhase, Edges, Element, ElementContext, ElementInputHandler, Entity, Hitbox, Hsla,
     InteractiveElement, IntoElement, ModifiersChangedEvent, MouseButton, MouseDownEvent,
     MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, ScrollDelta, ScrollWheelEvent, ShapedLine,
@@ -34,6 +34,7 @@ use lsp::DiagnosticSeverity;
 use multi_buffer::Anchor;
 use project::{
     project_settings::{GitGutterSetting, ProjectSettings},
+    repository::BlameEntry,
     ProjectPath,
 };
 use settings::Settings;
@@ -49,9 +50,11 @@ use std::{
 };
 use sum_tree::Bias;
 use theme::{ActiveTheme, PlayerColor};
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
 use ui::prelude::*;
-use ui::{h_flex, ButtonLike, ButtonStyle, Tooltip};
-use util::ResultExt;
+use ui::{h_flex, tooltip_container, ButtonLike, ButtonStyle, Tooltip};
+use util::{truncate_and_trailoff, ResultExt};
 use workspace::item::Item;
 
 struct SelectionLayout {
@@ -283,6 +286,9 @@ impl EditorElement {
         register_action(view, cx, Editor::open_url);
         register_action(view, cx, Editor::fold);
         register_action(view, cx, Editor::fold_at);
+        register_action(view, cx, Editor::fold_all_comments);
+        register_action(view, cx, Editor::fold_all_imports);
+        register_action(view, cx, Editor::fold_all_regions);
         register_action(view, cx, Editor::unfold_lines);
         register_action(view, cx, Editor::unfold_at);
         register_action(view, cx, Editor::fold_selected_ranges);
@@ -292,8 +298,13 @@ impl EditorElement {
         register_action(view, cx, Editor::open_excerpts_in_split);
         register_action(view, cx, Editor::toggle_soft_wrap);
         register_action(view, cx, Editor::toggle_line_numbers);
+        register_action(view, cx, Editor::toggle_git_blame);
+        register_action(view, cx, Editor::toggle_git_blame_inline);
         register_action(view, cx, Editor::toggle_inlay_hints);
         register_action(view, cx, hover_popover::hover);
+        register_action(view, cx, signature_help::show_signature_help);
+        register_action(view, cx, signature_help::signature_help_next);
+        register_action(view, cx, signature_help::signature_help_previous);
         register_action(view, cx, Editor::reveal_in_finder);
         register_action(view, cx, Editor::copy_path);
         register_action(view, cx, Editor::copy_relative_path);
@@ -344,6 +355,34 @@ impl EditorElement {
                 cx.propagate();
             }
         });
+        register_action(view, cx, |editor, action, cx| {
+            if let Some(task) = editor.find_incoming_calls(action, cx) {
+                task.detach_and_log_err(cx);
+            } else {
+                cx.propagate();
+            }
+        });
+        register_action(view, cx, |editor, action, cx| {
+            if let Some(task) = editor.find_outgoing_calls(action, cx) {
+                task.detach_and_log_err(cx);
+            } else {
+                cx.propagate();
+            }
+        });
+        register_action(view, cx, |editor, action, cx| {
+            if let Some(task) = editor.find_supertypes(action, cx) {
+                task.detach_and_log_err(cx);
+            } else {
+                cx.propagate();
+            }
+        });
+        register_action(view, cx, |editor, action, cx| {
+            if let Some(task) = editor.find_subtypes(action, cx) {
+                task.detach_and_log_err(cx);
+            } else {
+                cx.propagate();
+            }
+        });
         register_action(view, cx, Editor::next_copilot_suggestion);
         register_action(view, cx, Editor::previous_copilot_suggestion);
         register_action(view, cx, Editor::copilot_suggest);
@@ -356,6 +395,9 @@ impl EditorElement {
         register_action(view, cx, Editor::unique_lines_case_sensitive);
         register_action(view, cx, Editor::accept_partial_copilot_suggestion);
         register_action(view, cx, Editor::revert_selected_hunks);
+        register_action(view, cx, Editor::stage_selected_hunks);
+        register_action(view, cx, Editor::unstage_selected_hunks);
+        register_action(view, cx, Editor::toggle_hunk_diff);
     }
 
     fn register_key_listeners(&self, cx: &mut ElementContext, layout: &EditorLayout) {
@@ -1107,7 +1149,9 @@ impl EditorElement {
         );
         let indicator_size = button.measure(available_space, cx);
 
-        let mut x = Pixels::ZERO;
+        let mut x = gutter_dimensions
+            .git_blame_entries_width
+            .unwrap_or_default();
         let mut y = newest_selection_head.row() as f32 * line_height - scroll_pixel_position.y;
         // Center indicator.
         x +=
@@ -1117,6 +1161,162 @@ impl EditorElement {
         Some(button)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_run_indicators(
+        &self,
+        rows: Range<u32>,
+        snapshot: &EditorSnapshot,
+        code_actions_row: Option<u32>,
+        line_height: Pixels,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        cx: &mut ElementContext,
+    ) -> Vec<AnyElement> {
+        let mut runnables = self
+            .editor
+            .read(cx)
+            .runnables
+            .runnables
+            .iter()
+            .map(|runnable| {
+                let row = runnable.position.to_display_point(snapshot).row();
+                (row, runnable.clone())
+            })
+            .filter(|(row, _)| rows.contains(row) && Some(*row) != code_actions_row)
+            .collect::<Vec<_>>();
+        runnables.dedup_by_key(|(row, _)| *row);
+
+        let buttons = self.editor.update(cx, |editor, cx| {
+            runnables
+                .into_iter()
+                .map(|(row, runnable)| {
+                    let button = editor.render_run_indicator(&self.style, row, runnable, cx);
+                    (row, button)
+                })
+                .collect::<Vec<_>>()
+        });
+        buttons
+            .into_iter()
+            .map(|(row, button)| {
+                let mut button = button.into_any_element();
+                let available_space = size(
+                    AvailableSpace::MinContent,
+                    AvailableSpace::Definite(line_height),
+                );
+                let indicator_size = button.measure(available_space, cx);
+
+                let x = gutter_dimensions
+                    .git_blame_entries_width
+                    .unwrap_or_default()
+                    + (gutter_dimensions.margin + gutter_dimensions.left_padding
+                        - indicator_size.width)
+                        / 2.;
+                let y = row as f32 * line_height - scroll_pixel_position.y
+                    + (line_height - indicator_size.height) / 2.;
+                button.layout(gutter_hitbox.origin + point(x, y), available_space, cx);
+                button
+            })
+            .collect()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn layout_blame_entries(
+        &self,
+        buffer_rows: impl Iterator<Item = Option<u32>>,
+        em_width: Pixels,
+        scroll_position: gpui::Point<f32>,
+        line_height: Pixels,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        cx: &mut ElementContext,
+    ) -> Option<Vec<AnyElement>> {
+        let width = gutter_dimensions.git_blame_entries_width?;
+        let blame = self.editor.read(cx).blame()?.clone();
+        let blame = blame.read(cx);
+        let blamed_rows = buffer_rows
+            .map(|buffer_row| {
+                buffer_row.map(|buffer_row| {
+                    let entry = blame
+                        .blame_for_rows([Some(buffer_row)])
+                        .next()
+                        .flatten()
+                        .cloned();
+                    let message = entry
+                        .as_ref()
+                        .and_then(|entry| blame.message_for_commit(&entry.sha).map(str::to_string));
+                    (entry, message)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let scroll_top = scroll_position.y * line_height;
+        let available_space = size(
+            AvailableSpace::Definite(width - em_width),
+            AvailableSpace::Definite(line_height),
+        );
+        let elements = blamed_rows
+            .into_iter()
+            .enumerate()
+            .filter_map(|(ix, blamed_row)| {
+                let (entry, message) = blamed_row?;
+                let mut element = render_blame_entry(ix, entry, message, &self.style, cx);
+                let origin = gutter_hitbox.origin
+                    + point(
+                        em_width,
+                        ix as f32 * line_height - (scroll_top % line_height),
+                    );
+                element.layout(origin, available_space, cx);
+                Some(element)
+            })
+            .collect();
+        Some(elements)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn layout_inline_blame(
+        &self,
+        display_row: u32,
+        snapshot: &EditorSnapshot,
+        line_layout: &LineWithInvisibles,
+        em_width: Pixels,
+        content_origin: gpui::Point<Pixels>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        line_height: Pixels,
+        cx: &mut ElementContext,
+    ) -> Option<AnyElement> {
+        const INLINE_BLAME_PADDING_EM_WIDTHS: f32 = 6.;
+
+        let editor = self.editor.read(cx);
+        if !editor.render_git_blame_inline(cx) {
+            return None;
+        }
+        let blame = editor.blame()?.read(cx);
+        let buffer_row = DisplayPoint::new(display_row, 0).to_point(snapshot).row;
+        let entry = blame
+            .blame_for_rows([Some(buffer_row)])
+            .next()
+            .flatten()
+            .cloned();
+        let message = entry
+            .as_ref()
+            .and_then(|entry| blame.message_for_commit(&entry.sha).map(str::to_string));
+
+        let mut element = render_inline_blame_entry(entry, message, &self.style, cx);
+        let start_x = content_origin.x + line_layout.line.width - scroll_pixel_position.x
+            + em_width * INLINE_BLAME_PADDING_EM_WIDTHS;
+        let start_y = content_origin.y + line_height * display_row as f32 - scroll_pixel_position.y;
+        element.layout(
+            point(start_x, start_y),
+            size(
+                AvailableSpace::MinContent,
+                AvailableSpace::Definite(line_height),
+            ),
+            cx,
+        );
+        Some(element)
+    }
+
     fn calculate_relative_line_numbers(
         &self,
         snapshot: &EditorSnapshot,
@@ -1635,6 +1835,63 @@ impl EditorElement {
         true
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_signature_help(
+        &self,
+        hitbox: &Hitbox,
+        text_hitbox: &Hitbox,
+        content_origin: gpui::Point<Pixels>,
+        start_row: u32,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        line_layouts: &[LineWithInvisibles],
+        newest_selection_head: DisplayPoint,
+        line_height: Pixels,
+        em_width: Pixels,
+        cx: &mut ElementContext,
+    ) {
+        let max_size = size(
+            (120. * em_width)
+                .min(hitbox.size.width / 2.)
+                .max(MIN_POPOVER_CHARACTER_WIDTH * em_width),
+            (16. * line_height)
+                .min(hitbox.size.height / 2.)
+                .max(MIN_POPOVER_LINE_HEIGHT * line_height),
+        );
+        let Some(mut popover) = self.editor.update(cx, |editor, cx| {
+            let workspace = editor.workspace.as_ref().map(|(w, _)| w.clone());
+            editor
+                .signature_help_state
+                .popover()?
+                .render(&self.style, max_size, workspace, cx)
+        }) else {
+            return;
+        };
+
+        let available_space = size(AvailableSpace::MinContent, AvailableSpace::MinContent);
+        let popover_size = popover.measure(available_space, cx);
+
+        let cursor_row_layout =
+            &line_layouts[(newest_selection_head.row() - start_row) as usize].line;
+        let x = cursor_row_layout.x_for_index(newest_selection_head.column() as usize)
+            - scroll_pixel_position.x;
+        let y = newest_selection_head.row() as f32 * line_height - scroll_pixel_position.y;
+        let cursor_origin = content_origin + point(x, y);
+
+        // Prefer rendering above the cursor, so that the completions menu below stays visible.
+        let mut popover_origin = point(
+            cursor_origin.x,
+            cursor_origin.y - popover_size.height - HOVER_POPOVER_GAP,
+        );
+        if popover_origin.y < text_hitbox.origin.y {
+            popover_origin.y = cursor_origin.y + line_height + HOVER_POPOVER_GAP;
+        }
+        if popover_origin.x + popover_size.width > cx.viewport_size().width {
+            popover_origin.x = (cx.viewport_size().width - popover_size.width).max(Pixels::ZERO);
+        }
+
+        cx.defer_draw(popover, popover_origin, 1);
+    }
+
     fn layout_mouse_context_menu(&self, cx: &mut ElementContext) -> Option<AnyElement> {
         let mouse_context_menu = self.editor.read(cx).mouse_context_menu.as_ref()?;
         let mut element = overlay()
@@ -1918,12 +2175,26 @@ impl EditorElement {
         }
 
         cx.paint_layer(layout.gutter_hitbox.bounds, |cx| {
+            if let Some(blamed_display_rows) = layout.blamed_display_rows.as_mut() {
+                cx.with_element_id(Some("editor_blame"), |cx| {
+                    for element in blamed_display_rows {
+                        element.paint(cx);
+                    }
+                });
+            }
+
             cx.with_element_id(Some("gutter_fold_indicators"), |cx| {
                 for fold_indicator in layout.fold_indicators.iter_mut().flatten() {
                     fold_indicator.paint(cx);
                 }
             });
 
+            cx.with_element_id(Some("gutter_run_indicators"), |cx| {
+                for run_indicator in layout.run_indicators.iter_mut() {
+                    run_indicator.paint(cx);
+                }
+            });
+
             if let Some(indicator) = layout.code_actions_indicator.as_mut() {
                 indicator.paint(cx);
             }
@@ -2059,6 +2330,7 @@ impl EditorElement {
                 let invisible_display_ranges = self.paint_highlights(layout, cx);
                 self.paint_lines(&invisible_display_ranges, layout, cx);
                 self.paint_redactions(layout, cx);
+                self.paint_inline_blame(layout, cx);
                 self.paint_cursors(layout, cx);
             },
         )
@@ -2156,6 +2428,16 @@ impl EditorElement {
         });
     }
 
+    fn paint_inline_blame(&mut self, layout: &mut EditorLayout, cx: &mut ElementContext) {
+        if let Some(inline_blame) = layout.inline_blame.as_mut() {
+            cx.with_element_id(Some("inline_blame"), |cx| {
+                cx.paint_layer(layout.text_hitbox.bounds, |cx| {
+                    inline_blame.paint(cx);
+                })
+            });
+        }
+    }
+
     fn paint_cursors(&mut self, layout: &mut EditorLayout, cx: &mut ElementContext) {
         for cursor in &mut layout.cursors {
             cursor.paint(layout.content_origin, cx);
@@ -3150,6 +3432,20 @@ impl Element for EditorElement {
                     scroll_position.y * line_height,
                 );
 
+                let blamed_display_rows = cx.with_element_id(Some("editor_blame"), |cx| {
+                    self.layout_blame_entries(
+                        snapshot
+                            .buffer_rows(start_row)
+                            .take((end_row - start_row) as usize),
+                        em_width,
+                        scroll_position,
+                        line_height,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        cx,
+                    )
+                });
+
                 cx.with_element_id(Some("blocks"), |cx| {
                     self.layout_blocks(
                         &mut blocks,
@@ -3199,8 +3495,27 @@ impl Element for EditorElement {
 
                 let mut context_menu_visible = false;
                 let mut code_actions_indicator = None;
+                let mut inline_blame = None;
                 if let Some(newest_selection_head) = newest_selection_head {
                     if (start_row..end_row).contains(&newest_selection_head.row()) {
+                        let display_row = newest_selection_head.row();
+                        if let Some(line_layout) =
+                            line_layouts.get((display_row - start_row) as usize)
+                        {
+                            inline_blame = cx.with_element_id(Some("inline_blame"), |cx| {
+                                self.layout_inline_blame(
+                                    display_row,
+                                    &snapshot,
+                                    line_layout,
+                                    em_width,
+                                    content_origin,
+                                    scroll_pixel_position,
+                                    line_height,
+                                    cx,
+                                )
+                            });
+                        }
+
                         context_menu_visible = self.layout_context_menu(
                             line_height,
                             &hitbox,
@@ -3212,6 +3527,18 @@ impl Element for EditorElement {
                             newest_selection_head,
                             cx,
                         );
+                        self.layout_signature_help(
+                            &hitbox,
+                            &text_hitbox,
+                            content_origin,
+                            start_row,
+                            scroll_pixel_position,
+                            &line_layouts,
+                            newest_selection_head,
+                            line_height,
+                            em_width,
+                            cx,
+                        );
                         if gutter_settings.code_actions {
                             code_actions_indicator = self.layout_code_actions_indicator(
                                 line_height,
@@ -3242,6 +3569,25 @@ impl Element for EditorElement {
 
                 let mouse_context_menu = self.layout_mouse_context_menu(cx);
 
+                let run_indicators = if gutter_settings.runnables {
+                    cx.with_element_id(Some("gutter_run_indicators"), |cx| {
+                        self.layout_run_indicators(
+                            start_row..end_row,
+                            &snapshot,
+                            newest_selection_head
+                                .filter(|_| code_actions_indicator.is_some())
+                                .map(|head| head.row()),
+                            line_height,
+                            scroll_pixel_position,
+                            &gutter_dimensions,
+                            &gutter_hitbox,
+                            cx,
+                        )
+                    })
+                } else {
+                    Vec::new()
+                };
+
                 let fold_indicators = if gutter_settings.folds {
                     cx.with_element_id(Some("gutter_fold_indicators"), |cx| {
                         self.layout_gutter_fold_indicators(
@@ -3317,12 +3663,15 @@ impl Element for EditorElement {
                     redacted_ranges,
                     line_numbers,
                     display_hunks,
+                    blamed_display_rows,
+                    inline_blame,
                     folds,
                     blocks,
                     cursors,
                     selections,
                     mouse_context_menu,
                     code_actions_indicator,
+                    run_indicators,
                     fold_indicators,
                     tab_invisible,
                     space_invisible,
@@ -3403,6 +3752,8 @@ pub struct EditorLayout {
     highlighted_rows: BTreeMap<u32, Hsla>,
     line_numbers: Vec<Option<ShapedLine>>,
     display_hunks: Vec<DisplayDiffHunk>,
+    blamed_display_rows: Option<Vec<AnyElement>>,
+    inline_blame: Option<AnyElement>,
     folds: Vec<FoldLayout>,
     blocks: Vec<BlockLayout>,
     highlighted_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
@@ -3411,6 +3762,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     max_row: u32,
     code_actions_indicator: Option<AnyElement>,
+    run_indicators: Vec<AnyElement>,
     fold_indicators: Vec<Option<AnyElement>>,
     mouse_context_menu: Option<AnyElement>,
     tab_invisible: ShapedLine,
@@ -3842,6 +4194,134 @@ fn scale_horizontal_mouse_autoscroll_delta(delta: Pixels) -> f32 {
     (delta.pow(1.2) / 300.0).into()
 }
 
+const GIT_BLAME_MAX_AUTHOR_CHARS_DISPLAYED: usize = 20;
+
+fn render_blame_entry(
+    ix: usize,
+    entry: Option<BlameEntry>,
+    message: Option<String>,
+    style: &EditorStyle,
+    cx: &mut WindowContext,
+) -> AnyElement {
+    let text = match &entry {
+        Some(entry) => {
+            let author = entry.author.as_deref().unwrap_or_default();
+            format!(
+                "{:<width$} {}",
+                truncate_and_trailoff(author, GIT_BLAME_MAX_AUTHOR_CHARS_DISPLAYED),
+                blame_entry_timestamp(entry, TimestampFormat::Relative, cx),
+                width = GIT_BLAME_MAX_AUTHOR_CHARS_DISPLAYED,
+            )
+        }
+        None => "Not committed yet".to_string(),
+    };
+
+    h_flex()
+        .id(("blame", ix))
+        .w_full()
+        .font(style.text.font_family.clone())
+        .text_color(cx.theme().status().hint)
+        .overflow_hidden()
+        .child(text)
+        .when_some(entry, |this, entry| {
+            this.tooltip(move |cx| BlameEntryTooltip::new(entry.clone(), message.clone(), cx))
+        })
+        .into_any()
+}
+
+fn render_inline_blame_entry(
+    entry: Option<BlameEntry>,
+    message: Option<String>,
+    style: &EditorStyle,
+    cx: &mut WindowContext,
+) -> AnyElement {
+    let text = match &entry {
+        Some(entry) => {
+            let mut text = format!(
+                "{}, {}",
+                entry.author.as_deref().unwrap_or_default(),
+                blame_entry_timestamp(entry, TimestampFormat::Relative, cx)
+            );
+            if let Some(summary) = &entry.summary {
+                write!(text, " • {summary}").unwrap();
+            }
+            text
+        }
+        None => "Not committed yet".to_string(),
+    };
+
+    h_flex()
+        .id("inline-blame")
+        .font(style.text.font_family.clone())
+        .text_color(cx.theme().status().hint)
+        .child(text)
+        .when_some(entry, |this, entry| {
+            this.tooltip(move |cx| BlameEntryTooltip::new(entry.clone(), message.clone(), cx))
+        })
+        .into_any()
+}
+
+fn blame_entry_timestamp(
+    entry: &BlameEntry,
+    format: TimestampFormat,
+    cx: &WindowContext,
+) -> String {
+    match OffsetDateTime::from_unix_timestamp(entry.author_time) {
+        Ok(timestamp) => time_format::format_localized_timestamp(
+            timestamp,
+            OffsetDateTime::now_utc(),
+            cx.local_timezone(),
+            format,
+        ),
+        Err(_) => "Error parsing date".to_string(),
+    }
+}
+
+/// The details of the commit of a blame entry, with its full message.
+struct BlameEntryTooltip {
+    entry: BlameEntry,
+    message: Option<String>,
+}
+
+impl BlameEntryTooltip {
+    fn new(entry: BlameEntry, message: Option<String>, cx: &mut WindowContext) -> AnyView {
+        cx.new_view(|_| Self { entry, message }).into()
+    }
+}
+
+impl Render for BlameEntryTooltip {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let short_sha: SharedString = self.entry.sha.chars().take(7).collect::<String>().into();
+        let author = match (&self.entry.author, &self.entry.author_mail) {
+            (Some(author), Some(mail)) => format!("{author} {mail}"),
+            (Some(author), None) => author.clone(),
+            (None, mail) => mail.clone().unwrap_or_default(),
+        };
+        let timestamp = blame_entry_timestamp(&self.entry, TimestampFormat::Absolute, cx);
+        let message = self
+            .message
+            .clone()
+            .or_else(|| self.entry.summary.clone())
+            .unwrap_or_default();
+
+        tooltip_container(cx, move |this, _| {
+            this.gap_1()
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(short_sha))
+                        .child(Label::new(timestamp).color(Color::Muted)),
+                )
+                .child(
+                    Label::new(author)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(div().max_w(rems(40.)).pt_1().child(message))
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,229 @@
+use crate::{Anchor, Editor, EditorMode};
+use collections::{HashMap, HashSet};
+use futures::future;
+use gpui::{Task, ViewContext};
+use language::FoldRangeKind;
+use std::{ops::Range, sync::Arc, time::Duration};
+use text::BufferId;
+use util::ResultExt;
+
+pub const FOLDING_RANGES_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
+
+#[derive(Default)]
+pub(crate) struct FoldingRangesState {
+    task: Option<Task<()>>,
+    syntax_ranges: HashMap<BufferId, SyntaxFoldRanges>,
+}
+
+/// The fold ranges of a buffer according to its fold query and region comments. Finding them
+/// walks the whole buffer, so they are only recomputed after the buffer gets reparsed, their
+/// anchors following the edits made in between.
+#[derive(Clone)]
+struct SyntaxFoldRanges {
+    parse_count: usize,
+    ranges: Option<Arc<[(Range<text::Anchor>, Option<FoldRangeKind>)]>>,
+}
+
+/// Recomputes the foldable ranges of every buffer in the editor. Ranges reported by a
+/// buffer's language server take precedence over the ones from its language's fold query,
+/// and buffers with neither keep being folded by indentation.
+pub(crate) fn refresh_folding_ranges(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let project = editor.project.clone();
+    let buffers = editor.buffer.read(cx).all_buffers();
+    editor
+        .folding_ranges
+        .syntax_ranges
+        .retain(|buffer_id, _| buffers.iter().any(|b| b.read(cx).remote_id() == *buffer_id));
+
+    editor.folding_ranges.task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor()
+                .timer(FOLDING_RANGES_DEBOUNCE_TIMEOUT)
+                .await;
+        }
+
+        let Some(requests) = editor
+            .update(&mut cx, |editor, cx| {
+                buffers
+                    .into_iter()
+                    .map(|buffer| {
+                        let lsp_request = project.as_ref().map(|project| {
+                            project.update(cx, |project, cx| project.folding_ranges(&buffer, cx))
+                        });
+                        let snapshot = buffer.read(cx).snapshot();
+                        let cached_ranges = editor
+                            .folding_ranges
+                            .syntax_ranges
+                            .get(&snapshot.remote_id())
+                            .filter(|cached| cached.parse_count == snapshot.parse_count())
+                            .cloned();
+                        let syntax_ranges = match cached_ranges {
+                            Some(cached) => Task::ready(cached),
+                            None => cx.background_executor().spawn(async move {
+                                let ranges = snapshot.syntax_fold_ranges().map(|ranges| {
+                                    ranges
+                                        .into_iter()
+                                        .map(|(range, kind)| {
+                                            let range = snapshot.anchor_after(range.start)
+                                                ..snapshot.anchor_before(range.end);
+                                            (range, kind)
+                                        })
+                                        .collect()
+                                });
+                                SyntaxFoldRanges {
+                                    parse_count: snapshot.parse_count(),
+                                    ranges,
+                                }
+                            }),
+                        };
+                        async move {
+                            let lsp_ranges = match lsp_request {
+                                Some(request) => request.await.log_err().unwrap_or_default(),
+                                None => Vec::new(),
+                            };
+                            (buffer, lsp_ranges, syntax_ranges.await)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .log_err()
+        else {
+            return;
+        };
+        let responses = future::join_all(requests).await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                let multi_buffer = editor.buffer.read(cx);
+                let snapshot = multi_buffer.snapshot(cx);
+                let mut fold_ranges = Vec::new();
+                let mut buffer_ids = HashSet::default();
+                for (buffer, lsp_ranges, syntax_ranges) in responses {
+                    let buffer_id = buffer.read(cx).remote_id();
+                    editor
+                        .folding_ranges
+                        .syntax_ranges
+                        .insert(buffer_id, syntax_ranges.clone());
+                    let ranges = if !lsp_ranges.is_empty() {
+                        lsp_ranges
+                            .into_iter()
+                            .map(|range| (range.range, range.kind))
+                            .collect::<Vec<_>>()
+                    } else if let Some(syntax_ranges) = syntax_ranges.ranges {
+                        syntax_ranges.to_vec()
+                    } else {
+                        continue;
+                    };
+
+                    let buffer_snapshot = buffer.read(cx).snapshot();
+                    buffer_ids.insert(buffer_id);
+                    for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(&buffer, cx)
+                    {
+                        let context = excerpt_range.context;
+                        for (range, kind) in &ranges {
+                            // Ranges are folded from the row they start at, so they must start
+                            // within the excerpt, but may be cut off at its end.
+                            if range.start.cmp(&context.start, &buffer_snapshot).is_lt()
+                                || range.start.cmp(&context.end, &buffer_snapshot).is_ge()
+                            {
+                                continue;
+                            }
+                            let end = range.end.min(&context.end, &buffer_snapshot);
+
+                            let range = Anchor {
+                                buffer_id: Some(buffer_id),
+                                excerpt_id,
+                                text_anchor: range.start,
+                            }..Anchor {
+                                buffer_id: Some(buffer_id),
+                                excerpt_id,
+                                text_anchor: end,
+                            };
+                            fold_ranges.push((range, *kind));
+                        }
+                    }
+                }
+                fold_ranges.sort_by(|(a, _), (b, _)| a.start.cmp(&b.start, &snapshot));
+
+                editor
+                    .display_map
+                    .update(cx, |map, _| map.set_fold_ranges(fold_ranges, buffer_ids));
+                cx.notify();
+            })
+            .log_err();
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        actions::FoldAllComments, editor_tests::init_test,
+        test::editor_lsp_test_context::EditorLspTestContext,
+    };
+    use indoc::indoc;
+    use language::Point;
+
+    #[gpui::test]
+    async fn test_lsp_folding_ranges(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.handle_request::<lsp::request::FoldingRangeRequest, _, _>(|_, _, _| async move {
+            Ok(Some(vec![
+                lsp::FoldingRange {
+                    start_line: 0,
+                    end_line: 1,
+                    kind: Some(lsp::FoldingRangeKind::Comment),
+                    ..Default::default()
+                },
+                lsp::FoldingRange {
+                    start_line: 2,
+                    end_line: 4,
+                    ..Default::default()
+                },
+            ]))
+        });
+        cx.set_state(indoc! {"
+            // A function
+            // that does nothing.
+            fn main() {
+                ˇ
+            }
+        "});
+        cx.executor().advance_clock(FOLDING_RANGES_DEBOUNCE_TIMEOUT);
+        cx.run_until_parked();
+
+        cx.update_editor(|editor, cx| {
+            let snapshot = editor.snapshot(cx).display_snapshot;
+            // The closing brace is folded too, since the server reported it as part of the range.
+            assert_eq!(
+                snapshot.foldable_range(2),
+                Some(Point::new(2, 11)..Point::new(4, 1))
+            );
+            // Rows without a reported range are no longer folded by indentation.
+            assert!(!snapshot.is_foldable(3));
+
+            editor.fold_all_comments(&FoldAllComments, cx);
+            assert_eq!(
+                editor.display_text(cx),
+                "// A function⋯\nfn main() {\n    \n}\n"
+            );
+        });
+    }
+}
@@ -0,0 +1,256 @@
+use std::time::Duration;
+
+use collections::HashMap;
+use gpui::{Model, ModelContext, Subscription, Task};
+use language::{Buffer, BufferSnapshot, Point};
+use project::{
+    repository::{remap_blame_entries, BlameEntry, RowChange},
+    Project,
+};
+use util::ResultExt;
+
+const REGENERATE_ON_EDIT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The blame of the text of a buffer, kept in sync with its edits.
+///
+/// The edited rows are not attributed to any commit until the blame is regenerated,
+/// shortly after the edits or when the buffer is saved or its repository changes.
+pub struct GitBlame {
+    project: Model<Project>,
+    buffer: Model<Buffer>,
+    entries: Vec<BlameEntry>,
+    messages: HashMap<String, String>,
+    buffer_snapshot: BufferSnapshot,
+    generated: bool,
+    generate_task: Task<()>,
+    _subscription: Subscription,
+}
+
+impl GitBlame {
+    pub fn new(
+        buffer: Model<Buffer>,
+        project: Model<Project>,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&buffer, |this, _, event, cx| match event {
+            language::Event::Edited => {
+                this.sync(cx);
+                if this.generated {
+                    this.generate(Some(REGENERATE_ON_EDIT_DEBOUNCE), cx);
+                }
+            }
+            language::Event::Saved
+            | language::Event::Reloaded
+            | language::Event::FileHandleChanged
+            | language::Event::DiffBaseChanged => this.generate(None, cx),
+            _ => {}
+        });
+
+        let mut this = Self {
+            buffer_snapshot: buffer.read(cx).snapshot(),
+            project,
+            buffer,
+            entries: Vec::new(),
+            messages: HashMap::default(),
+            generated: false,
+            generate_task: Task::ready(()),
+            _subscription: subscription,
+        };
+        this.generate(None, cx);
+        this
+    }
+
+    pub fn has_generated_entries(&self) -> bool {
+        self.generated
+    }
+
+    /// Returns the blame entry of each of the given buffer rows, or `None` for the rows that are
+    /// not committed yet.
+    pub fn blame_for_rows<'a>(
+        &'a self,
+        rows: impl 'a + IntoIterator<Item = Option<u32>>,
+    ) -> impl 'a + Iterator<Item = Option<&'a BlameEntry>> {
+        rows.into_iter().map(move |row| {
+            let row = row?;
+            let ix = self.entries.partition_point(|entry| entry.range.end <= row);
+            self.entries
+                .get(ix)
+                .filter(|entry| entry.range.contains(&row))
+        })
+    }
+
+    /// Returns the full message of the commit with the given SHA.
+    pub fn message_for_commit(&self, sha: &str) -> Option<&str> {
+        self.messages.get(sha).map(String::as_str)
+    }
+
+    /// Moves the entries through the edits made to the buffer since it was last synced.
+    fn sync(&mut self, cx: &mut ModelContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot();
+        let mut changes = Vec::<RowChange>::new();
+        for edit in snapshot.edits_since::<Point>(&self.buffer_snapshot.version()) {
+            let old = edit.old.start.row..edit.old.end.row + 1;
+            let new = edit.new.start.row..edit.new.end.row + 1;
+            match changes.last_mut() {
+                Some(last) if old.start < last.old.end => {
+                    last.old.end = last.old.end.max(old.end);
+                    last.new.end = last.new.end.max(new.end);
+                }
+                _ => changes.push(RowChange { old, new }),
+            }
+        }
+        self.buffer_snapshot = snapshot;
+
+        if !changes.is_empty() {
+            self.entries = remap_blame_entries(std::mem::take(&mut self.entries), &changes);
+            cx.notify();
+        }
+    }
+
+    fn generate(&mut self, debounce: Option<Duration>, cx: &mut ModelContext<Self>) {
+        let buffer = self.buffer.clone();
+        let project = self.project.clone();
+        self.generate_task = cx.spawn(|this, mut cx| async move {
+            if let Some(debounce) = debounce {
+                cx.background_executor().timer(debounce).await;
+            }
+
+            let Some((snapshot, blame)) = project
+                .update(&mut cx, |project, cx| {
+                    let snapshot = buffer.read(cx).snapshot();
+                    (snapshot, project.blame_buffer(&buffer, cx))
+                })
+                .ok()
+            else {
+                return;
+            };
+            let blame = blame.await;
+
+            this.update(&mut cx, |this, cx| {
+                match blame {
+                    Ok(blame) => {
+                        this.entries = blame.entries;
+                        this.messages = blame.messages;
+                        this.buffer_snapshot = snapshot;
+                        this.generated = true;
+                        // Apply the edits made while the blame was generated.
+                        this.sync(cx);
+                    }
+                    Err(error) => {
+                        // Don't keep trying to blame files that are not in a repository while editing them.
+                        this.generated = false;
+                        log::debug!("failed to blame {:?}: {error:#}", buffer.entity_id());
+                    }
+                }
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor_tests::init_test;
+    use gpui::TestAppContext;
+    use project::{repository::Blame, FakeFs};
+    use serde_json::json;
+    use std::{ops::Range, path::Path};
+
+    fn blame_entry(sha: &str, range: Range<u32>) -> BlameEntry {
+        BlameEntry {
+            sha: sha.to_string(),
+            original_row: range.start,
+            range,
+            author: Some("Author".to_string()),
+            author_mail: None,
+            author_time: 0,
+            summary: None,
+        }
+    }
+
+    fn blamed_shas(blame: &GitBlame, rows: Range<u32>) -> Vec<Option<String>> {
+        blame
+            .blame_for_rows(rows.map(Some))
+            .map(|entry| entry.map(|entry| entry.sha.clone()))
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_blame_for_rows(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/my-repo",
+            json!({
+                ".git": {},
+                "file.txt": "AAA\nBBB\nCCC\nDDD\n",
+            }),
+        )
+        .await;
+        fs.set_blame_for_repo(
+            Path::new("/my-repo/.git"),
+            vec![(
+                Path::new("file.txt"),
+                Blame {
+                    entries: vec![
+                        blame_entry("1b1b1b", 0..1),
+                        blame_entry("0d0d0d", 1..2),
+                        blame_entry("3a3a3a", 3..4),
+                    ],
+                    messages: [("1b1b1b".to_string(), "First commit\n\nDetails".to_string())]
+                        .into_iter()
+                        .collect(),
+                },
+            )],
+        );
+
+        let project = Project::test(fs, ["/my-repo".as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/my-repo/file.txt", cx)
+            })
+            .await
+            .unwrap();
+        let blame = cx.new_model(|cx| GitBlame::new(buffer.clone(), project.clone(), cx));
+        cx.executor().run_until_parked();
+
+        blame.update(cx, |blame, _| {
+            assert!(blame.has_generated_entries());
+            assert_eq!(
+                blamed_shas(blame, 0..5),
+                [
+                    Some("1b1b1b".to_string()),
+                    Some("0d0d0d".to_string()),
+                    None,
+                    Some("3a3a3a".to_string()),
+                    None,
+                ]
+            );
+            assert_eq!(
+                blame.message_for_commit("1b1b1b"),
+                Some("First commit\n\nDetails")
+            );
+        });
+
+        // The edited rows are not committed yet, and the rows after them keep their entries.
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(Point::new(1, 3)..Point::new(1, 3), "\nXXX")], None, cx)
+        });
+        blame.update(cx, |blame, _| {
+            assert_eq!(
+                blamed_shas(blame, 0..6),
+                [
+                    Some("1b1b1b".to_string()),
+                    None,
+                    None,
+                    None,
+                    Some("3a3a3a".to_string()),
+                    None,
+                ]
+            );
+        });
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{scroll::Autoscroll, Editor};
+use anyhow::Result;
+use gpui::{
+    uniform_list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model,
+    ModelContext, Render, SharedString, Task, View, ViewContext, WeakView, WindowContext,
+};
+use language::Buffer;
+use project::{HierarchyItem, Project};
+use std::sync::Arc;
+use text::AnchorRangeExt;
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace,
+};
+
+/// Requests the items one level up or down the call or type hierarchy of the symbol at the given
+/// position.
+pub(crate) type HierarchyRequest = dyn Fn(
+    &mut Project,
+    &Model<Buffer>,
+    text::Anchor,
+    &mut ModelContext<Project>,
+) -> Task<Result<Vec<HierarchyItem>>>;
+
+/// A tree of the call or type hierarchy of a symbol, whose nodes load their children when they
+/// are expanded, and open their symbol when they are clicked.
+pub struct HierarchyView {
+    focus_handle: FocusHandle,
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    title: SharedString,
+    request: Arc<HierarchyRequest>,
+    nodes: Vec<HierarchyNode>,
+    roots: Vec<usize>,
+    /// The nodes shown in the tree, in order, along with their depth.
+    visible_nodes: Vec<(usize, usize)>,
+}
+
+struct HierarchyNode {
+    item: HierarchyItem,
+    /// The nodes one level further in the hierarchy, or `None` if they weren't loaded yet.
+    children: Option<Vec<usize>>,
+    expanded: bool,
+    load_children_task: Option<Task<()>>,
+}
+
+impl HierarchyView {
+    pub(crate) fn new(
+        title: String,
+        items: Vec<HierarchyItem>,
+        request: Arc<HierarchyRequest>,
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            workspace,
+            project,
+            title: title.into(),
+            request,
+            nodes: Vec::new(),
+            roots: Vec::new(),
+            visible_nodes: Vec::new(),
+        };
+        this.roots = this.push_nodes(items);
+        this.update_visible_nodes();
+        this
+    }
+
+    /// Returns the names of the symbols shown in the tree, indented by their depth.
+    pub fn visible_items(&self) -> Vec<String> {
+        self.visible_nodes
+            .iter()
+            .map(|&(ix, depth)| format!("{}{}", "  ".repeat(depth), self.nodes[ix].item.name))
+            .collect()
+    }
+
+    fn push_nodes(&mut self, items: Vec<HierarchyItem>) -> Vec<usize> {
+        items
+            .into_iter()
+            .map(|item| {
+                self.nodes.push(HierarchyNode {
+                    item,
+                    children: None,
+                    expanded: false,
+                    load_children_task: None,
+                });
+                self.nodes.len() - 1
+            })
+            .collect()
+    }
+
+    fn update_visible_nodes(&mut self) {
+        let mut visible_nodes = Vec::new();
+        let mut stack = self
+            .roots
+            .iter()
+            .rev()
+            .map(|&ix| (ix, 0))
+            .collect::<Vec<_>>();
+        while let Some((ix, depth)) = stack.pop() {
+            visible_nodes.push((ix, depth));
+            let node = &self.nodes[ix];
+            if let (true, Some(children)) = (node.expanded, &node.children) {
+                stack.extend(children.iter().rev().map(|&child| (child, depth + 1)));
+            }
+        }
+        self.visible_nodes = visible_nodes;
+    }
+
+    /// Expands or collapses the node, loading its children the first time it is expanded.
+    pub fn toggle(&mut self, visible_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(&(ix, _)) = self.visible_nodes.get(visible_ix) else {
+            return;
+        };
+        let node = &mut self.nodes[ix];
+        node.expanded = !node.expanded;
+        if node.expanded && node.children.is_none() && node.load_children_task.is_none() {
+            let location = node.item.location.clone();
+            let request = self.request.clone();
+            let children = self.project.update(cx, |project, cx| {
+                request(project, &location.buffer, location.range.start, cx)
+            });
+            self.nodes[ix].load_children_task = Some(cx.spawn(|this, mut cx| async move {
+                let children = children.await.log_err().unwrap_or_default();
+                this.update(&mut cx, |this, cx| {
+                    let children = this.push_nodes(children);
+                    let node = &mut this.nodes[ix];
+                    node.children = Some(children);
+                    node.load_children_task = None;
+                    this.update_visible_nodes();
+                    cx.notify();
+                })
+                .ok();
+            }));
+        }
+        self.update_visible_nodes();
+        cx.notify();
+    }
+
+    /// Opens the symbol of the node, or the first place where it calls its parent for incoming
+    /// calls.
+    pub fn open(&mut self, visible_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(&(ix, _)) = self.visible_nodes.get(visible_ix) else {
+            return;
+        };
+        let item = &self.nodes[ix].item;
+        let buffer = item.location.buffer.clone();
+        let range = item
+            .call_sites
+            .first()
+            .unwrap_or(&item.location.range)
+            .to_offset(buffer.read(cx));
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let editor: View<Editor> =
+                    workspace.open_project_item(workspace.active_pane().clone(), buffer, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                        s.select_ranges([range]);
+                    });
+                });
+            })
+            .ok();
+    }
+
+    fn render_node(&self, visible_ix: usize, cx: &mut ViewContext<Self>) -> ListItem {
+        let (ix, depth) = self.visible_nodes[visible_ix];
+        let node = &self.nodes[ix];
+        let toggle = match &node.children {
+            Some(children) if children.is_empty() => None,
+            _ => Some(node.expanded),
+        };
+        let file_name = node
+            .item
+            .location
+            .buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().to_string());
+        ListItem::new(visible_ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .indent_level(depth)
+            .toggle(toggle)
+            .on_toggle(cx.listener(move |this, _, cx| this.toggle(visible_ix, cx)))
+            .on_click(cx.listener(move |this, _, cx| this.open(visible_ix, cx)))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(node.item.name.clone()))
+                    .children(
+                        node.item
+                            .detail
+                            .clone()
+                            .or(file_name)
+                            .map(|detail| Label::new(detail).color(Color::Muted)),
+                    ),
+            )
+    }
+}
+
+impl EventEmitter<ItemEvent> for HierarchyView {}
+
+impl FocusableView for HierarchyView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for HierarchyView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_1()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "hierarchy",
+                    self.visible_nodes.len(),
+                    |this, range, cx| {
+                        range
+                            .map(|visible_ix| this.render_node(visible_ix, cx))
+                            .collect()
+                    },
+                )
+                .size_full(),
+            )
+    }
+}
+
+impl Item for HierarchyView {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        Label::new(self.title.clone())
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("hierarchy view")
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
@@ -3,16 +3,17 @@ use crate::{
     Anchor, Editor, EditorSnapshot, FindAllReferences, GoToDefinition, GoToTypeDefinition, InlayId,
     PointForPosition, SelectPhase,
 };
-use gpui::{px, AsyncWindowContext, Model, Modifiers, Task, ViewContext};
-use language::{Bias, ToOffset};
+use futures::{future::Shared, FutureExt};
+use gpui::{px, AsyncWindowContext, Model, Modifiers, Task, ViewContext, WeakView};
+use language::{Bias, Buffer, ToOffset};
 use linkify::{LinkFinder, LinkKind};
 use lsp::LanguageServerId;
 use project::{
-    HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip, LocationLink,
-    ResolveState,
+    DocumentLink, HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip,
+    LocationLink, Project, ResolveState,
 };
-use std::{cmp, ops::Range};
-use text::Point;
+use std::{cmp, ops::Range, sync::Arc};
+use text::{BufferId, Point};
 use theme::ActiveTheme as _;
 use util::{maybe, ResultExt, TryFutureExt};
 
@@ -25,6 +26,13 @@ pub struct HoveredLinkState {
     pub task: Option<Task<Option<()>>>,
 }
 
+/// The document links of the last buffer hovered over with a modifier, requested once per buffer version.
+pub(crate) struct DocumentLinksCache {
+    buffer_id: BufferId,
+    version: clock::Global,
+    links: Shared<Task<Arc<[DocumentLink]>>>,
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum RangeInEditor {
     Text(Range<Anchor>),
@@ -59,10 +67,44 @@ impl RangeInEditor {
 #[derive(Debug, Clone)]
 pub enum HoverLink {
     Url(String),
+    /// A file targeted by a document link, opened through the language server that reported it.
+    File(lsp::Location, LanguageServerId),
     Text(LocationLink),
     InlayHint(lsp::Location, LanguageServerId),
 }
 
+impl HoverLink {
+    /// Converts a document link into the link to follow when clicking it, opening `file` targets
+    /// in the workspace and all others in the browser.
+    fn from_document_link(link: &DocumentLink) -> Option<Self> {
+        let target = link.target()?;
+        if target.scheme() != "file" {
+            return Some(Self::Url(target.to_string()));
+        }
+
+        let mut uri = target.clone();
+        let position = uri
+            .fragment()
+            .and_then(position_from_fragment)
+            .unwrap_or_default();
+        uri.set_fragment(None);
+        Some(Self::File(
+            lsp::Location::new(uri, lsp::Range::new(position, position)),
+            link.server_id,
+        ))
+    }
+}
+
+/// Parses the `L<line>` and `L<line>,<column>` fragments that point file links at a
+/// position, where both numbers are one-based.
+fn position_from_fragment(fragment: &str) -> Option<lsp::Position> {
+    let fragment = fragment.strip_prefix('L')?;
+    let (line, column) = fragment.split_once(',').unwrap_or((fragment, "1"));
+    let line = line.parse::<u32>().ok()?.checked_sub(1)?;
+    let column = column.parse::<u32>().ok()?.checked_sub(1)?;
+    Some(lsp::Position::new(line, column))
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct InlayHighlight {
     pub inlay: InlayId,
@@ -86,6 +128,34 @@ impl TriggerPoint {
 }
 
 impl Editor {
+    fn document_links(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Shared<Task<Arc<[DocumentLink]>>>> {
+        let project = self.project.clone()?;
+        let (buffer_id, version) = {
+            let buffer = buffer.read(cx);
+            (buffer.remote_id(), buffer.version())
+        };
+        if let Some(cache) = &self.document_links_cache {
+            if cache.buffer_id == buffer_id && cache.version == version {
+                return Some(cache.links.clone());
+            }
+        }
+
+        let links = project.update(cx, |project, cx| project.document_links(buffer, cx));
+        let links = cx
+            .spawn(|_, _| async move { links.await.log_err().unwrap_or_default().into() })
+            .shared();
+        self.document_links_cache = Some(DocumentLinksCache {
+            buffer_id,
+            version,
+            links: links.clone(),
+        });
+        Some(links)
+    }
+
     pub(crate) fn update_hovered_link(
         &mut self,
         point_for_position: PointForPosition,
@@ -486,7 +556,7 @@ pub fn show_link_definition(
         || hovered_link_state
             .links
             .first()
-            .is_some_and(|d| matches!(d, HoverLink::Url(_)));
+            .is_some_and(|d| matches!(d, HoverLink::Url(_) | HoverLink::File(_, _)));
 
     if same_kind {
         if is_cached && (&hovered_link_state.last_trigger_point == &trigger_point)
@@ -510,15 +580,35 @@ pub fn show_link_definition(
         async move {
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, buffer_position, cx.clone()) {
+                    // Links reported by the language server take precedence over URLs found
+                    // in the text, which covers buffers without a server.
+                    let document_link = match &project {
+                        Some(project) => {
+                            find_document_link(
+                                this.clone(),
+                                project,
+                                &buffer,
+                                buffer_position,
+                                cx.clone(),
+                            )
+                            .await
+                        }
+                        None => None,
+                    };
+                    let link = document_link.or_else(|| {
+                        find_url(&buffer, buffer_position, cx.clone())
+                            .map(|(url_range, url)| (url_range, HoverLink::Url(url)))
+                    });
+
+                    if let Some((link_range, link)) = link {
                         this.update(&mut cx, |_, _| {
                             let range = maybe!({
                                 let start =
-                                    snapshot.anchor_in_excerpt(excerpt_id, url_range.start)?;
-                                let end = snapshot.anchor_in_excerpt(excerpt_id, url_range.end)?;
+                                    snapshot.anchor_in_excerpt(excerpt_id, link_range.start)?;
+                                let end = snapshot.anchor_in_excerpt(excerpt_id, link_range.end)?;
                                 Some(RangeInEditor::Text(start..end))
                             });
-                            (range, vec![HoverLink::Url(url)])
+                            (range, vec![link])
                         })
                         .ok()
                     } else if let Some(project) = project {
@@ -624,6 +714,38 @@ pub fn show_link_definition(
     editor.hovered_link_state = Some(hovered_link_state);
 }
 
+async fn find_document_link(
+    editor: WeakView<Editor>,
+    project: &Model<Project>,
+    buffer: &Model<language::Buffer>,
+    position: text::Anchor,
+    mut cx: AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let links = editor
+        .update(&mut cx, |editor, cx| editor.document_links(buffer, cx))
+        .ok()??
+        .await;
+    let snapshot = buffer.update(&mut cx, |buffer, _| buffer.snapshot()).ok()?;
+    let link = links
+        .iter()
+        .find(|link| {
+            link.range.start.cmp(&position, &snapshot).is_le()
+                && link.range.end.cmp(&position, &snapshot).is_ge()
+        })?
+        .clone();
+
+    // Computing targets can be expensive, so servers may leave them out until the link is
+    // resolved, which is only done for the hovered one.
+    let link = project
+        .update(&mut cx, |project, cx| {
+            project.resolve_document_link(link, buffer.clone(), cx)
+        })
+        .ok()?
+        .await
+        .log_err()?;
+    Some((link.range.clone(), HoverLink::from_document_link(&link)?))
+}
+
 pub(crate) fn find_url(
     buffer: &Model<language::Buffer>,
     position: text::Anchor,
@@ -1269,6 +1391,78 @@ mod tests {
         assert_eq!(cx.opened_url(), Some("https://zed.dev/releases".into()));
     }
 
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            use serde::Deserialize;ˇ
+        "});
+        let mut requests =
+            cx.handle_request::<lsp::request::DocumentLinkRequest, _, _>(|_, _, _| async move {
+                Ok(Some(vec![lsp::DocumentLink {
+                    range: lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 9)),
+                    target: None,
+                    tooltip: None,
+                    data: None,
+                }]))
+            });
+        cx.handle_request::<lsp::request::DocumentLinkResolve, _, _>(|link, _, _| async move {
+            Ok(lsp::DocumentLink {
+                target: Some(lsp::Url::parse("https://docs.rs/serde").unwrap()),
+                ..link
+            })
+        });
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            use seˇrde::Deserialize;
+        "});
+        cx.simulate_mouse_move(screen_coord, Modifiers::command());
+        requests.next().await;
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            use «serdeˇ»::Deserialize;
+        "});
+
+        cx.simulate_click(screen_coord, Modifiers::command());
+        assert_eq!(cx.opened_url(), Some("https://docs.rs/serde".into()));
+
+        // The links are not requested again until the buffer changes.
+        cx.simulate_mouse_move(screen_coord, Modifiers::none());
+        cx.run_until_parked();
+        cx.simulate_mouse_move(screen_coord, Modifiers::command());
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            use «serdeˇ»::Deserialize;
+        "});
+        assert!(requests.try_next().is_err());
+    }
+
+    #[test]
+    fn test_position_from_fragment() {
+        assert_eq!(
+            position_from_fragment("L12"),
+            Some(lsp::Position::new(11, 0))
+        );
+        assert_eq!(
+            position_from_fragment("L12,5"),
+            Some(lsp::Position::new(11, 4))
+        );
+        assert_eq!(position_from_fragment("L0"), None);
+        assert_eq!(position_from_fragment("section"), None);
+    }
+
     #[gpui::test]
     async fn test_cmd_click_back_and_forth(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
@@ -1,7 +1,7 @@
 use crate::{
-    editor_settings::SeedQuerySetting, persistence::DB, scroll::ScrollAnchor, Anchor, Autoscroll,
-    Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer, MultiBufferSnapshot,
-    NavigationData, ToPoint as _,
+    editor_settings::SeedQuerySetting, persistence::DB, scroll::ScrollAnchor, Anchor,
+    AnchorRangeExt, Autoscroll, Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange,
+    MultiBuffer, MultiBufferSnapshot, NavigationData, ToPoint as _,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::HashSet;
@@ -22,6 +22,7 @@ use settings::Settings;
 use workspace::item::ItemSettings;
 
 use std::{
+    any::TypeId,
     borrow::Cow,
     cmp::{self, Ordering},
     iter,
@@ -984,6 +985,8 @@ impl ProjectItem for Editor {
 impl EventEmitter<SearchEvent> for Editor {}
 
 pub(crate) enum BufferSearchHighlights {}
+/// The selections the buffer search is restricted to, kept as highlights so that they follow the edits.
+pub(crate) enum SearchWithinRange {}
 impl SearchableItem for Editor {
     type Match = Range<Anchor>;
 
@@ -991,7 +994,10 @@ impl SearchableItem for Editor {
         self.clear_background_highlights::<BufferSearchHighlights>(cx);
     }
 
-    fn update_matches(&mut self, matches: Vec<Range<Anchor>>, cx: &mut ViewContext<Self>) {
+    fn update_matches(&mut self, mut matches: Vec<Range<Anchor>>, cx: &mut ViewContext<Self>) {
+        // Fuzzy matches come ranked, but highlights are looked up by position.
+        let buffer = self.buffer().read(cx).snapshot(cx);
+        matches.sort_by(|a, b| a.start.cmp(&b.start, &buffer));
         self.highlight_background::<BufferSearchHighlights>(
             matches,
             |theme| theme.search_match_background,
@@ -1052,22 +1058,57 @@ impl SearchableItem for Editor {
         query: &SearchQuery,
         cx: &mut ViewContext<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
-        let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
-        let text: Cow<_> = if text.len() == 1 {
-            text.first().cloned().unwrap().into()
-        } else {
-            let joined_chunks = text.join("");
-            joined_chunks.into()
+        // Matches lie within a single excerpt, so that their replacement can be computed in the
+        // buffer, such as structural ones that depend on its syntax trees.
+        let buffer_ranges = self
+            .buffer
+            .read(cx)
+            .range_to_buffer_ranges(identifier.clone(), cx);
+        let replacement = match buffer_ranges.as_slice() {
+            [(buffer, range, _)] => {
+                query.replacement_for_range(&buffer.read(cx).snapshot(), range.clone())
+            }
+            _ => {
+                let text = self.buffer.read(cx).snapshot(cx);
+                let text = text.text_for_range(identifier.clone()).collect::<String>();
+                query
+                    .replacement_for(&text)
+                    .map(|replacement| replacement.into_owned())
+            }
         };
 
-        if let Some(replacement) = query.replacement_for(&text) {
+        if let Some(replacement) = replacement {
             self.transact(cx, |this, cx| {
                 this.edit([(identifier.clone(), Arc::from(&*replacement))], cx);
             });
         }
     }
+
+    fn toggle_filtered_search_ranges(&mut self, enabled: bool, cx: &mut ViewContext<Self>) {
+        if !enabled {
+            self.clear_background_highlights::<SearchWithinRange>(cx);
+            cx.notify();
+            return;
+        }
+
+        let buffer = self.buffer().read(cx).snapshot(cx);
+        let ranges = self
+            .selections
+            .disjoint_anchors()
+            .iter()
+            .map(|selection| selection.start..selection.end)
+            .filter(|range| !range.to_offset(&buffer).is_empty())
+            .collect::<Vec<_>>();
+        if ranges.is_empty() {
+            self.clear_background_highlights::<SearchWithinRange>(cx);
+        } else {
+            self.highlight_background::<SearchWithinRange>(
+                ranges,
+                |colors| colors.editor_document_highlight_read_background,
+                cx,
+            );
+        }
+    }
     fn match_index_for_direction(
         &mut self,
         matches: &Vec<Range<Anchor>>,
@@ -1123,18 +1164,38 @@ impl SearchableItem for Editor {
         cx: &mut ViewContext<Self>,
     ) -> Task<Vec<Range<Anchor>>> {
         let buffer = self.buffer().read(cx).snapshot(cx);
+        let search_within_ranges = self
+            .background_highlights
+            .get(&TypeId::of::<SearchWithinRange>())
+            .map(|(_, ranges)| {
+                ranges
+                    .iter()
+                    .map(|range| range.to_offset(&buffer))
+                    .collect::<Vec<_>>()
+            });
         cx.background_executor().spawn(async move {
             let mut ranges = Vec::new();
             if let Some((_, _, excerpt_buffer)) = buffer.as_singleton() {
-                ranges.extend(
-                    query
-                        .search(excerpt_buffer, None)
-                        .await
-                        .into_iter()
-                        .map(|range| {
-                            buffer.anchor_after(range.start)..buffer.anchor_before(range.end)
-                        }),
-                );
+                let search_ranges = match &search_within_ranges {
+                    Some(search_within_ranges) => search_within_ranges
+                        .iter()
+                        .map(|range| Some(range.clone()))
+                        .collect(),
+                    None => vec![None],
+                };
+                for search_range in search_ranges {
+                    let offset = search_range.as_ref().map_or(0, |range| range.start);
+                    ranges.extend(
+                        query
+                            .search(excerpt_buffer, search_range)
+                            .await
+                            .into_iter()
+                            .map(|range| {
+                                buffer.anchor_after(offset + range.start)
+                                    ..buffer.anchor_before(offset + range.end)
+                            }),
+                    );
+                }
             } else {
                 for excerpt in buffer.excerpt_boundaries_in_range(0..buffer.len()) {
                     let excerpt_range = excerpt.range.context.to_offset(&excerpt.buffer);
@@ -1155,6 +1216,14 @@ impl SearchableItem for Editor {
                             }),
                     );
                 }
+                if let Some(search_within_ranges) = &search_within_ranges {
+                    ranges.retain(|range| {
+                        let range = range.to_offset(&buffer);
+                        search_within_ranges.iter().any(|search_range| {
+                            search_range.start <= range.start && range.end <= search_range.end
+                        })
+                    });
+                }
             }
             ranges
         })
@@ -1165,11 +1234,21 @@ impl SearchableItem for Editor {
         matches: Vec<Range<Anchor>>,
         cx: &mut ViewContext<Self>,
     ) -> Option<usize> {
-        active_match_index(
-            &matches,
-            &self.selections.newest_anchor().head(),
-            &self.buffer().read(cx).snapshot(cx),
-        )
+        let cursor = self.selections.newest_anchor().head();
+        let buffer = self.buffer().read(cx).snapshot(cx);
+        let is_sorted = matches
+            .windows(2)
+            .all(|pair| pair[0].start.cmp(&pair[1].start, &buffer).is_le());
+        if is_sorted {
+            active_match_index(&matches, &cursor, &buffer)
+        } else {
+            // Fuzzy matches are ranked rather than sorted, so the cursor is looked up among them,
+            // falling back to the best one.
+            let ix = matches.iter().position(|range| {
+                range.start.cmp(&cursor, &buffer).is_le() && range.end.cmp(&cursor, &buffer).is_ge()
+            });
+            ix.or_else(|| (!matches.is_empty()).then_some(0))
+        }
     }
 }
 
@@ -0,0 +1,290 @@
+use crate::{Editor, EditorSettings};
+use gpui::{Model, Task, ViewContext};
+use language::{char_kind, Buffer, BufferSnapshot, CharKind};
+use settings::Settings;
+use std::{ops::Range, time::Duration};
+use text::Anchor;
+use util::ResultExt;
+
+pub const LINKED_EDITING_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
+pub(crate) struct LinkedEditingState {
+    task: Option<Task<()>>,
+    session: Option<LinkedEditingSession>,
+}
+
+/// A group of ranges whose contents are kept identical while the cursor stays in one of them.
+struct LinkedEditingSession {
+    buffer: Model<Buffer>,
+    ranges: Vec<Range<Anchor>>,
+}
+
+impl LinkedEditingSession {
+    fn range_containing(
+        &self,
+        position: Anchor,
+        snapshot: &BufferSnapshot,
+    ) -> Option<&Range<Anchor>> {
+        self.ranges.iter().find(|range| {
+            range.start.cmp(&position, snapshot).is_le()
+                && range.end.cmp(&position, snapshot).is_ge()
+        })
+    }
+}
+
+/// Ends the linked editing session once the cursor leaves its ranges, and looks up the ranges
+/// linked to the one at the new cursor position, asking the language server first and falling
+/// back to the linked editing query of the buffer's language.
+pub(crate) fn refresh_linked_editing_ranges(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let cursor = editor.selections.newest_anchor().head();
+    let cursor_buffer_position = editor.buffer.read(cx).text_anchor_for_position(cursor, cx);
+    let Some((buffer, position)) = cursor_buffer_position
+        .filter(|_| EditorSettings::get_global(cx).linked_edits && editor.selections.count() == 1)
+    else {
+        editor.linked_editing = LinkedEditingState::default();
+        return;
+    };
+
+    if let Some(session) = &editor.linked_editing.session {
+        if session.buffer == buffer
+            && session
+                .range_containing(position, &buffer.read(cx).snapshot())
+                .is_some()
+        {
+            return;
+        }
+    }
+    editor.linked_editing.session = None;
+
+    let project = editor.project.clone();
+    editor.linked_editing.task = Some(cx.spawn(|editor, mut cx| async move {
+        cx.background_executor()
+            .timer(LINKED_EDITING_DEBOUNCE_TIMEOUT)
+            .await;
+
+        let lsp_request = project.and_then(|project| {
+            project
+                .update(&mut cx, |project, cx| {
+                    project.linked_editing_ranges(&buffer, position, cx)
+                })
+                .log_err()
+        });
+        let lsp_ranges = match lsp_request {
+            Some(request) => request.await.log_err().unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                let cursor = editor.selections.newest_anchor().head();
+                let Some((cursor_buffer, position)) =
+                    editor.buffer.read(cx).text_anchor_for_position(cursor, cx)
+                else {
+                    return;
+                };
+                if cursor_buffer != buffer {
+                    return;
+                }
+
+                let snapshot = buffer.read(cx).snapshot();
+                let ranges = if lsp_ranges.is_empty() {
+                    snapshot
+                        .syntax_linked_editing_ranges(position)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|range| {
+                            snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end)
+                        })
+                        .collect()
+                } else {
+                    lsp_ranges
+                };
+                let session = LinkedEditingSession { buffer, ranges };
+                if session.ranges.len() > 1
+                    && session.range_containing(position, &snapshot).is_some()
+                {
+                    editor.linked_editing.session = Some(session);
+                }
+            })
+            .log_err();
+    }));
+}
+
+/// Mirrors the contents of the linked range containing the cursor into the other ranges of the
+/// session. The session ends instead when the edit turned the range into something that is no
+/// longer a name, such as when typing a space after a tag name.
+///
+/// This is called at the end of every transaction of the editor, so that the edits of typing,
+/// pasting, deleting and completions are all mirrored, undoing an edit also undoes the mirrored
+/// edits, and the edits of collaborators are not mirrored again.
+pub(crate) fn sync_linked_edits(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let Some(session) = &editor.linked_editing.session else {
+        return;
+    };
+    let cursor = editor.selections.newest_anchor().head();
+    let Some((buffer, position)) = editor.buffer.read(cx).text_anchor_for_position(cursor, cx)
+    else {
+        return;
+    };
+    if buffer != session.buffer {
+        return;
+    }
+
+    let snapshot = buffer.read(cx).snapshot();
+    let Some(edited_range) = session.range_containing(position, &snapshot) else {
+        editor.linked_editing.session = None;
+        return;
+    };
+    let text = snapshot
+        .text_for_range(edited_range.clone())
+        .collect::<String>();
+    let scope = snapshot.language_scope_at(position);
+    if !text
+        .chars()
+        .all(|c| char_kind(&scope, c) == CharKind::Word || matches!(c, '-' | '.' | ':'))
+    {
+        editor.linked_editing.session = None;
+        return;
+    }
+
+    let edits = session
+        .ranges
+        .iter()
+        .filter(|range| {
+            *range != edited_range
+                && snapshot
+                    .text_for_range((*range).clone())
+                    .collect::<String>()
+                    != text
+        })
+        .map(|range| (range.clone(), text.clone()))
+        .collect::<Vec<_>>();
+    if !edits.is_empty() {
+        buffer.update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use indoc::indoc;
+
+    #[gpui::test]
+    async fn test_linked_editing_ranges(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                linked_editing_range_provider: Some(
+                    lsp::LinkedEditingRangeServerCapabilities::Simple(true),
+                ),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            let ˇab = 1;
+            let c = ab;
+        "});
+        cx.handle_request::<lsp::request::LinkedEditingRange, _, _>(|_, _, _| async move {
+            Ok(Some(lsp::LinkedEditingRanges {
+                ranges: vec![
+                    lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 6)),
+                    lsp::Range::new(lsp::Position::new(1, 8), lsp::Position::new(1, 10)),
+                ],
+                word_pattern: None,
+            }))
+        });
+        cx.executor().advance_clock(LINKED_EDITING_DEBOUNCE_TIMEOUT);
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(["x", "y"]);
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            let xyˇab = 1;
+            let c = xyab;
+        "});
+
+        // The mirrored edits are undone along with the typed ones.
+        cx.update_editor(|editor, cx| editor.undo(&Default::default(), cx));
+        cx.assert_editor_state(indoc! {"
+            let ˇab = 1;
+            let c = ab;
+        "});
+        cx.update_editor(|editor, cx| editor.redo(&Default::default(), cx));
+        cx.assert_editor_state(indoc! {"
+            let xyˇab = 1;
+            let c = xyab;
+        "});
+
+        // Typing a character that can't be part of the name ends the session.
+        cx.simulate_keystroke(" ");
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            let xy ˇab = 1;
+            let c = xyab;
+        "});
+        cx.update_editor(|editor, _| assert!(editor.linked_editing.session.is_none()));
+    }
+
+    #[gpui::test]
+    async fn test_linked_editing_ranges_on_paste_and_delete(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                linked_editing_range_provider: Some(
+                    lsp::LinkedEditingRangeServerCapabilities::Simple(true),
+                ),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            let abˇ = 1;
+            let c = ab;
+        "});
+        cx.handle_request::<lsp::request::LinkedEditingRange, _, _>(|_, _, _| async move {
+            Ok(Some(lsp::LinkedEditingRanges {
+                ranges: vec![
+                    lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 6)),
+                    lsp::Range::new(lsp::Position::new(1, 8), lsp::Position::new(1, 10)),
+                ],
+                word_pattern: None,
+            }))
+        });
+        cx.executor().advance_clock(LINKED_EDITING_DEBOUNCE_TIMEOUT);
+        cx.run_until_parked();
+
+        cx.write_to_clipboard(gpui::ClipboardItem::new("xy".to_string()));
+        cx.update_editor(|editor, cx| editor.paste(&Default::default(), cx));
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            let abxyˇ = 1;
+            let c = abxy;
+        "});
+
+        cx.update_editor(|editor, cx| editor.backspace(&Default::default(), cx));
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            let abxˇ = 1;
+            let c = abx;
+        "});
+
+        cx.update_editor(|editor, cx| {
+            editor.move_left(&Default::default(), cx);
+            editor.delete(&Default::default(), cx);
+        });
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            let abˇ = 1;
+            let c = ab;
+        "});
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
-    DisplayPoint, Editor, EditorMode, FindAllReferences, GoToDefinition, GoToImplementation,
-    GoToTypeDefinition, Rename, RevealInFinder, SelectMode, ToggleCodeActions,
+    DisplayPoint, Editor, EditorMode, FindAllReferences, FindIncomingCalls, FindOutgoingCalls,
+    GoToDefinition, GoToImplementation, GoToTypeDefinition, Rename, RevealInFinder, SelectMode,
+    ToggleCodeActions,
 };
 use gpui::{DismissEvent, Pixels, Point, Subscription, View, ViewContext};
 
@@ -50,6 +51,8 @@ pub fn deploy_context_menu(
                 .action("Go to Type Definition", Box::new(GoToTypeDefinition))
                 .action("Go to Implementation", Box::new(GoToImplementation))
                 .action("Find All References", Box::new(FindAllReferences))
+                .action("Find Incoming Calls", Box::new(FindIncomingCalls))
+                .action("Find Outgoing Calls", Box::new(FindOutgoingCalls))
                 .action(
                     "Code Actions",
                     Box::new(ToggleCodeActions {
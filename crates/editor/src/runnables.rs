@@ -0,0 +1,218 @@
+use crate::{Anchor, Editor, EditorMode, EditorSettings, EditorStyle};
+use gpui::{Model, Task};
+use language::{Buffer, Location, Point, ToPoint};
+use project::{runnables_source::RunnablesSource, WorktreeId};
+use settings::Settings;
+use std::{collections::HashMap, ops::Range, path::PathBuf, sync::Arc};
+use task::{Task as _, TaskContext};
+use ui::{prelude::*, IconButton};
+
+#[derive(Default)]
+pub(crate) struct RunnablesState {
+    task: Option<Task<()>>,
+    pub runnables: Vec<Runnable>,
+}
+
+/// A runnable detected in the buffer of the editor, along with the task running it.
+#[derive(Clone)]
+pub(crate) struct Runnable {
+    pub position: Anchor,
+    task: Arc<dyn task::Task>,
+}
+
+/// Detects the runnables of the singleton buffer of the editor, keeping the ones its language knows
+/// how to run, so that the gutter shows buttons running them without querying the syntax tree on
+/// every layout.
+pub(crate) fn refresh_runnables(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.mode != EditorMode::Full || !EditorSettings::get_global(cx).gutter.runnables {
+        editor.runnables = RunnablesState::default();
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let Some(buffer) = editor.buffer.read(cx).as_singleton() else {
+        return;
+    };
+    let (snapshot, provider, abs_path) = {
+        let buffer = buffer.read(cx);
+        (
+            buffer.snapshot(),
+            buffer
+                .language()
+                .and_then(|language| language.context_provider()),
+            buffer
+                .file()
+                .and_then(|file| file.as_local())
+                .map(|file| file.abs_path(cx)),
+        )
+    };
+    let (Some(provider), Some(abs_path)) = (provider, abs_path) else {
+        editor.runnables = RunnablesState::default();
+        return;
+    };
+    let fs = project.read(cx).fs().clone();
+    let inventory = project.read(cx).task_inventory().clone();
+
+    editor.runnables.task = Some(cx.spawn(|editor, mut cx| async move {
+        let runnables = cx
+            .background_executor()
+            .spawn({
+                let snapshot = snapshot.clone();
+                async move { snapshot.runnable_ranges(0..snapshot.len()) }
+            })
+            .await;
+        let mut commands = Vec::new();
+        for runnable in runnables {
+            if let Some(command) = provider
+                .runnable_command(&runnable, &snapshot, &abs_path, fs.as_ref())
+                .await
+            {
+                commands.push((snapshot.anchor_before(runnable.range.start), command));
+            }
+        }
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                let tasks = inventory
+                    .read(cx)
+                    .source::<RunnablesSource>()
+                    .and_then(|source| {
+                        source.update(cx, |source, _| {
+                            let source = source.as_any().downcast_mut::<RunnablesSource>()?;
+                            Some(source.set_buffer_commands(
+                                abs_path,
+                                commands.iter().map(|(_, command)| command.clone()),
+                            ))
+                        })
+                    })
+                    .unwrap_or_default();
+                let multi_buffer = editor.buffer.read(cx).snapshot(cx);
+                let Some((excerpt_id, _, _)) = multi_buffer.as_singleton() else {
+                    return;
+                };
+                editor.runnables.runnables = commands
+                    .into_iter()
+                    .zip(tasks)
+                    .filter_map(|((position, _), task)| {
+                        Some(Runnable {
+                            position: multi_buffer.anchor_in_excerpt(*excerpt_id, position)?,
+                            task,
+                        })
+                    })
+                    .collect();
+                cx.notify();
+            })
+            .ok();
+    }));
+}
+
+impl Editor {
+    pub(crate) fn render_run_indicator(
+        &self,
+        _style: &EditorStyle,
+        row: u32,
+        runnable: Runnable,
+        cx: &mut ViewContext<Self>,
+    ) -> IconButton {
+        IconButton::new(("run_indicator", row as usize), ui::IconName::Play)
+            .icon_size(IconSize::XSmall)
+            .size(ui::ButtonSize::None)
+            .icon_color(Color::Muted)
+            .on_click(cx.listener(move |editor, _e, cx| {
+                editor.run_runnable(&runnable, cx);
+            }))
+    }
+
+    /// Spawns the task running a runnable detected in the singleton buffer of the editor.
+    fn run_runnable(&mut self, runnable: &Runnable, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        let position = runnable.position.text_anchor;
+        let cwd = buffer.read(cx).file().and_then(|file| {
+            let project = workspace.read(cx).project().read(cx);
+            let worktree =
+                project.worktree_for_id(WorktreeId::from_usize(file.worktree_id()), cx)?;
+            Some(worktree.read(cx).abs_path().to_path_buf())
+        });
+        let task_context = self.task_context(&buffer, position..position, cwd, cx);
+        let Some(spawn_in_terminal) = runnable.task.exec(task_context.clone()) else {
+            return;
+        };
+
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .project()
+                .read(cx)
+                .task_inventory()
+                .update(cx, |inventory, _| {
+                    inventory.task_scheduled(runnable.task.id().clone(), task_context);
+                });
+            cx.emit(workspace::Event::SpawnTask(spawn_in_terminal));
+        });
+    }
+
+    /// Builds the context of the tasks run for the given range of a buffer, which describes it with
+    /// `ZED_*` environment variables.
+    pub fn task_context(
+        &self,
+        buffer: &Model<Buffer>,
+        range: Range<text::Anchor>,
+        cwd: Option<PathBuf>,
+        cx: &mut ViewContext<Self>,
+    ) -> TaskContext {
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let Point { row, column } = range.start.to_point(&buffer_snapshot);
+        let selected_text = buffer_snapshot
+            .text_for_range(range.clone())
+            .collect::<String>();
+        let mut env = HashMap::from_iter([
+            ("ZED_ROW".into(), (row + 1).to_string()),
+            ("ZED_COLUMN".into(), (column + 1).to_string()),
+            ("ZED_SELECTED_TEXT".into(), selected_text),
+        ]);
+
+        if let Some(file) = buffer_snapshot.file() {
+            if let Some(file) = file.as_local() {
+                env.insert(
+                    "ZED_FILE".into(),
+                    file.abs_path(cx).to_string_lossy().to_string(),
+                );
+            }
+            let worktree_path = self.project.as_ref().and_then(|project| {
+                let worktree = project
+                    .read(cx)
+                    .worktree_for_id(WorktreeId::from_usize(file.worktree_id()), cx)?;
+                Some(worktree.read(cx).abs_path().to_string_lossy().to_string())
+            });
+            if let Some(worktree_path) = worktree_path {
+                env.insert("ZED_WORKTREE_ROOT".into(), worktree_path);
+            }
+        }
+
+        let language_context = buffer
+            .read(cx)
+            .language()
+            .and_then(|language| language.context_provider())
+            .and_then(|provider| {
+                provider
+                    .build_context(
+                        Location {
+                            buffer: buffer.clone(),
+                            range,
+                        },
+                        cx,
+                    )
+                    .ok()
+            });
+        if let Some(symbol) = language_context.and_then(|context| context.symbol) {
+            env.insert("ZED_SYMBOL".into(), symbol);
+        }
+
+        TaskContext { cwd, env }
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{Anchor, Editor, EditorMode};
+use futures::future;
+use gpui::{px, FontStyle, HighlightStyle, StrikethroughStyle, UnderlineStyle, ViewContext};
+use language::{language_settings::language_settings, HighlightMap};
+use project::semantic_tokens::BufferSemanticTokens;
+use std::time::Duration;
+use theme::{ActiveTheme, SyntaxTheme};
+use util::ResultExt;
+
+pub const SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// Requests semantic tokens for every buffer in the editor whose language has them enabled,
+/// and replaces the editor's semantic highlights with the result.
+pub(crate) fn refresh_semantic_tokens(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let buffers = editor
+        .buffer
+        .read(cx)
+        .all_buffers()
+        .into_iter()
+        .filter(|buffer| {
+            let buffer = buffer.read(cx);
+            language_settings(buffer.language(), buffer.file(), cx).semantic_tokens
+        })
+        .collect::<Vec<_>>();
+    if buffers.is_empty() {
+        editor.semantic_tokens_task = None;
+        editor
+            .display_map
+            .update(cx, |map, _| map.set_semantic_highlights(Vec::new()));
+        return;
+    }
+
+    editor.semantic_tokens_task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor()
+                .timer(SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT)
+                .await;
+        }
+
+        let Some(requests) = project
+            .update(&mut cx, |project, cx| {
+                buffers
+                    .into_iter()
+                    .map(|buffer| {
+                        let request = project.semantic_tokens(buffer.clone(), cx);
+                        async move { (buffer, request.await) }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .log_err()
+        else {
+            return;
+        };
+        let responses = future::join_all(requests).await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                let syntax_theme = cx.theme().syntax().clone();
+                let multi_buffer = editor.buffer.read(cx);
+                let snapshot = multi_buffer.snapshot(cx);
+                let mut highlights = Vec::new();
+                for (buffer, tokens) in responses {
+                    let Some(tokens) = tokens.log_err() else {
+                        continue;
+                    };
+                    let styles = token_styles(&tokens, &syntax_theme);
+                    let buffer_id = buffer.read(cx).remote_id();
+                    let buffer_snapshot = buffer.read(cx).snapshot();
+                    for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(&buffer, cx)
+                    {
+                        let context = excerpt_range.context;
+                        for (token, style) in tokens.tokens.iter().zip(&styles) {
+                            let Some(style) = style else {
+                                continue;
+                            };
+                            let start = token.range.start.max(&context.start, &buffer_snapshot);
+                            let end = token.range.end.min(&context.end, &buffer_snapshot);
+                            if start.cmp(&end, &buffer_snapshot).is_ge() {
+                                continue;
+                            }
+
+                            let range = Anchor {
+                                buffer_id: Some(buffer_id),
+                                excerpt_id,
+                                text_anchor: start,
+                            }..Anchor {
+                                buffer_id: Some(buffer_id),
+                                excerpt_id,
+                                text_anchor: end,
+                            };
+                            highlights.push((range, *style));
+                        }
+                    }
+                }
+                highlights.sort_by(|(a, _), (b, _)| a.start.cmp(&b.start, &snapshot));
+
+                editor
+                    .display_map
+                    .update(cx, |map, _| map.set_semantic_highlights(highlights));
+                cx.notify();
+            })
+            .log_err();
+    }));
+}
+
+/// Resolves the style of each token, `None` meaning that the token leaves the syntax
+/// highlighting untouched.
+fn token_styles(
+    tokens: &BufferSemanticTokens,
+    syntax_theme: &SyntaxTheme,
+) -> Vec<Option<HighlightStyle>> {
+    let capture_names = tokens
+        .token_types
+        .iter()
+        .map(|token_type| highlight_name_for_token_type(token_type))
+        .collect::<Vec<_>>();
+    let highlight_map = HighlightMap::new(&capture_names, syntax_theme);
+
+    tokens
+        .tokens
+        .iter()
+        .map(|token| {
+            let mut style = highlight_map.get(token.token_type).style(syntax_theme);
+            for modifier in tokens.token_modifiers(token) {
+                if let Some(modifier_style) = highlight_style_for_token_modifier(modifier) {
+                    style
+                        .get_or_insert_with(HighlightStyle::default)
+                        .highlight(modifier_style);
+                }
+            }
+            style
+        })
+        .collect()
+}
+
+/// Maps a semantic token type onto the names used by the tree-sitter highlight queries,
+/// so that themes style both sources of highlighting consistently.
+fn highlight_name_for_token_type(token_type: &str) -> &str {
+    match token_type {
+        "class" | "interface" | "struct" | "typeAlias" | "typeParameter" | "builtinType" => "type",
+        "enumMember" => "variant",
+        "parameter" => "variable.parameter",
+        "selfKeyword" | "selfTypeKeyword" => "variable.special",
+        "method" => "function.method",
+        "macro" => "function.special",
+        "decorator" => "attribute",
+        "regexp" => "string.regex",
+        token_type => token_type,
+    }
+}
+
+fn highlight_style_for_token_modifier(modifier: &str) -> Option<HighlightStyle> {
+    match modifier {
+        "mutable" => Some(HighlightStyle {
+            underline: Some(UnderlineStyle {
+                thickness: px(1.),
+                color: None,
+                wavy: false,
+            }),
+            ..Default::default()
+        }),
+        "deprecated" => Some(HighlightStyle {
+            strikethrough: Some(StrikethroughStyle {
+                thickness: px(1.),
+                color: None,
+            }),
+            ..Default::default()
+        }),
+        "unsafe" => Some(HighlightStyle {
+            font_style: Some(FontStyle::Italic),
+            ..Default::default()
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use indoc::indoc;
+    use language::language_settings::AllLanguageSettings;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_semantic_token_modifiers(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |settings| {
+            settings.defaults.semantic_tokens = Some(true)
+        });
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![lsp::SemanticTokenType::VARIABLE],
+                                token_modifiers: vec![lsp::SemanticTokenModifier::new("mutable")],
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.handle_request::<lsp::request::SemanticTokensFullRequest, _, _>(|_, _, _| async move {
+            Ok(Some(lsp::SemanticTokensResult::Tokens(
+                lsp::SemanticTokens {
+                    result_id: None,
+                    data: vec![
+                        lsp::SemanticToken {
+                            delta_line: 1,
+                            delta_start: 12,
+                            length: 1,
+                            token_type: 0,
+                            token_modifiers_bitset: 1,
+                        },
+                        lsp::SemanticToken {
+                            delta_line: 1,
+                            delta_start: 4,
+                            length: 1,
+                            token_type: 0,
+                            token_modifiers_bitset: 0,
+                        },
+                    ],
+                },
+            )))
+        });
+        cx.set_state(indoc! {"
+            fn main() {
+                let mut x = 1;
+                y = x;ˇ
+            }
+        "});
+        cx.executor()
+            .advance_clock(SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT);
+        cx.run_until_parked();
+
+        cx.update_editor(|editor, cx| {
+            let snapshot = editor.snapshot(cx).display_snapshot;
+            let underlined = snapshot
+                .chunks(0..snapshot.max_point().row() + 1, true, Default::default())
+                .filter(|chunk| {
+                    chunk
+                        .highlight_style
+                        .map_or(false, |style| style.underline.is_some())
+                })
+                .map(|chunk| chunk.text)
+                .collect::<String>();
+            assert_eq!(underlined, "x");
+        });
+
+        cx.update(|cx| {
+            cx.update_global(|store: &mut SettingsStore, cx| {
+                store.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                    settings.defaults.semantic_tokens = Some(false)
+                });
+            });
+        });
+        cx.update_editor(|editor, cx| {
+            let snapshot = editor.snapshot(cx).display_snapshot;
+            assert!(snapshot
+                .chunks(0..snapshot.max_point().row() + 1, true, Default::default())
+                .all(|chunk| chunk.highlight_style.is_none()));
+        });
+    }
+}
@@ -0,0 +1,564 @@
+use crate::{
+    hover_popover::parse_blocks, Anchor, Editor, EditorSettings, EditorStyle, ShowSignatureHelp,
+    SignatureHelpNext, SignatureHelpPrevious,
+};
+use gpui::{
+    div, AnyElement, FontWeight, HighlightStyle, InteractiveElement, IntoElement, MouseButton,
+    ParentElement, Pixels, Size, StatefulInteractiveElement, Styled, StyledText, Task, ViewContext,
+    WeakView,
+};
+use language::ParsedMarkdown;
+use project::{HoverBlock, MarkupContent, SignatureHelp};
+use settings::Settings;
+use ui::prelude::*;
+use util::TryFutureExt;
+use workspace::Workspace;
+
+/// Bindable action which requests signature help at the most recent selection head.
+pub fn show_signature_help(
+    editor: &mut Editor,
+    _: &ShowSignatureHelp,
+    cx: &mut ViewContext<Editor>,
+) {
+    request_signature_help(editor, None, false, cx);
+}
+
+/// Switches to the next overload, propagating the action when there is nothing to cycle
+/// so that the binding keeps its usual meaning.
+pub fn signature_help_next(
+    editor: &mut Editor,
+    _: &SignatureHelpNext,
+    cx: &mut ViewContext<Editor>,
+) {
+    if !cycle_signatures(editor, true, cx) {
+        cx.propagate();
+    }
+}
+
+/// Switches to the previous overload, propagating the action when there is nothing to cycle.
+pub fn signature_help_previous(
+    editor: &mut Editor,
+    _: &SignatureHelpPrevious,
+    cx: &mut ViewContext<Editor>,
+) {
+    if !cycle_signatures(editor, false, cx) {
+        cx.propagate();
+    }
+}
+
+/// Hides the signature help popover, returning whether it was visible.
+pub fn hide_signature_help(editor: &mut Editor, cx: &mut ViewContext<Editor>) -> bool {
+    editor.signature_help_state.task = None;
+    editor.signature_help_state.requested_at = None;
+    let did_hide = editor.signature_help_state.popover.take().is_some();
+    if did_hide {
+        cx.notify();
+    }
+    did_hide
+}
+
+/// Opens the signature help popover when one of the server's trigger characters was typed, or
+/// re-triggers a visible popover on its retrigger characters. Other edits update a visible popover
+/// through [`refresh_signature_help`], as they move the cursor.
+pub(crate) fn trigger_signature_help_on_input(
+    editor: &mut Editor,
+    text: &str,
+    cx: &mut ViewContext<Editor>,
+) {
+    if !EditorSettings::get_global(cx).show_signature_help_on_input {
+        return;
+    }
+
+    let is_visible = editor.signature_help_state.is_visible();
+    let is_trigger = editor.project.as_ref().map_or(false, |project| {
+        let position = editor.selections.newest_anchor().head();
+        let Some((buffer, _)) = editor
+            .buffer
+            .read(cx)
+            .text_anchor_for_position(position, cx)
+        else {
+            return false;
+        };
+        project
+            .read(cx)
+            .signature_help_trigger_characters(buffer.read(cx), is_visible, cx)
+            .iter()
+            .any(|trigger| trigger == text)
+    });
+
+    if is_trigger {
+        request_signature_help(editor, Some(text.to_string()), is_visible, cx);
+    }
+}
+
+/// Re-queries the visible popover after the cursor moved, so that the highlighted parameter
+/// follows the cursor and the popover disappears once it leaves the call. Nothing is requested
+/// while the popover is hidden, or if the cursor is still where it was last requested for.
+///
+/// The request is deferred, so that typing a trigger character replaces it with a request
+/// carrying that character instead of querying the server twice.
+pub(crate) fn refresh_signature_help(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if !editor.signature_help_state.is_visible() {
+        return;
+    }
+    let head = editor.selections.newest_anchor().head();
+    if editor.signature_help_state.requested_at == Some(head) {
+        return;
+    }
+    editor.signature_help_state.requested_at = Some(head);
+    editor.signature_help_state.task = Some(cx.spawn(|editor, mut cx| async move {
+        editor
+            .update(&mut cx, |editor, cx| {
+                request_signature_help(editor, None, true, cx)
+            })
+            .ok()
+    }));
+}
+
+fn cycle_signatures(editor: &mut Editor, forward: bool, cx: &mut ViewContext<Editor>) -> bool {
+    let Some(popover) = editor.signature_help_state.popover.as_mut() else {
+        return false;
+    };
+    let signature_count = popover.help.signatures.len();
+    if signature_count < 2 {
+        return false;
+    }
+
+    popover.current_signature = if forward {
+        (popover.current_signature + 1) % signature_count
+    } else {
+        (popover.current_signature + signature_count - 1) % signature_count
+    };
+    cx.notify();
+    true
+}
+
+fn request_signature_help(
+    editor: &mut Editor,
+    trigger_character: Option<String>,
+    is_retrigger: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.pending_rename.is_some() {
+        return;
+    }
+
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let position = editor.selections.newest_anchor().head();
+    let Some((buffer, buffer_position)) = editor
+        .buffer
+        .read(cx)
+        .text_anchor_for_position(position, cx)
+    else {
+        return;
+    };
+
+    let signature_help = project.update(cx, |project, cx| {
+        project.signature_help(
+            &buffer,
+            buffer_position,
+            trigger_character,
+            is_retrigger,
+            cx,
+        )
+    });
+    let task = cx.spawn(|editor, mut cx| {
+        async move {
+            let help = signature_help.await?.filter(|help| !help.is_empty());
+            let Some(help) = help else {
+                editor.update(&mut cx, |editor, cx| {
+                    if editor.signature_help_state.popover.take().is_some() {
+                        cx.notify();
+                    }
+                })?;
+                return Ok(());
+            };
+
+            let language_registry = project.update(&mut cx, |p, _| p.languages().clone())?;
+            let language = buffer.update(&mut cx, |buffer, _| buffer.language().cloned())?;
+            let mut documentation = Vec::with_capacity(help.signatures.len());
+            for signature in &help.signatures {
+                let blocks = signature
+                    .documentation
+                    .iter()
+                    .map(hover_block_for_markup)
+                    .collect::<Vec<_>>();
+                documentation.push(if blocks.is_empty() {
+                    None
+                } else {
+                    Some(parse_blocks(&blocks, &language_registry, language.clone()).await)
+                });
+            }
+
+            editor.update(&mut cx, |editor, cx| {
+                // Keep the overload the user picked as long as the server still offers the same ones.
+                let current_signature = editor
+                    .signature_help_state
+                    .popover
+                    .as_ref()
+                    .filter(|popover| {
+                        popover
+                            .help
+                            .signatures
+                            .iter()
+                            .map(|signature| &signature.label)
+                            .eq(help.signatures.iter().map(|signature| &signature.label))
+                    })
+                    .map_or(help.active_signature, |popover| popover.current_signature);
+
+                editor.signature_help_state.popover = Some(SignatureHelpPopover {
+                    help,
+                    current_signature,
+                    documentation,
+                });
+                cx.notify();
+            })?;
+
+            anyhow::Ok(())
+        }
+        .log_err()
+    });
+
+    editor.signature_help_state.task = Some(task);
+    editor.signature_help_state.requested_at = Some(position);
+}
+
+fn hover_block_for_markup(markup: &MarkupContent) -> HoverBlock {
+    HoverBlock {
+        text: markup.value.clone(),
+        kind: markup.kind.clone(),
+    }
+}
+
+#[derive(Default)]
+pub struct SignatureHelpState {
+    popover: Option<SignatureHelpPopover>,
+    task: Option<Task<Option<()>>>,
+    /// The cursor position that signature help was last requested for.
+    requested_at: Option<Anchor>,
+}
+
+impl SignatureHelpState {
+    pub fn is_visible(&self) -> bool {
+        self.popover.is_some()
+    }
+
+    pub fn popover(&self) -> Option<&SignatureHelpPopover> {
+        self.popover.as_ref()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SignatureHelpPopover {
+    pub help: SignatureHelp,
+    pub current_signature: usize,
+    documentation: Vec<Option<ParsedMarkdown>>,
+}
+
+impl SignatureHelpPopover {
+    pub fn render(
+        &self,
+        style: &EditorStyle,
+        max_size: Size<Pixels>,
+        workspace: Option<WeakView<Workspace>>,
+        cx: &mut ViewContext<Editor>,
+    ) -> Option<AnyElement> {
+        let signature = self.help.signatures.get(self.current_signature)?;
+
+        let mut highlights = Vec::new();
+        if let Some(parameter) = self.help.active_parameter_for(self.current_signature) {
+            let range = parameter.label_range.clone();
+            if signature.label.is_char_boundary(range.start)
+                && signature.label.is_char_boundary(range.end)
+            {
+                highlights.push((
+                    range,
+                    HighlightStyle {
+                        color: Some(cx.theme().colors().text_accent),
+                        font_weight: Some(FontWeight::BOLD),
+                        ..Default::default()
+                    },
+                ));
+            }
+        }
+
+        let signature_count = self.help.signatures.len();
+        let documentation = self
+            .documentation
+            .get(self.current_signature)
+            .and_then(Option::as_ref)
+            .filter(|documentation| !documentation.text.is_empty());
+
+        Some(
+            div()
+                .id("signature_help_popover")
+                .elevation_2(cx)
+                .p_2()
+                .overflow_y_scroll()
+                .max_w(max_size.width)
+                .max_h(max_size.height)
+                // Prevent a mouse down/move on the popover from being propagated to the editor,
+                // because that would dismiss the popover.
+                .on_mouse_move(|_, cx| cx.stop_propagation())
+                .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .when(signature_count > 1, |this| {
+                            this.child(
+                                Label::new(format!(
+                                    "{}/{}",
+                                    self.current_signature + 1,
+                                    signature_count
+                                ))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                            )
+                        })
+                        .child(
+                            StyledText::new(signature.label.clone())
+                                .with_highlights(&style.text, highlights),
+                        ),
+                )
+                .when_some(documentation, |this, documentation| {
+                    this.child(div().pt_1().child(crate::render_parsed_markdown(
+                        "signature_help_documentation",
+                        documentation,
+                        style,
+                        workspace,
+                        cx,
+                    )))
+                })
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext, MoveRight,
+    };
+    use futures::StreamExt;
+    use indoc::indoc;
+    use project::ParameterInformation;
+
+    fn rust_signature_help() -> lsp::SignatureHelp {
+        lsp::SignatureHelp {
+            signatures: vec![
+                lsp::SignatureInformation {
+                    label: "fn sum(a: u32, b: u32) -> u32".to_string(),
+                    documentation: Some(lsp::Documentation::String("Adds two numbers".to_string())),
+                    parameters: Some(vec![
+                        lsp::ParameterInformation {
+                            label: lsp::ParameterLabel::LabelOffsets([7, 13]),
+                            documentation: None,
+                        },
+                        lsp::ParameterInformation {
+                            label: lsp::ParameterLabel::Simple("b: u32".to_string()),
+                            documentation: None,
+                        },
+                    ]),
+                    active_parameter: None,
+                },
+                lsp::SignatureInformation {
+                    label: "fn sum(a: u64, b: u64) -> u64".to_string(),
+                    documentation: None,
+                    parameters: None,
+                    active_parameter: None,
+                },
+            ],
+            active_signature: Some(0),
+            active_parameter: Some(1),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_signature_help_on_trigger_character(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                signature_help_provider: Some(lsp::SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string()]),
+                    retrigger_characters: Some(vec![",".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn main() { sumˇ }
+        "});
+
+        let mut requests = cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(
+            |_, params, _| async move {
+                let context = params.context.unwrap();
+                assert_eq!(
+                    context.trigger_kind,
+                    lsp::SignatureHelpTriggerKind::TRIGGER_CHARACTER
+                );
+                assert_eq!(context.trigger_character.as_deref(), Some("("));
+                Ok(Some(rust_signature_help()))
+            },
+        );
+        cx.simulate_keystroke("(");
+        requests.next().await;
+        cx.run_until_parked();
+
+        cx.editor(|editor, _| {
+            let popover = editor.signature_help_state.popover().unwrap();
+            assert_eq!(popover.current_signature, 0);
+            assert_eq!(
+                popover.help.signatures[0].parameters,
+                vec![
+                    ParameterInformation {
+                        label_range: 7..13,
+                        documentation: None,
+                    },
+                    ParameterInformation {
+                        label_range: 15..21,
+                        documentation: None,
+                    },
+                ]
+            );
+            assert_eq!(
+                popover.help.active_parameter_for(0).unwrap().label_range,
+                15..21
+            );
+        });
+
+        cx.update_editor(|editor, cx| signature_help_next(editor, &SignatureHelpNext, cx));
+        cx.editor(|editor, _| {
+            assert_eq!(
+                editor
+                    .signature_help_state
+                    .popover()
+                    .unwrap()
+                    .current_signature,
+                1
+            );
+        });
+        cx.update_editor(|editor, cx| signature_help_previous(editor, &SignatureHelpPrevious, cx));
+        cx.editor(|editor, _| {
+            assert_eq!(
+                editor
+                    .signature_help_state
+                    .popover()
+                    .unwrap()
+                    .current_signature,
+                0
+            );
+        });
+
+        cx.update_editor(|editor, cx| assert!(hide_signature_help(editor, cx)));
+        cx.editor(|editor, _| assert!(!editor.signature_help_state.is_visible()));
+    }
+
+    #[gpui::test]
+    async fn test_signature_help_retrigger_characters(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                signature_help_provider: Some(lsp::SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string()]),
+                    retrigger_characters: Some(vec![",".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn main() { sum(1ˇ) }
+        "});
+
+        let mut requests = cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(
+            |_, params, _| async move {
+                let context = params.context.unwrap();
+                assert!(context.is_retrigger);
+                assert_eq!(context.trigger_character.as_deref(), Some(","));
+                Ok(Some(rust_signature_help()))
+            },
+        );
+
+        // Retrigger characters don't open the popover.
+        cx.simulate_keystroke(",");
+        cx.run_until_parked();
+        assert!(requests.try_next().is_err());
+        cx.editor(|editor, _| assert!(!editor.signature_help_state.is_visible()));
+
+        cx.update_editor(|editor, cx| {
+            editor.signature_help_state.popover = Some(SignatureHelpPopover {
+                help: project::SignatureHelp::default(),
+                current_signature: 0,
+                documentation: Vec::new(),
+            });
+        });
+        cx.simulate_keystroke(",");
+        requests.next().await;
+        cx.run_until_parked();
+        cx.editor(|editor, _| {
+            assert_eq!(
+                editor
+                    .signature_help_state
+                    .popover()
+                    .unwrap()
+                    .help
+                    .signatures
+                    .len(),
+                2
+            )
+        });
+    }
+
+    #[gpui::test]
+    async fn test_signature_help_hides_when_server_returns_nothing(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                signature_help_provider: Some(lsp::SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn main() { sum(ˇ) }
+        "});
+
+        let mut requests =
+            cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(|_, _, _| async move {
+                Ok(Some(rust_signature_help()))
+            });
+        cx.update_editor(|editor, cx| show_signature_help(editor, &ShowSignatureHelp, cx));
+        requests.next().await;
+        cx.run_until_parked();
+        cx.editor(|editor, _| assert!(editor.signature_help_state.is_visible()));
+
+        let mut requests = cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(
+            |_, params, _| async move {
+                assert!(params.context.unwrap().is_retrigger);
+                Ok(None)
+            },
+        );
+        cx.update_editor(|editor, cx| editor.move_right(&MoveRight, cx));
+        requests.next().await;
+        cx.run_until_parked();
+        cx.editor(|editor, _| assert!(!editor.signature_help_state.is_visible()));
+    }
+}
@@ -0,0 +1,287 @@
+use crate::debounced_delay::DebouncedDelay;
+use crate::{
+    AnchorRangeExt as _, CompletionsMenu, ContextMenu, Editor, SnippetState, ToOffset as _,
+    ToPoint as _,
+};
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{AppContext, Model, UniformListScrollHandle, ViewContext};
+use language::{char_kind, Buffer, CharKind, CodeLabel, Completion, Documentation, ToOffset as _};
+use lsp::LanguageServerId;
+use parking_lot::{Mutex, RwLock};
+use project::Project;
+use rand::Rng as _;
+use std::{ops::Range, sync::Arc};
+use util::post_inc;
+
+/// The language server id of the completions that come from no language server:
+/// the user's snippets and the values of snippet choices.
+pub(crate) const SNIPPET_COMPLETION_SERVER_ID: LanguageServerId = LanguageServerId(usize::MAX);
+
+/// The completions for the user's snippets whose prefix starts with the word before the given position.
+///
+/// Without a word before the position, all the snippets are offered, unless the completions were
+/// triggered by a character that is not whitespace, like `.`.
+pub(crate) fn snippet_completions(
+    project: &Model<Project>,
+    buffer: &Model<Buffer>,
+    position: text::Anchor,
+    cx: &AppContext,
+) -> Vec<Completion> {
+    let snapshot = buffer.read(cx).snapshot();
+    let offset = position.to_offset(&snapshot);
+    let language_name = snapshot.language_at(offset).map(|language| language.name());
+    let snippets = project
+        .read(cx)
+        .snippets()
+        .read(cx)
+        .snippets_for(language_name.as_deref(), cx);
+    if snippets.is_empty() {
+        return Vec::new();
+    }
+
+    let scope = snapshot.language_scope_at(offset);
+    let word_len = snapshot
+        .reversed_chars_at(offset)
+        .take_while(|c| char_kind(&scope, *c) == CharKind::Word)
+        .map(char::len_utf8)
+        .sum::<usize>();
+    let word_start = offset - word_len;
+    if word_len == 0
+        && snapshot
+            .reversed_chars_at(offset)
+            .next()
+            .map_or(false, |c| !c.is_whitespace())
+    {
+        return Vec::new();
+    }
+    let word = snapshot
+        .text_for_range(word_start..offset)
+        .collect::<String>()
+        .to_lowercase();
+
+    let old_range = snapshot.anchor_before(word_start)..position;
+    snippets
+        .iter()
+        .flat_map(|snippet| {
+            snippet
+                .prefixes
+                .iter()
+                .filter(|prefix| prefix.to_lowercase().starts_with(&word))
+                .map(|prefix| Completion {
+                    old_range: old_range.clone(),
+                    new_text: snippet.body.clone(),
+                    label: CodeLabel::plain(prefix.clone(), None),
+                    server_id: SNIPPET_COMPLETION_SERVER_ID,
+                    documentation: Some(Documentation::SingleLine(
+                        snippet
+                            .description
+                            .clone()
+                            .unwrap_or_else(|| snippet.name.clone()),
+                    )),
+                    lsp_completion: lsp::CompletionItem {
+                        label: prefix.clone(),
+                        kind: Some(lsp::CompletionItemKind::SNIPPET),
+                        insert_text: Some(snippet.body.clone()),
+                        insert_text_format: Some(lsp::InsertTextFormat::SNIPPET),
+                        ..Default::default()
+                    },
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// The value of a variable used in a snippet inserted in the given buffer, like `$TM_FILENAME` or `$CURRENT_YEAR`.
+pub(crate) fn snippet_variable(
+    editor: &Editor,
+    buffer: &Model<Buffer>,
+    name: &str,
+    cx: &AppContext,
+) -> Option<String> {
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let selection = editor.selections.newest_anchor();
+    let head = selection.head().to_point(&snapshot);
+    let file = buffer.read(cx).file();
+    let abs_path = file.and_then(|file| Some(file.as_local()?.abs_path(cx)));
+    let now = chrono::Local::now();
+    let value = match name {
+        "TM_SELECTED_TEXT" => snapshot
+            .text_for_range(selection.start..selection.end)
+            .collect(),
+        "TM_CURRENT_LINE" => snapshot
+            .text_for_range(
+                text::Point::new(head.row, 0)
+                    ..text::Point::new(head.row, snapshot.line_len(head.row)),
+            )
+            .collect(),
+        "TM_CURRENT_WORD" => {
+            let (range, kind) = snapshot.surrounding_word(head);
+            if kind == Some(CharKind::Word) {
+                snapshot.text_for_range(range).collect()
+            } else {
+                String::new()
+            }
+        }
+        "TM_LINE_INDEX" => head.row.to_string(),
+        "TM_LINE_NUMBER" => (head.row + 1).to_string(),
+        "TM_FILENAME" => file?.file_name(cx).to_string_lossy().into_owned(),
+        "TM_FILENAME_BASE" => file?.path().file_stem()?.to_string_lossy().into_owned(),
+        "TM_DIRECTORY" => abs_path?.parent()?.to_string_lossy().into_owned(),
+        "TM_FILEPATH" => abs_path?.to_string_lossy().into_owned(),
+        "RELATIVE_FILEPATH" => file?.path().to_string_lossy().into_owned(),
+        "CLIPBOARD" => cx.read_from_clipboard()?.text().clone(),
+        "LINE_COMMENT" => snapshot
+            .language_scope_at(head)?
+            .line_comment_prefixes()?
+            .first()?
+            .trim_end()
+            .to_string(),
+        "CURRENT_YEAR" => now.format("%Y").to_string(),
+        "CURRENT_YEAR_SHORT" => now.format("%y").to_string(),
+        "CURRENT_MONTH" => now.format("%m").to_string(),
+        "CURRENT_MONTH_NAME" => now.format("%B").to_string(),
+        "CURRENT_MONTH_NAME_SHORT" => now.format("%b").to_string(),
+        "CURRENT_DATE" => now.format("%d").to_string(),
+        "CURRENT_DAY_NAME" => now.format("%A").to_string(),
+        "CURRENT_DAY_NAME_SHORT" => now.format("%a").to_string(),
+        "CURRENT_HOUR" => now.format("%H").to_string(),
+        "CURRENT_MINUTE" => now.format("%M").to_string(),
+        "CURRENT_SECOND" => now.format("%S").to_string(),
+        "CURRENT_SECONDS_UNIX" => now.timestamp().to_string(),
+        "RANDOM" => format!("{:06}", rand::thread_rng().gen_range(0..1_000_000)),
+        "RANDOM_HEX" => format!("{:06x}", rand::thread_rng().gen_range(0..0x1000000)),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Replaces the transformed mirrors of the active tabstop of the snippet with its transformed text.
+pub(crate) fn apply_snippet_transforms(
+    editor: &mut Editor,
+    snippet: &mut SnippetState,
+    cx: &mut ViewContext<Editor>,
+) {
+    let active_index = snippet.active_index;
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let edits = snippet
+        .transforms
+        .iter()
+        .filter(|transform| transform.tabstop == active_index)
+        .map(|transform| {
+            let text = snapshot
+                .text_for_range(transform.source.clone())
+                .collect::<String>();
+            let start = transform.target.start.to_offset(&snapshot);
+            let end = transform.target.end.to_offset(&snapshot).max(start);
+            (start..end, transform.transform.apply(&text))
+        })
+        .collect::<Vec<_>>();
+    if edits.is_empty() {
+        return;
+    }
+
+    // Keep track of the replaced ranges, to mirror the tabstop again if it is edited later.
+    let replaced_ranges = edits
+        .iter()
+        .map(|(range, _)| snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end))
+        .collect::<Vec<_>>();
+    editor
+        .buffer
+        .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    for (transform, range) in snippet
+        .transforms
+        .iter_mut()
+        .filter(|transform| transform.tabstop == active_index)
+        .zip(replaced_ranges)
+    {
+        let range = range.to_offset(&snapshot);
+        transform.target = snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end);
+    }
+}
+
+/// Shows the values of the choice at the active tabstop of the snippet, to replace its text with.
+pub(crate) fn show_snippet_choices(
+    editor: &mut Editor,
+    snippet: &SnippetState,
+    cx: &mut ViewContext<Editor>,
+) {
+    let Some(choices) = snippet.choices.get(&snippet.active_index) else {
+        return;
+    };
+    let Some(range) = snippet
+        .ranges
+        .get(snippet.active_index)
+        .and_then(|ranges| ranges.first())
+    else {
+        return;
+    };
+    let multi_buffer = editor.buffer.read(cx);
+    let Some((buffer, start)) = multi_buffer.text_anchor_for_position(range.start, cx) else {
+        return;
+    };
+    let Some((_, end)) = multi_buffer.text_anchor_for_position(range.end, cx) else {
+        return;
+    };
+
+    let completions = choices
+        .iter()
+        .map(|choice| Completion {
+            old_range: start..end,
+            new_text: choice.clone(),
+            label: CodeLabel::plain(choice.clone(), None),
+            server_id: SNIPPET_COMPLETION_SERVER_ID,
+            documentation: Some(Documentation::Undocumented),
+            lsp_completion: lsp::CompletionItem {
+                label: choice.clone(),
+                ..Default::default()
+            },
+        })
+        .collect::<Vec<_>>();
+    let menu = CompletionsMenu {
+        id: post_inc(&mut editor.next_completion_id),
+        initial_position: range.start,
+        buffer,
+        match_candidates: choices
+            .iter()
+            .enumerate()
+            .map(|(id, choice)| StringMatchCandidate::new(id, choice.clone()))
+            .collect(),
+        matches: choices
+            .iter()
+            .enumerate()
+            .map(|(id, choice)| StringMatch {
+                candidate_id: id,
+                score: 0.,
+                positions: Vec::new(),
+                string: choice.clone(),
+            })
+            .collect(),
+        completions: Arc::new(RwLock::new(completions.into())),
+        selected_item: 0,
+        scroll_handle: UniformListScrollHandle::new(),
+        selected_completion_documentation_resolve_debounce: Arc::new(Mutex::new(
+            DebouncedDelay::new(),
+        )),
+    };
+    *editor.context_menu.write() = Some(ContextMenu::Completions(menu));
+    editor.discard_copilot_suggestion(cx);
+    cx.notify();
+}
+
+/// The range of every insertion of a snippet's text range, after the snippet was inserted at the given ranges.
+pub(crate) fn inserted_ranges(
+    insertion_ranges: &[Range<usize>],
+    snippet_len: usize,
+    range: &Range<isize>,
+) -> Vec<Range<usize>> {
+    let mut delta = 0_isize;
+    insertion_ranges
+        .iter()
+        .map(|insertion_range| {
+            let insertion_start = insertion_range.start as isize + delta;
+            delta += snippet_len as isize - insertion_range.len() as isize;
+            (insertion_start + range.start) as usize..(insertion_start + range.end) as usize
+        })
+        .collect()
+}
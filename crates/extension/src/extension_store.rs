@@ -28,6 +28,7 @@ use language::{
 };
 use node_runtime::NodeRuntime;
 use serde::{Deserialize, Serialize};
+use snippet_provider::{is_snippets_file, SnippetRegistry};
 use std::{
     cmp::Ordering,
     ffi::OsStr,
@@ -702,11 +703,17 @@ impl ExtensionStore {
             })
             .collect::<Vec<_>>();
         let mut grammars_to_remove = Vec::new();
+        let mut snippets_to_remove = Vec::new();
         for extension_id in &extensions_to_unload {
             let Some(extension) = old_index.extensions.get(extension_id) else {
                 continue;
             };
             grammars_to_remove.extend(extension.manifest.grammars.keys().cloned());
+            snippets_to_remove.extend(extension.manifest.snippets.iter().map(|snippets_path| {
+                let mut path = self.installed_dir.clone();
+                path.extend([Path::new(extension_id.as_ref()), snippets_path.as_path()]);
+                path
+            }));
             for (language_server_name, config) in extension.manifest.language_servers.iter() {
                 self.language_registry
                     .remove_lsp_adapter(config.language.as_ref(), language_server_name);
@@ -716,6 +723,8 @@ impl ExtensionStore {
         self.wasm_extensions
             .retain(|(extension, _)| !extensions_to_unload.contains(&extension.id));
         self.theme_registry.remove_user_themes(&themes_to_remove);
+        let snippet_registry = SnippetRegistry::default_global(cx);
+        snippet_registry.remove_files(&snippets_to_remove);
         self.language_registry
             .remove_languages(&languages_to_remove, &grammars_to_remove);
 
@@ -726,6 +735,7 @@ impl ExtensionStore {
             .collect::<Vec<_>>();
         let mut grammars_to_add = Vec::new();
         let mut themes_to_add = Vec::new();
+        let mut snippets_to_add = Vec::new();
         for extension_id in &extensions_to_load {
             let Some(extension) = new_index.extensions.get(extension_id) else {
                 continue;
@@ -743,6 +753,11 @@ impl ExtensionStore {
                 path.extend([Path::new(extension_id.as_ref()), theme_path.as_path()]);
                 path
             }));
+            snippets_to_add.extend(extension.manifest.snippets.iter().map(|snippets_path| {
+                let mut path = self.installed_dir.clone();
+                path.extend([Path::new(extension_id.as_ref()), snippets_path.as_path()]);
+                path
+            }));
         }
 
         self.language_registry
@@ -792,6 +807,12 @@ impl ExtensionStore {
                                 .await
                                 .log_err();
                         }
+                        for snippets_path in &snippets_to_add {
+                            snippet_registry
+                                .reload_file(snippets_path, fs.as_ref())
+                                .await
+                                .log_err();
+                        }
                     }
                 })
                 .await;
@@ -981,6 +1002,21 @@ impl ExtensionStore {
             }
         }
 
+        if let Ok(mut snippets_paths) = fs.read_dir(&extension_dir.join("snippets")).await {
+            while let Some(snippets_path) = snippets_paths.next().await {
+                let snippets_path = snippets_path?;
+                let Ok(relative_path) = snippets_path.strip_prefix(&extension_dir) else {
+                    continue;
+                };
+                let relative_path = relative_path.to_path_buf();
+                if is_snippets_file(&relative_path)
+                    && !extension_manifest.snippets.contains(&relative_path)
+                {
+                    extension_manifest.snippets.push(relative_path);
+                }
+            }
+        }
+
         let extension_wasm_path = extension_dir.join("extension.wasm");
         if fs.is_file(&extension_wasm_path).await {
             extension_manifest
@@ -1063,6 +1099,7 @@ fn manifest_from_old_manifest(
             .map(|grammar_name| (grammar_name, Default::default()))
             .collect(),
         language_servers: Default::default(),
+        snippets: Vec::new(),
     }
 }
 
@@ -158,6 +158,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         .into_iter()
                         .collect(),
                         language_servers: BTreeMap::default(),
+                        snippets: Vec::new(),
                     }),
                     dev: false,
                 },
@@ -180,6 +181,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         languages: Default::default(),
                         grammars: BTreeMap::default(),
                         language_servers: BTreeMap::default(),
+                        snippets: Vec::new(),
                     }),
                     dev: false,
                 },
@@ -332,6 +334,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 languages: Default::default(),
                 grammars: BTreeMap::default(),
                 language_servers: BTreeMap::default(),
+                snippets: Vec::new(),
             }),
             dev: false,
         },
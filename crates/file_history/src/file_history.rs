@@ -0,0 +1,539 @@
+mod revision_diff;
+
+use editor::{Editor, EditorMode, MultiBuffer};
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, rems, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    Model, Render, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use language::{Buffer, Point};
+use picker::{Picker, PickerDelegate};
+use project::{
+    repository::{CommitDetails, CommitLog},
+    Project,
+};
+pub use revision_diff::RevisionDiff;
+use std::{ops::Range, sync::Arc};
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{notifications::NotifyTaskExt, ModalView, Workspace};
+
+actions!(file_history, [Toggle, ToggleForSelection]);
+
+/// The maximum number of commits listed in the history of a file.
+const HISTORY_LIMIT: usize = 1000;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(FileHistory::register).detach();
+}
+
+/// Lists the commits that changed the file of the editor, or only the ones that changed the
+/// given rows of it.
+pub fn toggle(editor: View<Editor>, rows: Option<Range<u32>>, cx: &mut WindowContext) {
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let Some(workspace) = editor.read(cx).workspace() else {
+        return;
+    };
+    let project = workspace.read(cx).project().clone();
+    let history = project
+        .read(cx)
+        .file_history(&buffer, rows.clone(), Some(HISTORY_LIMIT), cx);
+    let workspace = workspace.downgrade();
+    cx.spawn(|mut cx| async move {
+        let log = history.await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let workspace_handle = cx.view().downgrade();
+            workspace.toggle_modal(cx, |cx| {
+                FileHistory::new(log, rows, buffer, project, workspace_handle, cx)
+            });
+        })
+    })
+    .detach_and_notify_err(cx);
+}
+
+pub struct FileHistory {
+    picker: View<Picker<FileHistoryDelegate>>,
+}
+
+impl FocusableView for FileHistory {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for FileHistory {}
+impl ModalView for FileHistory {}
+
+impl Render for FileHistory {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FileHistory {
+    fn register(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        if editor.mode() == EditorMode::Full {
+            let handle = cx.view().downgrade();
+            editor.register_action(move |_: &Toggle, cx| {
+                if let Some(editor) = handle.upgrade() {
+                    toggle(editor, None, cx);
+                }
+            });
+            let handle = cx.view().downgrade();
+            editor.register_action(move |_: &ToggleForSelection, cx| {
+                if let Some(editor) = handle.upgrade() {
+                    let selection = editor.read(cx).selections.newest::<Point>(cx);
+                    let mut end_row = selection.end.row;
+                    // A selection ending at the start of a row doesn't include that row.
+                    if selection.end.column == 0 && end_row > selection.start.row {
+                        end_row -= 1;
+                    }
+                    toggle(editor, Some(selection.start.row..end_row + 1), cx);
+                }
+            });
+        }
+    }
+
+    fn new(
+        log: CommitLog,
+        rows: Option<Range<u32>>,
+        buffer: Model<Buffer>,
+        project: Model<Project>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let file_name = buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let delegate = FileHistoryDelegate {
+            file_history: cx.view().downgrade(),
+            workspace,
+            project,
+            buffer,
+            file_name,
+            rows,
+            commits: log.commits,
+            truncated: log.truncated,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx).max_height(vh(0.75, cx)));
+        Self { picker }
+    }
+}
+
+pub struct FileHistoryDelegate {
+    file_history: WeakView<FileHistory>,
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    buffer: Model<Buffer>,
+    file_name: String,
+    rows: Option<Range<u32>>,
+    commits: Vec<CommitDetails>,
+    /// Whether only the most recent commits were looked at, so older ones may be missing.
+    truncated: bool,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl FileHistoryDelegate {
+    /// Opens the file as of the selected commit, either on its own as a read-only buffer, or
+    /// side by side with the working copy.
+    fn open_selected_revision(&self, side_by_side: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(commit) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.commits.get(hit.candidate_id))
+        else {
+            return;
+        };
+
+        let title = format!("{} @ {}", self.file_name, short_sha(&commit.sha));
+        let open_revision = self.project.update(cx, |project, cx| {
+            project.open_buffer_at_commit(&self.buffer, commit.sha.clone(), cx)
+        });
+        let project = self.project.clone();
+        let buffer = self.buffer.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn(|_, mut cx| async move {
+            let revision = open_revision.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                if side_by_side {
+                    let diff = cx.new_view(|cx| {
+                        RevisionDiff::new(revision, buffer, title.into(), project, cx)
+                    });
+                    workspace.add_item_to_active_pane(Box::new(diff), cx);
+                } else {
+                    let revision =
+                        cx.new_model(|cx| MultiBuffer::singleton(revision, cx).with_title(title));
+                    let editor =
+                        cx.new_view(|cx| Editor::for_multibuffer(revision, Some(project), cx));
+                    workspace.add_item_to_active_pane(Box::new(editor), cx);
+                }
+            })
+        })
+        .detach_and_notify_err(cx);
+    }
+}
+
+impl PickerDelegate for FileHistoryDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search commits...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .commits
+            .iter()
+            .enumerate()
+            .map(|(id, commit)| StringMatchCandidate::new(id, commit.summary().to_string()))
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    HISTORY_LIMIT,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        self.open_selected_revision(secondary, cx);
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.file_history
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_header(&self, _: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        let title = match &self.rows {
+            Some(rows) if rows.len() > 1 => format!(
+                "History of lines {}-{} of {}",
+                rows.start + 1,
+                rows.end,
+                self.file_name
+            ),
+            Some(rows) => format!("History of line {} of {}", rows.start + 1, self.file_name),
+            None => format!("History of {}", self.file_name),
+        };
+        Some(
+            h_flex()
+                .px_3()
+                .pt_1()
+                .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+                .into_any_element(),
+        )
+    }
+
+    fn render_footer(&self, _: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        self.truncated.then(|| {
+            h_flex()
+                .px_3()
+                .pb_1()
+                .child(
+                    Label::new("History truncated, older commits are not listed")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .into_any_element()
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let commit = self.commits.get(hit.candidate_id)?;
+        let timestamp = match OffsetDateTime::from_unix_timestamp(commit.author_time) {
+            Ok(timestamp) => time_format::format_localized_timestamp(
+                timestamp,
+                OffsetDateTime::now_utc(),
+                cx.local_timezone(),
+                TimestampFormat::Relative,
+            ),
+            Err(_) => "Error parsing date".to_string(),
+        };
+        let details = format!(
+            "{} · {} · {}",
+            short_sha(&commit.sha),
+            commit.author.as_deref().unwrap_or("Unknown author"),
+            timestamp
+        );
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    v_flex()
+                        .child(HighlightedLabel::new(
+                            commit.summary().to_string(),
+                            hit.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(details)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use std::path::Path;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_file_history(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                ".git": {},
+                "a.txt": "one\nTWO\nthree\n",
+                "b.txt": "b\n",
+            }),
+        )
+        .await;
+        let dot_git = Path::new("/root/.git");
+        fs.set_head_for_repo(dot_git, &[(Path::new("a.txt"), "one\nTWO\nthree\n".into())]);
+        fs.set_history_for_repo(
+            dot_git,
+            vec![
+                (
+                    commit('4', "Capitalize two"),
+                    vec![
+                        (Path::new("a.txt"), "one\nTWO\nthree\n".into()),
+                        (Path::new("b.txt"), "b\n".into()),
+                    ],
+                ),
+                (
+                    commit('3', "Add b"),
+                    vec![
+                        (Path::new("a.txt"), "one\ntwo\nthree\n".into()),
+                        (Path::new("b.txt"), "b\n".into()),
+                    ],
+                ),
+                (
+                    commit('2', "Add three"),
+                    vec![(Path::new("a.txt"), "one\ntwo\nthree\n".into())],
+                ),
+                (
+                    commit('1', "Add a"),
+                    vec![(Path::new("a.txt"), "one\ntwo\n".into())],
+                ),
+            ],
+        );
+
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees().next().unwrap().read(cx).id()
+            })
+        });
+        let editor = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, "a.txt"), None, true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+
+        // The commits that didn't change the file are not listed.
+        let picker = open_file_history(&workspace, Toggle, cx);
+        assert_eq!(
+            commit_summaries(&picker, cx),
+            ["Capitalize two", "Add three", "Add a"]
+        );
+        cx.dispatch_action(menu::Cancel);
+
+        // Only the commits that changed the selected rows are listed.
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([Point::new(2, 0)..Point::new(3, 0)])
+            })
+        });
+        let picker = open_file_history(&workspace, ToggleForSelection, cx);
+        assert_eq!(commit_summaries(&picker, cx), ["Add three"]);
+        cx.dispatch_action(menu::Cancel);
+
+        // Confirming opens the file as of the selected commit, in a read-only buffer.
+        open_file_history(&workspace, Toggle, cx);
+        cx.dispatch_action(menu::SelectNext);
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        let revision_editor = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<Editor>(cx).unwrap()
+        });
+        revision_editor.update(cx, |editor, cx| {
+            assert_eq!(editor.text(cx), "one\ntwo\nthree\n");
+            assert_eq!(editor.buffer().read(cx).title(cx), "a.txt @ 2222222");
+            assert!(editor.read_only(cx));
+        });
+
+        // The secondary confirmation compares the file as of the selected commit with the
+        // working copy.
+        workspace.update(cx, |workspace, cx| {
+            workspace.activate_item(&editor, cx);
+        });
+        open_file_history(&workspace, Toggle, cx);
+        cx.dispatch_action(menu::SelectNext);
+        cx.dispatch_action(menu::SecondaryConfirm);
+        cx.run_until_parked();
+        let revision_diff = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<RevisionDiff>(cx).unwrap()
+        });
+        revision_diff.update(cx, |revision_diff, cx| {
+            let old_editor = revision_diff.old_editor().clone();
+            let new_editor = revision_diff.new_editor().clone();
+            assert_eq!(old_editor.read(cx).text(cx), "one\ntwo\nthree\n");
+            assert_eq!(new_editor.read(cx).text(cx), "one\nTWO\nthree\n");
+            for editor in [old_editor, new_editor] {
+                let highlighted_rows = editor.update(cx, |editor, cx| {
+                    editor
+                        .highlighted_display_rows(cx)
+                        .into_keys()
+                        .collect::<Vec<_>>()
+                });
+                assert_eq!(highlighted_rows, [1]);
+            }
+        });
+
+        // Scrolling either side scrolls the other one along.
+        let (old_editor, new_editor) = revision_diff.update(cx, |revision_diff, _| {
+            (
+                revision_diff.old_editor().clone(),
+                revision_diff.new_editor().clone(),
+            )
+        });
+        old_editor.update(cx, |editor, cx| {
+            editor.set_scroll_position(gpui::Point::new(0., 2.), cx)
+        });
+        cx.run_until_parked();
+        let scroll_position = new_editor.update(cx, |editor, cx| editor.scroll_position(cx));
+        assert_eq!(scroll_position, gpui::Point::new(0., 2.));
+    }
+
+    fn commit(sha: char, message: &str) -> CommitDetails {
+        CommitDetails {
+            sha: sha.to_string().repeat(40),
+            parent_shas: Vec::new(),
+            author: Some("Author".to_string()),
+            author_mail: None,
+            author_time: 0,
+            message: message.to_string(),
+        }
+    }
+
+    fn open_file_history(
+        workspace: &View<Workspace>,
+        action: impl gpui::Action,
+        cx: &mut VisualTestContext,
+    ) -> View<Picker<FileHistoryDelegate>> {
+        cx.dispatch_action(action);
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<FileHistory>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        })
+    }
+
+    fn commit_summaries(
+        picker: &View<Picker<FileHistoryDelegate>>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<String> {
+        picker.update(cx, |picker, _| {
+            picker
+                .delegate
+                .matches
+                .iter()
+                .map(|hit| {
+                    picker.delegate.commits[hit.candidate_id]
+                        .summary()
+                        .to_string()
+                })
+                .collect()
+        })
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            crate::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            state
+        })
+    }
+}
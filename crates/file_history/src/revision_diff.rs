@@ -0,0 +1,272 @@
+use editor::{
+    display_map::{BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    Editor, EditorEvent,
+};
+use gpui::{
+    AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Hsla, Model, Render,
+    SharedString, Subscription, Task, View, ViewContext, VisualContext, WindowContext,
+};
+use language::{Bias, Buffer, Point};
+use project::{repository::diff_rows, Project};
+use std::{ops::Range, sync::Arc};
+use theme::ActiveTheme;
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::item::{Item, ItemEvent};
+
+/// A side-by-side view of a file as of a commit and in the working copy, with the rows that
+/// differ between them highlighted. Spacer blocks pad the shorter side of each change, so that
+/// both editors keep the same rows side by side as they scroll together.
+pub struct RevisionDiff {
+    focus_handle: FocusHandle,
+    title: SharedString,
+    old_buffer: Model<Buffer>,
+    new_buffer: Model<Buffer>,
+    old_editor: View<Editor>,
+    new_editor: View<Editor>,
+    old_spacers: Vec<BlockId>,
+    new_spacers: Vec<BlockId>,
+    update_highlights_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+enum RevisionDiffRowHighlights {}
+
+impl RevisionDiff {
+    pub fn new(
+        old_buffer: Model<Buffer>,
+        new_buffer: Model<Buffer>,
+        title: SharedString,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let old_editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(old_buffer.clone(), Some(project.clone()), cx);
+            editor.set_read_only(true);
+            editor
+        });
+        let new_editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(new_buffer.clone(), Some(project), cx);
+            editor.set_read_only(true);
+            editor
+        });
+        let buffer_subscription = cx.subscribe(&new_buffer, |this, _, event, cx| match event {
+            language::Event::Edited | language::Event::Reloaded => this.update_highlights(cx),
+            _ => {}
+        });
+        let old_scroll_subscription = cx.subscribe(&old_editor, |this, _, event, cx| {
+            if let EditorEvent::ScrollPositionChanged { local: true, .. } = event {
+                sync_scroll_position(&this.old_editor, &this.new_editor, cx);
+            }
+        });
+        let new_scroll_subscription = cx.subscribe(&new_editor, |this, _, event, cx| {
+            if let EditorEvent::ScrollPositionChanged { local: true, .. } = event {
+                sync_scroll_position(&this.new_editor, &this.old_editor, cx);
+            }
+        });
+        let focus_handle = cx.focus_handle();
+        let focus_in_subscription = cx.on_focus_in(&focus_handle, |this, cx| {
+            if this.focus_handle.is_focused(cx) {
+                this.new_editor.focus_handle(cx).focus(cx);
+            }
+        });
+
+        let mut this = Self {
+            focus_handle,
+            title,
+            old_buffer,
+            new_buffer,
+            old_editor,
+            new_editor,
+            old_spacers: Vec::new(),
+            new_spacers: Vec::new(),
+            update_highlights_task: Task::ready(()),
+            _subscriptions: vec![
+                buffer_subscription,
+                old_scroll_subscription,
+                new_scroll_subscription,
+                focus_in_subscription,
+            ],
+        };
+        this.update_highlights(cx);
+        this
+    }
+
+    pub fn old_editor(&self) -> &View<Editor> {
+        &self.old_editor
+    }
+
+    pub fn new_editor(&self) -> &View<Editor> {
+        &self.new_editor
+    }
+
+    fn update_highlights(&mut self, cx: &mut ViewContext<Self>) {
+        let old_text = self.old_buffer.read(cx).as_rope().clone();
+        let new_text = self.new_buffer.read(cx).as_rope().clone();
+        let changes = cx
+            .background_executor()
+            .spawn(async move { diff_rows(&old_text.to_string(), &new_text.to_string()) });
+        self.update_highlights_task = cx.spawn(|this, mut cx| async move {
+            let Some(changes) = changes.await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                let status = cx.theme().status();
+                let (deleted, created) = (status.deleted_background, status.created_background);
+                highlight_rows(
+                    &this.old_editor,
+                    changes.iter().map(|change| change.old.clone()),
+                    deleted,
+                    cx,
+                );
+                highlight_rows(
+                    &this.new_editor,
+                    changes.iter().map(|change| change.new.clone()),
+                    created,
+                    cx,
+                );
+                let old_spacers = std::mem::take(&mut this.old_spacers);
+                this.old_spacers = insert_spacers(
+                    &this.old_editor,
+                    old_spacers,
+                    changes.iter().map(|change| {
+                        let padding = change.new.len().saturating_sub(change.old.len());
+                        (change.old.end, padding as u32)
+                    }),
+                    cx,
+                );
+                let new_spacers = std::mem::take(&mut this.new_spacers);
+                this.new_spacers = insert_spacers(
+                    &this.new_editor,
+                    new_spacers,
+                    changes.iter().map(|change| {
+                        let padding = change.old.len().saturating_sub(change.new.len());
+                        (change.new.end, padding as u32)
+                    }),
+                    cx,
+                );
+                sync_scroll_position(&this.new_editor, &this.old_editor, cx);
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+}
+
+fn highlight_rows(
+    editor: &View<Editor>,
+    rows: impl Iterator<Item = Range<u32>>,
+    color: Hsla,
+    cx: &mut WindowContext,
+) {
+    editor.update(cx, |editor, cx| {
+        editor.clear_row_highlights::<RevisionDiffRowHighlights>();
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        for rows in rows.filter(|rows| !rows.is_empty()) {
+            let start = snapshot.clip_point(Point::new(rows.start, 0), Bias::Left);
+            let end = snapshot.clip_point(Point::new(rows.end - 1, 0), Bias::Left);
+            editor.highlight_rows::<RevisionDiffRowHighlights>(
+                snapshot.anchor_before(start)..snapshot.anchor_before(end),
+                Some(color),
+                cx,
+            );
+        }
+    });
+}
+
+/// Replaces the spacer blocks of the editor with ones of the given heights, each shown above the
+/// given row, or below the last row if it is past the end of the buffer.
+fn insert_spacers(
+    editor: &View<Editor>,
+    old_spacers: Vec<BlockId>,
+    spacers: impl Iterator<Item = (u32, u32)>,
+    cx: &mut WindowContext,
+) -> Vec<BlockId> {
+    editor.update(cx, |editor, cx| {
+        if !old_spacers.is_empty() {
+            editor.remove_blocks(old_spacers.into_iter().collect(), None, cx);
+        }
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let max_point = snapshot.max_point();
+        let mut blocks = Vec::new();
+        for (row, mut height) in spacers {
+            let (position, disposition) = if row <= max_point.row {
+                (Point::new(row, 0), BlockDisposition::Above)
+            } else {
+                (max_point, BlockDisposition::Below)
+            };
+            // Block heights are bytes, so taller spacers are split into several blocks.
+            while height > 0 {
+                let block_height = height.min(u8::MAX as u32);
+                height -= block_height;
+                blocks.push(BlockProperties {
+                    position: snapshot.anchor_before(position),
+                    height: block_height as u8,
+                    style: BlockStyle::Fixed,
+                    render: Arc::new(|_| div().into_any_element()),
+                    disposition,
+                });
+            }
+        }
+        editor.insert_blocks(blocks, None, cx)
+    })
+}
+
+/// Scrolls the `target` editor to the scroll position of the `source` one, unless it is already
+/// there, which stops the editors from echoing each other's scroll events.
+fn sync_scroll_position(source: &View<Editor>, target: &View<Editor>, cx: &mut WindowContext) {
+    let scroll_position = source.update(cx, |editor, cx| editor.scroll_position(cx));
+    target.update(cx, |editor, cx| {
+        if editor.scroll_position(cx) != scroll_position {
+            editor.set_scroll_position(scroll_position, cx);
+        }
+    });
+}
+
+impl EventEmitter<ItemEvent> for RevisionDiff {}
+
+impl FocusableView for RevisionDiff {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RevisionDiff {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let border_color = cx.theme().colors().border;
+        h_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(
+                div()
+                    .h_full()
+                    .flex_1()
+                    .border_r_1()
+                    .border_color(border_color)
+                    .child(self.old_editor.clone()),
+            )
+            .child(div().h_full().flex_1().child(self.new_editor.clone()))
+    }
+}
+
+impl Item for RevisionDiff {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        Label::new(self.title.clone())
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("revision diff")
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
@@ -468,6 +468,8 @@ pub struct FakeFs {
     // Use an unfair lock to ensure tests are deterministic.
     state: Mutex<FakeFsState>,
     executor: gpui::BackgroundExecutor,
+    /// Where the fake git repositories report the changes they make to themselves.
+    git_event_tx: smol::channel::Sender<PathBuf>,
 }
 
 #[cfg(any(test, feature = "test-support"))]
@@ -615,8 +617,10 @@ lazy_static::lazy_static! {
 #[cfg(any(test, feature = "test-support"))]
 impl FakeFs {
     pub fn new(executor: gpui::BackgroundExecutor) -> Arc<Self> {
-        Arc::new(Self {
-            executor,
+        let (git_event_tx, git_event_rx) = smol::channel::unbounded::<PathBuf>();
+        let this = Arc::new(Self {
+            executor: executor.clone(),
+            git_event_tx,
             state: Mutex::new(FakeFsState {
                 root: Arc::new(Mutex::new(FakeFsEntry::Dir {
                     inode: 0,
@@ -632,7 +636,22 @@ impl FakeFs {
                 read_dir_call_count: 0,
                 metadata_call_count: 0,
             }),
-        })
+        });
+
+        executor
+            .spawn({
+                let this = Arc::downgrade(&this);
+                async move {
+                    while let Ok(dot_git) = git_event_rx.recv().await {
+                        let Some(this) = this.upgrade() else {
+                            break;
+                        };
+                        this.state.lock().emit_event([dot_git]);
+                    }
+                }
+            })
+            .detach();
+        this
     }
 
     pub async fn insert_file(&self, path: impl AsRef<Path>, content: Vec<u8>) {
@@ -775,7 +794,12 @@ impl FakeFs {
         let mut entry = entry.lock();
 
         if let FakeFsEntry::Dir { git_repo_state, .. } = &mut *entry {
-            let repo_state = git_repo_state.get_or_insert_with(Default::default);
+            let repo_state = git_repo_state.get_or_insert_with(|| {
+                Arc::new(Mutex::new(FakeGitRepositoryState::new(
+                    dot_git.to_path_buf(),
+                    self.git_event_tx.clone(),
+                )))
+            });
             let mut repo_state = repo_state.lock();
 
             f(&mut repo_state);
@@ -805,6 +829,45 @@ impl FakeFs {
         });
     }
 
+    pub fn set_head_for_repo(&self, dot_git: &Path, head_state: &[(&Path, String)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.head_contents.clear();
+            state.head_contents.extend(
+                head_state
+                    .iter()
+                    .map(|(path, content)| (path.to_path_buf(), content.clone())),
+            );
+        });
+    }
+
+    pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(&Path, repository::Blame)>) {
+        self.with_git_state(dot_git, true, |state| {
+            state.blames.clear();
+            state
+                .blames
+                .extend(blames.into_iter().map(|(path, blame)| (path.into(), blame)));
+        });
+    }
+
+    pub fn set_history_for_repo(
+        &self,
+        dot_git: &Path,
+        history: Vec<(repository::CommitDetails, Vec<(&Path, String)>)>,
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.history = history
+                .into_iter()
+                .map(|(commit, contents)| {
+                    let contents = contents
+                        .into_iter()
+                        .map(|(path, content)| (path.into(), content))
+                        .collect();
+                    (commit, contents)
+                })
+                .collect();
+        });
+    }
+
     pub fn set_status_for_repo_via_working_copy_change(
         &self,
         dot_git: &Path,
@@ -1373,7 +1436,12 @@ impl Fs for FakeFs {
         let mut entry = entry.lock();
         if let FakeFsEntry::Dir { git_repo_state, .. } = &mut *entry {
             let state = git_repo_state
-                .get_or_insert_with(|| Arc::new(Mutex::new(FakeGitRepositoryState::default())))
+                .get_or_insert_with(|| {
+                    Arc::new(Mutex::new(FakeGitRepositoryState::new(
+                        abs_dot_git.to_path_buf(),
+                        self.git_event_tx.clone(),
+                    )))
+                })
                 .clone();
             Some(repository::FakeGitRepository::open(state))
         } else {
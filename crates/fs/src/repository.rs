@@ -1,10 +1,12 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use collections::HashMap;
 use git2::{BranchType, StatusShow};
 use parking_lot::Mutex;
 use serde_derive::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
+    ffi::OsStr,
+    ops::Range,
     path::{Component, Path, PathBuf},
     sync::Arc,
     time::SystemTime,
@@ -21,10 +23,110 @@ pub struct Branch {
     pub unix_timestamp: Option<i64>,
 }
 
+/// The commits that last changed each row of a file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Blame {
+    /// The entries sorted by row. The rows not covered by any entry are not committed yet.
+    pub entries: Vec<BlameEntry>,
+    /// The full messages of the commits, by SHA.
+    pub messages: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlameEntry {
+    pub sha: String,
+    /// The rows of the blamed text that were last changed by the commit.
+    pub range: Range<u32>,
+    /// The row of the first line of the entry in the commit.
+    pub original_row: u32,
+    pub author: Option<String>,
+    pub author_mail: Option<String>,
+    /// The author time, as a Unix timestamp.
+    pub author_time: i64,
+    /// The first line of the commit message.
+    pub summary: Option<String>,
+}
+
+/// A file that differs between the HEAD commit, the index and the working directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitStatusEntry {
+    pub repo_path: RepoPath,
+    /// The status of the file in the index, with respect to the HEAD commit.
+    pub staged: Option<GitFileStatus>,
+    /// The status of the file in the working directory, with respect to the index.
+    pub unstaged: Option<GitFileStatus>,
+}
+
+impl GitStatusEntry {
+    pub fn is_untracked(&self) -> bool {
+        self.staged.is_none() && self.unstaged == Some(GitFileStatus::Added)
+    }
+}
+
+/// A commit in the history of a repository.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommitDetails {
+    pub sha: String,
+    /// The SHAs of the parents of the commit, the first one being the commit it was made on top of.
+    pub parent_shas: Vec<String>,
+    pub author: Option<String>,
+    pub author_mail: Option<String>,
+    /// The author time, as a Unix timestamp.
+    pub author_time: i64,
+    /// The full message of the commit.
+    pub message: String,
+}
+
+impl CommitDetails {
+    /// The first line of the commit message.
+    pub fn summary(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+}
+
+/// Which of the commits reachable from HEAD to list.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogOptions {
+    /// Only list the commits that changed the file at this path.
+    pub path: Option<RepoPath>,
+    /// Only list the commits that changed these rows of the file at `path`, as of HEAD. The rows
+    /// are followed back through the first parents of the commits, until the file was added.
+    pub rows: Option<Range<u32>>,
+    /// The maximum number of commits to list.
+    pub limit: Option<usize>,
+    /// The maximum number of commits to look at, whether they get listed or not. This bounds the
+    /// time spent walking the history when few commits match the path.
+    pub max_walked_commits: Option<usize>,
+}
+
+/// The commits listed by [`GitRepository::log`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommitLog {
+    pub commits: Vec<CommitDetails>,
+    /// Whether the walk stopped after [`LogOptions::max_walked_commits`], so that older commits
+    /// that match the options may be missing.
+    pub truncated: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CommitOptions {
+    /// Whether to replace the HEAD commit instead of committing on top of it.
+    pub amend: bool,
+    /// Whether to add a `Signed-off-by` trailer with the committer's identity to the message.
+    pub signoff: bool,
+}
+
 pub trait GitRepository: Send {
     fn reload_index(&self);
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String>;
 
+    /// Returns the text of the file at the given path in the HEAD commit.
+    fn load_head_text(&self, relative_file_path: &Path) -> Option<String>;
+
+    /// Writes the given text into the index as the content of the file at the given path,
+    /// or removes the file from the index if there is no text.
+    fn set_index_text(&self, relative_file_path: &Path, text: Option<String>) -> Result<()>;
+
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
     fn branch_name(&self) -> Option<String>;
@@ -53,6 +155,34 @@ pub trait GitRepository: Send {
     fn branches(&self) -> Result<Vec<Branch>>;
     fn change_branch(&self, _: &str) -> Result<()>;
     fn create_branch(&self, _: &str) -> Result<()>;
+
+    /// Returns the commits that last changed each row of the given content of the file at
+    /// the given path, as of HEAD. The rows that differ from HEAD are not attributed to any commit.
+    fn blame(&self, path: &Path, content: &str) -> Result<Blame>;
+
+    /// Returns the files that differ between the HEAD commit, the index and the working
+    /// directory, sorted by path.
+    fn file_statuses(&self) -> Result<Vec<GitStatusEntry>>;
+
+    /// Writes the content of the given files in the working directory to the index, and
+    /// removes the ones that were deleted from it.
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Resets the given files in the index to their content in the HEAD commit.
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Returns the message of the HEAD commit.
+    fn head_commit_message(&self) -> Option<String>;
+
+    /// Commits the index with the given message, and returns the SHA of the new commit.
+    fn commit(&self, message: &str, options: CommitOptions) -> Result<String>;
+
+    /// Returns the commits reachable from HEAD that match the given options, newest first.
+    fn log(&self, options: &LogOptions) -> Result<CommitLog>;
+
+    /// Returns the text of the file at the given path in the commit with the given SHA, or
+    /// `None` if the file does not exist in that commit.
+    fn load_commit_text(&self, sha: &str, relative_file_path: &Path) -> Result<Option<String>>;
 }
 
 impl std::fmt::Debug for dyn GitRepository {
@@ -92,6 +222,80 @@ impl GitRepository for LibGitRepository {
         None
     }
 
+    fn load_head_text(&self, relative_file_path: &Path) -> Option<String> {
+        fn logic(repo: &LibGitRepository, relative_file_path: &Path) -> Result<Option<String>> {
+            check_path_to_repo_path_errors(relative_file_path)?;
+
+            let tree = repo.head()?.peel_to_tree()?;
+            let oid = match tree.get_path(relative_file_path) {
+                Ok(entry) => entry.id(),
+                Err(error) if error.code() == git2::ErrorCode::NotFound => return Ok(None),
+                Err(error) => return Err(error.into()),
+            };
+
+            let content = repo.find_blob(oid)?.content().to_owned();
+            Ok(Some(String::from_utf8(content)?))
+        }
+
+        match logic(self, relative_file_path) {
+            Ok(value) => return value,
+            Err(err) => log::error!("Error loading HEAD text: {:?}", err),
+        }
+        None
+    }
+
+    fn set_index_text(&self, relative_file_path: &Path, text: Option<String>) -> Result<()> {
+        const STAGE_NORMAL: i32 = 0;
+        check_path_to_repo_path_errors(relative_file_path)?;
+
+        let mut index = self.index()?;
+        // Pick up changes made to the index since it was loaded, e.g. by `git add`, so that
+        // writing it doesn't revert them.
+        index.read(false)?;
+        if let Some(text) = text {
+            let oid = self.blob(text.as_bytes())?;
+            let mut entry = match index.get_path(relative_file_path, STAGE_NORMAL) {
+                Some(entry) => entry,
+                None => git2::IndexEntry {
+                    ctime: git2::IndexTime::new(0, 0),
+                    mtime: git2::IndexTime::new(0, 0),
+                    dev: 0,
+                    ino: 0,
+                    mode: 0o100644,
+                    uid: 0,
+                    gid: 0,
+                    file_size: 0,
+                    id: oid,
+                    flags: 0,
+                    flags_extended: 0,
+                    path: relative_file_path
+                        .to_str()
+                        .context("repo path is not valid UTF-8")?
+                        .as_bytes()
+                        .to_vec(),
+                },
+            };
+            if entry.id != oid {
+                // The cached stat data describes the working copy file, which no longer matches
+                // the staged blob. Zeroing it makes git compare the file's contents again instead
+                // of trusting the stat data and reporting the file as unchanged.
+                entry.ctime = git2::IndexTime::new(0, 0);
+                entry.mtime = git2::IndexTime::new(0, 0);
+                entry.dev = 0;
+                entry.ino = 0;
+                entry.uid = 0;
+                entry.gid = 0;
+                entry.id = oid;
+            }
+            entry.file_size = text.len() as u32;
+            index.add(&entry)?;
+        } else {
+            index.remove_path(relative_file_path)?;
+        }
+        index.write()?;
+        Ok(())
+    }
+
     fn remote_url(&self, name: &str) -> Option<String> {
         let remote = self.find_remote(name).ok()?;
         remote.url().map(|url| url.to_string())
@@ -209,6 +413,673 @@ impl GitRepository for LibGitRepository {
 
         Ok(())
     }
+
+    fn blame(&self, path: &Path, content: &str) -> Result<Blame> {
+        check_path_to_repo_path_errors(path)?;
+
+        let head = self.head()?.peel_to_commit()?;
+        let committed_text = match head.tree()?.get_path(path) {
+            Ok(entry) => String::from_utf8(self.find_blob(entry.id())?.content().to_owned())?,
+            Err(error) if error.code() == git2::ErrorCode::NotFound => return Ok(Blame::default()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut options = git2::BlameOptions::new();
+        options.newest_commit(head.id());
+        let blame = self.blame_file(path, Some(&mut options))?;
+
+        let mut messages = HashMap::default();
+        let mut entries = Vec::new();
+        for hunk in blame.iter() {
+            let oid = hunk.final_commit_id();
+            let sha = oid.to_string();
+            if !messages.contains_key(&sha) {
+                if let Some(commit) = self.find_commit(oid).log_err() {
+                    let message = String::from_utf8_lossy(commit.message_bytes());
+                    messages.insert(sha.clone(), message.trim_end().to_string());
+                }
+            }
+
+            let signature = hunk.final_signature();
+            let start = hunk.final_start_line().saturating_sub(1) as u32;
+            entries.push(BlameEntry {
+                range: start..start + hunk.lines_in_hunk() as u32,
+                original_row: hunk.orig_start_line().saturating_sub(1) as u32,
+                author: signature.name().map(str::to_string),
+                author_mail: signature.email().map(str::to_string),
+                author_time: signature.when().seconds(),
+                summary: messages
+                    .get(&sha)
+                    .and_then(|message| message.lines().next())
+                    .map(str::to_string),
+                sha,
+            });
+        }
+
+        let changes = diff_rows(&committed_text, content)?;
+
+        Ok(Blame {
+            entries: remap_blame_entries(entries, &changes),
+            messages,
+        })
+    }
+
+    fn file_statuses(&self) -> Result<Vec<GitStatusEntry>> {
+        let mut options = git2::StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false);
+        let statuses = self.statuses(Some(&mut options))?;
+
+        let mut entries = statuses
+            .iter()
+            .filter_map(|entry| {
+                let status = entry.status();
+                let (staged, unstaged) = if status.contains(git2::Status::CONFLICTED) {
+                    (None, Some(GitFileStatus::Conflict))
+                } else {
+                    (read_staged_status(status), read_unstaged_status(status))
+                };
+                if staged.is_none() && unstaged.is_none() {
+                    return None;
+                }
+                Some(GitStatusEntry {
+                    repo_path: RepoPath::new(entry.path()?.into()),
+                    staged,
+                    unstaged,
+                })
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.repo_path.cmp(&b.repo_path));
+        Ok(entries)
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let work_directory = self
+            .workdir()
+            .context("cannot stage files in a bare repository")?;
+        let mut index = self.index()?;
+        index.read(false)?;
+        for path in paths {
+            check_path_to_repo_path_errors(path)?;
+            if work_directory.join(path).exists() {
+                index.add_path(path)?;
+            } else {
+                index.remove_path(path)?;
+            }
+        }
+        index.write()?;
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        for path in paths {
+            check_path_to_repo_path_errors(path)?;
+        }
+        self.index()?.read(false)?;
+        match self.head().and_then(|head| head.peel_to_commit()) {
+            Ok(head) => self.reset_default(
+                Some(head.as_object()),
+                paths.iter().map(|path| path.0.as_path()),
+            )?,
+            // Before the first commit, unstaging a file removes it from the index.
+            Err(error) if error.code() == git2::ErrorCode::UnbornBranch => {
+                let mut index = self.index()?;
+                for path in paths {
+                    index.remove_path(path)?;
+                }
+                index.write()?;
+            }
+            Err(error) => return Err(error.into()),
+        }
+        Ok(())
+    }
+
+    fn head_commit_message(&self) -> Option<String> {
+        let head = self.head().ok()?.peel_to_commit().ok()?;
+        let message = String::from_utf8_lossy(head.message_bytes());
+        Some(message.trim_end().to_string())
+    }
+
+    fn commit(&self, message: &str, options: CommitOptions) -> Result<String> {
+        let work_directory = self
+            .workdir()
+            .context("cannot commit in a bare repository")?;
+        let signature = self.signature()?;
+        let mut message = message.trim_end().to_string();
+        if options.signoff {
+            message = append_signoff(
+                &message,
+                signature.name().unwrap_or_default(),
+                signature.email().unwrap_or_default(),
+            );
+        }
+
+        let head = match self.head().and_then(|head| head.peel_to_commit()) {
+            Ok(head) => Some(head),
+            Err(error) if error.code() == git2::ErrorCode::UnbornBranch => None,
+            Err(error) => return Err(error.into()),
+        };
+        if options.amend && head.is_none() {
+            anyhow::bail!("there is no commit to amend");
+        }
+
+        run_git_hook(self, work_directory, "pre-commit", &[])?;
+        // The `commit-msg` hook may rewrite the message in place.
+        let message_path = self.path().join("COMMIT_EDITMSG");
+        std::fs::write(&message_path, &message)?;
+        run_git_hook(
+            self,
+            work_directory,
+            "commit-msg",
+            &[message_path.as_os_str()],
+        )?;
+        let message = std::fs::read_to_string(&message_path)?;
+        if message.trim().is_empty() {
+            anyhow::bail!("the commit message is empty");
+        }
+
+        // The `pre-commit` hook may have staged changes.
+        let mut index = self.index()?;
+        index.read(false)?;
+        let tree = self.find_tree(index.write_tree()?)?;
+        let oid = match &head {
+            Some(head) if options.amend => head.amend(
+                Some("HEAD"),
+                None,
+                Some(&signature),
+                None,
+                Some(&message),
+                Some(&tree),
+            )?,
+            _ => {
+                if head
+                    .as_ref()
+                    .map_or(false, |head| head.tree_id() == tree.id())
+                {
+                    anyhow::bail!("there are no staged changes to commit");
+                }
+                let parents = head.iter().collect::<Vec<_>>();
+                LibGitRepository::commit(
+                    self,
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    &message,
+                    &tree,
+                    &parents,
+                )?
+            }
+        };
+
+        run_git_hook(self, work_directory, "post-commit", &[]).log_err();
+        Ok(oid.to_string())
+    }
+
+    fn log(&self, options: &LogOptions) -> Result<CommitLog> {
+        if let Some(path) = &options.path {
+            check_path_to_repo_path_errors(path)?;
+        }
+
+        let mut revwalk = self.revwalk()?;
+        match revwalk.push_head() {
+            Ok(()) => {}
+            // There is no history before the first commit.
+            Err(error)
+                if matches!(
+                    error.code(),
+                    git2::ErrorCode::UnbornBranch | git2::ErrorCode::NotFound
+                ) =>
+            {
+                return Ok(CommitLog::default())
+            }
+            Err(error) => return Err(error.into()),
+        }
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+        let mut rows = options.rows.clone();
+        if rows.is_some() {
+            revwalk.simplify_first_parent()?;
+        }
+
+        let limit = options.limit.unwrap_or(usize::MAX);
+        let max_walked_commits = options.max_walked_commits.unwrap_or(usize::MAX);
+        let mut commits = Vec::new();
+        let mut truncated = false;
+        for (walked_commits, oid) in revwalk.enumerate() {
+            if commits.len() >= limit {
+                break;
+            }
+            if walked_commits == max_walked_commits {
+                truncated = true;
+                break;
+            }
+            let commit = self.find_commit(oid?)?;
+            let Some(path) = &options.path else {
+                commits.push(commit_details(&commit));
+                continue;
+            };
+
+            if let Some(rows) = rows.as_mut() {
+                let Some(text) = commit_text(self, &commit, path)? else {
+                    break;
+                };
+                let parent_text = match commit.parents().next() {
+                    Some(parent) => commit_text(self, &parent, path)?,
+                    None => None,
+                };
+                if trace_rows_to_parent(parent_text.as_deref(), &text, rows)? {
+                    commits.push(commit_details(&commit));
+                }
+                // Stop once the commit that added all of the rows is found.
+                if parent_text.is_none() || rows.is_empty() {
+                    break;
+                }
+            } else {
+                let blob_id = commit_blob_id(&commit, path)?;
+                let parent_blob_ids = commit
+                    .parents()
+                    .map(|parent| commit_blob_id(&parent, path))
+                    .collect::<Result<Vec<_>>>()?;
+                // Like `git log`, skip the merges that kept the file of one of their parents.
+                let changed = if parent_blob_ids.is_empty() {
+                    blob_id.is_some()
+                } else {
+                    parent_blob_ids
+                        .iter()
+                        .all(|parent_blob_id| *parent_blob_id != blob_id)
+                };
+                if changed {
+                    commits.push(commit_details(&commit));
+                }
+            }
+        }
+        Ok(CommitLog { commits, truncated })
+    }
+
+    fn load_commit_text(&self, sha: &str, relative_file_path: &Path) -> Result<Option<String>> {
+        check_path_to_repo_path_errors(relative_file_path)?;
+        let commit = self.find_commit(git2::Oid::from_str(sha)?)?;
+        commit_text(self, &commit, relative_file_path)
+    }
+}
+
+fn commit_details(commit: &git2::Commit) -> CommitDetails {
+    let author = commit.author();
+    CommitDetails {
+        sha: commit.id().to_string(),
+        parent_shas: commit.parent_ids().map(|id| id.to_string()).collect(),
+        author: author.name().map(str::to_string),
+        author_mail: author.email().map(str::to_string),
+        author_time: author.when().seconds(),
+        message: String::from_utf8_lossy(commit.message_bytes())
+            .trim_end()
+            .to_string(),
+    }
+}
+
+/// Returns the id of the blob of the file at the given path in a commit, if it exists.
+fn commit_blob_id(commit: &git2::Commit, path: &Path) -> Result<Option<git2::Oid>> {
+    match commit.tree()?.get_path(path) {
+        Ok(entry) => Ok(Some(entry.id())),
+        Err(error) if error.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn commit_text(
+    repo: &LibGitRepository,
+    commit: &git2::Commit,
+    path: &Path,
+) -> Result<Option<String>> {
+    let Some(blob_id) = commit_blob_id(commit, path)? else {
+        return Ok(None);
+    };
+    let content = repo.find_blob(blob_id)?.content().to_owned();
+    Ok(Some(String::from_utf8(content)?))
+}
+
+/// Runs the hook with the given name, if it exists. Recent versions of git run the hook
+/// themselves, the same way they do on the command line; otherwise, it's run from
+/// `core.hooksPath` or `.git/hooks` if it is executable.
+fn run_git_hook(
+    repo: &LibGitRepository,
+    work_directory: &Path,
+    hook: &str,
+    args: &[&OsStr],
+) -> Result<()> {
+    let mut command = if *GIT_SUPPORTS_HOOK_RUN {
+        let mut command = std::process::Command::new("git");
+        command
+            .args(["hook", "run", "--ignore-missing", hook, "--"])
+            .args(args);
+        command
+    } else {
+        let hooks_path = match repo.config()?.get_path("core.hooksPath") {
+            Ok(hooks_path) => work_directory.join(hooks_path),
+            Err(error) if error.code() == git2::ErrorCode::NotFound => repo.path().join("hooks"),
+            Err(error) => return Err(error.into()),
+        };
+        let hook_path = hooks_path.join(hook);
+        if !is_executable(&hook_path) {
+            return Ok(());
+        }
+        let mut command = hook_command(&hook_path);
+        command.args(args);
+        command
+    };
+
+    let output = command
+        .current_dir(work_directory)
+        .output()
+        .with_context(|| format!("running {hook} hook"))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{hook} hook failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// The first version of git whose `git hook run` accepts `--ignore-missing`.
+const GIT_HOOK_RUN_MIN_VERSION: (u32, u32) = (2, 36);
+
+lazy_static::lazy_static! {
+    static ref GIT_SUPPORTS_HOOK_RUN: bool = std::process::Command::new("git")
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| parse_git_version(&String::from_utf8_lossy(&output.stdout)))
+        .map_or(false, |version| version >= GIT_HOOK_RUN_MIN_VERSION);
+}
+
+/// Parses the major and minor version from the output of `git --version`, e.g.
+/// `git version 2.43.0.windows.1`.
+fn parse_git_version(output: &str) -> Option<(u32, u32)> {
+    let mut parts = output.trim().strip_prefix("git version ")?.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+#[cfg(unix)]
+fn hook_command(hook_path: &Path) -> std::process::Command {
+    std::process::Command::new(hook_path)
+}
+
+/// Hooks are usually shell scripts, which Windows can't run directly, so run them through `sh`
+/// like Git for Windows does.
+#[cfg(not(unix))]
+fn hook_command(hook_path: &Path) -> std::process::Command {
+    let mut command = std::process::Command::new("sh");
+    command.arg(hook_path);
+    command
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Appends a `Signed-off-by` trailer with the given identity to a commit message, unless
+/// the message already ends with it.
+pub fn append_signoff(message: &str, name: &str, email: &str) -> String {
+    let message = message.trim_end();
+    let trailer = format!("Signed-off-by: {name} <{email}>");
+    let last_line = message.lines().last().unwrap_or_default();
+    if last_line == trailer {
+        message.to_string()
+    } else if last_line.starts_with("Signed-off-by: ") {
+        format!("{message}\n{trailer}")
+    } else if message.is_empty() {
+        trailer
+    } else {
+        format!("{message}\n\n{trailer}")
+    }
+}
+
+fn read_staged_status(status: git2::Status) -> Option<GitFileStatus> {
+    if status.contains(git2::Status::INDEX_NEW) {
+        Some(GitFileStatus::Added)
+    } else if status.intersects(
+        git2::Status::INDEX_MODIFIED
+            | git2::Status::INDEX_DELETED
+            | git2::Status::INDEX_RENAMED
+            | git2::Status::INDEX_TYPECHANGE,
+    ) {
+        Some(GitFileStatus::Modified)
+    } else {
+        None
+    }
+}
+
+fn read_unstaged_status(status: git2::Status) -> Option<GitFileStatus> {
+    if status.contains(git2::Status::WT_NEW) {
+        Some(GitFileStatus::Added)
+    } else if status.intersects(
+        git2::Status::WT_MODIFIED
+            | git2::Status::WT_DELETED
+            | git2::Status::WT_RENAMED
+            | git2::Status::WT_TYPECHANGE,
+    ) {
+        Some(GitFileStatus::Modified)
+    } else {
+        None
+    }
+}
+
+/// A change of the rows of a text, from the `old` rows to the `new` ones.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowChange {
+    pub old: Range<u32>,
+    pub new: Range<u32>,
+}
+
+/// The rows of a hunk of a patch, whose start is 1-based, unless the hunk has no rows.
+fn patch_hunk_rows(start: u32, len: u32) -> Range<u32> {
+    let start = if len == 0 { start } else { start - 1 };
+    start..start + len
+}
+
+/// Returns the changes of the rows of the line diff from the `old` text to the `new` one.
+pub fn diff_rows(old: &str, new: &str) -> Result<Vec<RowChange>> {
+    let mut diff_options = git2::DiffOptions::new();
+    diff_options.context_lines(0);
+    let patch = git2::Patch::from_buffers(
+        old.as_bytes(),
+        None,
+        new.as_bytes(),
+        None,
+        Some(&mut diff_options),
+    )?;
+    let mut changes = Vec::with_capacity(patch.num_hunks());
+    for hunk_ix in 0..patch.num_hunks() {
+        let (hunk, _) = patch.hunk(hunk_ix)?;
+        changes.push(RowChange {
+            old: patch_hunk_rows(hunk.old_start(), hunk.old_lines()),
+            new: patch_hunk_rows(hunk.new_start(), hunk.new_lines()),
+        });
+    }
+    Ok(changes)
+}
+
+/// Returns the text of the index after staging the hunks of the diff between the index and
+/// the buffer that intersect the given rows of the buffer.
+pub fn stage_hunks_in_rows(
+    index_text: &str,
+    buffer_text: &str,
+    rows: &[Range<u32>],
+) -> Result<String> {
+    let changes = diff_rows(index_text, buffer_text)?;
+    Ok(apply_row_changes(
+        index_text,
+        buffer_text,
+        changes
+            .iter()
+            .filter(|change| intersects_rows(&change.new, rows))
+            .cloned(),
+    ))
+}
+
+/// Returns the text of the index after unstaging the hunks of the diff between HEAD and the
+/// index that intersect the given rows of the buffer.
+pub fn unstage_hunks_in_rows(
+    head_text: &str,
+    index_text: &str,
+    buffer_text: &str,
+    rows: &[Range<u32>],
+) -> Result<String> {
+    let unstaged_changes = diff_rows(index_text, buffer_text)?;
+    let index_rows = rows
+        .iter()
+        .map(|rows| old_rows(&unstaged_changes, rows))
+        .collect::<Vec<_>>();
+    let staged_changes = diff_rows(head_text, index_text)?;
+    // Reverting the staged changes replaces the rows of the index with the ones of HEAD.
+    Ok(apply_row_changes(
+        index_text,
+        head_text,
+        staged_changes
+            .iter()
+            .filter(|change| intersects_rows(&change.new, &index_rows))
+            .map(|change| RowChange {
+                old: change.new.clone(),
+                new: change.old.clone(),
+            }),
+    ))
+}
+
+/// Whether the rows of a change intersect any of the given rows. A change without rows
+/// intersects the rows that end or start where it is.
+fn intersects_rows(change_rows: &Range<u32>, rows: &[Range<u32>]) -> bool {
+    rows.iter().any(|rows| {
+        if change_rows.is_empty() {
+            rows.start <= change_rows.start && change_rows.start <= rows.end
+        } else {
+            change_rows.start < rows.end && rows.start < change_rows.end
+        }
+    })
+}
+
+/// Maps a row of the new text of the given changes to a row of their old text. A row within
+/// a change is mapped to the start of its old rows, or to their end if `bias_end` is set.
+fn old_row(changes: &[RowChange], new_row: u32, bias_end: bool) -> u32 {
+    let mut delta = 0_i64;
+    for change in changes {
+        if new_row < change.new.start {
+            break;
+        }
+        if new_row < change.new.end {
+            return if bias_end {
+                change.old.end
+            } else {
+                change.old.start
+            };
+        }
+        delta = change.old.end as i64 - change.new.end as i64;
+    }
+    (new_row as i64 + delta) as u32
+}
+
+/// Maps rows of the new text of the given changes to rows of their old text, including the old
+/// rows of the changes they intersect.
+pub fn old_rows(changes: &[RowChange], rows: &Range<u32>) -> Range<u32> {
+    old_row(changes, rows.start, false)..old_row(changes, rows.end, true)
+}
+
+/// Moves rows of a file in a commit to the rows of the file in the commit's parent, and returns
+/// whether the commit changed them. A commit without the file in its parent added all the rows.
+pub fn trace_rows_to_parent(
+    parent_text: Option<&str>,
+    text: &str,
+    rows: &mut Range<u32>,
+) -> Result<bool> {
+    let Some(parent_text) = parent_text else {
+        return Ok(true);
+    };
+    let changes = diff_rows(parent_text, text)?;
+    let changed = changes
+        .iter()
+        .any(|change| intersects_rows(&change.new, std::slice::from_ref(rows)));
+    *rows = old_rows(&changes, rows);
+    Ok(changed)
+}
+
+/// Replaces the old rows of the given changes, sorted by row, with their new rows.
+fn apply_row_changes(
+    old_text: &str,
+    new_text: &str,
+    changes: impl Iterator<Item = RowChange>,
+) -> String {
+    fn row_starts(text: &str) -> Vec<usize> {
+        let mut starts = vec![0];
+        starts.extend(text.match_indices('\n').map(|(ix, _)| ix + 1));
+        starts
+    }
+    fn byte_range(text: &str, row_starts: &[usize], rows: &Range<u32>) -> Range<usize> {
+        let offset = |row: u32| row_starts.get(row as usize).copied().unwrap_or(text.len());
+        offset(rows.start)..offset(rows.end)
+    }
+
+    let old_row_starts = row_starts(old_text);
+    let new_row_starts = row_starts(new_text);
+    let mut result = String::with_capacity(old_text.len());
+    let mut old_ix = 0;
+    for change in changes {
+        let old_range = byte_range(old_text, &old_row_starts, &change.old);
+        result.push_str(&old_text[old_ix..old_range.start]);
+        result.push_str(&new_text[byte_range(new_text, &new_row_starts, &change.new)]);
+        old_ix = old_range.end;
+    }
+    result.push_str(&old_text[old_ix..]);
+    result
+}
+
+/// Moves the entries of a blame through the given changes, sorted by row. The changed rows
+/// are left out of the entries, since they are not committed yet.
+pub fn remap_blame_entries(entries: Vec<BlameEntry>, changes: &[RowChange]) -> Vec<BlameEntry> {
+    let mut remapped = Vec::with_capacity(entries.len());
+    let mut change_ix = 0;
+    let mut delta = 0_i64;
+    let mut push = |entry: &BlameEntry, rows: Range<u32>, delta: i64| {
+        remapped.push(BlameEntry {
+            range: (rows.start as i64 + delta) as u32..(rows.end as i64 + delta) as u32,
+            original_row: entry.original_row + (rows.start - entry.range.start),
+            ..entry.clone()
+        })
+    };
+
+    for entry in entries {
+        let mut start = entry.range.start;
+        while let Some(change) = changes.get(change_ix) {
+            if change.old.start >= entry.range.end {
+                break;
+            }
+            if start < change.old.start {
+                push(&entry, start..change.old.start, delta);
+            }
+            start = start.max(change.old.end);
+            // A change spanning several entries is skipped once it has been applied to all of them.
+            if change.old.end > entry.range.end {
+                break;
+            }
+            delta = change.new.end as i64 - change.old.end as i64;
+            change_ix += 1;
+        }
+        if start < entry.range.end {
+            push(&entry, start..entry.range.end, delta);
+        }
+    }
+    remapped
 }
 
 fn matches_index(repo: &LibGitRepository, path: &RepoPath, mtime: SystemTime) -> bool {
@@ -250,9 +1121,40 @@ pub struct FakeGitRepository {
 
 #[derive(Debug, Clone, Default)]
 pub struct FakeGitRepositoryState {
+    /// The path of the repository's `.git` directory.
+    pub path: PathBuf,
+    /// Where the repository reports the changes it makes to itself, like a real repository's
+    /// `.git` directory being written to.
+    pub event_emitter: Option<smol::channel::Sender<PathBuf>>,
     pub index_contents: HashMap<PathBuf, String>,
+    pub head_contents: HashMap<PathBuf, String>,
+    /// The statuses of the files in the index.
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
+    /// The statuses of the files in the working directory, with respect to the index.
+    pub unstaged_statuses: HashMap<RepoPath, GitFileStatus>,
     pub branch_name: Option<String>,
+    pub blames: HashMap<RepoPath, Blame>,
+    /// The messages of the commits, the last one being HEAD.
+    pub commits: Vec<String>,
+    /// The commits listed by `log`, newest first, each one being the first parent of the
+    /// previous one, along with the contents of their files.
+    pub history: Vec<(CommitDetails, HashMap<RepoPath, String>)>,
+}
+
+impl FakeGitRepositoryState {
+    pub fn new(path: PathBuf, event_emitter: smol::channel::Sender<PathBuf>) -> Self {
+        Self {
+            path,
+            event_emitter: Some(event_emitter),
+            ..Default::default()
+        }
+    }
+
+    fn emit_event(&self) {
+        if let Some(event_emitter) = &self.event_emitter {
+            event_emitter.try_send(self.path.clone()).ok();
+        }
+    }
 }
 
 impl FakeGitRepository {
@@ -269,6 +1171,20 @@ impl GitRepository for FakeGitRepository {
         state.index_contents.get(path).cloned()
     }
 
+    fn load_head_text(&self, path: &Path) -> Option<String> {
+        let state = self.state.lock();
+        state.head_contents.get(path).cloned()
+    }
+
+    fn set_index_text(&self, path: &Path, text: Option<String>) -> Result<()> {
+        let mut state = self.state.lock();
+        match text {
+            Some(text) => state.index_contents.insert(path.to_path_buf(), text),
+            None => state.index_contents.remove(path),
+        };
+        Ok(())
+    }
+
     fn remote_url(&self, _name: &str) -> Option<String> {
         None
     }
@@ -293,13 +1209,18 @@ impl GitRepository for FakeGitRepository {
         map
     }
 
-    fn unstaged_status(&self, _path: &RepoPath, _mtime: SystemTime) -> Option<GitFileStatus> {
-        None
+    fn unstaged_status(&self, path: &RepoPath, _mtime: SystemTime) -> Option<GitFileStatus> {
+        let state = self.state.lock();
+        state.unstaged_statuses.get(path).cloned()
     }
 
     fn status(&self, path: &RepoPath, _mtime: SystemTime) -> Option<GitFileStatus> {
         let state = self.state.lock();
-        state.worktree_statuses.get(path).cloned()
+        GitFileStatus::merge(
+            state.worktree_statuses.get(path).cloned(),
+            state.unstaged_statuses.get(path).cloned(),
+            false,
+        )
     }
 
     fn branches(&self) -> Result<Vec<Branch>> {
@@ -317,6 +1238,140 @@ impl GitRepository for FakeGitRepository {
         state.branch_name = Some(name.to_owned());
         Ok(())
     }
+
+    fn blame(&self, path: &Path, _content: &str) -> Result<Blame> {
+        let state = self.state.lock();
+        state
+            .blames
+            .get(&RepoPath::from(path))
+            .cloned()
+            .with_context(|| format!("failed to get blame for {:?}", path))
+    }
+
+    fn file_statuses(&self) -> Result<Vec<GitStatusEntry>> {
+        let state = self.state.lock();
+        let mut paths = state
+            .worktree_statuses
+            .keys()
+            .chain(state.unstaged_statuses.keys())
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths.dedup();
+        Ok(paths
+            .into_iter()
+            .map(|path| GitStatusEntry {
+                repo_path: path.clone(),
+                staged: state.worktree_statuses.get(path).copied(),
+                unstaged: state.unstaged_statuses.get(path).copied(),
+            })
+            .collect())
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            if let Some(status) = state.unstaged_statuses.remove(path) {
+                state
+                    .worktree_statuses
+                    .entry(path.clone())
+                    .or_insert(status);
+            }
+        }
+        state.emit_event();
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            if let Some(status) = state.worktree_statuses.remove(path) {
+                state
+                    .unstaged_statuses
+                    .entry(path.clone())
+                    .or_insert(status);
+            }
+        }
+        state.emit_event();
+        Ok(())
+    }
+
+    fn head_commit_message(&self) -> Option<String> {
+        let state = self.state.lock();
+        state.commits.last().cloned()
+    }
+
+    fn commit(&self, message: &str, options: CommitOptions) -> Result<String> {
+        if message.trim().is_empty() {
+            anyhow::bail!("the commit message is empty");
+        }
+        let mut state = self.state.lock();
+        if options.amend {
+            state.commits.pop().context("there is no commit to amend")?;
+        } else if state.worktree_statuses.is_empty() {
+            anyhow::bail!("there are no staged changes to commit");
+        }
+        let mut message = message.trim_end().to_string();
+        if options.signoff {
+            message = append_signoff(&message, "Fake Committer", "fake@example.com");
+        }
+        state.commits.push(message);
+        state.head_contents = state.index_contents.clone();
+        state.worktree_statuses.clear();
+        state.emit_event();
+        Ok(format!("{:040x}", state.commits.len()))
+    }
+
+    fn log(&self, options: &LogOptions) -> Result<CommitLog> {
+        let state = self.state.lock();
+        let limit = options.limit.unwrap_or(usize::MAX);
+        let mut rows = options.rows.clone();
+        let max_walked_commits = options.max_walked_commits.unwrap_or(usize::MAX);
+        let mut commits = Vec::new();
+        let mut truncated = false;
+        for (ix, (commit, contents)) in state.history.iter().enumerate() {
+            if commits.len() >= limit {
+                break;
+            }
+            if ix == max_walked_commits {
+                truncated = true;
+                break;
+            }
+            let Some(path) = &options.path else {
+                commits.push(commit.clone());
+                continue;
+            };
+
+            let text = contents.get(path);
+            let parent_text = state
+                .history
+                .get(ix + 1)
+                .and_then(|(_, contents)| contents.get(path));
+            if let Some(rows) = rows.as_mut() {
+                let Some(text) = text else {
+                    break;
+                };
+                if trace_rows_to_parent(parent_text.map(String::as_str), text, rows)? {
+                    commits.push(commit.clone());
+                }
+                if parent_text.is_none() || rows.is_empty() {
+                    break;
+                }
+            } else if text != parent_text {
+                commits.push(commit.clone());
+            }
+        }
+        Ok(CommitLog { commits, truncated })
+    }
+
+    fn load_commit_text(&self, sha: &str, path: &Path) -> Result<Option<String>> {
+        let state = self.state.lock();
+        let (_, contents) = state
+            .history
+            .iter()
+            .find(|(commit, _)| commit.sha == sha)
+            .with_context(|| format!("commit {sha} not found"))?;
+        Ok(contents.get(&RepoPath::from(path)).cloned())
+    }
 }
 
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
@@ -435,3 +1490,231 @@ impl<'a> MapSeekTarget<RepoPath> for RepoPathDescendants<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_index_text_resets_stat_data() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = LibGitRepository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        let entry = repo
+            .index()
+            .unwrap()
+            .get_path(Path::new("a.txt"), 0)
+            .unwrap();
+        assert_ne!(entry.mtime.seconds(), 0);
+
+        repo.set_index_text(Path::new("a.txt"), Some("two\n".to_string()))
+            .unwrap();
+        let entry = repo
+            .index()
+            .unwrap()
+            .get_path(Path::new("a.txt"), 0)
+            .unwrap();
+        assert_eq!(
+            entry.id,
+            git2::Oid::hash_object(git2::ObjectType::Blob, b"two\n").unwrap()
+        );
+        assert_eq!(entry.mtime.seconds(), 0);
+        assert_eq!(entry.ctime.seconds(), 0);
+        assert_eq!(entry.ino, 0);
+        assert_eq!(entry.file_size, 4);
+    }
+
+    #[test]
+    fn test_stage_paths_keeps_changes_made_outside_of_the_repository() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = LibGitRepository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b\n").unwrap();
+        // Load the index before it's changed by another process, e.g. `git add a.txt`.
+        repo.index().unwrap();
+        let mut index = LibGitRepository::open(dir.path()).unwrap().index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+
+        repo.stage_paths(&[RepoPath::from(Path::new("b.txt"))])
+            .unwrap();
+        let index = LibGitRepository::open(dir.path()).unwrap().index().unwrap();
+        assert!(index.get_path(Path::new("a.txt"), 0).is_some());
+        assert!(index.get_path(Path::new("b.txt"), 0).is_some());
+    }
+
+    #[test]
+    fn test_log_reports_truncated_walks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = LibGitRepository::init(dir.path()).unwrap();
+        let signature = git2::Signature::now("Author", "author@example.com").unwrap();
+        for (ix, message) in ["one", "two", "three"].into_iter().enumerate() {
+            std::fs::write(dir.path().join("a.txt"), message).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("a.txt")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = (ix > 0).then(|| repo.head().unwrap().peel_to_commit().unwrap());
+            LibGitRepository::commit(
+                &repo,
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parent.iter().collect::<Vec<_>>(),
+            )
+            .unwrap();
+        }
+
+        let options = LogOptions {
+            path: Some(RepoPath::from(Path::new("a.txt"))),
+            max_walked_commits: Some(2),
+            ..Default::default()
+        };
+        let log = GitRepository::log(&repo, &options).unwrap();
+        assert_eq!(
+            log.commits
+                .iter()
+                .map(|commit| commit.summary())
+                .collect::<Vec<_>>(),
+            ["three", "two"]
+        );
+        assert!(log.truncated);
+
+        let options = LogOptions {
+            max_walked_commits: Some(3),
+            ..options
+        };
+        let log = GitRepository::log(&repo, &options).unwrap();
+        assert_eq!(log.commits.len(), 3);
+        assert!(!log.truncated);
+    }
+
+    #[test]
+    fn test_parse_git_version() {
+        assert_eq!(parse_git_version("git version 2.43.0\n"), Some((2, 43)));
+        assert_eq!(
+            parse_git_version("git version 2.39.3 (Apple Git-145)\n"),
+            Some((2, 39))
+        );
+        assert_eq!(
+            parse_git_version("git version 2.35.1.windows.2\n"),
+            Some((2, 35))
+        );
+        assert_eq!(parse_git_version("not git"), None);
+    }
+
+    #[test]
+    fn test_remap_blame_entries() {
+        fn entry(sha: &str, range: Range<u32>) -> BlameEntry {
+            BlameEntry {
+                sha: sha.to_string(),
+                original_row: range.start,
+                range,
+                author: None,
+                author_mail: None,
+                author_time: 0,
+                summary: None,
+            }
+        }
+
+        let entries = vec![entry("a", 0..3), entry("b", 3..6), entry("c", 6..10)];
+        let changes = [
+            // A row inserted after the first one.
+            RowChange {
+                old: 1..1,
+                new: 1..2,
+            },
+            // The last row of "a" and the first one of "b" replaced by three rows.
+            RowChange {
+                old: 2..4,
+                new: 3..6,
+            },
+            // Two rows of "c" removed.
+            RowChange {
+                old: 7..9,
+                new: 9..9,
+            },
+        ];
+        let remapped = remap_blame_entries(entries, &changes)
+            .into_iter()
+            .map(|entry| (entry.sha, entry.range, entry.original_row))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            remapped,
+            [
+                ("a".to_string(), 0..1, 0),
+                ("a".to_string(), 2..3, 1),
+                ("b".to_string(), 6..8, 4),
+                ("c".to_string(), 8..9, 6),
+                ("c".to_string(), 9..10, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trace_rows_to_parent() {
+        let parent_text = "one\ntwo\nthree\nfour\n";
+        let text = "zero\none\nTWO\nthree\nfour\n";
+
+        // Rows after a change are moved by it, without being changed.
+        let mut rows = 3..5;
+        assert!(!trace_rows_to_parent(Some(parent_text), text, &mut rows).unwrap());
+        assert_eq!(rows, 2..4);
+
+        // Rows within a change are extended over its old rows.
+        let mut rows = 1..3;
+        assert!(trace_rows_to_parent(Some(parent_text), text, &mut rows).unwrap());
+        assert_eq!(rows, 0..2);
+
+        // The commit that added the file changed all of its rows.
+        let mut rows = 3..4;
+        assert!(trace_rows_to_parent(None, text, &mut rows).unwrap());
+        assert_eq!(rows, 3..4);
+    }
+
+    #[test]
+    fn test_stage_and_unstage_hunks_in_rows() {
+        let index_text = "one\ntwo\nthree\nfour\n";
+        let buffer_text = "one\nTWO\nthree\nfour\nfive\n";
+        assert_eq!(
+            stage_hunks_in_rows(index_text, buffer_text, &[1..2]).unwrap(),
+            "one\nTWO\nthree\nfour\n"
+        );
+        assert_eq!(
+            stage_hunks_in_rows(index_text, buffer_text, &[4..5]).unwrap(),
+            "one\ntwo\nthree\nfour\nfive\n"
+        );
+        assert_eq!(
+            stage_hunks_in_rows(index_text, buffer_text, &[0..5]).unwrap(),
+            buffer_text
+        );
+
+        // Deleted rows are staged from the rows just above or below them.
+        assert_eq!(
+            stage_hunks_in_rows(index_text, "one\nthree\nfour\n", &[0..1]).unwrap(),
+            "one\nthree\nfour\n"
+        );
+
+        // The rows of the buffer are mapped to the rows of the index through the unstaged changes.
+        let head_text = "one\ntwo\nthree\nfour\n";
+        let index_text = "one\nTWO\nthree\nfour\nfive\n";
+        let buffer_text = "zero\none\nTWO\nthree\nfour\nfive\n";
+        assert_eq!(
+            unstage_hunks_in_rows(head_text, index_text, buffer_text, &[2..3]).unwrap(),
+            "one\ntwo\nthree\nfour\nfive\n"
+        );
+        assert_eq!(
+            unstage_hunks_in_rows(head_text, index_text, buffer_text, &[5..6]).unwrap(),
+            "one\nTWO\nthree\nfour\n"
+        );
+        assert_eq!(
+            unstage_hunks_in_rows(head_text, index_text, buffer_text, &[0..1]).unwrap(),
+            index_text
+        );
+    }
+}
@@ -0,0 +1,819 @@
+mod git_panel_settings;
+
+use anyhow::{anyhow, Result};
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use git_panel_settings::GitPanelSettings;
+use gpui::{
+    actions, Action, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView,
+    Model, Pixels, Render, SharedString, Subscription, Task, View, ViewContext, VisualContext as _,
+    WeakView, WindowContext,
+};
+use parking_lot::Mutex;
+use project::{
+    repository::{CommitOptions, GitFileStatus, GitRepository, GitStatusEntry, RepoPath},
+    Fs, Project, ProjectPath, WorktreeId,
+};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::{path::Path, sync::Arc};
+use ui::{prelude::*, CheckboxWithLabel, KeyBinding, ListHeader, ListItem, Selection, Tooltip};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+const GIT_PANEL_KEY: &str = "GitPanel";
+
+actions!(git_panel, [ToggleFocus, Commit]);
+
+pub fn init_settings(cx: &mut AppContext) {
+    GitPanelSettings::register(cx);
+}
+
+pub fn init(cx: &mut AppContext) {
+    init_settings(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<GitPanel>(cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedGitPanel {
+    width: Option<Pixels>,
+}
+
+/// A panel listing the changes of the project's git repositories, to stage and commit them.
+pub struct GitPanel {
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    workspace: WeakView<Workspace>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    repositories: Vec<GitPanelRepository>,
+    selected_repository: Option<(WorktreeId, Arc<Path>)>,
+    commit_editor: View<Editor>,
+    amend: bool,
+    signoff: bool,
+    pending_commit: bool,
+    error: Option<SharedString>,
+    pending_serialization: Task<Option<()>>,
+    refresh_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// A git repository of one of the project's local worktrees, with the changes of its files.
+struct GitPanelRepository {
+    worktree_id: WorktreeId,
+    /// The path of the repository's working directory, relative to the worktree's root.
+    work_directory: Arc<Path>,
+    name: SharedString,
+    branch: Option<Arc<str>>,
+    repo: Arc<Mutex<dyn GitRepository>>,
+    statuses: Vec<GitStatusEntry>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Section {
+    Staged,
+    Unstaged,
+    Untracked,
+}
+
+impl Section {
+    const ALL: [Section; 3] = [Section::Staged, Section::Unstaged, Section::Untracked];
+
+    fn label(self) -> &'static str {
+        match self {
+            Section::Staged => "Staged Changes",
+            Section::Unstaged => "Changes",
+            Section::Untracked => "Untracked Files",
+        }
+    }
+
+    fn contains(self, entry: &GitStatusEntry) -> bool {
+        match self {
+            Section::Staged => entry.staged.is_some(),
+            Section::Unstaged => entry.unstaged.is_some() && !entry.is_untracked(),
+            Section::Untracked => entry.is_untracked(),
+        }
+    }
+
+    fn status(self, entry: &GitStatusEntry) -> Option<GitFileStatus> {
+        match self {
+            Section::Staged => entry.staged,
+            Section::Unstaged | Section::Untracked => entry.unstaged,
+        }
+    }
+}
+
+impl GitPanel {
+    fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace_handle = cx.view().downgrade();
+        cx.new_view(|cx: &mut ViewContext<Self>| {
+            let commit_editor = cx.new_view(|cx| {
+                let mut editor = Editor::auto_height(8, cx);
+                editor.set_placeholder_text("Commit message", cx);
+                editor
+            });
+            let subscriptions = vec![cx.subscribe(&project, |this, _, event, cx| match event {
+                project::Event::WorktreeAdded
+                | project::Event::WorktreeRemoved(_)
+                | project::Event::WorktreeUpdatedEntries(_, _)
+                | project::Event::WorktreeUpdatedGitRepositories(_) => this.refresh(cx),
+                _ => {}
+            })];
+
+            let mut this = Self {
+                project,
+                fs,
+                workspace: workspace_handle,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                repositories: Vec::new(),
+                selected_repository: None,
+                commit_editor,
+                amend: false,
+                signoff: false,
+                pending_commit: false,
+                error: None,
+                pending_serialization: Task::ready(None),
+                refresh_task: Task::ready(()),
+                _subscriptions: subscriptions,
+            };
+            this.refresh(cx);
+            this
+        })
+    }
+
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<View<Self>> {
+        let serialized_panel = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(GIT_PANEL_KEY) })
+            .await
+            .map_err(|e| anyhow!("Failed to load git panel: {}", e))
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedGitPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = GitPanel::new(workspace, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        GIT_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedGitPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    /// Reads the statuses of the files of the repositories in the project's local worktrees.
+    fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        let mut repositories = Vec::new();
+        for worktree in self.project.read(cx).visible_worktrees(cx) {
+            let worktree = worktree.read(cx);
+            let Some(local_worktree) = worktree.as_local() else {
+                continue;
+            };
+            let snapshot = local_worktree.snapshot();
+            for (work_directory, repository_entry) in snapshot.repositories() {
+                let Some(repo) = snapshot.local_git_repo(work_directory) else {
+                    continue;
+                };
+                let name = if work_directory.as_os_str().is_empty() {
+                    worktree.root_name().to_string()
+                } else {
+                    Path::new(worktree.root_name())
+                        .join(work_directory)
+                        .to_string_lossy()
+                        .into_owned()
+                };
+                repositories.push((
+                    worktree.id(),
+                    work_directory.clone(),
+                    name,
+                    repository_entry.branch(),
+                    repo,
+                ));
+            }
+        }
+
+        self.refresh_task = cx.spawn(|this, mut cx| async move {
+            let repositories = cx
+                .background_executor()
+                .spawn(async move {
+                    repositories
+                        .into_iter()
+                        .map(|(worktree_id, work_directory, name, branch, repo)| {
+                            let statuses = repo.lock().file_statuses().log_err();
+                            GitPanelRepository {
+                                worktree_id,
+                                work_directory,
+                                name: name.into(),
+                                branch,
+                                repo,
+                                statuses: statuses.unwrap_or_default(),
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.repositories = repositories;
+                if this.active_repository().is_none() {
+                    this.selected_repository = this.repositories.first().map(|repository| {
+                        (repository.worktree_id, repository.work_directory.clone())
+                    });
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn active_repository(&self) -> Option<&GitPanelRepository> {
+        let (worktree_id, work_directory) = self.selected_repository.as_ref()?;
+        self.repositories.iter().find(|repository| {
+            repository.worktree_id == *worktree_id && repository.work_directory == *work_directory
+        })
+    }
+
+    fn select_repository(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if let Some(repository) = self.repositories.get(ix) {
+            self.selected_repository =
+                Some((repository.worktree_id, repository.work_directory.clone()));
+            self.error = None;
+            cx.notify();
+        }
+    }
+
+    fn paths_in_section(&self, section: Section) -> Vec<RepoPath> {
+        self.active_repository()
+            .map(|repository| {
+                repository
+                    .statuses
+                    .iter()
+                    .filter(|entry| section.contains(entry))
+                    .map(|entry| entry.repo_path.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn stage_paths(&mut self, paths: Vec<RepoPath>, cx: &mut ViewContext<Self>) {
+        self.update_index(paths, true, cx);
+    }
+
+    fn unstage_paths(&mut self, paths: Vec<RepoPath>, cx: &mut ViewContext<Self>) {
+        self.update_index(paths, false, cx);
+    }
+
+    fn update_index(&mut self, paths: Vec<RepoPath>, stage: bool, cx: &mut ViewContext<Self>) {
+        let Some(repository) = self.active_repository() else {
+            return;
+        };
+        if paths.is_empty() {
+            return;
+        }
+        let repo = repository.repo.clone();
+        cx.spawn(|this, mut cx| async move {
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    let repo = repo.lock();
+                    if stage {
+                        repo.stage_paths(&paths)
+                    } else {
+                        repo.unstage_paths(&paths)
+                    }
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.error = result.err().map(|error| format!("{error:#}").into());
+                this.refresh(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn set_amend(&mut self, amend: bool, cx: &mut ViewContext<Self>) {
+        self.amend = amend;
+        // Start from the message of the commit being amended, as `git commit --amend` does.
+        if amend && self.commit_editor.read(cx).text(cx).trim().is_empty() {
+            if let Some(message) = self
+                .active_repository()
+                .and_then(|repository| repository.repo.lock().head_commit_message())
+            {
+                self.commit_editor.update(cx, |editor, cx| {
+                    editor.set_text(message.trim_end(), cx);
+                });
+            }
+        }
+        cx.notify();
+    }
+
+    fn set_signoff(&mut self, signoff: bool, cx: &mut ViewContext<Self>) {
+        self.signoff = signoff;
+        cx.notify();
+    }
+
+    fn can_commit(&self, cx: &AppContext) -> bool {
+        let Some(repository) = self.active_repository() else {
+            return false;
+        };
+        !self.pending_commit
+            && !self.commit_editor.read(cx).text(cx).trim().is_empty()
+            && (self.amend
+                || repository
+                    .statuses
+                    .iter()
+                    .any(|entry| entry.staged.is_some()))
+    }
+
+    fn commit(&mut self, _: &Commit, cx: &mut ViewContext<Self>) {
+        if !self.can_commit(cx) {
+            return;
+        }
+        let Some(repository) = self.active_repository() else {
+            return;
+        };
+        let repo = repository.repo.clone();
+        let message = self.commit_editor.read(cx).text(cx);
+        let options = CommitOptions {
+            amend: self.amend,
+            signoff: self.signoff,
+        };
+
+        self.pending_commit = true;
+        cx.notify();
+        cx.spawn(|this, mut cx| async move {
+            let result = cx
+                .background_executor()
+                .spawn(async move { repo.lock().commit(&message, options) })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_commit = false;
+                match result {
+                    Ok(_) => {
+                        this.error = None;
+                        this.amend = false;
+                        this.commit_editor.update(cx, |editor, cx| editor.clear(cx));
+                    }
+                    Err(error) => this.error = Some(format!("{error:#}").into()),
+                }
+                this.refresh(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_entry(&mut self, repo_path: &RepoPath, cx: &mut ViewContext<Self>) {
+        let Some(repository) = self.active_repository() else {
+            return;
+        };
+        let project_path = ProjectPath {
+            worktree_id: repository.worktree_id,
+            path: repository.work_directory.join(repo_path).into(),
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn render_repositories(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().children(
+            self.repositories
+                .iter()
+                .enumerate()
+                .map(|(ix, repository)| {
+                    let is_selected = self.active_repository().map_or(false, |active| {
+                        active.worktree_id == repository.worktree_id
+                            && active.work_directory == repository.work_directory
+                    });
+                    ListItem::new(("repository", ix))
+                        .selected(is_selected && self.repositories.len() > 1)
+                        .start_slot(Icon::new(IconName::FileGit).color(Color::Muted))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(Label::new(repository.name.clone()))
+                                .children(repository.branch.as_ref().map(|branch| {
+                                    Label::new(branch.to_string())
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted)
+                                })),
+                        )
+                        .on_click(cx.listener(move |this, _, cx| this.select_repository(ix, cx)))
+                }),
+        )
+    }
+
+    fn render_section(
+        &self,
+        section: Section,
+        repository: &GitPanelRepository,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<impl IntoElement> {
+        let entries = repository
+            .statuses
+            .iter()
+            .filter(|entry| section.contains(entry))
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return None;
+        }
+
+        let is_staged = section == Section::Staged;
+        let header_button = if is_staged {
+            IconButton::new(("unstage-all", section as usize), IconName::Dash)
+                .tooltip(|cx| Tooltip::text("Unstage All", cx))
+                .on_click(cx.listener(move |this, _, cx| {
+                    let paths = this.paths_in_section(section);
+                    this.unstage_paths(paths, cx);
+                }))
+        } else {
+            IconButton::new(("stage-all", section as usize), IconName::Plus)
+                .tooltip(|cx| Tooltip::text("Stage All", cx))
+                .on_click(cx.listener(move |this, _, cx| {
+                    let paths = this.paths_in_section(section);
+                    this.stage_paths(paths, cx);
+                }))
+        };
+
+        Some(
+            v_flex()
+                .child(
+                    ListHeader::new(format!("{} ({})", section.label(), entries.len()))
+                        .end_slot(header_button),
+                )
+                .children(entries.into_iter().enumerate().map(|(ix, entry)| {
+                    let repo_path = entry.repo_path.clone();
+                    let color = match section.status(entry) {
+                        Some(GitFileStatus::Added) => Color::Created,
+                        Some(GitFileStatus::Modified) => Color::Modified,
+                        Some(GitFileStatus::Conflict) => Color::Conflict,
+                        None => Color::Default,
+                    };
+                    let button = if is_staged {
+                        IconButton::new(("unstage", ix), IconName::Dash)
+                            .tooltip(|cx| Tooltip::text("Unstage", cx))
+                            .on_click(cx.listener({
+                                let repo_path = repo_path.clone();
+                                move |this, _, cx| this.unstage_paths(vec![repo_path.clone()], cx)
+                            }))
+                    } else {
+                        IconButton::new(("stage", ix), IconName::Plus)
+                            .tooltip(|cx| Tooltip::text("Stage", cx))
+                            .on_click(cx.listener({
+                                let repo_path = repo_path.clone();
+                                move |this, _, cx| this.stage_paths(vec![repo_path.clone()], cx)
+                            }))
+                    };
+
+                    ListItem::new(SharedString::from(format!(
+                        "{}-{}",
+                        section.label(),
+                        repo_path.to_string_lossy()
+                    )))
+                    .inset(true)
+                    .child(Label::new(repo_path.to_string_lossy().into_owned()).color(color))
+                    .end_hover_slot(button)
+                    .on_click(cx.listener(move |this, _, cx| this.open_entry(&repo_path, cx)))
+                })),
+        )
+    }
+
+    fn render_commit_controls(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let can_commit = self.can_commit(cx);
+        v_flex()
+            .p_2()
+            .gap_2()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                div()
+                    .p_1()
+                    .border_1()
+                    .rounded_md()
+                    .border_color(cx.theme().colors().border_variant)
+                    .bg(cx.theme().colors().editor_background)
+                    .child(self.commit_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .gap_4()
+                    .child(CheckboxWithLabel::new(
+                        "amend",
+                        Label::new("Amend"),
+                        selection(self.amend),
+                        cx.listener(|this, selection: &Selection, cx| {
+                            this.set_amend(*selection == Selection::Selected, cx)
+                        }),
+                    ))
+                    .child(CheckboxWithLabel::new(
+                        "signoff",
+                        Label::new("Sign Off"),
+                        selection(self.signoff),
+                        cx.listener(|this, selection: &Selection, cx| {
+                            this.set_signoff(*selection == Selection::Selected, cx)
+                        }),
+                    )),
+            )
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| Label::new(error).size(LabelSize::Small).color(Color::Error)),
+            )
+            .child(
+                Button::new("commit", if self.amend { "Amend Commit" } else { "Commit" })
+                    .style(ButtonStyle::Filled)
+                    .full_width()
+                    .disabled(!can_commit)
+                    .key_binding(KeyBinding::for_action_in(
+                        &Commit,
+                        &self.commit_editor.focus_handle(cx),
+                        cx,
+                    ))
+                    .on_click(cx.listener(|this, _, cx| this.commit(&Commit, cx))),
+            )
+    }
+}
+
+fn selection(checked: bool) -> Selection {
+    if checked {
+        Selection::Selected
+    } else {
+        Selection::Unselected
+    }
+}
+
+impl Render for GitPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = if let Some(repository) = self.active_repository() {
+            let sections = Section::ALL
+                .into_iter()
+                .filter_map(|section| self.render_section(section, repository, cx))
+                .collect::<Vec<_>>();
+            let is_clean = sections.is_empty();
+            v_flex()
+                .id("git-panel-entries")
+                .flex_1()
+                .overflow_y_scroll()
+                .child(self.render_repositories(cx))
+                .children(sections)
+                .when(is_clean, |this| {
+                    this.child(
+                        div()
+                            .p_2()
+                            .child(Label::new("No changes").color(Color::Muted)),
+                    )
+                })
+                .into_any_element()
+        } else {
+            v_flex()
+                .flex_1()
+                .p_4()
+                .child(Label::new("No git repositories in this project").color(Color::Muted))
+                .into_any_element()
+        };
+
+        v_flex()
+            .key_context("GitPanel")
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::commit))
+            .child(content)
+            .when(self.active_repository().is_some(), |this| {
+                this.child(self.render_commit_controls(cx))
+            })
+    }
+}
+
+impl EventEmitter<PanelEvent> for GitPanel {}
+
+impl Panel for GitPanel {
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        GitPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<GitPanelSettings>(self.fs.clone(), cx, move |settings| {
+            settings.dock = Some(position)
+        });
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| GitPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        Some(IconName::FileGit).filter(|_| GitPanelSettings::get_global(cx).button)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Git Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn persistent_name() -> &'static str {
+        "Git Panel"
+    }
+}
+
+impl FocusableView for GitPanel {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_staging_and_committing(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                ".git": {},
+                "a.txt": "a",
+                "b.txt": "b",
+                "c.txt": "c",
+            }),
+        )
+        .await;
+        let dot_git = Path::new("/root/.git");
+        fs.with_git_state(dot_git, true, |state| {
+            state.branch_name = Some("main".into());
+            state.commits.push("Initial commit".into());
+            state
+                .worktree_statuses
+                .insert(Path::new("a.txt").into(), GitFileStatus::Modified);
+            state
+                .unstaged_statuses
+                .insert(Path::new("b.txt").into(), GitFileStatus::Modified);
+            state
+                .unstaged_statuses
+                .insert(Path::new("c.txt").into(), GitFileStatus::Added);
+        });
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| {
+                let panel = GitPanel::new(workspace, cx);
+                workspace.add_panel(panel.clone(), cx);
+                panel
+            })
+            .unwrap();
+        cx.run_until_parked();
+
+        panel.update(cx, |panel, _| {
+            let repository = panel.active_repository().unwrap();
+            assert_eq!(&*repository.name, "root");
+            assert_eq!(repository.branch.as_deref(), Some("main"));
+            assert_eq!(section_paths(panel, Section::Staged), ["a.txt"]);
+            assert_eq!(section_paths(panel, Section::Unstaged), ["b.txt"]);
+            assert_eq!(section_paths(panel, Section::Untracked), ["c.txt"]);
+        });
+
+        panel.update(cx, |panel, cx| {
+            panel.stage_paths(vec![Path::new("b.txt").into()], cx);
+            panel.unstage_paths(vec![Path::new("a.txt").into()], cx);
+        });
+        cx.run_until_parked();
+        panel.update(cx, |panel, _| {
+            assert_eq!(section_paths(panel, Section::Staged), ["b.txt"]);
+            assert_eq!(section_paths(panel, Section::Unstaged), ["a.txt"]);
+        });
+
+        // Nothing is committed without a message.
+        panel.update(cx, |panel, cx| panel.commit(&Commit, cx));
+        cx.run_until_parked();
+        assert_eq!(commits(&fs, dot_git), ["Initial commit"]);
+
+        panel.update(cx, |panel, cx| {
+            panel
+                .commit_editor
+                .update(cx, |editor, cx| editor.set_text("Change b", cx));
+            panel.set_signoff(true, cx);
+            panel.commit(&Commit, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            commits(&fs, dot_git),
+            [
+                "Initial commit",
+                "Change b\n\nSigned-off-by: Fake Committer <fake@example.com>"
+            ]
+        );
+        panel.update(cx, |panel, cx| {
+            assert_eq!(panel.commit_editor.read(cx).text(cx), "");
+            assert!(section_paths(panel, Section::Staged).is_empty());
+            assert_eq!(section_paths(panel, Section::Unstaged), ["a.txt"]);
+        });
+
+        // Amending starts from the message of the last commit, and replaces that commit.
+        panel.update(cx, |panel, cx| {
+            panel.set_signoff(false, cx);
+            panel.set_amend(true, cx);
+            assert_eq!(
+                panel.commit_editor.read(cx).text(cx),
+                "Change b\n\nSigned-off-by: Fake Committer <fake@example.com>"
+            );
+            panel
+                .commit_editor
+                .update(cx, |editor, cx| editor.set_text("Change b again", cx));
+            panel.commit(&Commit, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(commits(&fs, dot_git), ["Initial commit", "Change b again"]);
+        panel.update(cx, |panel, _| {
+            assert!(!panel.amend);
+            assert!(panel.error.is_none());
+        });
+    }
+
+    fn section_paths(panel: &GitPanel, section: Section) -> Vec<String> {
+        panel
+            .paths_in_section(section)
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn commits(fs: &FakeFs, dot_git: &Path) -> Vec<String> {
+        let mut commits = Vec::new();
+        fs.with_git_state(dot_git, false, |state| commits = state.commits.clone());
+        commits
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let app_state = AppState::test(cx);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init(app_state, cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+        });
+    }
+}
@@ -13,10 +13,11 @@ use crate::{
         SyntaxLayer, SyntaxMap, SyntaxMapCapture, SyntaxMapCaptures, SyntaxMapMatches,
         SyntaxSnapshot, ToTreeSitterPoint,
     },
-    CodeLabel, LanguageScope, Outline,
+    CodeLabel, FoldRangeKind, LanguageScope, Outline, RunnableRange,
 };
 use anyhow::{anyhow, Context, Result};
 pub use clock::ReplicaId;
+use collections::HashMap;
 use futures::channel::oneshot;
 use gpui::{AppContext, EventEmitter, HighlightStyle, ModelContext, Task, TaskLabel};
 use lazy_static::lazy_static;
@@ -98,6 +99,7 @@ pub struct Buffer {
     sync_parse_timeout: Duration,
     syntax_map: Mutex<SyntaxMap>,
     parsing_in_background: bool,
+    parsing_waiters: Vec<oneshot::Sender<()>>,
     parse_count: usize,
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
@@ -682,6 +684,7 @@ impl Buffer {
             capability,
             syntax_map: Mutex::new(SyntaxMap::new()),
             parsing_in_background: false,
+            parsing_waiters: Vec::new(),
             parse_count: 0,
             sync_parse_timeout: Duration::from_millis(1),
             autoindent_requests: Default::default(),
@@ -1101,6 +1104,11 @@ impl Buffer {
                         if parse_again {
                             this.reparse(cx);
                         }
+                        if !this.parsing_in_background {
+                            for waiter in this.parsing_waiters.drain(..) {
+                                waiter.send(()).ok();
+                            }
+                        }
                     })
                     .ok();
                 })
@@ -1659,6 +1667,19 @@ impl Buffer {
         self.text.wait_for_version(version)
     }
 
+    /// Waits for the background parse of the buffer, if any, to complete, so that the syntax tree matches the buffer's text.
+    pub fn wait_for_parsing(&mut self) -> impl 'static + Future<Output = ()> {
+        let (tx, rx) = oneshot::channel();
+        if self.parsing_in_background {
+            self.parsing_waiters.push(tx);
+        } else {
+            tx.send(()).ok();
+        }
+        async move {
+            rx.await.ok();
+        }
+    }
+
     /// Forces all futures returned by [`Buffer::wait_for_version`], [`Buffer::wait_for_edits`], or
     /// [`Buffer::wait_for_version`] to resolve with an error.
     pub fn give_up_waiting(&mut self) {
@@ -2996,6 +3017,219 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the ranges that can be folded according to the fold queries of the buffer's
+    /// languages, along with the ranges delimited by `region` and `endregion` comments.
+    /// Returns `None` if none of the buffer's languages has a fold query.
+    ///
+    /// Like indentation-based folds, each range starts at the end of its first line, and a
+    /// last line that only closes the folded construct is left out of the range.
+    pub fn syntax_fold_ranges(&self) -> Option<Vec<(Range<Point>, Option<FoldRangeKind>)>> {
+        let mut syntax_matches = self.syntax.matches(0..self.len(), self, |grammar| {
+            grammar.folds_config.as_ref().map(|config| &config.query)
+        });
+        if syntax_matches.grammars().is_empty() {
+            return None;
+        }
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.folds_config.as_ref())
+            .collect::<Vec<_>>();
+        let mut node_ranges = Vec::new();
+        while let Some(mat) = syntax_matches.peek() {
+            if let Some(config) = configs[mat.grammar_index] {
+                for capture in mat.captures {
+                    let kind = if Some(capture.index) == config.fold_capture_ix {
+                        None
+                    } else if Some(capture.index) == config.comment_capture_ix {
+                        Some(FoldRangeKind::Comment)
+                    } else if Some(capture.index) == config.imports_capture_ix {
+                        Some(FoldRangeKind::Imports)
+                    } else {
+                        continue;
+                    };
+                    let range = capture.node.byte_range().to_point(self);
+                    node_ranges.push((range, kind));
+                }
+            }
+            syntax_matches.advance();
+        }
+        node_ranges.sort_by_key(|(range, _)| (range.start, cmp::Reverse(range.end)));
+
+        // Comments and imports are usually a node per line, so consecutive ones are merged
+        // into a single range.
+        let mut merged_ranges: Vec<(Range<Point>, Option<FoldRangeKind>)> = Vec::new();
+        let mut last_range_ix_by_kind = HashMap::default();
+        for (range, kind) in node_ranges {
+            if let Some(kind) = kind {
+                if let Some(&ix) = last_range_ix_by_kind.get(&kind) {
+                    let last_range: &mut Range<Point> = &mut merged_ranges[ix].0;
+                    if range.start.row <= last_range.end.row + 1 {
+                        last_range.end = cmp::max(last_range.end, range.end);
+                        continue;
+                    }
+                }
+                last_range_ix_by_kind.insert(kind, merged_ranges.len());
+            }
+            merged_ranges.push((range, kind));
+        }
+
+        let mut fold_ranges = merged_ranges
+            .into_iter()
+            .filter_map(|(range, kind)| Some((self.fold_range_for_node(range)?, kind)))
+            .chain(
+                self.region_fold_ranges()
+                    .into_iter()
+                    .map(|range| (range, Some(FoldRangeKind::Region))),
+            )
+            .collect::<Vec<_>>();
+        fold_ranges.sort_by_key(|(range, _)| (range.start, cmp::Reverse(range.end)));
+        fold_ranges.dedup_by(|(a, _), (b, _)| a == b);
+        Some(fold_ranges)
+    }
+
+    fn fold_range_for_node(&self, range: Range<Point>) -> Option<Range<Point>> {
+        let mut end = range.end;
+        if end.column == 0 && end.row > range.start.row {
+            end = Point::new(end.row - 1, self.line_len(end.row - 1));
+        }
+
+        let mut end_row = end.row;
+        let last_line_start = Point::new(end.row, self.indent_size_for_line(end.row).len);
+        if last_line_start < end
+            && self
+                .chars_for_range(last_line_start..end)
+                .all(|c| matches!(c, ')' | ']' | '}' | '>' | ';' | ','))
+        {
+            end_row -= 1;
+        }
+
+        (end_row > range.start.row).then(|| {
+            Point::new(range.start.row, self.line_len(range.start.row))
+                ..Point::new(end_row, self.line_len(end_row))
+        })
+    }
+
+    fn region_fold_ranges(&self) -> Vec<Range<Point>> {
+        let line_comments = self
+            .language
+            .as_ref()
+            .map_or(&[][..], |language| &language.config.line_comments[..]);
+        let mut region_starts = Vec::new();
+        let mut ranges = Vec::new();
+        for row in 0..=self.max_point().row {
+            let line_start = Point::new(row, self.indent_size_for_line(row).len);
+            let line = self
+                .chars_at(line_start)
+                .take_while(|c| *c != '\n')
+                .take(64)
+                .collect::<String>();
+            let marker = line_comments
+                .iter()
+                .find_map(|prefix| line.strip_prefix(prefix.trim_end()))
+                .unwrap_or(line.as_str())
+                .trim_start();
+            let marker = marker.strip_prefix('#').unwrap_or(marker);
+            if is_region_marker(marker, "region") {
+                region_starts.push(row);
+            } else if is_region_marker(marker, "endregion") {
+                if let Some(start_row) = region_starts.pop() {
+                    ranges.push(
+                        Point::new(start_row, self.line_len(start_row))
+                            ..Point::new(row, self.line_len(row)),
+                    );
+                }
+            }
+        }
+        ranges
+    }
+
+    /// Returns the ranges that are edited together with the one containing the given position,
+    /// such as the names in an opening and a closing tag, according to the linked editing
+    /// queries of the buffer's languages.
+    pub fn syntax_linked_editing_ranges<T: ToOffset>(
+        &self,
+        position: T,
+    ) -> Option<Vec<Range<usize>>> {
+        let offset = position.to_offset(self);
+        let row = self.offset_to_point(offset).row;
+        let line_range =
+            Point::new(row, 0).to_offset(self)..Point::new(row, self.line_len(row)).to_offset(self);
+        let mut syntax_matches = self.syntax.matches(line_range, self, |grammar| {
+            grammar
+                .linked_editing_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.linked_editing_config.as_ref())
+            .collect::<Vec<_>>();
+        while let Some(mat) = syntax_matches.peek() {
+            if let Some(config) = configs[mat.grammar_index] {
+                let ranges = mat
+                    .captures
+                    .iter()
+                    .filter(|capture| capture.index == config.linked_capture_ix)
+                    .map(|capture| capture.node.byte_range())
+                    .collect::<Vec<_>>();
+                if ranges.len() > 1
+                    && ranges
+                        .iter()
+                        .any(|range| range.start <= offset && offset <= range.end)
+                {
+                    return Some(ranges);
+                }
+            }
+            syntax_matches.advance();
+        }
+        None
+    }
+
+    /// Returns the runnables, such as tests and main functions, that start in the given range,
+    /// according to the runnables queries of the buffer's languages.
+    pub fn runnable_ranges(&self, range: Range<usize>) -> Vec<RunnableRange> {
+        let mut syntax_matches = self.syntax.matches(range.clone(), self, |grammar| {
+            grammar.runnable_config.as_ref().map(|config| &config.query)
+        });
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.runnable_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut runnables = Vec::new();
+        while let Some(mat) = syntax_matches.peek() {
+            if let Some(config) = configs[mat.grammar_index] {
+                let capture = |capture_ix| {
+                    mat.captures
+                        .iter()
+                        .find(|capture| capture.index == capture_ix)
+                        .map(|capture| capture.node.byte_range())
+                };
+                let tag = config.tags[mat.pattern_index].clone();
+                if let Some(((run_range, name_range), tag)) = capture(config.run_capture_ix)
+                    .zip(capture(config.name_capture_ix))
+                    .zip(tag)
+                {
+                    if range.contains(&run_range.start) {
+                        runnables.push(RunnableRange {
+                            range: run_range,
+                            name: self.text_for_range(name_range).collect(),
+                            tag,
+                        });
+                    }
+                }
+            }
+            syntax_matches.advance();
+        }
+        runnables.sort_by_key(|runnable| runnable.range.start);
+        runnables.dedup_by(|a, b| a.range.start == b.range.start && a.tag == b.tag);
+        runnables
+    }
+
     /// Returns selections for remote peers intersecting the given range.
     #[allow(clippy::type_complexity)]
     pub fn remote_selections_in_range(
@@ -3192,6 +3426,12 @@ impl BufferSnapshot {
     }
 }
 
+fn is_region_marker(text: &str, marker: &str) -> bool {
+    text.strip_prefix(marker).map_or(false, |rest| {
+        !rest.starts_with(|c: char| c.is_alphanumeric())
+    })
+}
+
 fn indent_size_for_line(text: &text::BufferSnapshot, row: u32) -> IndentSize {
     indent_size_for_text(text.chars_at(Point::new(row, 0)))
 }
@@ -586,6 +586,189 @@ async fn test_resetting_language(cx: &mut gpui::TestAppContext) {
     assert_eq!(get_tree_sexp(&buffer, cx), "(document (object))");
 }
 
+#[gpui::test]
+fn test_syntax_fold_ranges(cx: &mut AppContext) {
+    let text = r#"
+        use std::fmt;
+        use std::sync::Arc;
+
+        // A comment
+        // spanning lines
+        fn main() {
+            // region: setup
+            let x = 1;
+            // endregion
+            println!("{x}");
+        }
+    "#
+    .unindent();
+
+    let mut language = rust_lang()
+        .with_folds_query(
+            r#"
+            (function_item) @fold
+            (line_comment) @fold.comment
+            (use_declaration) @fold.imports
+            "#,
+        )
+        .unwrap();
+    language.config.line_comments = vec!["// ".into()];
+
+    let buffer = cx.new_model(|cx| {
+        Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), text)
+            .with_language(Arc::new(language), cx)
+    });
+    let fold_ranges = buffer.read(cx).snapshot().syntax_fold_ranges().unwrap();
+    assert_eq!(
+        fold_ranges,
+        &[
+            (
+                Point::new(0, 13)..Point::new(1, 19),
+                Some(FoldRangeKind::Imports)
+            ),
+            (
+                Point::new(3, 12)..Point::new(4, 17),
+                Some(FoldRangeKind::Comment)
+            ),
+            (Point::new(5, 11)..Point::new(9, 20), None),
+            (
+                Point::new(6, 20)..Point::new(8, 16),
+                Some(FoldRangeKind::Region)
+            ),
+        ]
+    );
+
+    let buffer = cx.new_model(|cx| {
+        Buffer::new(
+            0,
+            BufferId::new(cx.entity_id().as_u64()).unwrap(),
+            "fn a() {}",
+        )
+        .with_language(Arc::new(rust_lang()), cx)
+    });
+    assert_eq!(buffer.read(cx).snapshot().syntax_fold_ranges(), None);
+}
+
+#[gpui::test]
+fn test_syntax_linked_editing_ranges(cx: &mut AppContext) {
+    let text = r#"
+        <div>
+            <p>text</p>
+        </div>
+    "#
+    .unindent();
+
+    let language = html_lang()
+        .with_linked_editing_query(
+            "
+            (element
+              (start_tag (tag_name) @linked)
+              (end_tag (tag_name) @linked))
+            ",
+        )
+        .unwrap();
+    let buffer = cx.new_model(|cx| {
+        Buffer::new(
+            0,
+            BufferId::new(cx.entity_id().as_u64()).unwrap(),
+            text.clone(),
+        )
+        .with_language(Arc::new(language), cx)
+    });
+    let snapshot = buffer.read(cx).snapshot();
+    let ranges = |position: usize| {
+        snapshot
+            .syntax_linked_editing_ranges(position)
+            .map(|ranges| {
+                ranges
+                    .into_iter()
+                    .map(|range| &text[range])
+                    .collect::<Vec<_>>()
+            })
+    };
+
+    assert_eq!(ranges(text.find("div").unwrap()), Some(vec!["div", "div"]));
+    assert_eq!(ranges(text.find("p>").unwrap() + 1), Some(vec!["p", "p"]));
+    assert_eq!(ranges(text.find("text").unwrap() + 1), None);
+}
+
+#[gpui::test]
+fn test_runnable_ranges(cx: &mut AppContext) {
+    let text = r#"
+        fn main() {}
+
+        #[test]
+        #[should_panic]
+        fn first() {}
+
+        fn helper() {}
+
+        #[tokio::test]
+        async fn second() {}
+    "#
+    .unindent();
+
+    let language = rust_lang()
+        .with_runnable_query(
+            r#"
+            (
+              (attribute_item
+                (attribute
+                  [((identifier) @_attribute)
+                   (scoped_identifier (identifier) @_attribute)])
+                (#eq? @_attribute "test"))
+              .
+              (attribute_item)*
+              .
+              (function_item name: (_) @name) @run
+              (#set! tag rust-test)
+            )
+
+            (
+              (function_item name: (_) @name) @run
+              (#eq? @name "main")
+              (#set! tag rust-main)
+            )
+            "#,
+        )
+        .unwrap();
+    let buffer = cx.new_model(|cx| {
+        Buffer::new(
+            0,
+            BufferId::new(cx.entity_id().as_u64()).unwrap(),
+            text.clone(),
+        )
+        .with_language(Arc::new(language), cx)
+    });
+    let snapshot = buffer.read(cx).snapshot();
+    let runnables = |range: Range<usize>| {
+        snapshot
+            .runnable_ranges(range)
+            .into_iter()
+            .map(|runnable| {
+                (
+                    runnable.name,
+                    runnable.tag.to_string(),
+                    snapshot.offset_to_point(runnable.range.start).row,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        runnables(0..text.len()),
+        vec![
+            ("main".to_string(), "rust-main".to_string(), 0),
+            ("first".to_string(), "rust-test".to_string(), 4),
+            ("second".to_string(), "rust-test".to_string(), 9),
+        ]
+    );
+    assert_eq!(
+        runnables(text.find("helper").unwrap()..text.len()),
+        vec![("second".to_string(), "rust-test".to_string(), 9)]
+    );
+}
+
 #[gpui::test]
 async fn test_outline(cx: &mut gpui::TestAppContext) {
     let text = r#"
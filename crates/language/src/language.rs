@@ -22,6 +22,7 @@ pub mod markdown;
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use collections::{HashMap, HashSet};
+use fs::Fs;
 use futures::Future;
 use gpui::{AppContext, AsyncAppContext, Model, Task};
 pub use highlight_map::HighlightMap;
@@ -125,13 +126,52 @@ pub struct LanguageContext {
     pub symbol: Option<String>,
 }
 
+#[async_trait]
 pub trait LanguageContextProvider: Send + Sync {
     fn build_context(&self, location: Location, cx: &mut AppContext) -> Result<LanguageContext>;
+
+    /// Returns the command running the given runnable, detected in the buffer with the runnables query
+    /// of the language, or `None` if the language does not know how to run runnables of its kind.
+    async fn runnable_command(
+        &self,
+        _runnable: &RunnableRange,
+        _buffer: &BufferSnapshot,
+        _abs_path: &Path,
+        _fs: &dyn Fs,
+    ) -> Option<RunnableCommand> {
+        None
+    }
+}
+
+/// A piece of code that can be run, such as a test or a main function,
+/// detected with the runnables query of a language.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunnableRange {
+    /// The range of the runnable item, captured as `@run`.
+    pub range: Range<usize>,
+    /// The name of the runnable, captured as `@name`.
+    pub name: String,
+    /// The kind of the runnable, set with `(#set! tag <kind>)` in the query.
+    pub tag: Arc<str>,
+}
+
+/// A command that runs one of the runnables detected in a buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunnableCommand {
+    /// Human readable name of the command.
+    pub label: String,
+    /// Executable command to spawn.
+    pub command: String,
+    /// Arguments to the command.
+    pub args: Vec<String>,
+    /// Current working directory to spawn the command into.
+    pub cwd: Option<PathBuf>,
 }
 
 /// A context provider that fills out LanguageContext without inspecting the contents.
 pub struct DefaultContextProvider;
 
+#[async_trait]
 impl LanguageContextProvider for DefaultContextProvider {
     fn build_context(
         &self,
@@ -797,6 +837,9 @@ pub struct Grammar {
     pub(crate) highlights_query: Option<Query>,
     pub(crate) brackets_config: Option<BracketConfig>,
     pub(crate) redactions_config: Option<RedactionConfig>,
+    pub(crate) folds_config: Option<FoldConfig>,
+    pub(crate) linked_editing_config: Option<LinkedEditingConfig>,
+    pub(crate) runnable_config: Option<RunnableConfig>,
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
     pub embedding_config: Option<EmbeddingConfig>,
@@ -843,6 +886,35 @@ struct RedactionConfig {
     pub redaction_capture_ix: u32,
 }
 
+struct FoldConfig {
+    query: Query,
+    fold_capture_ix: Option<u32>,
+    comment_capture_ix: Option<u32>,
+    imports_capture_ix: Option<u32>,
+}
+
+/// The kind of a foldable range, used to fold all ranges of a kind at once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FoldRangeKind {
+    Comment,
+    Imports,
+    /// A range delimited by `region` and `endregion` marker comments.
+    Region,
+}
+
+struct LinkedEditingConfig {
+    query: Query,
+    linked_capture_ix: u32,
+}
+
+struct RunnableConfig {
+    query: Query,
+    run_capture_ix: u32,
+    name_capture_ix: u32,
+    /// The kind of the runnables matched by each pattern of the query.
+    tags: Vec<Option<Arc<str>>>,
+}
+
 struct OverrideConfig {
     query: Query,
     values: HashMap<u32, (String, LanguageConfigOverride)>,
@@ -884,6 +956,9 @@ impl Language {
                     injection_config: None,
                     override_config: None,
                     redactions_config: None,
+                    folds_config: None,
+                    linked_editing_config: None,
+                    runnable_config: None,
                     error_query: Query::new(&ts_language, "(ERROR) @error").unwrap(),
                     ts_language,
                     highlight_map: Default::default(),
@@ -942,6 +1017,21 @@ impl Language {
                 .with_redaction_query(query.as_ref())
                 .context("Error loading redaction query")?;
         }
+        if let Some(query) = queries.folds {
+            self = self
+                .with_folds_query(query.as_ref())
+                .context("Error loading folds query")?;
+        }
+        if let Some(query) = queries.linked_editing {
+            self = self
+                .with_linked_editing_query(query.as_ref())
+                .context("Error loading linked editing query")?;
+        }
+        if let Some(query) = queries.runnables {
+            self = self
+                .with_runnable_query(query.as_ref())
+                .context("Error loading runnables query")?;
+        }
         Ok(self)
     }
 
@@ -1188,6 +1278,75 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_folds_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self.grammar_mut();
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut fold_capture_ix = None;
+        let mut comment_capture_ix = None;
+        let mut imports_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [
+                ("fold", &mut fold_capture_ix),
+                ("fold.comment", &mut comment_capture_ix),
+                ("fold.imports", &mut imports_capture_ix),
+            ],
+        );
+
+        grammar.folds_config = Some(FoldConfig {
+            query,
+            fold_capture_ix,
+            comment_capture_ix,
+            imports_capture_ix,
+        });
+        Ok(self)
+    }
+
+    pub fn with_linked_editing_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self.grammar_mut();
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut linked_capture_ix = None;
+        get_capture_indices(&query, &mut [("linked", &mut linked_capture_ix)]);
+
+        if let Some(linked_capture_ix) = linked_capture_ix {
+            grammar.linked_editing_config = Some(LinkedEditingConfig {
+                query,
+                linked_capture_ix,
+            });
+        }
+        Ok(self)
+    }
+
+    pub fn with_runnable_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self.grammar_mut();
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut run_capture_ix = None;
+        let mut name_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [("run", &mut run_capture_ix), ("name", &mut name_capture_ix)],
+        );
+        let tags = (0..query.pattern_count())
+            .map(|ix| {
+                query
+                    .property_settings(ix)
+                    .iter()
+                    .find(|setting| setting.key.as_ref() == "tag")
+                    .and_then(|setting| setting.value.as_deref().map(Arc::from))
+            })
+            .collect();
+
+        if let Some((run_capture_ix, name_capture_ix)) = run_capture_ix.zip(name_capture_ix) {
+            grammar.runnable_config = Some(RunnableConfig {
+                query,
+                run_capture_ix,
+                name_capture_ix,
+                tags,
+            });
+        }
+        Ok(self)
+    }
+
     fn grammar_mut(&mut self) -> &mut Grammar {
         Arc::get_mut(self.grammar.as_mut().unwrap()).unwrap()
     }
@@ -101,6 +101,10 @@ pub struct LanguageSettings {
     pub extend_comment_on_newline: bool,
     /// Inlay hint related settings.
     pub inlay_hints: InlayHintSettings,
+    /// Whether to layer language server semantic tokens over tree-sitter highlights.
+    pub semantic_tokens: bool,
+    /// Whether to show code lenses provided by language servers above the code they refer to.
+    pub code_lens: bool,
     /// Whether to automatically close brackets.
     pub use_autoclose: bool,
     /// Which code actions to run on save
@@ -226,6 +230,16 @@ pub struct LanguageSettingsContent {
     /// Inlay hint related settings.
     #[serde(default)]
     pub inlay_hints: Option<InlayHintSettings>,
+    /// Whether to layer language server semantic tokens over tree-sitter highlights.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub semantic_tokens: Option<bool>,
+    /// Whether to show code lenses provided by language servers above the code they refer to.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub code_lens: Option<bool>,
     /// Whether to automatically type closing characters for you. For example,
     /// when you type (, Zed will automatically add a closing ) at the correct position.
     ///
@@ -638,6 +652,8 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.extend_comment_on_newline,
     );
     merge(&mut settings.inlay_hints, src.inlay_hints);
+    merge(&mut settings.semantic_tokens, src.semantic_tokens);
+    merge(&mut settings.code_lens, src.code_lens);
     fn merge<T>(target: &mut T, value: Option<T>) {
         if let Some(value) = value {
             *target = value;
@@ -696,8 +696,15 @@ impl SearchableItem for LspLogView {
             regex: true,
             // LSP log is read-only.
             replacement: false,
+            fuzzy: true,
+            selection: true,
         }
     }
+
+    fn toggle_filtered_search_ranges(&mut self, enabled: bool, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |e, cx| e.toggle_filtered_search_ranges(enabled, cx))
+    }
     fn active_match_index(
         &mut self,
         matches: Vec<Self::Match>,
@@ -0,0 +1,11 @@
+[
+  (function_definition)
+  (compound_statement)
+  (struct_specifier)
+  (enum_specifier)
+  (initializer_list)
+] @fold
+
+(comment) @fold.comment
+
+(preproc_include) @fold.imports
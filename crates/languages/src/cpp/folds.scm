@@ -0,0 +1,13 @@
+[
+  (function_definition)
+  (compound_statement)
+  (class_specifier)
+  (struct_specifier)
+  (enum_specifier)
+  (namespace_definition)
+  (initializer_list)
+] @fold
+
+(comment) @fold.comment
+
+(preproc_include) @fold.imports
@@ -1,10 +1,11 @@
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use futures::StreamExt;
-use gpui::{AsyncAppContext, Task};
+use gpui::{AppContext, AsyncAppContext, Task};
 pub use language::*;
 use lazy_static::lazy_static;
 use lsp::LanguageServerBinary;
+use project::Fs;
 use regex::Regex;
 use serde_json::json;
 use smol::{fs, process};
@@ -12,7 +13,7 @@ use std::{
     any::Any,
     ffi::{OsStr, OsString},
     ops::Range,
-    path::PathBuf,
+    path::{Path, PathBuf},
     str,
     sync::{
         atomic::{AtomicBool, Ordering::SeqCst},
@@ -405,6 +406,40 @@ fn adjust_runs(
     runs
 }
 
+/// Runs the tests and the main package of Go files with the `go` tool, in the directory of the file.
+pub(super) struct GoContextProvider;
+
+#[async_trait]
+impl LanguageContextProvider for GoContextProvider {
+    fn build_context(&self, location: Location, cx: &mut AppContext) -> Result<LanguageContext> {
+        DefaultContextProvider.build_context(location, cx)
+    }
+
+    async fn runnable_command(
+        &self,
+        runnable: &RunnableRange,
+        _: &BufferSnapshot,
+        abs_path: &Path,
+        _: &dyn Fs,
+    ) -> Option<RunnableCommand> {
+        let args = match runnable.tag.as_ref() {
+            "go-test" => vec![
+                "test".to_string(),
+                "-run".to_string(),
+                crate::shell_quote(&format!("^{}$", runnable.name)),
+            ],
+            "go-main" => vec!["run".to_string(), ".".to_string()],
+            _ => return None,
+        };
+        Some(RunnableCommand {
+            label: format!("go {}", args.join(" ")),
+            command: "go".to_string(),
+            args,
+            cwd: abs_path.parent().map(Path::to_path_buf),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,13 @@
+[
+  (function_declaration)
+  (method_declaration)
+  (type_declaration)
+  (const_declaration)
+  (var_declaration)
+  (block)
+  (composite_literal)
+] @fold
+
+(comment) @fold.comment
+
+(import_declaration) @fold.imports
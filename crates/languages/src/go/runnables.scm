@@ -0,0 +1,14 @@
+(
+  (function_declaration
+    name: (_) @name) @run
+  (#match? @name "^Test([A-Z0-9_]|$)")
+  (#set! tag go-test)
+)
+
+(
+  (source_file
+    (function_declaration
+      name: (_) @name) @run)
+  (#eq? @name "main")
+  (#set! tag go-main)
+)
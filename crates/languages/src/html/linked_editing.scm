@@ -0,0 +1,3 @@
+(element
+  (start_tag (tag_name) @linked)
+  (end_tag (tag_name) @linked))
@@ -0,0 +1,10 @@
+; Test suites and test cases of Jest, Vitest and Mocha.
+(
+  (call_expression
+    function: (identifier) @_function
+    arguments: (arguments
+      .
+      [(string) (template_string)] @name)) @run
+  (#match? @_function "^(describe|it|test)$")
+  (#set! tag js-test)
+)
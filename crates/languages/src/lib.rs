@@ -150,7 +150,7 @@ pub fn init(
             let config = load_config($name);
             // typeck helper
             let adapters: Vec<Arc<dyn LspAdapter>> = $adapters;
-            for adapter in $adapters {
+            for adapter in adapters {
                 languages.register_lsp_adapter(config.name.clone(), adapter);
             }
             languages.register_language(
@@ -215,7 +215,11 @@ pub fn init(
     language!("erlang", vec![Arc::new(erlang::ErlangLspAdapter)]);
 
     language!("gleam", vec![Arc::new(gleam::GleamLspAdapter)]);
-    language!("go", vec![Arc::new(go::GoLspAdapter)]);
+    language!(
+        "go",
+        vec![Arc::new(go::GoLspAdapter)],
+        go::GoContextProvider
+    );
     language!("gomod");
     language!("gowork");
     language!("zig", vec![Arc::new(zig::ZlsAdapter)]);
@@ -238,9 +242,14 @@ pub fn init(
         "python",
         vec![Arc::new(python::PythonLspAdapter::new(
             node_runtime.clone(),
-        ))]
+        ))],
+        python::PythonContextProvider
+    );
+    language!(
+        "rust",
+        vec![Arc::new(rust::RustLspAdapter)],
+        rust::RustContextProvider
     );
-    language!("rust", vec![Arc::new(rust::RustLspAdapter)]);
     language!("toml", vec![Arc::new(toml::TaploLspAdapter)]);
     match &DenoSettings::get(None, cx).enable {
         true => {
@@ -249,15 +258,21 @@ pub fn init(
                 vec![
                     Arc::new(deno::DenoLspAdapter::new()),
                     Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
-                ]
+                ],
+                typescript::TypeScriptContextProvider
+            );
+            language!(
+                "typescript",
+                vec![Arc::new(deno::DenoLspAdapter::new())],
+                typescript::TypeScriptContextProvider
             );
-            language!("typescript", vec![Arc::new(deno::DenoLspAdapter::new())]);
             language!(
                 "javascript",
                 vec![
                     Arc::new(deno::DenoLspAdapter::new()),
                     Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
-                ]
+                ],
+                typescript::TypeScriptContextProvider
             );
             language!("jsdoc", vec![Arc::new(deno::DenoLspAdapter::new())]);
         }
@@ -268,14 +283,16 @@ pub fn init(
                     Arc::new(typescript::TypeScriptLspAdapter::new(node_runtime.clone())),
                     Arc::new(typescript::EsLintLspAdapter::new(node_runtime.clone())),
                     Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
-                ]
+                ],
+                typescript::TypeScriptContextProvider
             );
             language!(
                 "typescript",
                 vec![
                     Arc::new(typescript::TypeScriptLspAdapter::new(node_runtime.clone())),
                     Arc::new(typescript::EsLintLspAdapter::new(node_runtime.clone())),
-                ]
+                ],
+                typescript::TypeScriptContextProvider
             );
             language!(
                 "javascript",
@@ -283,7 +300,8 @@ pub fn init(
                     Arc::new(typescript::TypeScriptLspAdapter::new(node_runtime.clone())),
                     Arc::new(typescript::EsLintLspAdapter::new(node_runtime.clone())),
                     Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
-                ]
+                ],
+                typescript::TypeScriptContextProvider
             );
             language!(
                 "jsdoc",
@@ -375,6 +393,19 @@ pub fn language(name: &str, grammar: tree_sitter::Language) -> Arc<Language> {
     )
 }
 
+/// Quotes an argument of a command spawned in a shell, unless it has no characters special to the shell.
+pub(crate) fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | ':' | '='))
+    {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
 fn load_config(name: &str) -> LanguageConfig {
     let config_toml = String::from_utf8(
         LanguageDir::get(&format!("{}/config.toml", name))
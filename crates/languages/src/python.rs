@@ -1,8 +1,13 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use gpui::AppContext;
+use language::{
+    BufferSnapshot, DefaultContextProvider, LanguageContext, LanguageContextProvider,
+    LanguageServerName, Location, LspAdapter, LspAdapterDelegate, RunnableCommand, RunnableRange,
+};
 use lsp::LanguageServerBinary;
 use node_runtime::NodeRuntime;
+use project::Fs;
 use std::{
     any::Any,
     ffi::OsString,
@@ -178,6 +183,49 @@ async fn get_cached_server_binary(
     }
 }
 
+/// Runs the tests of Python files with pytest, in the directory of the file.
+pub(super) struct PythonContextProvider;
+
+#[async_trait]
+impl LanguageContextProvider for PythonContextProvider {
+    fn build_context(&self, location: Location, cx: &mut AppContext) -> Result<LanguageContext> {
+        DefaultContextProvider.build_context(location, cx)
+    }
+
+    async fn runnable_command(
+        &self,
+        runnable: &RunnableRange,
+        buffer: &BufferSnapshot,
+        abs_path: &Path,
+        _: &dyn Fs,
+    ) -> Option<RunnableCommand> {
+        if runnable.tag.as_ref() != "python-pytest" {
+            return None;
+        }
+        let mut node_id = vec![abs_path.file_name()?.to_string_lossy().into_owned()];
+        node_id.extend(
+            buffer
+                .symbols_containing(runnable.range.start, None)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|item| item.text.starts_with("class "))
+                .filter_map(|item| Some(item.text[item.name_ranges.last()?.clone()].to_string())),
+        );
+        node_id.push(runnable.name.clone());
+        let args = vec![
+            "-m".to_string(),
+            "pytest".to_string(),
+            crate::shell_quote(&node_id.join("::")),
+        ];
+        Some(RunnableCommand {
+            label: format!("pytest {}", node_id.join("::")),
+            command: "python".to_string(),
+            args,
+            cwd: abs_path.parent().map(Path::to_path_buf),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use gpui::{Context, ModelContext, TestAppContext};
@@ -0,0 +1,18 @@
+[
+  (function_definition)
+  (class_definition)
+  (if_statement)
+  (for_statement)
+  (while_statement)
+  (with_statement)
+  (try_statement)
+  (dictionary)
+  (list)
+] @fold
+
+(comment) @fold.comment
+
+[
+  (import_statement)
+  (import_from_statement)
+] @fold.imports
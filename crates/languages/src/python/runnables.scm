@@ -0,0 +1,7 @@
+; Test functions and methods, as collected by pytest.
+(
+  (function_definition
+    name: (identifier) @name) @run
+  (#match? @name "^test")
+  (#set! tag python-pytest)
+)
@@ -2,15 +2,21 @@ use anyhow::{anyhow, bail, Context, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_trait::async_trait;
 use futures::{io::BufReader, StreamExt};
-use gpui::AsyncAppContext;
+use gpui::{AppContext, AsyncAppContext};
 pub use language::*;
 use lazy_static::lazy_static;
 use lsp::LanguageServerBinary;
-use project::project_settings::ProjectSettings;
+use project::{project_settings::ProjectSettings, Fs};
 use regex::Regex;
 use settings::Settings;
 use smol::fs::{self, File};
-use std::{any::Any, borrow::Cow, env::consts, path::PathBuf, sync::Arc};
+use std::{
+    any::Any,
+    borrow::Cow,
+    env::consts,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use util::{
     async_maybe,
     fs::remove_matching,
@@ -335,6 +341,148 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
     .log_err()
 }
 
+/// Runs the tests and the binaries of Rust files with Cargo, in the package the file belongs to.
+pub(super) struct RustContextProvider;
+
+#[async_trait]
+impl LanguageContextProvider for RustContextProvider {
+    fn build_context(&self, location: Location, cx: &mut AppContext) -> Result<LanguageContext> {
+        DefaultContextProvider.build_context(location, cx)
+    }
+
+    async fn runnable_command(
+        &self,
+        runnable: &RunnableRange,
+        buffer: &BufferSnapshot,
+        abs_path: &Path,
+        fs: &dyn Fs,
+    ) -> Option<RunnableCommand> {
+        let (package_dir, package_name) = cargo_package(abs_path, fs).await?;
+        let path_in_package = abs_path.strip_prefix(&package_dir).ok()?;
+        let target = CargoTarget::for_path(path_in_package)?;
+        let mut args = match runnable.tag.as_ref() {
+            "rust-test" => vec!["test".to_string()],
+            "rust-main" if target.runs_main() => vec!["run".to_string()],
+            _ => return None,
+        };
+        args.extend(["-p".to_string(), package_name]);
+        args.extend(target.args());
+        if runnable.tag.as_ref() == "rust-test" {
+            let mut test_path = target.module_path();
+            test_path.extend(
+                buffer
+                    .symbols_containing(runnable.range.start, None)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|item| item.text.split_whitespace().any(|word| word == "mod"))
+                    .filter_map(|item| {
+                        Some(item.text[item.name_ranges.last()?.clone()].to_string())
+                    }),
+            );
+            test_path.push(runnable.name.clone());
+            args.extend([
+                "--".to_string(),
+                test_path.join("::"),
+                "--exact".to_string(),
+            ]);
+        }
+
+        Some(RunnableCommand {
+            label: format!("cargo {}", args.join(" ")),
+            command: "cargo".to_string(),
+            args,
+            cwd: Some(package_dir),
+        })
+    }
+}
+
+/// Finds the closest Cargo package containing the file, returning its directory and name.
+async fn cargo_package(abs_path: &Path, fs: &dyn Fs) -> Option<(PathBuf, String)> {
+    for dir in abs_path.ancestors().skip(1) {
+        let Ok(manifest) = fs.load(&dir.join("Cargo.toml")).await else {
+            continue;
+        };
+        let name = ::toml::from_str::<::toml::Value>(&manifest)
+            .ok()
+            .and_then(|manifest| Some(manifest.get("package")?.get("name")?.as_str()?.to_string()));
+        if let Some(name) = name {
+            return Some((dir.to_path_buf(), name));
+        }
+    }
+    None
+}
+
+/// The Cargo target a file belongs to, based on Cargo's default project layout.
+#[derive(Debug, PartialEq)]
+enum CargoTarget {
+    /// The library or the main binary of the package, with the path of the module to the file.
+    Source {
+        module_path: Vec<String>,
+        main: bool,
+    },
+    Bin(String),
+    Example(String),
+    Test(String),
+    Bench(String),
+}
+
+impl CargoTarget {
+    fn for_path(path_in_package: &Path) -> Option<Self> {
+        let components = path_in_package
+            .with_extension("")
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        let target_name = || components.get(1).cloned();
+        match components.first()?.as_str() {
+            "src" if components.get(1).map(String::as_str) == Some("bin") => {
+                components.get(2).cloned().map(Self::Bin)
+            }
+            "src" => {
+                let mut module_path = components[1..].to_vec();
+                let main = module_path == ["main"];
+                if matches!(
+                    module_path.last().map(String::as_str),
+                    Some("lib" | "main" | "mod")
+                ) {
+                    module_path.pop();
+                }
+                Some(Self::Source { module_path, main })
+            }
+            "examples" => target_name().map(Self::Example),
+            "tests" => target_name().map(Self::Test),
+            "benches" => target_name().map(Self::Bench),
+            _ => None,
+        }
+    }
+
+    fn runs_main(&self) -> bool {
+        match self {
+            Self::Source { main, .. } => *main,
+            Self::Bin(_) | Self::Example(_) => true,
+            Self::Test(_) | Self::Bench(_) => false,
+        }
+    }
+
+    fn args(&self) -> Vec<String> {
+        let (kind, name) = match self {
+            Self::Source { .. } => return Vec::new(),
+            Self::Bin(name) => ("--bin", name),
+            Self::Example(name) => ("--example", name),
+            Self::Test(name) => ("--test", name),
+            Self::Bench(name) => ("--bench", name),
+        };
+        vec![kind.to_string(), name.clone()]
+    }
+
+    fn module_path(&self) -> Vec<String> {
+        match self {
+            Self::Source { module_path, .. } => module_path.clone(),
+            _ => Vec::new(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::num::NonZeroU32;
@@ -617,4 +765,37 @@ mod tests {
             buffer
         });
     }
+
+    #[test]
+    fn test_cargo_target_for_path() {
+        let source = |module_path: &[&str], main| CargoTarget::Source {
+            module_path: module_path.iter().map(ToString::to_string).collect(),
+            main,
+        };
+        assert_eq!(
+            CargoTarget::for_path(Path::new("src/main.rs")),
+            Some(source(&[], true))
+        );
+        assert_eq!(
+            CargoTarget::for_path(Path::new("src/lib.rs")),
+            Some(source(&[], false))
+        );
+        assert_eq!(
+            CargoTarget::for_path(Path::new("src/editor/display_map/mod.rs")),
+            Some(source(&["editor", "display_map"], false))
+        );
+        assert_eq!(
+            CargoTarget::for_path(Path::new("src/bin/cli.rs")),
+            Some(CargoTarget::Bin("cli".to_string()))
+        );
+        assert_eq!(
+            CargoTarget::for_path(Path::new("examples/hello/main.rs")),
+            Some(CargoTarget::Example("hello".to_string()))
+        );
+        assert_eq!(
+            CargoTarget::for_path(Path::new("tests/integration.rs")),
+            Some(CargoTarget::Test("integration".to_string()))
+        );
+        assert_eq!(CargoTarget::for_path(Path::new("build.rs")), None);
+    }
 }
@@ -0,0 +1,20 @@
+[
+  (mod_item)
+  (function_item)
+  (impl_item)
+  (trait_item)
+  (struct_item)
+  (enum_item)
+  (macro_definition)
+  (macro_invocation)
+  (block)
+  (match_expression)
+  (closure_expression)
+] @fold
+
+[
+  (line_comment)
+  (block_comment)
+] @fold.comment
+
+(use_declaration) @fold.imports
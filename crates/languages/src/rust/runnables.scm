@@ -0,0 +1,23 @@
+; Test functions, marked with `#[test]` or an attribute like `#[tokio::test]`.
+(
+  (attribute_item
+    (attribute
+      [((identifier) @_attribute)
+       (scoped_identifier (identifier) @_attribute)])
+    (#eq? @_attribute "test"))
+  .
+  (attribute_item)*
+  .
+  (function_item
+    name: (_) @name) @run
+  (#set! tag rust-test)
+)
+
+; The main function of a binary.
+(
+  (source_file
+    (function_item
+      name: (_) @name) @run)
+  (#eq? @name "main")
+  (#set! tag rust-main)
+)
@@ -0,0 +1,14 @@
+[
+  (class_declaration)
+  (interface_declaration)
+  (enum_declaration)
+  (function_declaration)
+  (method_definition)
+  (statement_block)
+  (object)
+  (array)
+] @fold
+
+(comment) @fold.comment
+
+(import_statement) @fold.imports
@@ -4,10 +4,13 @@ use async_tar::Archive;
 use async_trait::async_trait;
 use collections::HashMap;
 use gpui::AppContext;
-use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use language::{
+    BufferSnapshot, DefaultContextProvider, LanguageContext, LanguageContextProvider,
+    LanguageServerName, Location, LspAdapter, LspAdapterDelegate, RunnableCommand, RunnableRange,
+};
 use lsp::{CodeActionKind, LanguageServerBinary};
 use node_runtime::NodeRuntime;
-use project::project_settings::ProjectSettings;
+use project::{project_settings::ProjectSettings, Fs};
 use serde_json::{json, Value};
 use settings::Settings;
 use smol::{fs, io::BufReader, stream::StreamExt};
@@ -404,6 +407,74 @@ async fn get_cached_eslint_server_binary(
     .log_err()
 }
 
+/// Runs the tests of JavaScript and TypeScript files with the test runner the closest `package.json` depends on.
+pub(super) struct TypeScriptContextProvider;
+
+#[async_trait]
+impl LanguageContextProvider for TypeScriptContextProvider {
+    fn build_context(&self, location: Location, cx: &mut AppContext) -> Result<LanguageContext> {
+        DefaultContextProvider.build_context(location, cx)
+    }
+
+    async fn runnable_command(
+        &self,
+        runnable: &RunnableRange,
+        _: &BufferSnapshot,
+        abs_path: &Path,
+        fs: &dyn Fs,
+    ) -> Option<RunnableCommand> {
+        if runnable.tag.as_ref() != "js-test" {
+            return None;
+        }
+        let (package_dir, package_json) = closest_package_json(abs_path, fs).await?;
+        let depends_on = |package: &str| {
+            ["dependencies", "devDependencies"].iter().any(|key| {
+                package_json
+                    .get(key)
+                    .and_then(|deps| deps.get(package))
+                    .is_some()
+            })
+        };
+        let (runner, mut args, name_filter) = if depends_on("vitest") {
+            ("vitest", vec!["run".to_string()], "-t")
+        } else if depends_on("mocha") {
+            ("mocha", Vec::new(), "--grep")
+        } else {
+            ("jest", Vec::new(), "-t")
+        };
+
+        let test_name = runnable
+            .name
+            .trim_matches(|c| matches!(c, '"' | '\'' | '`'))
+            .to_string();
+        let relative_path = abs_path.strip_prefix(&package_dir).ok()?.to_string_lossy();
+        args.extend([
+            crate::shell_quote(&relative_path),
+            name_filter.to_string(),
+            crate::shell_quote(&test_name),
+        ]);
+        Some(RunnableCommand {
+            label: format!("{runner} {relative_path} {name_filter} {test_name}"),
+            command: "npx".to_string(),
+            args: std::iter::once(runner.to_string()).chain(args).collect(),
+            cwd: Some(package_dir),
+        })
+    }
+}
+
+/// Finds the closest `package.json` above the file, returning its directory and contents.
+async fn closest_package_json(abs_path: &Path, fs: &dyn Fs) -> Option<(PathBuf, Value)> {
+    for dir in abs_path.ancestors().skip(1) {
+        let Ok(package_json) = fs.load(&dir.join("package.json")).await else {
+            continue;
+        };
+        if let Ok(package_json) = serde_json::from_str::<Value>(&package_json) {
+            return Some((dir.to_path_buf(), package_json));
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use gpui::{Context, TestAppContext};
@@ -0,0 +1,7 @@
+(element
+  (start_tag (tag_name) @linked)
+  (end_tag (tag_name) @linked))
+
+(template_element
+  (start_tag (tag_name) @linked)
+  (end_tag (tag_name) @linked))
@@ -1,7 +1,9 @@
 use crate::{
-    DocumentHighlight, Hover, HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel,
-    InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location, LocationLink,
-    MarkupContent, Project, ProjectTransaction, ResolveState,
+    CallHierarchyDirection, CodeLens, DocumentHighlight, DocumentLink, FoldingRange, HierarchyItem,
+    Hover, HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart,
+    InlayHintLabelPartTooltip, InlayHintTooltip, Location, LocationLink, MarkupContent,
+    ParameterInformation, Project, ProjectTransaction, ResolveState, SignatureHelp,
+    SignatureInformation, TypeHierarchyDirection,
 };
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
@@ -13,14 +15,14 @@ use language::{
     point_from_lsp, point_to_lsp, prepare_completion_documentation,
     proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
     range_from_lsp, range_to_lsp, Anchor, Bias, Buffer, BufferSnapshot, CachedLspAdapter, CharKind,
-    CodeAction, Completion, OffsetRangeExt, PointUtf16, ToOffset, ToPointUtf16, Transaction,
-    Unclipped,
+    CodeAction, Completion, FoldRangeKind, OffsetRangeExt, Point, PointUtf16, ToOffset,
+    ToPointUtf16, Transaction, Unclipped,
 };
 use lsp::{
     CompletionListItemDefaultsEditRange, DocumentHighlightKind, LanguageServer, LanguageServerId,
     OneOf, ServerCapabilities,
 };
-use std::{cmp::Reverse, ops::Range, path::Path, sync::Arc};
+use std::{cmp::Reverse, mem, ops::Range, path::Path, sync::Arc};
 use text::{BufferId, LineEnding};
 
 pub fn lsp_formatting_options(tab_size: u32) -> lsp::FormattingOptions {
@@ -121,6 +123,12 @@ pub(crate) struct GetHover {
     pub position: PointUtf16,
 }
 
+pub(crate) struct GetSignatureHelp {
+    pub position: PointUtf16,
+    pub trigger_character: Option<String>,
+    pub is_retrigger: bool,
+}
+
 pub(crate) struct GetCompletions {
     pub position: PointUtf16,
 }
@@ -130,6 +138,26 @@ pub(crate) struct GetCodeActions {
     pub kinds: Option<Vec<lsp::CodeActionKind>>,
 }
 
+pub(crate) struct GetCodeLens;
+
+pub(crate) struct GetFoldingRanges;
+
+pub(crate) struct GetLinkedEditingRanges {
+    pub position: PointUtf16,
+}
+
+pub(crate) struct GetDocumentLinks;
+
+pub(crate) struct GetCallHierarchy {
+    pub position: PointUtf16,
+    pub direction: CallHierarchyDirection,
+}
+
+pub(crate) struct GetTypeHierarchy {
+    pub position: PointUtf16,
+    pub direction: TypeHierarchyDirection,
+}
+
 pub(crate) struct OnTypeFormatting {
     pub position: PointUtf16,
     pub trigger: String,
@@ -1425,6 +1453,293 @@ impl LspCommand for GetHover {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetSignatureHelp {
+    type Response = Option<SignatureHelp>;
+    type LspRequest = lsp::request::SignatureHelpRequest;
+    type ProtoRequest = proto::GetSignatureHelp;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.signature_help_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::SignatureHelpParams {
+        let trigger_kind = if self.trigger_character.is_some() {
+            lsp::SignatureHelpTriggerKind::TRIGGER_CHARACTER
+        } else if self.is_retrigger {
+            lsp::SignatureHelpTriggerKind::CONTENT_CHANGE
+        } else {
+            lsp::SignatureHelpTriggerKind::INVOKED
+        };
+
+        lsp::SignatureHelpParams {
+            context: Some(lsp::SignatureHelpContext {
+                trigger_kind,
+                trigger_character: self.trigger_character.clone(),
+                is_retrigger: self.is_retrigger,
+                active_signature_help: None,
+            }),
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::SignatureHelp>,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: LanguageServerId,
+        _: AsyncAppContext,
+    ) -> Result<Self::Response> {
+        let Some(help) = message else {
+            return Ok(None);
+        };
+        if help.signatures.is_empty() {
+            return Ok(None);
+        }
+
+        let signatures = help
+            .signatures
+            .into_iter()
+            .map(SignatureInformation::from_lsp)
+            .collect::<Vec<_>>();
+        let active_signature = help
+            .active_signature
+            .map(|ix| ix as usize)
+            .filter(|ix| *ix < signatures.len())
+            .unwrap_or(0);
+
+        Ok(Some(SignatureHelp {
+            signatures,
+            active_signature,
+            active_parameter: help.active_parameter.map(|ix| ix as usize),
+        }))
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetSignatureHelp {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            trigger_character: self.trigger_character.clone(),
+            is_retrigger: self.is_retrigger,
+            version: serialize_version(&buffer.version),
+        }
+    }
+
+    async fn from_proto(
+        message: Self::ProtoRequest,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+            trigger_character: message.trigger_character,
+            is_retrigger: message.is_retrigger,
+        })
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut Project,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetSignatureHelpResponse {
+        proto::GetSignatureHelpResponse {
+            signature_help: response.map(|help| proto::SignatureHelp {
+                signatures: help
+                    .signatures
+                    .into_iter()
+                    .map(|signature| proto::SignatureInformation {
+                        label: signature.label,
+                        documentation: signature.documentation.map(markup_content_to_proto),
+                        parameters: signature
+                            .parameters
+                            .into_iter()
+                            .map(|parameter| proto::ParameterInformation {
+                                label_start: parameter.label_range.start as u64,
+                                label_end: parameter.label_range.end as u64,
+                                documentation: parameter.documentation.map(markup_content_to_proto),
+                            })
+                            .collect(),
+                        active_parameter: signature.active_parameter.map(|ix| ix as u32),
+                    })
+                    .collect(),
+                active_signature: help.active_signature as u32,
+                active_parameter: help.active_parameter.map(|ix| ix as u32),
+            }),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSignatureHelpResponse,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self::Response> {
+        let Some(help) = message.signature_help else {
+            return Ok(None);
+        };
+
+        let signatures = help
+            .signatures
+            .into_iter()
+            .map(|signature| {
+                let label_len = signature.label.len();
+                SignatureInformation {
+                    parameters: signature
+                        .parameters
+                        .into_iter()
+                        .map(|parameter| {
+                            let start = (parameter.label_start as usize).min(label_len);
+                            let end = (parameter.label_end as usize).clamp(start, label_len);
+                            ParameterInformation {
+                                label_range: start..end,
+                                documentation: parameter
+                                    .documentation
+                                    .map(markup_content_from_proto),
+                            }
+                        })
+                        .collect(),
+                    label: signature.label,
+                    documentation: signature.documentation.map(markup_content_from_proto),
+                    active_parameter: signature.active_parameter.map(|ix| ix as usize),
+                }
+            })
+            .collect::<Vec<_>>();
+        if signatures.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(SignatureHelp {
+            active_signature: (help.active_signature as usize).min(signatures.len() - 1),
+            active_parameter: help.active_parameter.map(|ix| ix as usize),
+            signatures,
+        }))
+    }
+
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+impl SignatureInformation {
+    fn from_lsp(signature: lsp::SignatureInformation) -> Self {
+        let mut search_start = 0;
+        let parameters = signature
+            .parameters
+            .unwrap_or_default()
+            .into_iter()
+            .map(|parameter| {
+                let label_range = match parameter.label {
+                    lsp::ParameterLabel::Simple(parameter_label) => {
+                        // Parameters are listed in order, so look for each one after the
+                        // previous match to avoid matching a name that is also a type. Keep
+                        // the ones that can't be found, so that the active parameter's index
+                        // still refers to the right one.
+                        match signature.label[search_start..].find(parameter_label.as_str()) {
+                            Some(offset) => {
+                                let start = search_start + offset;
+                                start..start + parameter_label.len()
+                            }
+                            None => search_start..search_start,
+                        }
+                    }
+                    lsp::ParameterLabel::LabelOffsets([start, end]) => {
+                        let start = utf16_offset_to_byte_offset(&signature.label, start as usize);
+                        let end = utf16_offset_to_byte_offset(&signature.label, end as usize);
+                        start.min(end)..start.max(end)
+                    }
+                };
+                search_start = label_range.end;
+                ParameterInformation {
+                    label_range,
+                    documentation: parameter.documentation.map(markup_content_from_lsp),
+                }
+            })
+            .collect();
+
+        Self {
+            label: signature.label,
+            documentation: signature.documentation.map(markup_content_from_lsp),
+            parameters,
+            active_parameter: signature.active_parameter.map(|ix| ix as usize),
+        }
+    }
+}
+
+fn utf16_offset_to_byte_offset(text: &str, utf16_offset: usize) -> usize {
+    let mut utf16_count = 0;
+    for (byte_offset, char) in text.char_indices() {
+        if utf16_count >= utf16_offset {
+            return byte_offset;
+        }
+        utf16_count += char.len_utf16();
+    }
+    text.len()
+}
+
+fn markup_content_from_lsp(documentation: lsp::Documentation) -> MarkupContent {
+    match documentation {
+        lsp::Documentation::String(value) => MarkupContent {
+            kind: HoverBlockKind::PlainText,
+            value,
+        },
+        lsp::Documentation::MarkupContent(markup_content) => MarkupContent {
+            kind: if markup_content.kind == lsp::MarkupKind::Markdown {
+                HoverBlockKind::Markdown
+            } else {
+                HoverBlockKind::PlainText
+            },
+            value: markup_content.value,
+        },
+    }
+}
+
+fn markup_content_to_proto(markup_content: MarkupContent) -> proto::MarkupContent {
+    proto::MarkupContent {
+        is_markdown: markup_content.kind == HoverBlockKind::Markdown,
+        value: markup_content.value,
+    }
+}
+
+fn markup_content_from_proto(markup_content: proto::MarkupContent) -> MarkupContent {
+    MarkupContent {
+        kind: if markup_content.is_markdown {
+            HoverBlockKind::Markdown
+        } else {
+            HoverBlockKind::PlainText
+        },
+        value: markup_content.value,
+    }
+}
+
 #[async_trait(?Send)]
 impl LspCommand for GetCompletions {
     type Response = Vec<Completion>;
@@ -1847,6 +2162,1067 @@ impl GetCodeActions {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetCodeLens {
+    type Response = Vec<CodeLens>;
+    type LspRequest = lsp::request::CodeLensRequest;
+    type ProtoRequest = proto::GetCodeLens;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.code_lens_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CodeLensParams {
+        lsp::CodeLensParams {
+            text_document: lsp::TextDocumentIdentifier::new(
+                lsp::Url::from_file_path(path).unwrap(),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        lenses: Option<Vec<lsp::CodeLens>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CodeLens>> {
+        let language_server = project
+            .update(&mut cx, |project, _| {
+                project.language_server_for_id(server_id)
+            })?
+            .ok_or_else(|| anyhow!("no language server found for buffer"))?;
+        let can_resolve = language_server
+            .capabilities()
+            .code_lens_provider
+            .as_ref()
+            .and_then(|options| options.resolve_provider)
+            .unwrap_or(false);
+
+        // Servers may send lenses without a command, expecting them to be resolved
+        // before they're displayed.
+        let lenses = future::join_all(lenses.unwrap_or_default().into_iter().map(|lens| {
+            let language_server = language_server.clone();
+            async move {
+                if lens.command.is_some() || !can_resolve {
+                    return lens;
+                }
+                match language_server
+                    .request::<lsp::request::CodeLensResolve>(lens.clone())
+                    .await
+                {
+                    Ok(resolved) => resolved,
+                    Err(error) => {
+                        log::warn!("failed to resolve code lens: {error:#}");
+                        lens
+                    }
+                }
+            }
+        }))
+        .await;
+
+        let snapshot = buffer.update(&mut cx, |buffer, _| buffer.snapshot())?;
+        Ok(lenses
+            .into_iter()
+            .filter(|lens| lens.command.is_some())
+            .map(|lsp_lens| {
+                let range = range_from_lsp(lsp_lens.range);
+                let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+                let end = snapshot.clip_point_utf16(range.end, Bias::Left);
+                CodeLens {
+                    server_id,
+                    range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                    lsp_lens,
+                }
+            })
+            .collect())
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetCodeLens {
+        proto::GetCodeLens {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetCodeLens,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        lenses: Vec<CodeLens>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetCodeLensResponse {
+        proto::GetCodeLensResponse {
+            lenses: lenses.iter().map(CodeLens::to_proto).collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetCodeLensResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CodeLens>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .lenses
+            .into_iter()
+            .map(CodeLens::from_proto)
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetCodeLens) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+impl CodeLens {
+    fn to_proto(&self) -> proto::CodeLens {
+        proto::CodeLens {
+            server_id: self.server_id.0 as u64,
+            start: Some(serialize_anchor(&self.range.start)),
+            end: Some(serialize_anchor(&self.range.end)),
+            lsp_lens: serde_json::to_vec(&self.lsp_lens).unwrap(),
+        }
+    }
+
+    fn from_proto(lens: proto::CodeLens) -> Result<Self> {
+        let start = lens
+            .start
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid start"))?;
+        let end = lens
+            .end
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid end"))?;
+        Ok(Self {
+            server_id: LanguageServerId(lens.server_id as usize),
+            range: start..end,
+            lsp_lens: serde_json::from_slice(&lens.lsp_lens)?,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetFoldingRanges {
+    type Response = Vec<FoldingRange>;
+    type LspRequest = lsp::request::FoldingRangeRequest;
+    type ProtoRequest = proto::GetFoldingRanges;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.folding_range_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::FoldingRangeParams {
+        lsp::FoldingRangeParams {
+            text_document: lsp::TextDocumentIdentifier::new(
+                lsp::Url::from_file_path(path).unwrap(),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        ranges: Option<Vec<lsp::FoldingRange>>,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<FoldingRange>> {
+        buffer.update(&mut cx, |buffer, _| {
+            let max_row = buffer.max_point().row;
+            ranges
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|range| range.start_line < range.end_line && range.end_line <= max_row)
+                .map(|range| {
+                    // Zed only asks for line folding ranges, which are folded after the end
+                    // of their first line.
+                    let start = Point::new(range.start_line, buffer.line_len(range.start_line));
+                    let end = Point::new(range.end_line, buffer.line_len(range.end_line));
+                    FoldingRange {
+                        range: buffer.anchor_after(start)..buffer.anchor_before(end),
+                        kind: range.kind.map(|kind| match kind {
+                            lsp::FoldingRangeKind::Comment => FoldRangeKind::Comment,
+                            lsp::FoldingRangeKind::Imports => FoldRangeKind::Imports,
+                            lsp::FoldingRangeKind::Region => FoldRangeKind::Region,
+                        }),
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetFoldingRanges {
+        proto::GetFoldingRanges {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetFoldingRanges,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        ranges: Vec<FoldingRange>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetFoldingRangesResponse {
+        proto::GetFoldingRangesResponse {
+            ranges: ranges
+                .into_iter()
+                .map(|range| proto::FoldingRange {
+                    start: Some(serialize_anchor(&range.range.start)),
+                    end: Some(serialize_anchor(&range.range.end)),
+                    kind: range.kind.map(|kind| {
+                        match kind {
+                            FoldRangeKind::Comment => proto::folding_range::Kind::Comment,
+                            FoldRangeKind::Imports => proto::folding_range::Kind::Imports,
+                            FoldRangeKind::Region => proto::folding_range::Kind::Region,
+                        }
+                        .into()
+                    }),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetFoldingRangesResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<FoldingRange>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .ranges
+            .into_iter()
+            .map(|range| {
+                let start = range
+                    .start
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("invalid start"))?;
+                let end = range
+                    .end
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("invalid end"))?;
+                let kind = range
+                    .kind
+                    .and_then(proto::folding_range::Kind::from_i32)
+                    .map(|kind| match kind {
+                        proto::folding_range::Kind::Comment => FoldRangeKind::Comment,
+                        proto::folding_range::Kind::Imports => FoldRangeKind::Imports,
+                        proto::folding_range::Kind::Region => FoldRangeKind::Region,
+                    });
+                Ok(FoldingRange {
+                    range: start..end,
+                    kind,
+                })
+            })
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetFoldingRanges) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetLinkedEditingRanges {
+    type Response = Vec<Range<Anchor>>;
+    type LspRequest = lsp::request::LinkedEditingRange;
+    type ProtoRequest = proto::GetLinkedEditingRanges;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.linked_editing_range_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::LinkedEditingRangeParams {
+        lsp::LinkedEditingRangeParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_ranges: Option<lsp::LinkedEditingRanges>,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<Range<Anchor>>> {
+        buffer.update(&mut cx, |buffer, _| {
+            lsp_ranges
+                .map(|lsp_ranges| lsp_ranges.ranges)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|range| {
+                    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                    // Text typed at either end of a linked range becomes part of it.
+                    buffer.anchor_before(start)..buffer.anchor_after(end)
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetLinkedEditingRanges {
+        proto::GetLinkedEditingRanges {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetLinkedEditingRanges,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        ranges: Vec<Range<Anchor>>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetLinkedEditingRangesResponse {
+        proto::GetLinkedEditingRangesResponse {
+            ranges: ranges
+                .into_iter()
+                .map(|range| proto::LinkedEditingRange {
+                    start: Some(serialize_anchor(&range.start)),
+                    end: Some(serialize_anchor(&range.end)),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetLinkedEditingRangesResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<Range<Anchor>>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .ranges
+            .into_iter()
+            .map(|range| {
+                let start = range
+                    .start
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("missing linked editing range start"))?;
+                let end = range
+                    .end
+                    .and_then(deserialize_anchor)
+                    .ok_or_else(|| anyhow!("missing linked editing range end"))?;
+                Ok(start..end)
+            })
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetLinkedEditingRanges) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentLinks {
+    type Response = Vec<DocumentLink>;
+    type LspRequest = lsp::request::DocumentLinkRequest;
+    type ProtoRequest = proto::GetDocumentLinks;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.document_link_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::DocumentLinkParams {
+        lsp::DocumentLinkParams {
+            text_document: lsp::TextDocumentIdentifier::new(
+                lsp::Url::from_file_path(path).unwrap(),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        links: Option<Vec<lsp::DocumentLink>>,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<DocumentLink>> {
+        let snapshot = buffer.update(&mut cx, |buffer, _| buffer.snapshot())?;
+        Ok(links
+            .unwrap_or_default()
+            .into_iter()
+            .map(|lsp_link| {
+                let range = range_from_lsp(lsp_link.range);
+                let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+                let end = snapshot.clip_point_utf16(range.end, Bias::Left);
+                DocumentLink {
+                    server_id,
+                    range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                    lsp_link,
+                }
+            })
+            .collect())
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentLinks {
+        proto::GetDocumentLinks {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDocumentLinks,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        links: Vec<DocumentLink>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetDocumentLinksResponse {
+        proto::GetDocumentLinksResponse {
+            links: links.iter().map(DocumentLink::to_proto).collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentLinksResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<DocumentLink>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .links
+            .into_iter()
+            .map(DocumentLink::from_proto)
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDocumentLinks) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+impl DocumentLink {
+    pub(crate) fn to_proto(&self) -> proto::DocumentLink {
+        proto::DocumentLink {
+            server_id: self.server_id.0 as u64,
+            start: Some(serialize_anchor(&self.range.start)),
+            end: Some(serialize_anchor(&self.range.end)),
+            lsp_link: serde_json::to_vec(&self.lsp_link).unwrap(),
+        }
+    }
+
+    pub(crate) fn from_proto(link: proto::DocumentLink) -> Result<Self> {
+        let start = link
+            .start
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid start"))?;
+        let end = link
+            .end
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid end"))?;
+        Ok(Self {
+            server_id: LanguageServerId(link.server_id as usize),
+            range: start..end,
+            lsp_link: serde_json::from_slice(&link.lsp_link)?,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetCallHierarchy {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::GetCallHierarchy;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.call_hierarchy_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CallHierarchyPrepareParams {
+        lsp::CallHierarchyPrepareParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::CallHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyItem>> {
+        let (lsp_adapter, language_server) =
+            language_server_for_buffer(&project, &buffer, server_id, &mut cx)?;
+
+        let mut hierarchy = Vec::new();
+        for item in items.unwrap_or_default() {
+            match self.direction {
+                CallHierarchyDirection::Incoming => {
+                    let calls = language_server
+                        .request::<lsp::request::CallHierarchyIncomingCalls>(
+                            lsp::CallHierarchyIncomingCallsParams {
+                                item,
+                                work_done_progress_params: Default::default(),
+                                partial_result_params: Default::default(),
+                            },
+                        )
+                        .await?;
+                    for call in calls.unwrap_or_default() {
+                        let from = call.from;
+                        hierarchy.push(
+                            hierarchy_item_from_lsp(
+                                LspHierarchyItem {
+                                    name: from.name,
+                                    kind: from.kind,
+                                    detail: from.detail,
+                                    uri: from.uri,
+                                    selection_range: from.selection_range,
+                                    call_sites: call.from_ranges,
+                                },
+                                &project,
+                                &lsp_adapter,
+                                &language_server,
+                                &mut cx,
+                            )
+                            .await?,
+                        );
+                    }
+                }
+                CallHierarchyDirection::Outgoing => {
+                    let calls = language_server
+                        .request::<lsp::request::CallHierarchyOutgoingCalls>(
+                            lsp::CallHierarchyOutgoingCallsParams {
+                                item,
+                                work_done_progress_params: Default::default(),
+                                partial_result_params: Default::default(),
+                            },
+                        )
+                        .await?;
+                    for call in calls.unwrap_or_default() {
+                        let to = call.to;
+                        hierarchy.push(
+                            hierarchy_item_from_lsp(
+                                LspHierarchyItem {
+                                    name: to.name,
+                                    kind: to.kind,
+                                    detail: to.detail,
+                                    uri: to.uri,
+                                    selection_range: to.selection_range,
+                                    // The ranges of outgoing calls are in the caller's document.
+                                    call_sites: Vec::new(),
+                                },
+                                &project,
+                                &lsp_adapter,
+                                &language_server,
+                                &mut cx,
+                            )
+                            .await?,
+                        );
+                    }
+                }
+            }
+        }
+        Ok(hierarchy)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetCallHierarchy {
+        let direction = match self.direction {
+            CallHierarchyDirection::Incoming => proto::get_call_hierarchy::Direction::Incoming,
+            CallHierarchyDirection::Outgoing => proto::get_call_hierarchy::Direction::Outgoing,
+        };
+        proto::GetCallHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            direction: direction.into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetCallHierarchy,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        let direction = match proto::get_call_hierarchy::Direction::from_i32(message.direction) {
+            Some(proto::get_call_hierarchy::Direction::Outgoing) => {
+                CallHierarchyDirection::Outgoing
+            }
+            Some(proto::get_call_hierarchy::Direction::Incoming) | None => {
+                CallHierarchyDirection::Incoming
+            }
+        };
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+            direction,
+        })
+    }
+
+    fn response_to_proto(
+        items: Vec<HierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::GetCallHierarchyResponse {
+        proto::GetCallHierarchyResponse {
+            items: hierarchy_items_to_proto(items, project, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetCallHierarchyResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetCallHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetTypeHierarchy {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchyPrepare;
+    type ProtoRequest = proto::GetTypeHierarchy;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.type_hierarchy_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::TypeHierarchyPrepareParams {
+        lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::TypeHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyItem>> {
+        let (lsp_adapter, language_server) =
+            language_server_for_buffer(&project, &buffer, server_id, &mut cx)?;
+
+        let mut hierarchy = Vec::new();
+        for item in items.unwrap_or_default() {
+            let params = lsp::TypeHierarchySupertypesParams {
+                item,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            };
+            let types = match self.direction {
+                TypeHierarchyDirection::Supertypes => {
+                    language_server
+                        .request::<lsp::request::TypeHierarchySupertypes>(params)
+                        .await?
+                }
+                TypeHierarchyDirection::Subtypes => {
+                    language_server
+                        .request::<lsp::request::TypeHierarchySubtypes>(params)
+                        .await?
+                }
+            };
+            for ty in types.unwrap_or_default() {
+                hierarchy.push(
+                    hierarchy_item_from_lsp(
+                        LspHierarchyItem {
+                            name: ty.name,
+                            kind: ty.kind,
+                            detail: ty.detail,
+                            uri: ty.uri,
+                            selection_range: ty.selection_range,
+                            call_sites: Vec::new(),
+                        },
+                        &project,
+                        &lsp_adapter,
+                        &language_server,
+                        &mut cx,
+                    )
+                    .await?,
+                );
+            }
+        }
+        Ok(hierarchy)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetTypeHierarchy {
+        let direction = match self.direction {
+            TypeHierarchyDirection::Supertypes => proto::get_type_hierarchy::Direction::Supertypes,
+            TypeHierarchyDirection::Subtypes => proto::get_type_hierarchy::Direction::Subtypes,
+        };
+        proto::GetTypeHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            direction: direction.into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetTypeHierarchy,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        let direction = match proto::get_type_hierarchy::Direction::from_i32(message.direction) {
+            Some(proto::get_type_hierarchy::Direction::Subtypes) => {
+                TypeHierarchyDirection::Subtypes
+            }
+            Some(proto::get_type_hierarchy::Direction::Supertypes) | None => {
+                TypeHierarchyDirection::Supertypes
+            }
+        };
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+            direction,
+        })
+    }
+
+    fn response_to_proto(
+        items: Vec<HierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::GetTypeHierarchyResponse {
+        proto::GetTypeHierarchyResponse {
+            items: hierarchy_items_to_proto(items, project, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetTypeHierarchyResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetTypeHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+/// The parts of an LSP call or type hierarchy item that are kept in a [`HierarchyItem`].
+struct LspHierarchyItem {
+    name: String,
+    kind: lsp::SymbolKind,
+    detail: Option<String>,
+    uri: lsp::Url,
+    selection_range: lsp::Range,
+    call_sites: Vec<lsp::Range>,
+}
+
+async fn hierarchy_item_from_lsp(
+    item: LspHierarchyItem,
+    project: &Model<Project>,
+    lsp_adapter: &Arc<CachedLspAdapter>,
+    language_server: &Arc<LanguageServer>,
+    cx: &mut AsyncAppContext,
+) -> Result<HierarchyItem> {
+    let target_buffer_handle = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_via_lsp(
+                item.uri,
+                language_server.server_id(),
+                lsp_adapter.name.clone(),
+                cx,
+            )
+        })?
+        .await?;
+
+    target_buffer_handle.clone().update(cx, |target_buffer, _| {
+        let anchor_range = |range: lsp::Range| {
+            let start = target_buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+            let end = target_buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+            target_buffer.anchor_after(start)..target_buffer.anchor_before(end)
+        };
+        HierarchyItem {
+            name: item.name,
+            kind: item.kind,
+            detail: item.detail,
+            location: Location {
+                buffer: target_buffer_handle,
+                range: anchor_range(item.selection_range),
+            },
+            call_sites: item.call_sites.into_iter().map(anchor_range).collect(),
+        }
+    })
+}
+
+fn hierarchy_items_to_proto(
+    items: Vec<HierarchyItem>,
+    project: &mut Project,
+    peer_id: PeerId,
+    cx: &mut AppContext,
+) -> Vec<proto::HierarchyItem> {
+    items
+        .into_iter()
+        .map(|item| {
+            let buffer_id = project.create_buffer_for_peer(&item.location.buffer, peer_id, cx);
+            proto::HierarchyItem {
+                name: item.name,
+                kind: unsafe { mem::transmute(item.kind) },
+                detail: item.detail,
+                location: Some(proto::Location {
+                    start: Some(serialize_anchor(&item.location.range.start)),
+                    end: Some(serialize_anchor(&item.location.range.end)),
+                    buffer_id: buffer_id.into(),
+                }),
+                call_sites: item
+                    .call_sites
+                    .iter()
+                    .map(|range| proto::hierarchy_item::CallSite {
+                        start: Some(serialize_anchor(&range.start)),
+                        end: Some(serialize_anchor(&range.end)),
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+async fn hierarchy_items_from_proto(
+    items: Vec<proto::HierarchyItem>,
+    project: Model<Project>,
+    mut cx: AsyncAppContext,
+) -> Result<Vec<HierarchyItem>> {
+    let mut hierarchy = Vec::new();
+    for item in items {
+        let location = item.location.ok_or_else(|| anyhow!("missing location"))?;
+        let buffer_id = BufferId::new(location.buffer_id)?;
+        let target_buffer = project
+            .update(&mut cx, |this, cx| {
+                this.wait_for_remote_buffer(buffer_id, cx)
+            })?
+            .await?;
+        let mut anchors = Vec::new();
+        for (start, end) in [(location.start, location.end)].into_iter().chain(
+            item.call_sites
+                .into_iter()
+                .map(|call_site| (call_site.start, call_site.end)),
+        ) {
+            let start = start
+                .and_then(deserialize_anchor)
+                .ok_or_else(|| anyhow!("missing target start"))?;
+            let end = end
+                .and_then(deserialize_anchor)
+                .ok_or_else(|| anyhow!("missing target end"))?;
+            anchors.push(start..end);
+        }
+        target_buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_anchors(
+                    anchors
+                        .iter()
+                        .flat_map(|range| [range.start, range.end])
+                        .collect::<Vec<_>>(),
+                )
+            })?
+            .await?;
+
+        let mut ranges = anchors.into_iter();
+        hierarchy.push(HierarchyItem {
+            name: item.name,
+            kind: unsafe { mem::transmute(item.kind) },
+            detail: item.detail,
+            location: Location {
+                buffer: target_buffer,
+                range: ranges.next().unwrap(),
+            },
+            call_sites: ranges.collect(),
+        });
+    }
+    Ok(hierarchy)
+}
+
 #[async_trait(?Send)]
 impl LspCommand for OnTypeFormatting {
     type Response = Option<Transaction>;
@@ -1,4 +1,7 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -7,6 +10,11 @@ use language::{point_to_lsp, proto::deserialize_anchor, Buffer};
 use lsp::{LanguageServer, LanguageServerId};
 use rpc::proto::{self, PeerId};
 use serde::{Deserialize, Serialize};
+use task::{
+    problem_matcher::{BuiltInProblemMatcher, ProblemMatcher},
+    static_source::RevealStrategy,
+    SpawnInTerminal, TaskId,
+};
 use text::{BufferId, PointUtf16, ToPointUtf16};
 
 use crate::{lsp_command::LspCommand, Project};
@@ -135,3 +143,72 @@ impl LspCommand for ExpandMacro {
         BufferId::new(message.buffer_id)
     }
 }
+
+/// The commands of rust-analyzer's "Run" and "Debug" lenses. Clients are expected to run
+/// them on their own rather than through `workspace/executeCommand`.
+const RUN_SINGLE_COMMAND: &str = "rust-analyzer.runSingle";
+const DEBUG_SINGLE_COMMAND: &str = "rust-analyzer.debugSingle";
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Runnable {
+    pub label: String,
+    pub kind: RunnableKind,
+    pub args: CargoRunnableArgs,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RunnableKind {
+    Cargo,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CargoRunnableArgs {
+    pub workspace_root: Option<PathBuf>,
+    #[serde(default)]
+    pub cargo_args: Vec<String>,
+    #[serde(default)]
+    pub cargo_extra_args: Vec<String>,
+    #[serde(default)]
+    pub executable_args: Vec<String>,
+    pub override_cargo: Option<String>,
+}
+
+/// Turns a runnable lens command into a task to spawn in the terminal.
+/// Zed has no debugger, so "Debug" lenses run the same command as "Run" ones.
+pub fn task_for_runnable_command(command: &lsp::Command) -> Option<SpawnInTerminal> {
+    if command.command != RUN_SINGLE_COMMAND && command.command != DEBUG_SINGLE_COMMAND {
+        return None;
+    }
+    let runnable =
+        serde_json::from_value::<Runnable>(command.arguments.as_ref()?.first()?.clone()).ok()?;
+
+    let CargoRunnableArgs {
+        workspace_root,
+        cargo_args,
+        cargo_extra_args,
+        executable_args,
+        override_cargo,
+    } = runnable.args;
+    let mut args = cargo_args;
+    args.extend(cargo_extra_args);
+    if !executable_args.is_empty() {
+        args.push("--".to_string());
+        args.extend(executable_args);
+    }
+
+    Some(SpawnInTerminal {
+        id: TaskId(format!("rust-analyzer runnable {}", runnable.label)),
+        label: runnable.label,
+        command: override_cargo.unwrap_or_else(|| "cargo".to_string()),
+        args,
+        cwd: workspace_root,
+        env: Default::default(),
+        use_new_terminal: false,
+        allow_concurrent_runs: false,
+        reveal: RevealStrategy::default(),
+        problem_matcher: Some(ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)),
+    })
+}
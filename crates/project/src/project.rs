@@ -3,7 +3,9 @@ pub mod lsp_command;
 pub mod lsp_ext_command;
 mod prettier_support;
 pub mod project_settings;
+pub mod runnables_source;
 pub mod search;
+pub mod semantic_tokens;
 mod task_inventory;
 pub mod terminals;
 
@@ -19,10 +21,13 @@ use clock::ReplicaId;
 use collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque};
 use copilot::Copilot;
 use debounced_delay::DebouncedDelay;
-use fs::repository::GitRepository;
+use fs::repository::{
+    diff_rows, old_rows, stage_hunks_in_rows, unstage_hunks_in_rows, Blame, CommitLog,
+    GitRepository, LogOptions,
+};
 use futures::{
     channel::mpsc::{self, UnboundedReceiver},
-    future::{try_join_all, Shared},
+    future::{self, try_join_all, Shared},
     select,
     stream::FuturesUnordered,
     AsyncWriteExt, Future, FutureExt, StreamExt, TryFutureExt,
@@ -42,9 +47,9 @@ use language::{
     },
     range_from_lsp, Bias, Buffer, BufferSnapshot, CachedLspAdapter, Capability, CodeAction,
     CodeLabel, Completion, Diagnostic, DiagnosticEntry, DiagnosticSet, Diff, Documentation,
-    Event as BufferEvent, File as _, Language, LanguageRegistry, LanguageServerName, LocalFile,
-    LspAdapterDelegate, Operation, Patch, PendingLanguageServer, PointUtf16, TextBufferSnapshot,
-    ToOffset, ToPointUtf16, Transaction, Unclipped,
+    Event as BufferEvent, File as _, FoldRangeKind, Language, LanguageRegistry, LanguageServerName,
+    LocalFile, LspAdapterDelegate, Operation, Patch, PendingLanguageServer, PointUtf16,
+    TextBufferSnapshot, ToOffset, ToPointUtf16, Transaction, Unclipped,
 };
 use log::error;
 use lsp::{
@@ -63,12 +68,17 @@ use worktree::LocalSnapshot;
 
 use rpc::{ErrorCode, ErrorExt as _};
 use search::SearchQuery;
+use semantic_tokens::{
+    apply_semantic_token_edits, decode_semantic_tokens, flatten_lsp_tokens, BufferSemanticTokens,
+    CachedSemanticTokens,
+};
 use serde::Serialize;
 use settings::{watch_config_file, Settings, SettingsLocation, SettingsStore};
 use sha2::{Digest, Sha256};
 use similar::{ChangeTag, TextDiff};
 use smol::channel::{Receiver, Sender};
 use smol::lock::Semaphore;
+use snippet_provider::{is_snippets_file, SnippetProvider};
 use std::{
     cmp::{self, Ordering},
     convert::TryInto,
@@ -87,14 +97,21 @@ use std::{
     },
     time::{Duration, Instant},
 };
-use task::static_source::StaticSource;
+use task::{
+    discovered_source::{DiscoveredFile, DiscoveredSource},
+    static_source::StaticSource,
+    vscode_format::VsCodeFile,
+    SpawnInTerminal,
+};
 use terminals::Terminals;
 use text::{Anchor, BufferId};
 use util::{
     debug_panic, defer,
     http::HttpClient,
     merge_json_value_into,
-    paths::{LOCAL_SETTINGS_RELATIVE_PATH, LOCAL_TASKS_RELATIVE_PATH},
+    paths::{
+        LOCAL_SETTINGS_RELATIVE_PATH, LOCAL_SNIPPETS_RELATIVE_PATH, LOCAL_TASKS_RELATIVE_PATH,
+    },
     post_inc, ResultExt, TryFutureExt as _,
 };
 use worktree::{Snapshot, Traversal};
@@ -105,7 +122,7 @@ pub use language::Location;
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 #[cfg(feature = "test-support")]
 pub use task_inventory::test_inventory::*;
-pub use task_inventory::{Inventory, TaskSourceKind};
+pub use task_inventory::{Inventory, ResolvedTask, TaskSourceKind};
 pub use worktree::{
     DiagnosticSummary, Entry, EntryKind, File, LocalWorktree, PathChange, ProjectEntryId,
     RepositoryEntry, UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId,
@@ -116,6 +133,8 @@ const MAX_SERVER_REINSTALL_ATTEMPT_COUNT: u64 = 4;
 const SERVER_REINSTALL_DEBOUNCE_TIMEOUT: Duration = Duration::from_secs(1);
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 pub const SERVER_PROGRESS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(100);
+const PULL_DIAGNOSTICS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
+const MAX_FILE_HISTORY_WALKED_COMMITS: usize = 10_000;
 
 pub trait Item {
     fn try_open(
@@ -173,6 +192,15 @@ pub struct Project {
     /// Used for re-issuing buffer requests when peers temporarily disconnect
     incomplete_remote_buffers: HashMap<BufferId, Option<Model<Buffer>>>,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    cached_semantic_tokens: HashMap<(BufferId, LanguageServerId), CachedSemanticTokens>,
+    /// The result IDs of the last diagnostics pulled for each document, sent back to
+    /// the language server on the next pull so that it can report them as unchanged.
+    diagnostic_result_ids: HashMap<(LanguageServerId, lsp::Url), String>,
+    /// The diagnostics of the language servers that support pulling them, kept apart from the
+    /// ones they push, so that updating either kind leaves the other kind in place.
+    diagnostics_by_model: HashMap<(LanguageServerId, lsp::Url), DiagnosticsByModel>,
+    pull_diagnostics_tasks: HashMap<BufferId, Task<()>>,
+    pull_workspace_diagnostics_tasks: HashMap<LanguageServerId, Task<()>>,
     buffers_being_formatted: HashSet<BufferId>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
     git_diff_debouncer: DebouncedDelay,
@@ -188,6 +216,7 @@ pub struct Project {
     prettiers_per_worktree: HashMap<WorktreeId, HashSet<Option<PathBuf>>>,
     prettier_instances: HashMap<PathBuf, PrettierInstance>,
     tasks: Model<Inventory>,
+    snippets: Model<SnippetProvider>,
     hosted_project_id: Option<ProjectId>,
 }
 
@@ -201,6 +230,12 @@ struct LspBufferSnapshot {
     snapshot: TextBufferSnapshot,
 }
 
+#[derive(Default)]
+struct DiagnosticsByModel {
+    pushed: Vec<lsp::Diagnostic>,
+    pulled: Vec<lsp::Diagnostic>,
+}
+
 /// Message ordered with respect to buffer operations
 #[derive(Debug)]
 enum BufferOrderedMessage {
@@ -288,6 +323,7 @@ pub enum Event {
     WorktreeAdded,
     WorktreeRemoved(WorktreeId),
     WorktreeUpdatedEntries(WorktreeId, UpdatedEntriesSet),
+    WorktreeUpdatedGitRepositories(WorktreeId),
     DiskBasedDiagnosticsStarted {
         language_server_id: LanguageServerId,
     },
@@ -309,6 +345,8 @@ pub enum Event {
     CollaboratorJoined(proto::PeerId),
     CollaboratorLeft(proto::PeerId),
     RefreshInlayHints,
+    RefreshSemanticTokens,
+    RefreshCodeLens,
     RevealInProjectPanel(ProjectEntryId),
 }
 
@@ -409,6 +447,61 @@ pub struct LocationLink {
     pub target: Location,
 }
 
+/// A foldable range reported by a language server, spanning from the end of its
+/// first line to the end of its last line.
+#[derive(Clone, Debug)]
+pub struct FoldingRange {
+    pub range: Range<Anchor>,
+    pub kind: Option<FoldRangeKind>,
+}
+
+/// A link reported by a language server, such as a dependency name in a manifest or an
+/// import path. Servers may leave out the target until the link is resolved with
+/// [`Project::resolve_document_link`].
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    pub server_id: LanguageServerId,
+    pub range: Range<Anchor>,
+    pub lsp_link: lsp::DocumentLink,
+}
+
+impl DocumentLink {
+    pub fn target(&self) -> Option<&lsp::Url> {
+        self.lsp_link.target.as_ref()
+    }
+
+    pub fn tooltip(&self) -> Option<&str> {
+        self.lsp_link.tooltip.as_deref()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallHierarchyDirection {
+    /// The functions calling the symbol.
+    Incoming,
+    /// The functions called by the symbol.
+    Outgoing,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeHierarchyDirection {
+    Supertypes,
+    Subtypes,
+}
+
+/// A symbol one level up or down a call or type hierarchy.
+#[derive(Debug, Clone)]
+pub struct HierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The name of the symbol in its declaration.
+    pub location: Location,
+    /// For incoming calls, the ranges in the symbol's buffer where it calls the
+    /// requested symbol. Empty otherwise.
+    pub call_sites: Vec<Range<Anchor>>,
+}
+
 #[derive(Debug)]
 pub struct DocumentHighlight {
     pub range: Range<language::Anchor>,
@@ -453,6 +546,67 @@ impl Hover {
     }
 }
 
+/// The call signatures that apply at a position, as reported by `textDocument/signatureHelp`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SignatureHelp {
+    pub signatures: Vec<SignatureInformation>,
+    pub active_signature: usize,
+    pub active_parameter: Option<usize>,
+}
+
+impl SignatureHelp {
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// Returns the parameter to highlight for the signature at the given index.
+    /// A signature's own active parameter takes precedence over the response-wide one.
+    pub fn active_parameter_for(&self, signature_ix: usize) -> Option<&ParameterInformation> {
+        let signature = self.signatures.get(signature_ix)?;
+        let parameter_ix = signature.active_parameter.or(self.active_parameter)?;
+        signature.parameters.get(parameter_ix)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureInformation {
+    pub label: String,
+    pub documentation: Option<MarkupContent>,
+    pub parameters: Vec<ParameterInformation>,
+    pub active_parameter: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterInformation {
+    /// Byte range of the parameter within its signature's label.
+    pub label_range: Range<usize>,
+    pub documentation: Option<MarkupContent>,
+}
+
+/// A code lens reported by a language server. Lenses are resolved before they reach
+/// the editor, so they always have a command.
+#[derive(Clone, Debug)]
+pub struct CodeLens {
+    pub server_id: LanguageServerId,
+    pub range: Range<Anchor>,
+    pub lsp_lens: lsp::CodeLens,
+}
+
+impl CodeLens {
+    pub fn title(&self) -> &str {
+        self.lsp_lens
+            .command
+            .as_ref()
+            .map_or("", |command| command.title.as_str())
+    }
+
+    /// The task to spawn when this lens runs a test or a binary, for lenses whose
+    /// commands are meant to be executed by the client.
+    pub fn runnable(&self) -> Option<SpawnInTerminal> {
+        lsp_ext_command::task_for_runnable_command(self.lsp_lens.command.as_ref()?)
+    }
+}
+
 #[derive(Default)]
 pub struct ProjectTransaction(pub HashMap<Model<Buffer>, language::Transaction>);
 
@@ -538,13 +692,24 @@ impl Project {
         client.add_model_request_handler(Self::handle_on_type_formatting);
         client.add_model_request_handler(Self::handle_inlay_hints);
         client.add_model_request_handler(Self::handle_resolve_inlay_hint);
+        client.add_model_request_handler(Self::handle_resolve_document_link);
         client.add_model_request_handler(Self::handle_refresh_inlay_hints);
+        client.add_model_request_handler(Self::handle_semantic_tokens);
+        client.add_model_request_handler(Self::handle_refresh_semantic_tokens);
+        client.add_model_request_handler(Self::handle_refresh_code_lens);
         client.add_model_request_handler(Self::handle_reload_buffers);
         client.add_model_request_handler(Self::handle_synchronize_buffers);
         client.add_model_request_handler(Self::handle_format_buffers);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCodeActions>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetCodeLens>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetCallHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetTypeHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetFoldingRanges>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetLinkedEditingRanges>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentLinks>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetHover>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSignatureHelp>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDefinition>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetTypeDefinition>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
@@ -558,6 +723,8 @@ impl Project {
         client.add_model_request_handler(Self::handle_open_buffer_by_path);
         client.add_model_request_handler(Self::handle_save_buffer);
         client.add_model_message_handler(Self::handle_update_diff_base);
+        client.add_model_request_handler(Self::handle_stage_hunks);
+        client.add_model_request_handler(Self::handle_unstage_hunks);
         client.add_model_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
     }
 
@@ -592,6 +759,11 @@ impl Project {
                 local_buffer_ids_by_path: Default::default(),
                 local_buffer_ids_by_entry_id: Default::default(),
                 buffer_snapshots: Default::default(),
+                cached_semantic_tokens: Default::default(),
+                diagnostic_result_ids: Default::default(),
+                diagnostics_by_model: Default::default(),
+                pull_diagnostics_tasks: Default::default(),
+                pull_workspace_diagnostics_tasks: Default::default(),
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 opened_buffer: watch::channel(),
@@ -623,6 +795,8 @@ impl Project {
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostic_sources: HashMap::default(),
+                    task_completion_waiters: HashMap::default(),
                 },
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
@@ -632,6 +806,7 @@ impl Project {
                 prettiers_per_worktree: HashMap::default(),
                 prettier_instances: HashMap::default(),
                 tasks,
+                snippets: cx.new_model(|_| SnippetProvider::default()),
                 hosted_project_id: None,
             }
         })
@@ -755,9 +930,16 @@ impl Project {
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 buffer_snapshots: Default::default(),
+                cached_semantic_tokens: Default::default(),
+                diagnostic_result_ids: Default::default(),
+                diagnostics_by_model: Default::default(),
+                pull_diagnostics_tasks: Default::default(),
+                pull_workspace_diagnostics_tasks: Default::default(),
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostic_sources: HashMap::default(),
+                    task_completion_waiters: HashMap::default(),
                 },
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
@@ -767,6 +949,7 @@ impl Project {
                 prettiers_per_worktree: HashMap::default(),
                 prettier_instances: HashMap::default(),
                 tasks,
+                snippets: cx.new_model(|_| SnippetProvider::default()),
                 hosted_project_id: None,
             };
             this.set_role(role, cx);
@@ -1092,6 +1275,10 @@ impl Project {
         &self.tasks
     }
 
+    pub fn snippets(&self) -> &Model<SnippetProvider> {
+        &self.snippets
+    }
+
     pub fn collaborators(&self) -> &HashMap<proto::PeerId, Collaborator> {
         &self.collaborators
     }
@@ -2209,6 +2396,8 @@ impl Project {
                         .or_default()
                         .insert(server.server_id(), vec![snapshot]);
                 }
+
+                self.pull_diagnostics(buffer_handle.clone(), false, cx);
             }
         }
     }
@@ -2237,6 +2426,9 @@ impl Project {
             }
 
             self.buffer_snapshots.remove(&buffer.remote_id());
+            self.cached_semantic_tokens
+                .retain(|(buffer_id, _), _| *buffer_id != buffer.remote_id());
+            self.pull_diagnostics_tasks.remove(&buffer.remote_id());
             let file_url = lsp::Url::from_file_path(old_path).unwrap();
             for (_, language_server) in self.language_servers_for_buffer(buffer, cx) {
                 language_server
@@ -2391,6 +2583,8 @@ impl Project {
             }
 
             BufferEvent::Edited { .. } => {
+                self.pull_diagnostics(buffer.clone(), true, cx);
+
                 let buffer = buffer.read(cx);
                 let file = File::from_dyn(buffer.file())?;
                 let abs_path = file.as_local()?.abs_path(cx);
@@ -3082,9 +3276,10 @@ impl Project {
                     if let Some(this) = this.upgrade() {
                         adapter.process_diagnostics(&mut params);
                         this.update(&mut cx, |this, cx| {
-                            this.update_diagnostics(
+                            this.update_diagnostics_by_model(
                                 server_id,
                                 params,
+                                false,
                                 &adapter.disk_based_diagnostic_sources,
                                 cx,
                             )
@@ -3208,6 +3403,61 @@ impl Project {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::CodeLensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |project, cx| {
+                            cx.emit(Event::RefreshCodeLens);
+                            project.remote_id().map(|project_id| {
+                                project.client.send(proto::RefreshCodeLens { project_id })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |project, cx| {
+                            cx.emit(Event::RefreshSemanticTokens);
+                            project.remote_id().map(|project_id| {
+                                project
+                                    .client
+                                    .send(proto::RefreshSemanticTokens { project_id })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |project, cx| {
+                            project.refresh_pulled_diagnostics(server_id, cx)
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::ShowMessageRequest, _, _>({
                 let this = this.clone();
@@ -3459,6 +3709,7 @@ impl Project {
                 });
             }
         }
+        self.refresh_pulled_diagnostics(server_id, cx);
 
         cx.notify();
         Ok(())
@@ -3506,6 +3757,11 @@ impl Project {
 
             self.language_server_watched_paths.remove(&server_id);
             self.language_server_statuses.remove(&server_id);
+            self.diagnostic_result_ids
+                .retain(|(diagnostics_server_id, _), _| *diagnostics_server_id != server_id);
+            self.diagnostics_by_model
+                .retain(|(diagnostics_server_id, _), _| *diagnostics_server_id != server_id);
+            self.pull_workspace_diagnostics_tasks.remove(&server_id);
             cx.notify();
 
             let server_state = self.language_servers.remove(&server_id);
@@ -4031,6 +4287,291 @@ impl Project {
         self.last_formatting_failure.as_deref()
     }
 
+    /// Pulls the diagnostics of a buffer from its language servers that support the pull model.
+    fn pull_diagnostics(
+        &mut self,
+        buffer: Model<Buffer>,
+        debounce: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let task = cx.spawn(move |this, mut cx| async move {
+            if debounce {
+                cx.background_executor()
+                    .timer(PULL_DIAGNOSTICS_DEBOUNCE_TIMEOUT)
+                    .await;
+            }
+
+            let Some(requests) = this
+                .update(&mut cx, |this, cx| {
+                    this.document_diagnostic_requests(&buffer, cx)
+                })
+                .ok()
+            else {
+                return;
+            };
+            let responses = future::join_all(requests.into_iter().map(
+                |(adapter, server_id, uri, version, request)| async move {
+                    (adapter, server_id, uri, version, request.await)
+                },
+            ))
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                for (adapter, server_id, uri, version, response) in responses {
+                    let Some(response) = response.log_err() else {
+                        continue;
+                    };
+                    for (uri, version, report) in
+                        document_diagnostic_reports(uri, version, response)
+                    {
+                        this.update_pulled_diagnostics(
+                            server_id, &adapter, uri, version, report, cx,
+                        )
+                        .log_err();
+                    }
+                }
+            })
+            .ok();
+        });
+        self.pull_diagnostics_tasks.insert(buffer_id, task);
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn document_diagnostic_requests(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Vec<(
+        Arc<CachedLspAdapter>,
+        LanguageServerId,
+        lsp::Url,
+        i32,
+        impl 'static + Future<Output = Result<lsp::DocumentDiagnosticReportResult>>,
+    )> {
+        let buffer = buffer.read(cx);
+        let Some(abs_path) =
+            File::from_dyn(buffer.file()).and_then(|file| Some(file.as_local()?.abs_path(cx)))
+        else {
+            return Vec::new();
+        };
+        let Ok(uri) = lsp::Url::from_file_path(abs_path) else {
+            return Vec::new();
+        };
+
+        self.language_servers_for_buffer(buffer, cx)
+            .filter_map(|(adapter, server)| {
+                let identifier = match server.capabilities().diagnostic_provider.as_ref()? {
+                    lsp::DiagnosticServerCapabilities::Options(options) => {
+                        options.identifier.clone()
+                    }
+                    lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+                        options.diagnostic_options.identifier.clone()
+                    }
+                };
+                let server_id = server.server_id();
+                let version = self
+                    .buffer_snapshots
+                    .get(&buffer.remote_id())?
+                    .get(&server_id)?
+                    .last()?
+                    .version;
+                let request = server.request::<lsp::request::DocumentDiagnosticRequest>(
+                    lsp::DocumentDiagnosticParams {
+                        text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                        identifier,
+                        previous_result_id: self
+                            .diagnostic_result_ids
+                            .get(&(server_id, uri.clone()))
+                            .cloned(),
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                );
+                Some((adapter.clone(), server_id, uri.clone(), version, request))
+            })
+            .collect()
+    }
+
+    /// Pulls the diagnostics of the whole workspace from a language server, if it supports it.
+    fn pull_workspace_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(LanguageServerState::Running {
+            adapter, server, ..
+        }) = self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let identifier = match server.capabilities().diagnostic_provider.as_ref() {
+            Some(lsp::DiagnosticServerCapabilities::Options(options))
+                if options.workspace_diagnostics =>
+            {
+                options.identifier.clone()
+            }
+            Some(lsp::DiagnosticServerCapabilities::RegistrationOptions(options))
+                if options.diagnostic_options.workspace_diagnostics =>
+            {
+                options.diagnostic_options.identifier.clone()
+            }
+            _ => return,
+        };
+
+        let previous_result_ids = self
+            .diagnostic_result_ids
+            .iter()
+            .filter(|((diagnostics_server_id, _), _)| *diagnostics_server_id == server_id)
+            .map(|((_, uri), result_id)| lsp::PreviousResultId {
+                uri: uri.clone(),
+                value: result_id.clone(),
+            })
+            .collect();
+        let request = server.request::<lsp::request::WorkspaceDiagnosticRequest>(
+            lsp::WorkspaceDiagnosticParams {
+                identifier,
+                previous_result_ids,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        );
+        let adapter = adapter.clone();
+        // Replacing the task of the previous pull drops its request, which cancels it.
+        let task = cx.spawn(move |this, mut cx| async move {
+            let Some(lsp::WorkspaceDiagnosticReportResult::Report(report)) =
+                request.await.log_err()
+            else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                for item in report.items {
+                    let (uri, version, report) = match item {
+                        lsp::WorkspaceDocumentDiagnosticReport::Full(report) => (
+                            report.uri,
+                            report.version,
+                            lsp::DocumentDiagnosticReportKind::Full(
+                                report.full_document_diagnostic_report,
+                            ),
+                        ),
+                        lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                            report.uri,
+                            report.version,
+                            lsp::DocumentDiagnosticReportKind::Unchanged(
+                                report.unchanged_document_diagnostic_report,
+                            ),
+                        ),
+                    };
+                    let version = version.map(|version| version as i32);
+                    this.update_pulled_diagnostics(server_id, &adapter, uri, version, report, cx)
+                        .log_err();
+                }
+            })
+            .ok();
+        });
+        self.pull_workspace_diagnostics_tasks
+            .insert(server_id, task);
+    }
+
+    /// Pulls the diagnostics of every open buffer and of the workspace again, such as when
+    /// the language server asks for it after a configuration change.
+    fn refresh_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffers = self
+            .opened_buffers
+            .values()
+            .filter_map(|buffer| buffer.upgrade())
+            .filter(|buffer| {
+                self.language_server_ids_for_buffer(buffer.read(cx), cx)
+                    .contains(&server_id)
+            })
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.pull_diagnostics(buffer, false, cx);
+        }
+        self.pull_workspace_diagnostics(server_id, cx);
+    }
+
+    /// Applies a diagnostic report pulled from a language server, keeping its result ID
+    /// for the next pull.
+    fn update_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        adapter: &CachedLspAdapter,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::DocumentDiagnosticReportKind,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        match report {
+            lsp::DocumentDiagnosticReportKind::Full(report) => {
+                let key = (server_id, uri.clone());
+                match report.result_id {
+                    Some(result_id) => self.diagnostic_result_ids.insert(key, result_id),
+                    None => self.diagnostic_result_ids.remove(&key),
+                };
+
+                let mut params = lsp::PublishDiagnosticsParams {
+                    uri,
+                    diagnostics: report.items,
+                    version,
+                };
+                adapter.process_diagnostics(&mut params);
+                self.update_diagnostics_by_model(
+                    server_id,
+                    params,
+                    true,
+                    &adapter.disk_based_diagnostic_sources,
+                    cx,
+                )
+            }
+            lsp::DocumentDiagnosticReportKind::Unchanged(report) => {
+                self.diagnostic_result_ids
+                    .insert((server_id, uri), report.result_id);
+                Ok(())
+            }
+        }
+    }
+
+    /// Updates the diagnostics pushed or pulled by a language server. For servers supporting
+    /// pulls, the new diagnostics only replace the ones of the same kind.
+    fn update_diagnostics_by_model(
+        &mut self,
+        server_id: LanguageServerId,
+        mut params: lsp::PublishDiagnosticsParams,
+        pulled: bool,
+        disk_based_sources: &[String],
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let supports_pulls = matches!(
+            self.language_servers.get(&server_id),
+            Some(LanguageServerState::Running { server, .. })
+                if server.capabilities().diagnostic_provider.is_some()
+        );
+        if supports_pulls {
+            let key = (server_id, params.uri.clone());
+            let diagnostics = self.diagnostics_by_model.entry(key.clone()).or_default();
+            if pulled {
+                diagnostics.pulled = params.diagnostics;
+            } else {
+                diagnostics.pushed = params.diagnostics;
+            }
+            params.diagnostics = diagnostics
+                .pushed
+                .iter()
+                .chain(&diagnostics.pulled)
+                .cloned()
+                .collect();
+            if params.diagnostics.is_empty() {
+                self.diagnostics_by_model.remove(&key);
+            }
+        }
+        self.update_diagnostics(server_id, params, disk_based_sources, cx)
+    }
+
     pub fn update_diagnostics(
         &mut self,
         language_server_id: LanguageServerId,
@@ -4883,36 +5424,119 @@ impl Project {
         self.references_impl(buffer, position, cx)
     }
 
-    fn document_highlights_impl(
+    pub fn folding_ranges(
         &self,
         buffer: &Model<Buffer>,
-        position: PointUtf16,
         cx: &mut ModelContext<Self>,
-    ) -> Task<Result<Vec<DocumentHighlight>>> {
+    ) -> Task<Result<Vec<FoldingRange>>> {
         self.request_lsp(
             buffer.clone(),
             LanguageServerToQuery::Primary,
-            GetDocumentHighlights { position },
+            GetFoldingRanges,
             cx,
         )
     }
 
-    pub fn document_highlights<T: ToPointUtf16>(
+    pub fn document_links(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetDocumentLinks,
+            cx,
+        )
+    }
+
+    /// Returns the ranges that are edited together with the one at the given position, such
+    /// as the names of an opening and a closing tag.
+    pub fn linked_editing_ranges<T: ToPointUtf16>(
         &self,
         buffer: &Model<Buffer>,
         position: T,
         cx: &mut ModelContext<Self>,
-    ) -> Task<Result<Vec<DocumentHighlight>>> {
+    ) -> Task<Result<Vec<Range<Anchor>>>> {
         let position = position.to_point_utf16(buffer.read(cx));
-        self.document_highlights_impl(buffer, position, cx)
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetLinkedEditingRanges { position },
+            cx,
+        )
     }
 
-    pub fn symbols(&self, query: &str, cx: &mut ModelContext<Self>) -> Task<Result<Vec<Symbol>>> {
-        if self.is_local() {
-            let mut requests = Vec::new();
-            for ((worktree_id, _), server_id) in self.language_server_ids.iter() {
-                let Some(worktree_handle) = self.worktree_for_id(*worktree_id, cx) else {
-                    continue;
+    /// Returns the symbols calling, or called by, the function at the given position.
+    pub fn call_hierarchy<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        direction: CallHierarchyDirection,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetCallHierarchy {
+                position,
+                direction,
+            },
+            cx,
+        )
+    }
+
+    /// Returns the direct supertypes, or subtypes, of the type at the given position.
+    pub fn type_hierarchy<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        direction: TypeHierarchyDirection,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetTypeHierarchy {
+                position,
+                direction,
+            },
+            cx,
+        )
+    }
+
+    fn document_highlights_impl(
+        &self,
+        buffer: &Model<Buffer>,
+        position: PointUtf16,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<DocumentHighlight>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetDocumentHighlights { position },
+            cx,
+        )
+    }
+
+    pub fn document_highlights<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<DocumentHighlight>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.document_highlights_impl(buffer, position, cx)
+    }
+
+    pub fn symbols(&self, query: &str, cx: &mut ModelContext<Self>) -> Task<Result<Vec<Symbol>>> {
+        if self.is_local() {
+            let mut requests = Vec::new();
+            for ((worktree_id, _), server_id) in self.language_server_ids.iter() {
+                let Some(worktree_handle) = self.worktree_for_id(*worktree_id, cx) else {
+                    continue;
                 };
                 let worktree = worktree_handle.read(cx);
                 if !worktree.is_visible() {
@@ -5160,6 +5784,64 @@ impl Project {
         self.hover_impl(buffer, position, cx)
     }
 
+    pub fn signature_help<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        trigger_character: Option<String>,
+        is_retrigger: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<SignatureHelp>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSignatureHelp {
+                position,
+                trigger_character,
+                is_retrigger,
+            },
+            cx,
+        )
+    }
+
+    /// Returns the characters that should trigger signature help when typed in the given buffer,
+    /// along with the ones that only re-trigger it while its popover is visible.
+    ///
+    /// Guests don't know the host's server capabilities, so they fall back to the
+    /// characters that open and continue an argument list in most languages.
+    pub fn signature_help_trigger_characters(
+        &self,
+        buffer: &Buffer,
+        is_retrigger: bool,
+        cx: &AppContext,
+    ) -> Vec<String> {
+        if self.is_remote() {
+            let mut characters = vec!["(".to_string()];
+            if is_retrigger {
+                characters.push(",".to_string());
+            }
+            return characters;
+        }
+
+        self.primary_language_server_for_buffer(buffer, cx)
+            .and_then(|(_, server)| server.capabilities().signature_help_provider.clone())
+            .map(|provider| {
+                let retrigger_characters = if is_retrigger {
+                    provider.retrigger_characters
+                } else {
+                    None
+                };
+                provider
+                    .trigger_characters
+                    .into_iter()
+                    .chain(retrigger_characters)
+                    .flatten()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     #[inline(never)]
     fn completions_impl(
         &self,
@@ -5529,6 +6211,56 @@ impl Project {
         self.code_actions_impl(buffer_handle, range, cx)
     }
 
+    pub fn code_lens(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CodeLens>>> {
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetCodeLens,
+            cx,
+        )
+    }
+
+    /// Runs the lens' command through `workspace/executeCommand`, applying the workspace
+    /// edits the server sends back while the command runs.
+    pub fn apply_code_lens(
+        &self,
+        buffer_handle: Model<Buffer>,
+        lens: CodeLens,
+        push_to_history: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let Some(command) = lens.lsp_lens.command else {
+            return Task::ready(Ok(Default::default()));
+        };
+        if self.is_local() {
+            let supports_command = self
+                .language_server_for_buffer(buffer_handle.read(cx), lens.server_id, cx)
+                .and_then(|(_, server)| server.capabilities().execute_command_provider.as_ref())
+                .map_or(false, |options| options.commands.contains(&command.command));
+            if !supports_command {
+                return Task::ready(Err(anyhow!(
+                    "language server does not support command {}",
+                    command.command
+                )));
+            }
+        }
+
+        let action = CodeAction {
+            server_id: lens.server_id,
+            range: lens.range,
+            lsp_action: lsp::CodeAction {
+                title: command.title.clone(),
+                command: Some(command),
+                ..Default::default()
+            },
+        };
+        self.apply_code_action(buffer_handle, action, push_to_history, cx)
+    }
+
     pub fn apply_code_action(
         &self,
         buffer_handle: Model<Buffer>,
@@ -6030,6 +6762,158 @@ impl Project {
         }
     }
 
+    /// Requests the semantic tokens of the whole buffer from the first of its
+    /// language servers that provides them, asking for a delta against the
+    /// previous response when the server supports it.
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<BufferSemanticTokens>> {
+        let buffer = buffer_handle.read(cx);
+        if self.is_local() {
+            let server = self
+                .language_servers_for_buffer(buffer, cx)
+                .find_map(|(_, server)| {
+                    let options = match server.capabilities().semantic_tokens_provider.clone()? {
+                        lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => {
+                            options
+                        }
+                        lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(
+                            options,
+                        ) => options.semantic_tokens_options,
+                    };
+                    let supports_delta = match options.full? {
+                        lsp::SemanticTokensFullOptions::Bool(full) => {
+                            if !full {
+                                return None;
+                            }
+                            false
+                        }
+                        lsp::SemanticTokensFullOptions::Delta { delta } => delta.unwrap_or(false),
+                    };
+                    Some((server.clone(), options.legend, supports_delta))
+                });
+            let file = File::from_dyn(buffer.file()).and_then(File::as_local);
+            let (Some((language_server, legend, supports_delta)), Some(file)) = (server, file)
+            else {
+                return Task::ready(Ok(Default::default()));
+            };
+
+            let text_document = lsp::TextDocumentIdentifier::new(
+                lsp::Url::from_file_path(file.abs_path(cx)).unwrap(),
+            );
+            let snapshot = buffer.snapshot();
+            let key = (buffer.remote_id(), language_server.server_id());
+            let previous_result_id = supports_delta
+                .then(|| self.cached_semantic_tokens.get(&key)?.result_id.clone())
+                .flatten();
+            let token_types: Arc<[String]> = legend
+                .token_types
+                .iter()
+                .map(|token_type| token_type.as_str().to_string())
+                .collect();
+            let token_modifiers: Arc<[String]> = legend
+                .token_modifiers
+                .iter()
+                .map(|modifier| modifier.as_str().to_string())
+                .collect();
+
+            cx.spawn(move |this, mut cx| async move {
+                let mut result = None;
+                if let Some(previous_result_id) = previous_result_id {
+                    let response = language_server
+                        .request::<lsp::request::SemanticTokensFullDeltaRequest>(
+                            lsp::SemanticTokensDeltaParams {
+                                text_document: text_document.clone(),
+                                previous_result_id: previous_result_id.clone(),
+                                work_done_progress_params: Default::default(),
+                                partial_result_params: Default::default(),
+                            },
+                        )
+                        .await;
+                    result = match response {
+                        Ok(Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens))) => {
+                            Some((tokens.result_id, flatten_lsp_tokens(&tokens.data)))
+                        }
+                        Ok(Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta))) => this
+                            .update(&mut cx, |this, _| {
+                                let cached = this.cached_semantic_tokens.get(&key)?;
+                                if cached.result_id.as_ref() != Some(&previous_result_id) {
+                                    return None;
+                                }
+                                let mut data = cached.data.clone();
+                                apply_semantic_token_edits(&mut data, delta.edits);
+                                Some((delta.result_id, data))
+                            })?,
+                        Ok(_) => None,
+                        Err(error) => {
+                            log::warn!("semantic tokens delta request failed: {error:#}");
+                            None
+                        }
+                    };
+                }
+
+                let (result_id, data) = match result {
+                    Some(result) => result,
+                    None => {
+                        let response = language_server
+                            .request::<lsp::request::SemanticTokensFullRequest>(
+                                lsp::SemanticTokensParams {
+                                    text_document,
+                                    work_done_progress_params: Default::default(),
+                                    partial_result_params: Default::default(),
+                                },
+                            )
+                            .await
+                            .context("semantic tokens LSP request")?;
+                        match response {
+                            Some(lsp::SemanticTokensResult::Tokens(tokens)) => {
+                                (tokens.result_id, flatten_lsp_tokens(&tokens.data))
+                            }
+                            Some(lsp::SemanticTokensResult::Partial(partial)) => {
+                                (None, flatten_lsp_tokens(&partial.data))
+                            }
+                            None => (None, Vec::new()),
+                        }
+                    }
+                };
+
+                let tokens = decode_semantic_tokens(&data, &snapshot);
+                this.update(&mut cx, |this, _| {
+                    this.cached_semantic_tokens
+                        .insert(key, CachedSemanticTokens { result_id, data });
+                })?;
+                Ok(BufferSemanticTokens {
+                    tokens,
+                    token_types,
+                    token_modifiers,
+                })
+            })
+        } else if let Some(project_id) = self.remote_id() {
+            let client = self.client.clone();
+            let request = proto::GetSemanticTokens {
+                project_id,
+                buffer_id: buffer.remote_id().into(),
+                version: serialize_version(&buffer.version()),
+            };
+            cx.spawn(move |_, mut cx| async move {
+                let response = client
+                    .request(request)
+                    .await
+                    .context("semantic tokens proto request")?;
+                buffer_handle
+                    .update(&mut cx, |buffer, _| {
+                        buffer.wait_for_version(deserialize_version(&response.version))
+                    })?
+                    .await?;
+                BufferSemanticTokens::from_proto(response)
+            })
+        } else {
+            Task::ready(Err(anyhow!("project does not have a remote id")))
+        }
+    }
+
     pub fn resolve_inlay_hint(
         &self,
         hint: InlayHint,
@@ -6093,6 +6977,65 @@ impl Project {
         }
     }
 
+    /// Fills in the target of a document link that was reported without one.
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer_handle: Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        if link.target().is_some() {
+            return Task::ready(Ok(link));
+        }
+
+        if self.is_local() {
+            let buffer = buffer_handle.read(cx);
+            let Some((_, lang_server)) =
+                self.language_server_for_buffer(buffer, link.server_id, cx)
+            else {
+                return Task::ready(Ok(link));
+            };
+            let can_resolve = lang_server
+                .capabilities()
+                .document_link_provider
+                .as_ref()
+                .and_then(|options| options.resolve_provider)
+                .unwrap_or(false);
+            if !can_resolve {
+                return Task::ready(Ok(link));
+            }
+
+            let resolve_task =
+                lang_server.request::<lsp::request::DocumentLinkResolve>(link.lsp_link.clone());
+            cx.spawn(move |_, _| async move {
+                let lsp_link = resolve_task
+                    .await
+                    .context("document link resolve LSP request")?;
+                Ok(DocumentLink { lsp_link, ..link })
+            })
+        } else if let Some(project_id) = self.remote_id() {
+            let client = self.client.clone();
+            let request = proto::ResolveDocumentLink {
+                project_id,
+                buffer_id: buffer_handle.read(cx).remote_id().into(),
+                link: Some(link.to_proto()),
+            };
+            cx.spawn(move |_, _| async move {
+                let response = client
+                    .request(request)
+                    .await
+                    .context("document link proto request")?;
+                match response.link {
+                    Some(resolved_link) => DocumentLink::from_proto(resolved_link)
+                        .context("document link proto resolve response conversion"),
+                    None => Ok(link),
+                }
+            })
+        } else {
+            Task::ready(Err(anyhow!("project does not have a remote id")))
+        }
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn search(
         &self,
@@ -6246,7 +7189,8 @@ impl Project {
             ))
             .detach();
 
-        let (buffers, buffers_rx) = Self::sort_candidates_and_open_buffers(matching_paths_rx, cx);
+        let (buffers, buffers_rx) =
+            Self::sort_candidates_and_open_buffers(matching_paths_rx, query.is_structural(), cx);
         let background = cx.background_executor().clone();
         let (result_tx, result_rx) = smol::channel::bounded(1024);
         cx.background_executor()
@@ -6559,6 +7503,51 @@ impl Project {
             .await;
     }
 
+    /// Replaces the given matches of the query in their buffers, editing every buffer in a single transaction.
+    pub fn replace_search_matches(
+        &mut self,
+        query: &SearchQuery,
+        matches: Vec<(Model<Buffer>, Vec<Range<Anchor>>)>,
+        push_to_history: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> ProjectTransaction {
+        let mut project_transaction = ProjectTransaction::default();
+        for (buffer, ranges) in matches {
+            let transaction = buffer.update(cx, |buffer, cx| {
+                let snapshot = buffer.snapshot();
+                let edits = ranges
+                    .into_iter()
+                    .filter_map(|range| {
+                        let offset_range =
+                            range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot);
+                        let replacement = query.replacement_for_range(&snapshot, offset_range)?;
+                        Some((range, replacement))
+                    })
+                    .collect::<Vec<_>>();
+                if edits.is_empty() {
+                    return None;
+                }
+
+                buffer.finalize_last_transaction();
+                buffer.start_transaction();
+                buffer.edit(edits, None, cx);
+                if buffer.end_transaction(cx).is_some() {
+                    let transaction = buffer.finalize_last_transaction().unwrap().clone();
+                    if !push_to_history {
+                        buffer.forget_transaction(transaction.id);
+                    }
+                    Some(transaction)
+                } else {
+                    None
+                }
+            });
+            if let Some(transaction) = transaction {
+                project_transaction.0.insert(buffer, transaction);
+            }
+        }
+        project_transaction
+    }
+
     pub fn request_lsp<R: LspCommand>(
         &self,
         buffer_handle: Model<Buffer>,
@@ -6650,6 +7639,7 @@ impl Project {
 
     fn sort_candidates_and_open_buffers(
         mut matching_paths_rx: Receiver<SearchMatchCandidate>,
+        wait_for_parsing: bool,
         cx: &mut ModelContext<Self>,
     ) -> (
         futures::channel::oneshot::Receiver<Vec<SearchMatchCandidate>>,
@@ -6700,6 +7690,11 @@ impl Project {
                             .log_err(),
                     };
                     if let Some(buffer) = buffer {
+                        if wait_for_parsing {
+                            buffer
+                                .update(&mut cx, |buffer, _| buffer.wait_for_parsing())?
+                                .await;
+                        }
                         let snapshot = buffer.update(&mut cx, |buffer, _| buffer.snapshot())?;
                         buffers_tx
                             .send((Some((buffer, snapshot)), index))
@@ -6900,8 +7895,16 @@ impl Project {
                 }
                 worktree::Event::UpdatedGitRepositories(updated_repos) => {
                     if is_local {
-                        this.update_local_worktree_buffers_git_repos(worktree, updated_repos, cx)
+                        this.update_local_worktree_buffers_git_repos(
+                            worktree.clone(),
+                            updated_repos,
+                            cx,
+                        )
                     }
+
+                    cx.emit(Event::WorktreeUpdatedGitRepositories(
+                        worktree.read(cx).id(),
+                    ));
                 }
             }
         })
@@ -7275,10 +8278,54 @@ impl Project {
                                 abs_path,
                             },
                             |cx| {
-                                let tasks_file_rx =
-                                    watch_config_file(&cx.background_executor(), fs, task_abs_path);
+                                let tasks_file_rx =
+                                    watch_config_file(&cx.background_executor(), fs, task_abs_path);
+                                StaticSource::new(
+                                    format!("local_tasks_for_workspace_{remote_worktree_id}"),
+                                    tasks_file_rx,
+                                    cx,
+                                )
+                            },
+                            cx,
+                        );
+                    }
+                })
+            } else if let Some(vscode_file) = VsCodeFile::for_path(&abs_path) {
+                self.task_inventory().update(cx, |task_inventory, cx| {
+                    if removed {
+                        task_inventory.remove_local_static_source(&abs_path);
+                    } else {
+                        let fs = self.fs.clone();
+                        let task_abs_path = abs_path.clone();
+                        task_inventory.add_source(
+                            TaskSourceKind::Worktree {
+                                id: remote_worktree_id,
+                                abs_path,
+                            },
+                            |cx| {
+                                let tasks_file_rx = watch_config_file(
+                                    &cx.background_executor(),
+                                    fs,
+                                    task_abs_path.clone(),
+                                )
+                                .map(move |contents| {
+                                    if contents.trim().is_empty() {
+                                        return contents;
+                                    }
+                                    let Some(converted) = vscode_file
+                                        .convert(&contents)
+                                        .with_context(|| format!("converting {task_abs_path:?}"))
+                                        .log_err()
+                                    else {
+                                        return String::new();
+                                    };
+                                    for warning in &converted.warnings {
+                                        log::warn!("{task_abs_path:?}: {warning}");
+                                    }
+                                    converted.to_json()
+                                });
                                 StaticSource::new(
-                                    format!("local_tasks_for_workspace_{remote_worktree_id}"),
+                                    format!("vscode_tasks_for_workspace_{remote_worktree_id}_{vscode_file:?}"),
                                     tasks_file_rx,
                                     cx,
                                 )
@@ -7287,6 +8334,59 @@ impl Project {
                         );
                     }
                 })
+            } else if abs_path
+                .parent()
+                .map_or(false, |dir| dir.ends_with(&*LOCAL_SNIPPETS_RELATIVE_PATH))
+                && is_snippets_file(&abs_path)
+            {
+                if removed {
+                    self.snippets.update(cx, |snippets, _| {
+                        snippets.remove_local_file(&abs_path);
+                    });
+                } else {
+                    let fs = self.fs.clone();
+                    let snippets = self.snippets.downgrade();
+                    cx.spawn(move |_, mut cx| async move {
+                        let contents = fs.load(&abs_path).await?;
+                        snippets.update(&mut cx, |snippets, _| {
+                            snippets.insert_local_file(abs_path, &contents)
+                        })?
+                    })
+                    .detach_and_log_err(cx);
+                }
+            } else if let Some(file) = abs_path
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .and_then(DiscoveredFile::for_file_name)
+            {
+                let ignored = worktree
+                    .read(cx)
+                    .entry_for_path(path)
+                    .map_or(false, |entry| entry.is_ignored);
+                self.task_inventory().update(cx, |task_inventory, cx| {
+                    if removed {
+                        task_inventory.remove_local_static_source(&abs_path);
+                    } else if !ignored {
+                        let fs = self.fs.clone();
+                        let task_abs_path = abs_path.clone();
+                        task_inventory.add_source(
+                            TaskSourceKind::Discovered {
+                                id: remote_worktree_id,
+                                abs_path,
+                                file,
+                            },
+                            |cx| {
+                                let file_rx = watch_config_file(
+                                    &cx.background_executor(),
+                                    fs,
+                                    task_abs_path.clone(),
+                                );
+                                DiscoveredSource::new(file, task_abs_path, file_rx, cx)
+                            },
+                            cx,
+                        );
+                    }
+                })
             }
         }
 
@@ -7451,6 +8551,267 @@ impl Project {
             .local_git_repo(&project_path.path)
     }
 
+    /// Returns the commits that last changed each row of the buffer's current text.
+    pub fn blame_buffer(&self, buffer: &Model<Buffer>, cx: &AppContext) -> Task<Result<Blame>> {
+        if self.is_remote() {
+            return Task::ready(Err(anyhow!(
+                "git blame is not supported in remote projects"
+            )));
+        }
+
+        let (repo, repo_path) = match self.local_git_repo_for_buffer(buffer, cx) {
+            Ok(repo) => repo,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let content = buffer.read(cx).as_rope().clone();
+
+        cx.background_executor()
+            .spawn(async move { repo.lock().blame(&repo_path, &content.to_string()) })
+    }
+
+    /// Returns the commits that changed the buffer's file, newest first. With rows, only the
+    /// commits that changed those rows of the buffer are returned. Only the most recent
+    /// commits of long histories are looked at, in which case the log is marked as truncated.
+    pub fn file_history(
+        &self,
+        buffer: &Model<Buffer>,
+        rows: Option<Range<u32>>,
+        limit: Option<usize>,
+        cx: &AppContext,
+    ) -> Task<Result<CommitLog>> {
+        if self.is_remote() {
+            return Task::ready(Err(anyhow!(
+                "git history is not supported in remote projects"
+            )));
+        }
+
+        let (repo, repo_path) = match self.local_git_repo_for_buffer(buffer, cx) {
+            Ok(repo) => repo,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let content = buffer.read(cx).as_rope().clone();
+
+        cx.background_executor().spawn(async move {
+            let repo = repo.lock();
+            // The log follows the rows of the file as of HEAD.
+            let rows = match rows {
+                Some(rows) => {
+                    let head_text = repo.load_head_text(&repo_path).unwrap_or_default();
+                    let changes = diff_rows(&head_text, &content.to_string())?;
+                    Some(old_rows(&changes, &rows))
+                }
+                None => None,
+            };
+            repo.log(&LogOptions {
+                path: Some(repo_path.into()),
+                rows,
+                limit,
+                max_walked_commits: Some(MAX_FILE_HISTORY_WALKED_COMMITS),
+            })
+        })
+    }
+
+    /// Opens the text of the buffer's file in the commit with the given SHA as a new read-only
+    /// buffer, in the language of the buffer.
+    pub fn open_buffer_at_commit(
+        &mut self,
+        buffer: &Model<Buffer>,
+        sha: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Model<Buffer>>> {
+        if self.is_remote() {
+            return Task::ready(Err(anyhow!(
+                "git history is not supported in remote projects"
+            )));
+        }
+
+        let (repo, repo_path) = match self.local_git_repo_for_buffer(buffer, cx) {
+            Ok(repo) => repo,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let language = buffer.read(cx).language().cloned();
+
+        cx.spawn(move |this, mut cx| async move {
+            let text = cx
+                .background_executor()
+                .spawn(async move {
+                    repo.lock()
+                        .load_commit_text(&sha, &repo_path)?
+                        .with_context(|| format!("{repo_path:?} does not exist in commit {sha}"))
+                })
+                .await?;
+            this.update(&mut cx, |this, cx| {
+                let replica_id = this.replica_id();
+                let id = this.next_buffer_id.next();
+                let buffer = cx.new_model(|cx| {
+                    Buffer::build(
+                        text::Buffer::new(replica_id, id, text),
+                        None,
+                        None,
+                        Capability::ReadOnly,
+                    )
+                    .with_language(language.unwrap_or_else(|| language::PLAIN_TEXT.clone()), cx)
+                });
+                this.register_buffer(&buffer, cx)?;
+                Ok(buffer)
+            })?
+        })
+    }
+
+    /// Stages the hunks of the buffer's diff against the git index that intersect the given
+    /// rows, by writing the buffer's text of those hunks into the index.
+    pub fn stage_hunks(
+        &mut self,
+        buffer: &Model<Buffer>,
+        rows: Vec<Range<u32>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.update_index_for_hunks(buffer, rows, true, cx)
+    }
+
+    /// Unstages the hunks of the git index's diff against HEAD that intersect the given rows
+    /// of the buffer, by writing the HEAD text of those hunks into the index.
+    pub fn unstage_hunks(
+        &mut self,
+        buffer: &Model<Buffer>,
+        rows: Vec<Range<u32>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.update_index_for_hunks(buffer, rows, false, cx)
+    }
+
+    fn update_index_for_hunks(
+        &mut self,
+        buffer: &Model<Buffer>,
+        rows: Vec<Range<u32>>,
+        stage: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if self.is_remote() {
+            let Some(project_id) = self.remote_id() else {
+                return Task::ready(Err(anyhow!("project is not connected")));
+            };
+            let buffer = buffer.read(cx);
+            let buffer_id = buffer.remote_id().into();
+            let version = serialize_version(&buffer.version());
+            let rows = rows
+                .into_iter()
+                .map(|rows| proto::Range {
+                    start: rows.start as u64,
+                    end: rows.end as u64,
+                })
+                .collect();
+            let client = self.client.clone();
+            return cx.background_executor().spawn(async move {
+                if stage {
+                    client
+                        .request(proto::StageHunks {
+                            project_id,
+                            buffer_id,
+                            version,
+                            rows,
+                        })
+                        .await?;
+                } else {
+                    client
+                        .request(proto::UnstageHunks {
+                            project_id,
+                            buffer_id,
+                            version,
+                            rows,
+                        })
+                        .await?;
+                }
+                Ok(())
+            });
+        }
+
+        let (repo, repo_path) = match self.local_git_repo_for_buffer(buffer, cx) {
+            Ok(repo) => repo,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let content = buffer.read(cx).as_rope().clone();
+        let buffer = buffer.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let index_text = cx
+                .background_executor()
+                .spawn(async move {
+                    let repo = repo.lock();
+                    let buffer_text = content.to_string();
+                    let index_text = repo.load_index_text(&repo_path);
+                    let new_index_text = if stage {
+                        stage_hunks_in_rows(
+                            index_text.as_deref().unwrap_or(""),
+                            &buffer_text,
+                            &rows,
+                        )?
+                    } else {
+                        let head_text = repo.load_head_text(&repo_path);
+                        let text = unstage_hunks_in_rows(
+                            head_text.as_deref().unwrap_or(""),
+                            index_text.as_deref().unwrap_or(""),
+                            &buffer_text,
+                            &rows,
+                        )?;
+                        // A file that is not in HEAD leaves the index once all of it is unstaged.
+                        if head_text.is_none() && text.is_empty() {
+                            None
+                        } else {
+                            Some(text)
+                        }
+                    };
+                    if new_index_text == index_text {
+                        return Ok(index_text);
+                    }
+                    repo.set_index_text(&repo_path, new_index_text.clone())?;
+                    anyhow::Ok(new_index_text)
+                })
+                .await?;
+
+            // Update the diff base right away, rather than once the repository's change is noticed.
+            let buffer_id = buffer.update(&mut cx, |buffer, cx| {
+                buffer.set_diff_base(index_text.clone(), cx);
+                buffer.remote_id().into()
+            })?;
+            this.update(&mut cx, |this, _| {
+                if let Some(project_id) = this.remote_id() {
+                    this.client
+                        .send(proto::UpdateDiffBase {
+                            project_id,
+                            buffer_id,
+                            diff_base: index_text,
+                        })
+                        .log_err();
+                }
+            })?;
+            Ok(())
+        })
+    }
+
+    /// Returns the git repository containing the buffer's file in a local worktree, along with
+    /// the path of the file relative to the repository's work directory.
+    fn local_git_repo_for_buffer(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Result<(Arc<Mutex<dyn GitRepository>>, PathBuf)> {
+        let file = File::from_dyn(buffer.read(cx).file()).context("buffer has no file")?;
+        let worktree = file
+            .worktree
+            .read(cx)
+            .as_local()
+            .context("buffer is not in a local worktree")?;
+        let snapshot = worktree.snapshot();
+        let (work_directory, _) = snapshot
+            .repository_and_work_directory_for_path(&file.path)
+            .with_context(|| format!("{:?} is not in a git repository", file.path))?;
+        let repo = snapshot
+            .local_git_repo(&file.path)
+            .with_context(|| format!("git repository not found for {:?}", file.path))?;
+        let repo_path = file.path.strip_prefix(&work_directory)?.to_path_buf();
+        Ok((repo, repo_path))
+    }
+
     // RPC message handlers
 
     async fn handle_unshare_project(
@@ -8328,6 +9689,74 @@ impl Project {
         })
     }
 
+    async fn handle_stage_hunks(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::StageHunks>,
+        _: Arc<Client>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let payload = envelope.payload;
+        Self::update_index_for_remote_hunks(
+            this,
+            payload.buffer_id,
+            payload.version,
+            payload.rows,
+            true,
+            cx,
+        )
+        .await
+    }
+
+    async fn handle_unstage_hunks(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UnstageHunks>,
+        _: Arc<Client>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let payload = envelope.payload;
+        Self::update_index_for_remote_hunks(
+            this,
+            payload.buffer_id,
+            payload.version,
+            payload.rows,
+            false,
+            cx,
+        )
+        .await
+    }
+
+    async fn update_index_for_remote_hunks(
+        this: Model<Self>,
+        buffer_id: u64,
+        version: Vec<proto::VectorClockEntry>,
+        rows: Vec<proto::Range>,
+        stage: bool,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let buffer_id = BufferId::new(buffer_id)?;
+        let buffer = this.update(&mut cx, |this, _| {
+            this.opened_buffers
+                .get(&buffer_id)
+                .and_then(|buffer| buffer.upgrade())
+                .ok_or_else(|| anyhow!("unknown buffer id {}", buffer_id))
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&version))
+            })?
+            .await?;
+
+        let rows = rows
+            .into_iter()
+            .map(|rows| rows.start as u32..rows.end as u32)
+            .collect();
+        this.update(&mut cx, |this, cx| {
+            this.update_index_for_hunks(&buffer, rows, stage, cx)
+        })?
+        .await?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_on_type_formatting(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::OnTypeFormatting>,
@@ -8445,6 +9874,34 @@ impl Project {
         })
     }
 
+    async fn handle_resolve_document_link(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ResolveDocumentLink>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ResolveDocumentLinkResponse> {
+        let link = envelope
+            .payload
+            .link
+            .ok_or_else(|| anyhow!("missing document link"))?;
+        let link = DocumentLink::from_proto(link)?;
+        let buffer = this.update(&mut cx, |this, _| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            this.opened_buffers
+                .get(&buffer_id)
+                .and_then(|buffer| buffer.upgrade())
+                .ok_or_else(|| anyhow!("unknown buffer id {}", buffer_id))
+        })??;
+        let link = this
+            .update(&mut cx, |project, cx| {
+                project.resolve_document_link(link, buffer, cx)
+            })?
+            .await?;
+        Ok(proto::ResolveDocumentLinkResponse {
+            link: Some(link.to_proto()),
+        })
+    }
+
     async fn try_resolve_code_action(
         lang_server: &LanguageServer,
         action: &mut CodeAction,
@@ -8474,6 +9931,60 @@ impl Project {
         Ok(proto::Ack {})
     }
 
+    async fn handle_semantic_tokens(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetSemanticTokens>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetSemanticTokensResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let buffer = this.update(&mut cx, |this, _| {
+            this.opened_buffers
+                .get(&buffer_id)
+                .and_then(|buffer| buffer.upgrade())
+                .ok_or_else(|| anyhow!("unknown buffer id {}", envelope.payload.buffer_id))
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&envelope.payload.version))
+            })?
+            .await
+            .with_context(|| format!("waiting for version for buffer {}", buffer.entity_id()))?;
+
+        let version = buffer.update(&mut cx, |buffer, _| buffer.version())?;
+        let tokens = this
+            .update(&mut cx, |project, cx| {
+                project.semantic_tokens(buffer.clone(), cx)
+            })?
+            .await
+            .context("semantic tokens fetch")?;
+        Ok(tokens.to_proto(&version))
+    }
+
+    async fn handle_refresh_code_lens(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::RefreshCodeLens>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(Event::RefreshCodeLens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_refresh_semantic_tokens(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(Event::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_lsp_command<T: LspCommand>(
         this: Model<Self>,
         envelope: TypedEnvelope<T::ProtoRequest>,
@@ -9740,3 +11251,42 @@ async fn load_shell_environment(dir: &Path) -> Result<HashMap<String, String>> {
     }
     Ok(parsed_env)
 }
+
+/// Flattens a pulled document diagnostic report into the reports for the document itself and
+/// for the related documents whose diagnostics changed along with it.
+fn document_diagnostic_reports(
+    uri: lsp::Url,
+    version: i32,
+    result: lsp::DocumentDiagnosticReportResult,
+) -> Vec<(lsp::Url, Option<i32>, lsp::DocumentDiagnosticReportKind)> {
+    let (report, related_documents) = match result {
+        lsp::DocumentDiagnosticReportResult::Report(lsp::DocumentDiagnosticReport::Full(
+            report,
+        )) => (
+            Some(lsp::DocumentDiagnosticReportKind::Full(
+                report.full_document_diagnostic_report,
+            )),
+            report.related_documents,
+        ),
+        lsp::DocumentDiagnosticReportResult::Report(lsp::DocumentDiagnosticReport::Unchanged(
+            report,
+        )) => (
+            Some(lsp::DocumentDiagnosticReportKind::Unchanged(
+                report.unchanged_document_diagnostic_report,
+            )),
+            report.related_documents,
+        ),
+        lsp::DocumentDiagnosticReportResult::Partial(report) => (None, report.related_documents),
+    };
+
+    report
+        .map(|report| (uri, Some(version), report))
+        .into_iter()
+        .chain(
+            related_documents
+                .into_iter()
+                .flatten()
+                .map(|(uri, report)| (uri, None, report)),
+        )
+        .collect()
+}
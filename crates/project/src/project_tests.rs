@@ -186,6 +186,52 @@ async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext)
     });
 }
 
+#[gpui::test]
+async fn test_managing_project_specific_snippets(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            ".zed": {
+                "snippets": {
+                    "rust.json": r#"{ "Main": { "prefix": "main", "body": "fn main() {\n    $0\n}" } }"#,
+                    "project.code-snippets": r#"{ "Todo": { "prefix": "todo", "body": "TODO: $0" } }"#,
+                },
+            },
+            "a.rs": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+
+    let snippet_names = |language_name, cx: &mut gpui::TestAppContext| {
+        project.update(cx, |project, cx| {
+            project
+                .snippets()
+                .read(cx)
+                .snippets_for(language_name, cx)
+                .iter()
+                .map(|snippet| snippet.name.clone())
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(snippet_names(Some("Rust"), cx), ["Todo", "Main"]);
+    assert_eq!(snippet_names(Some("Python"), cx), ["Todo"]);
+
+    fs.remove_file(
+        "/the-root/.zed/snippets/rust.json".as_ref(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(snippet_names(Some("Rust"), cx), ["Todo"]);
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1271,6 +1317,125 @@ async fn test_restarting_server_with_diagnostics_published(cx: &mut gpui::TestAp
     });
 }
 
+#[gpui::test]
+async fn test_pulled_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "let a = 1;" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions::default(),
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let previous_result_ids = Arc::new(Mutex::new(Vec::new()));
+    fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>({
+        let previous_result_ids = previous_result_ids.clone();
+        move |params, _| {
+            previous_result_ids
+                .lock()
+                .push(params.previous_result_id.clone());
+            async move {
+                let report = if params.previous_result_id.is_some() {
+                    lsp::DocumentDiagnosticReport::Unchanged(
+                        lsp::RelatedUnchangedDocumentDiagnosticReport {
+                            related_documents: None,
+                            unchanged_document_diagnostic_report:
+                                lsp::UnchangedDocumentDiagnosticReport {
+                                    result_id: "1".to_string(),
+                                },
+                        },
+                    )
+                } else {
+                    lsp::DocumentDiagnosticReport::Full(lsp::RelatedFullDocumentDiagnosticReport {
+                        related_documents: None,
+                        full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                            result_id: Some("1".to_string()),
+                            items: vec![lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 4),
+                                    lsp::Position::new(0, 5),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::WARNING),
+                                message: "unused variable".to_string(),
+                                ..Default::default()
+                            }],
+                        },
+                    })
+                };
+                Ok(lsp::DocumentDiagnosticReportResult::Report(report))
+            }
+        }
+    });
+    fake_server
+        .request::<lsp::request::WorkspaceDiagnosticRefresh>(())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let diagnostic_messages = |buffer: &Buffer| {
+        buffer
+            .snapshot()
+            .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+            .map(|entry| entry.diagnostic.message.clone())
+            .collect::<Vec<_>>()
+    };
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(diagnostic_messages(buffer), ["unused variable".to_string()]);
+    });
+
+    // Pushed diagnostics don't replace the pulled ones.
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(lsp::PublishDiagnosticsParams {
+        uri: lsp::Url::from_file_path("/dir/a.rs").unwrap(),
+        version: None,
+        diagnostics: vec![lsp::Diagnostic {
+            range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 3)),
+            severity: Some(lsp::DiagnosticSeverity::ERROR),
+            message: "pushed error".to_string(),
+            ..Default::default()
+        }],
+    });
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            diagnostic_messages(buffer),
+            ["pushed error".to_string(), "unused variable".to_string()]
+        );
+    });
+
+    // Edits pull the diagnostics again, which are kept when the server reports them as unchanged.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "\n")], None, cx));
+    cx.executor()
+        .advance_clock(PULL_DIAGNOSTICS_DEBOUNCE_TIMEOUT);
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            diagnostic_messages(buffer),
+            ["pushed error".to_string(), "unused variable".to_string()]
+        );
+    });
+    assert_eq!(*previous_result_ids.lock(), [None, Some("1".to_string())]);
+}
+
 #[gpui::test]
 async fn test_restarted_server_reporting_invalid_buffer_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2306,6 +2471,140 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_semantic_tokens_full_and_delta(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() {}\nfn b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![lsp::SemanticTokenType::FUNCTION],
+                                token_modifiers: vec![lsp::SemanticTokenModifier::DECLARATION],
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::SemanticTokensFullRequest, _, _>(
+        |_, _| async move {
+            Ok(Some(lsp::SemanticTokensResult::Tokens(
+                lsp::SemanticTokens {
+                    result_id: Some("1".into()),
+                    data: vec![
+                        lsp::SemanticToken {
+                            delta_line: 0,
+                            delta_start: 3,
+                            length: 1,
+                            token_type: 0,
+                            token_modifiers_bitset: 0,
+                        },
+                        lsp::SemanticToken {
+                            delta_line: 1,
+                            delta_start: 3,
+                            length: 1,
+                            token_type: 0,
+                            token_modifiers_bitset: 0,
+                        },
+                    ],
+                },
+            )))
+        },
+    );
+
+    let tokens = project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        let ranges = tokens
+            .tokens
+            .iter()
+            .map(|token| token.range.to_offset(buffer))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [3..4, 13..14]);
+        assert_eq!(tokens.token_type(&tokens.tokens[0]), Some("function"));
+        assert_eq!(tokens.token_modifiers(&tokens.tokens[1]).count(), 0);
+    });
+
+    // The second request only receives the changed token.
+    fake_server.handle_request::<lsp::request::SemanticTokensFullDeltaRequest, _, _>(
+        |params, _| async move {
+            assert_eq!(params.previous_result_id, "1");
+            Ok(Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(
+                lsp::SemanticTokensDelta {
+                    result_id: Some("2".into()),
+                    edits: vec![lsp::SemanticTokensEdit {
+                        start: 5,
+                        delete_count: 5,
+                        data: Some(vec![lsp::SemanticToken {
+                            delta_line: 1,
+                            delta_start: 3,
+                            length: 1,
+                            token_type: 0,
+                            token_modifiers_bitset: 1,
+                        }]),
+                    }],
+                },
+            )))
+        },
+    );
+
+    let tokens = project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        let ranges = tokens
+            .tokens
+            .iter()
+            .map(|token| token.range.to_offset(buffer))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [3..4, 13..14]);
+        assert_eq!(
+            tokens
+                .token_modifiers(&tokens.tokens[1])
+                .collect::<Vec<_>>(),
+            ["declaration"]
+        );
+        assert_eq!(tokens.token_modifiers(&tokens.tokens[0]).count(), 0);
+    });
+}
+
 #[gpui::test]
 async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3790,6 +4089,189 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_search_structural(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let one = r#"fn one() -> usize {
+    let a = parse("1").unwrap();
+    let b = a.checked_add(parse("2").unwrap()).unwrap();
+    // ignored.unwrap()
+    a + b
+}
+"#;
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": one,
+            "two.rs": "fn two() { unwrap(); }",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages().add(rust_lang()));
+
+    let first_match = one.find(r#"parse("1")"#).unwrap();
+    let second_match = one.find("a.checked_add").unwrap();
+    let second_match_end = one.find(".unwrap();\n    //").unwrap() + ".unwrap()".len();
+    assert_eq!(
+        search(
+            &project,
+            SearchQuery::structural("$X.unwrap()", false, Vec::new(), Vec::new()).unwrap(),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([(
+            "dir/one.rs".to_string(),
+            vec![
+                first_match..first_match + r#"parse("1").unwrap()"#.len(),
+                second_match..second_match_end
+            ]
+        )])
+    );
+
+    let query = SearchQuery::structural("$X.unwrap()", false, Vec::new(), Vec::new())
+        .unwrap()
+        .with_replacement("$X?".to_string());
+    assert_eq!(
+        query
+            .replacement_for(r#"a.checked_add(parse("2").unwrap()).unwrap()"#)
+            .as_deref(),
+        Some(r#"a.checked_add(parse("2").unwrap())?"#)
+    );
+    let query = SearchQuery::structural("checked_add($$$ARGS)", false, Vec::new(), Vec::new())
+        .unwrap()
+        .with_replacement("saturating_add($$$ARGS)".to_string());
+    assert_eq!(
+        query
+            .replacement_for(r#"checked_add(parse("2").unwrap())"#)
+            .as_deref(),
+        Some(r#"saturating_add(parse("2").unwrap())"#)
+    );
+
+    assert!(SearchQuery::structural("$X", false, Vec::new(), Vec::new()).is_err());
+}
+
+#[gpui::test]
+async fn test_structural_replacement_uses_syntax_nodes(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let text = "fn one() { a + b + c; }";
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "one.rs": text })).await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages().add(rust_lang()));
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/one.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let query = SearchQuery::structural("$A + $B;", false, Vec::new(), Vec::new())
+        .unwrap()
+        .with_replacement("$B - $A;".to_string());
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    let matches = query.search(&snapshot, None).await;
+    let match_start = text.find("a + b").unwrap();
+    let match_range_end = match_start + "a + b + c;".len();
+    let match_range = match_start..match_range_end;
+    assert_eq!(matches, [match_range.clone()]);
+
+    // The search captured `a + b` as `$A`, since `a` is followed by `b + c`, which isn't a node.
+    assert_eq!(
+        query
+            .replacement_for_range(&snapshot, match_range.clone())
+            .as_deref(),
+        Some("c - a + b;")
+    );
+    // Without the syntax tree, `$B` captures the balanced tokens `b + c`.
+    assert_eq!(
+        query.replacement_for(&text[match_range]).as_deref(),
+        Some("b + c - a;")
+    );
+
+    let anchor_range = snapshot.anchor_before(match_start)..snapshot.anchor_after(match_range_end);
+    project.update(cx, |project, cx| {
+        project.replace_search_matches(&query, vec![(buffer.clone(), vec![anchor_range])], true, cx)
+    });
+    assert_eq!(
+        buffer.update(cx, |buffer, _| buffer.text()),
+        "fn one() { c - a + b; }"
+    );
+}
+
+#[gpui::test]
+async fn test_search_and_replace_preserving_case(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "let fooBar = FooBar::new(FOO_BAR);",
+            "two.rs": "fn foo_bar() {}",
+            "three.rs": "foobarbaz",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+
+    let query = SearchQuery::text("fooBar", true, true, false, Vec::new(), Vec::new())
+        .unwrap()
+        .with_preserve_case(true)
+        .unwrap()
+        .with_replacement("bazQux".to_string());
+    assert_eq!(
+        search(&project, query.clone(), cx).await.unwrap(),
+        HashMap::from_iter([
+            ("dir/one.rs".to_string(), vec![4..10, 13..19, 25..32]),
+            ("dir/two.rs".to_string(), vec![3..10]),
+        ])
+    );
+
+    let mut search_rx = project.update(cx, |project, cx| project.search(query.clone(), cx));
+    let mut matches = Vec::new();
+    while let Some(buffer_matches) = search_rx.next().await {
+        matches.push(buffer_matches);
+    }
+    let buffers = matches
+        .iter()
+        .map(|(buffer, _)| buffer.clone())
+        .collect::<Vec<_>>();
+    let transaction = project.update(cx, |project, cx| {
+        project.replace_search_matches(&query, matches, true, cx)
+    });
+    assert_eq!(transaction.0.len(), 2);
+    let mut texts = buffers
+        .iter()
+        .map(|buffer| buffer.read_with(cx, |buffer, _| buffer.text()))
+        .collect::<Vec<_>>();
+    texts.sort();
+    assert_eq!(
+        texts,
+        ["fn baz_qux() {}", "let bazQux = BazQux::new(BAZ_QUX);"]
+    );
+
+    for (buffer, transaction) in transaction.0 {
+        buffer.update(cx, |buffer, cx| {
+            buffer.undo_transaction(transaction.id, cx);
+        });
+    }
+    let mut texts = buffers
+        .iter()
+        .map(|buffer| buffer.read_with(cx, |buffer, _| buffer.text()))
+        .collect::<Vec<_>>();
+    texts.sort();
+    assert_eq!(
+        texts,
+        ["fn foo_bar() {}", "let fooBar = FooBar::new(FOO_BAR);"]
+    );
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -0,0 +1,120 @@
+//! A source of tasks, running the tests, binaries and other runnables detected in the code of the open buffers.
+
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use collections::HashMap;
+use gpui::{AppContext, Context, Model, ModelContext};
+use language::RunnableCommand;
+use task::{static_source::RevealStrategy, SpawnInTerminal, Task, TaskContext, TaskId, TaskSource};
+
+/// Holds the tasks running the runnables that editors detect in their buffers with the runnable queries
+/// of their languages, using the commands the language context providers build.
+pub struct RunnablesSource {
+    /// The tasks last detected in each buffer, by the absolute path of the buffer.
+    buffer_tasks: HashMap<PathBuf, Vec<Arc<dyn Task>>>,
+    /// All tasks detected so far, to be able to rerun them when their buffer is not active anymore.
+    detected_tasks: Vec<Arc<dyn Task>>,
+}
+
+struct RunnableTask {
+    id: TaskId,
+    command: RunnableCommand,
+}
+
+impl RunnableTask {
+    fn new(command: RunnableCommand) -> Self {
+        Self {
+            id: TaskId(format!("runnable {}", command.label)),
+            command,
+        }
+    }
+}
+
+impl Task for RunnableTask {
+    fn id(&self) -> &TaskId {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.command.label
+    }
+
+    fn cwd(&self) -> Option<&str> {
+        self.command.cwd.as_deref().and_then(Path::to_str)
+    }
+
+    fn exec(&self, cx: TaskContext) -> Option<SpawnInTerminal> {
+        let TaskContext { cwd, env } = cx;
+        Some(SpawnInTerminal {
+            id: self.id.clone(),
+            label: self.command.label.clone(),
+            command: self.command.command.clone(),
+            args: self.command.args.clone(),
+            cwd: self.command.cwd.clone().or(cwd),
+            env,
+            use_new_terminal: false,
+            allow_concurrent_runs: false,
+            reveal: RevealStrategy::default(),
+            problem_matcher: None,
+        })
+    }
+}
+
+impl RunnablesSource {
+    pub fn new(cx: &mut AppContext) -> Model<Box<dyn TaskSource>> {
+        cx.new_model(|_| {
+            Box::new(Self {
+                buffer_tasks: HashMap::default(),
+                detected_tasks: Vec::new(),
+            }) as Box<dyn TaskSource>
+        })
+    }
+
+    /// Replaces the tasks of the buffer with the given path with ones running the given commands,
+    /// returning them in the same order.
+    pub fn set_buffer_commands(
+        &mut self,
+        abs_path: PathBuf,
+        commands: impl IntoIterator<Item = RunnableCommand>,
+    ) -> Vec<Arc<dyn Task>> {
+        let tasks = commands
+            .into_iter()
+            .map(|command| self.remember(Arc::new(RunnableTask::new(command))))
+            .collect::<Vec<_>>();
+        self.buffer_tasks.insert(abs_path, tasks.clone());
+        tasks
+    }
+
+    fn remember(&mut self, task: Arc<dyn Task>) -> Arc<dyn Task> {
+        match self
+            .detected_tasks
+            .iter_mut()
+            .find(|detected| detected.id() == task.id())
+        {
+            Some(detected) => *detected = task.clone(),
+            None => self.detected_tasks.push(task.clone()),
+        }
+        task
+    }
+}
+
+impl TaskSource for RunnablesSource {
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn tasks_for_path(
+        &mut self,
+        path: Option<&Path>,
+        _: &mut ModelContext<Box<dyn TaskSource>>,
+    ) -> Vec<Arc<dyn Task>> {
+        match path {
+            Some(path) => self.buffer_tasks.get(path).cloned().unwrap_or_default(),
+            None => self.detected_tasks.clone(),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use anyhow::{Context, Result};
 use client::proto;
+use fuzzy::{matcher::Matcher, CharBag, StringMatch, StringMatchCandidate};
 use itertools::Itertools;
 use language::{char_kind, BufferSnapshot};
 use regex::{Regex, RegexBuilder};
@@ -10,10 +11,18 @@ use std::{
     io::{BufRead, BufReader, Read},
     ops::Range,
     path::Path,
-    sync::Arc,
+    sync::{atomic::AtomicBool, Arc},
 };
 use util::paths::PathMatcher;
 
+mod replacement;
+mod structural;
+
+pub use structural::StructuralPattern;
+
+/// The most lines a fuzzy query matches in a buffer.
+const MAX_FUZZY_LINE_MATCHES: usize = 100;
+
 #[derive(Clone, Debug)]
 pub struct SearchInputs {
     query: Arc<str>,
@@ -40,6 +49,9 @@ pub enum SearchQuery {
         whole_word: bool,
         case_sensitive: bool,
         include_ignored: bool,
+        /// Whether to find the query spelled in every case style, e.g. `foo_bar`, `fooBar` and `FOO_BAR`,
+        /// and replace it spelled in the same style.
+        preserve_case: bool,
         inner: SearchInputs,
     },
 
@@ -52,6 +64,19 @@ pub enum SearchQuery {
         include_ignored: bool,
         inner: SearchInputs,
     },
+
+    Structural {
+        pattern: Arc<StructuralPattern>,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
+
+    /// Matches the best ranked lines containing the characters of the query in order, not necessarily contiguous.
+    Fuzzy {
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 impl SearchQuery {
@@ -78,6 +103,7 @@ impl SearchQuery {
             whole_word,
             case_sensitive,
             include_ignored,
+            preserve_case: false,
             inner,
         })
     }
@@ -121,8 +147,60 @@ impl SearchQuery {
         })
     }
 
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: Vec<PathMatcher>,
+        files_to_exclude: Vec<PathMatcher>,
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let pattern = StructuralPattern::new(&query)?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+        };
+        Ok(Self::Structural {
+            pattern: Arc::new(pattern),
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
+    pub fn fuzzy(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: Vec<PathMatcher>,
+        files_to_exclude: Vec<PathMatcher>,
+    ) -> Result<Self> {
+        let inner = SearchInputs {
+            query: query.to_string().into(),
+            files_to_exclude,
+            files_to_include,
+        };
+        Ok(Self::Fuzzy {
+            include_ignored,
+            inner,
+        })
+    }
+
     pub fn from_proto(message: proto::SearchProject) -> Result<Self> {
-        if message.regex {
+        if message.fuzzy {
+            Self::fuzzy(
+                message.query,
+                message.include_ignored,
+                deserialize_path_matches(&message.files_to_include)?,
+                deserialize_path_matches(&message.files_to_exclude)?,
+            )
+        } else if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                deserialize_path_matches(&message.files_to_include)?,
+                deserialize_path_matches(&message.files_to_exclude)?,
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -139,7 +217,8 @@ impl SearchQuery {
                 message.include_ignored,
                 deserialize_path_matches(&message.files_to_include)?,
                 deserialize_path_matches(&message.files_to_exclude)?,
-            )
+            )?
+            .with_preserve_case(message.preserve_case)
         }
     }
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
@@ -151,20 +230,53 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
             }
+            Self::Fuzzy { .. } => self,
         }
     }
+    /// Makes a text query find every case style the query can be spelled in, and replace it preserving the style.
+    /// Other queries are left as they are.
+    pub fn with_preserve_case(mut self, new_preserve_case: bool) -> Result<Self> {
+        if let Self::Text {
+            ref mut search,
+            ref mut preserve_case,
+            case_sensitive,
+            ref inner,
+            ..
+        } = self
+        {
+            let patterns = if new_preserve_case {
+                replacement::case_variants(inner.as_str())
+            } else {
+                vec![inner.as_str().to_string()]
+            };
+            *search = Arc::new(
+                AhoCorasickBuilder::new()
+                    .ascii_case_insensitive(!case_sensitive)
+                    .build(&patterns)?,
+            );
+            *preserve_case = new_preserve_case;
+        }
+        Ok(self)
+    }
     pub fn to_proto(&self, project_id: u64) -> proto::SearchProject {
         proto::SearchProject {
             project_id,
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
+            fuzzy: self.is_fuzzy(),
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
+            preserve_case: self.preserve_case(),
             files_to_include: self
                 .files_to_include()
                 .iter()
@@ -213,29 +325,83 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { pattern, .. } => {
+                let mut text = String::new();
+                BufReader::new(stream).read_to_string(&mut text)?;
+                Ok(pattern.may_match(&text))
+            }
+            Self::Fuzzy { inner, .. } => {
+                let mut text = String::new();
+                BufReader::new(stream).read_to_string(&mut text)?;
+                Ok(CharBag::from(text.as_str()).is_superset(CharBag::from(inner.as_str())))
+            }
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
+            SearchQuery::Fuzzy { .. } => None,
+        }
+    }
+    /// Replaces the search hit at the given range of the buffer if replacement is set. Unlike
+    /// [`Self::replacement_for`], structural replacements capture the same syntax nodes as the search did.
+    pub fn replacement_for_range(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        match self {
+            SearchQuery::Structural {
+                pattern,
+                replacement,
+                ..
+            } => pattern.replace_in_buffer(buffer, range, replacement.as_deref()?),
+            _ => {
+                let text = buffer.text_for_range(range).collect::<String>();
+                Some(self.replacement_for(&text)?.into_owned())
             }
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
+    ///
+    /// Regex replacements can refer to the captured groups as `$1` or `${name}` and change their case with `\u`, `\l`, `\U`, `\L` and `\E`.
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
-            SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
+            SearchQuery::Text {
+                replacement,
+                preserve_case,
+                ..
+            } => {
+                let replacement = replacement.as_deref()?;
+                if *preserve_case {
+                    Some(Cow::from(replacement::preserve_case(text, replacement)))
+                } else {
+                    Some(Cow::from(replacement.to_string()))
+                }
+            }
             SearchQuery::Regex {
                 regex, replacement, ..
             } => {
-                if let Some(replacement) = replacement {
-                    Some(regex.replace(text, replacement))
-                } else {
-                    None
+                let replacement = replacement.as_deref()?;
+                match regex.captures(text) {
+                    Some(captures) => Some(Cow::from(replacement::expand_regex_replacement(
+                        &captures,
+                        replacement,
+                    ))),
+                    None => Some(Cow::from(text)),
                 }
             }
+            SearchQuery::Structural {
+                pattern,
+                replacement,
+                ..
+            } => pattern
+                .replace(text, replacement.as_deref()?)
+                .map(Cow::from),
+            SearchQuery::Fuzzy { .. } => None,
         }
     }
     pub async fn search(
@@ -322,6 +488,20 @@ impl SearchQuery {
                     }
                 }
             }
+
+            Self::Structural { pattern, .. } => {
+                yield_now().await;
+                matches = pattern
+                    .search(buffer, Some(range_offset..range_offset + rope.len()))
+                    .into_iter()
+                    .map(|range| range.start - range_offset..range.end - range_offset)
+                    .collect();
+            }
+
+            Self::Fuzzy { inner, .. } => {
+                yield_now().await;
+                matches = fuzzy_line_matches(inner.as_str(), &rope.to_string());
+            }
         }
 
         matches
@@ -339,6 +519,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } | Self::Fuzzy { .. } => false,
         }
     }
 
@@ -346,6 +527,8 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => true,
+            Self::Fuzzy { .. } => false,
         }
     }
 
@@ -357,6 +540,19 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
+            Self::Fuzzy {
+                include_ignored, ..
+            } => *include_ignored,
+        }
+    }
+
+    pub fn preserve_case(&self) -> bool {
+        match self {
+            Self::Text { preserve_case, .. } => *preserve_case,
+            Self::Regex { .. } | Self::Structural { .. } | Self::Fuzzy { .. } => false,
         }
     }
 
@@ -364,6 +560,14 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
+    pub fn is_fuzzy(&self) -> bool {
+        matches!(self, Self::Fuzzy { .. })
+    }
+
     pub fn files_to_include(&self) -> &[PathMatcher] {
         self.as_inner().files_to_include()
     }
@@ -400,11 +604,65 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. }
+            | Self::Fuzzy { inner, .. } => inner,
         }
     }
 }
 
+/// Ranks the lines of the text by how well they match the fuzzy query, returning the span of the matched characters
+/// in each of the best ranked lines, best first.
+fn fuzzy_line_matches(query: &str, text: &str) -> Vec<Range<usize>> {
+    let query_chars = query.chars().collect::<Vec<_>>();
+    let lowercase_query = query.to_lowercase().chars().collect::<Vec<_>>();
+    let mut line_starts = Vec::new();
+    let mut candidates = Vec::new();
+    let mut line_start = 0;
+    for (ix, line) in text.split('\n').enumerate() {
+        line_starts.push(line_start);
+        candidates.push(StringMatchCandidate::new(ix, line.to_string()));
+        line_start += line.len() + 1;
+    }
+
+    let mut matcher = Matcher::new(
+        &query_chars,
+        &lowercase_query,
+        CharBag::from(&lowercase_query[..]),
+        true,
+        MAX_FUZZY_LINE_MATCHES,
+    );
+    let mut results = Vec::<StringMatch>::new();
+    matcher.match_candidates(
+        &[],
+        &[],
+        candidates.iter(),
+        &mut results,
+        &AtomicBool::new(false),
+        |candidate, score| StringMatch {
+            candidate_id: candidate.id,
+            score,
+            positions: Vec::new(),
+            string: candidate.string.clone(),
+        },
+    );
+
+    results
+        .into_iter()
+        .filter_map(|line_match| {
+            let line_start = line_starts[line_match.candidate_id];
+            let first = *line_match.positions.first()?;
+            let last = *line_match.positions.last()?;
+            let last_len = line_match.string[last..]
+                .chars()
+                .next()
+                .map_or(0, char::len_utf8);
+            Some(line_start + first..line_start + last + last_len)
+        })
+        .collect()
+}
+
 fn deserialize_path_matches(glob_set: &str) -> anyhow::Result<Vec<PathMatcher>> {
     glob_set
         .split(',')
@@ -421,6 +679,14 @@ fn deserialize_path_matches(glob_set: &str) -> anyhow::Result<Vec<PathMatcher>>
 mod tests {
     use super::*;
 
+    #[test]
+    fn fuzzy_line_matches_are_ranked() {
+        assert_eq!(
+            fuzzy_line_matches("strings", "a string of things\nstrings"),
+            vec![19..26, 2..18]
+        );
+    }
+
     #[test]
     fn path_matcher_creation_for_valid_paths() {
         for valid_path in [
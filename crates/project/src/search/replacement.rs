@@ -0,0 +1,277 @@
+//! Expansion of the replacement text: capture groups and case changes for regular expressions,
+//! and keeping the case of the replaced text for plain text queries.
+
+use regex::Captures;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Case {
+    Upper,
+    Lower,
+}
+
+/// Text with the case changes requested in the replacement applied to it.
+#[derive(Default)]
+struct CaseChangingText {
+    text: String,
+    next_char_case: Option<Case>,
+    case: Option<Case>,
+}
+
+impl CaseChangingText {
+    fn push_str(&mut self, text: &str) {
+        for c in text.chars() {
+            match self.next_char_case.take().or(self.case) {
+                Some(Case::Upper) => self.text.extend(c.to_uppercase()),
+                Some(Case::Lower) => self.text.extend(c.to_lowercase()),
+                None => self.text.push(c),
+            }
+        }
+    }
+}
+
+/// Expands `$1`, `$name` and `${name}` in the replacement with the groups captured by the regex, `$$` being a literal `$`.
+///
+/// `\u` and `\l` change the case of the next character to upper or lower, while `\U` and `\L` change the case
+/// of all the text up to `\E`, e.g. `\u$1` capitalizes the first group.
+pub(super) fn expand_regex_replacement(captures: &Captures, replacement: &str) -> String {
+    let mut result = CaseChangingText::default();
+    let mut rest = replacement;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after_backslash) = rest.strip_prefix('\\') {
+            match after_backslash.chars().next() {
+                Some('u') => result.next_char_case = Some(Case::Upper),
+                Some('l') => result.next_char_case = Some(Case::Lower),
+                Some('U') => result.case = Some(Case::Upper),
+                Some('L') => result.case = Some(Case::Lower),
+                Some('E') => result.case = None,
+                _ => {
+                    result.push_str("\\");
+                    rest = after_backslash;
+                    continue;
+                }
+            }
+            rest = &after_backslash[1..];
+        } else if let Some(after_dollar) = rest.strip_prefix('$') {
+            if let Some(after_escape) = after_dollar.strip_prefix('$') {
+                result.push_str("$");
+                rest = after_escape;
+                continue;
+            }
+            let braced_name = after_dollar.strip_prefix('{').and_then(|braced| {
+                let end = braced.find('}')?;
+                Some((&braced[..end], &braced[end + 1..]))
+            });
+            let (name, after_name) = braced_name.unwrap_or_else(|| {
+                let end = after_dollar
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after_dollar.len());
+                (&after_dollar[..end], &after_dollar[end..])
+            });
+            if name.is_empty() {
+                result.push_str("$");
+                rest = after_dollar;
+                continue;
+            }
+            let group = match name.parse::<usize>() {
+                Ok(index) => captures.get(index),
+                Err(_) => captures.name(name),
+            };
+            result.push_str(group.map_or("", |group| group.as_str()));
+            rest = after_name;
+        } else {
+            result.push_str(&rest[..c.len_utf8()]);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    result.text
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Capitalization {
+    /// `foo_bar`
+    Lower,
+    /// `FOO_BAR`
+    Upper,
+    /// `fooBar`
+    Camel,
+    /// `FooBar`
+    Pascal,
+}
+
+/// The way the words of an identifier are spelled and joined together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CaseStyle {
+    capitalization: Capitalization,
+    separator: Option<char>,
+}
+
+const SEPARATORS: [char; 3] = ['_', '-', ' '];
+
+impl CaseStyle {
+    fn of(text: &str) -> Self {
+        let separator = SEPARATORS
+            .into_iter()
+            .find(|separator| text.contains(*separator));
+        let mut letters = text.chars().filter(|c| c.is_alphabetic());
+        let capitalization = match letters.next() {
+            None => Capitalization::Lower,
+            Some(first) if first.is_uppercase() => {
+                let mut rest = letters.peekable();
+                if rest.peek().is_some() && rest.all(char::is_uppercase) {
+                    Capitalization::Upper
+                } else {
+                    Capitalization::Pascal
+                }
+            }
+            Some(_) => {
+                if letters.any(char::is_uppercase) {
+                    Capitalization::Camel
+                } else {
+                    Capitalization::Lower
+                }
+            }
+        };
+        Self {
+            capitalization,
+            separator,
+        }
+    }
+
+    fn apply(&self, words: &[String]) -> String {
+        let mut result = String::new();
+        for (ix, word) in words.iter().enumerate() {
+            if ix > 0 {
+                result.extend(self.separator);
+            }
+            let capitalize = match self.capitalization {
+                Capitalization::Upper => {
+                    result.push_str(&word.to_uppercase());
+                    continue;
+                }
+                Capitalization::Lower => false,
+                Capitalization::Camel => ix > 0,
+                Capitalization::Pascal => true,
+            };
+            let mut chars = word.chars();
+            if let Some(first) = chars.next().filter(|_| capitalize) {
+                result.extend(first.to_uppercase());
+                result.push_str(chars.as_str());
+            } else {
+                result.push_str(word);
+            }
+        }
+        result
+    }
+}
+
+/// Splits the identifier into its lowercase words, at the separators and the camel case humps.
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut chars = text.chars().peekable();
+    let mut previous = None::<char>;
+    while let Some(c) = chars.next() {
+        if SEPARATORS.contains(&c) {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            previous = None;
+            continue;
+        }
+        let starts_hump = c.is_uppercase()
+            && previous.is_some_and(|previous| {
+                previous.is_lowercase()
+                    || previous.is_ascii_digit()
+                    || (previous.is_uppercase() && chars.peek().is_some_and(|c| c.is_lowercase()))
+            });
+        if starts_hump && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.extend(c.to_lowercase());
+        previous = Some(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// The query, followed by its words spelled in every case style, to find all the spellings of an identifier.
+pub(super) fn case_variants(query: &str) -> Vec<String> {
+    let words = words(query);
+    let mut variants = vec![query.to_string()];
+    if words.is_empty() {
+        return variants;
+    }
+    for separator in [None, Some('_'), Some('-')] {
+        for capitalization in [
+            Capitalization::Lower,
+            Capitalization::Upper,
+            Capitalization::Camel,
+            Capitalization::Pascal,
+        ] {
+            let variant = CaseStyle {
+                capitalization,
+                separator,
+            }
+            .apply(&words);
+            if !variants.contains(&variant) {
+                variants.push(variant);
+            }
+        }
+    }
+    variants
+}
+
+/// Spells the replacement in the case style of the replaced text, e.g. replacing `FOO_BAR` with `bazQux` results in `BAZ_QUX`.
+pub(super) fn preserve_case(replaced: &str, replacement: &str) -> String {
+    let words = words(replacement);
+    if words.is_empty() {
+        return replacement.to_string();
+    }
+    CaseStyle::of(replaced).apply(&words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_expand_regex_replacement() {
+        let regex = Regex::new(r"(?P<key>\w+)_(\w+)").unwrap();
+        let captures = regex.captures("user_name").unwrap();
+        let expand = |replacement| expand_regex_replacement(&captures, replacement);
+        assert_eq!(expand("$2 of $1"), "name of user");
+        assert_eq!(expand("${key}s"), "users");
+        assert_eq!(expand("$$1 costs $"), "$1 costs $");
+        assert_eq!(expand(r"\u$1\u$2"), "UserName");
+        assert_eq!(expand(r"\U$key\E_$2"), "USER_name");
+        assert_eq!(expand(r"\L\uSOME_$2"), "Some_name");
+        assert_eq!(expand(r"a\tb"), r"a\tb");
+    }
+
+    #[test]
+    fn test_preserve_case() {
+        for (replaced, expected) in [
+            ("fooBar", "bazQux"),
+            ("FooBar", "BazQux"),
+            ("FOO_BAR", "BAZ_QUX"),
+            ("foo_bar", "baz_qux"),
+            ("foo-bar", "baz-qux"),
+            ("foobar", "bazqux"),
+        ] {
+            assert_eq!(preserve_case(replaced, "bazQux"), expected);
+        }
+        assert_eq!(preserve_case("Foo", "bar"), "Bar");
+        assert_eq!(preserve_case("HTTPServer", "http_client"), "HttpClient");
+
+        assert_eq!(
+            case_variants("fooBar"),
+            [
+                "fooBar", "foobar", "FOOBAR", "FooBar", "foo_bar", "FOO_BAR", "foo_Bar", "Foo_Bar",
+                "foo-bar", "FOO-BAR", "foo-Bar", "Foo-Bar"
+            ]
+        );
+    }
+}
@@ -0,0 +1,429 @@
+use anyhow::{anyhow, Result};
+use language::{BufferSnapshot, SyntaxLayer};
+use std::ops::Range;
+
+/// A code pattern with metavariables, matched against the syntax trees of the buffers.
+///
+/// The pattern is compared token by token, ignoring whitespace and comments:
+/// * `$NAME` matches a single syntax node, and every other occurrence of `$NAME` has to match the same code;
+/// * `$$$NAME` matches any (possibly empty) sequence of code with balanced brackets, e.g. the arguments of a call;
+/// * `$_` and `$$$_` match the same, without capturing.
+///
+/// The captured code can be used in the replacement, referring to it as `$NAME` or `$$$NAME`.
+#[derive(Clone, Debug)]
+pub struct StructuralPattern {
+    tokens: Vec<PatternToken>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PatternToken {
+    Literal(String),
+    Node(Option<String>),
+    Nodes(Option<String>),
+}
+
+type Captures = Vec<(String, Range<usize>)>;
+
+impl StructuralPattern {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let mut tokens = Vec::new();
+        let mut literal_start = 0;
+        let mut offset = 0;
+        while offset < pattern.len() {
+            let Some(metavariable) = metavariable_at(pattern, offset) else {
+                offset += pattern[offset..].chars().next().map_or(1, char::len_utf8);
+                continue;
+            };
+            tokens.extend(
+                tokenize(pattern, literal_start..offset)
+                    .map(|range| PatternToken::Literal(pattern[range].to_string())),
+            );
+            offset += metavariable.len;
+            literal_start = offset;
+            tokens.push(metavariable.token);
+        }
+        tokens.extend(
+            tokenize(pattern, literal_start..pattern.len())
+                .map(|range| PatternToken::Literal(pattern[range].to_string())),
+        );
+
+        if !tokens
+            .iter()
+            .any(|token| matches!(token, PatternToken::Literal(_)))
+        {
+            return Err(anyhow!(
+                "structural pattern has no code to match besides metavariables"
+            ));
+        }
+        Ok(Self { tokens })
+    }
+
+    /// Whether the given text contains every word of the pattern, and so may have matches.
+    pub fn may_match(&self, text: &str) -> bool {
+        self.tokens.iter().all(|token| match token {
+            PatternToken::Literal(literal) => {
+                !literal.starts_with(is_word_char) || text.contains(literal.as_str())
+            }
+            _ => true,
+        })
+    }
+
+    /// Finds the code matching the pattern in the syntax trees of the buffer, in every language it is written in.
+    pub fn search(
+        &self,
+        buffer: &BufferSnapshot,
+        subrange: Option<Range<usize>>,
+    ) -> Vec<Range<usize>> {
+        let range = subrange.unwrap_or(0..buffer.len());
+        let text = buffer.text();
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers() {
+            let root = layer.node();
+            let layer_range = root.start_byte().max(range.start)..root.end_byte().min(range.end);
+            if layer_range.is_empty() {
+                continue;
+            }
+
+            let (tokens, node_ends) = syntax_tokens(&layer, &text, layer_range);
+            let matcher = Matcher {
+                pattern: &self.tokens,
+                text: &text,
+                tokens: &tokens,
+                node_ends: Some(&node_ends),
+                anchored: false,
+            };
+            let mut token_ix = 0;
+            while token_ix < tokens.len() {
+                match matcher.match_from(0, token_ix, &mut Captures::new()) {
+                    Some(end) if end > token_ix => {
+                        matches.push(tokens[token_ix].start..tokens[end - 1].end);
+                        token_ix = end;
+                    }
+                    _ => token_ix += 1,
+                }
+            }
+        }
+
+        matches.sort_by_key(|range| (range.start, range.end));
+        matches.dedup();
+        matches
+    }
+
+    /// Expands the metavariables of the replacement with the code they captured in the match at
+    /// the given range of the buffer, matching its syntax trees the same way [`Self::search`] does.
+    pub fn replace_in_buffer(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+        replacement: &str,
+    ) -> Option<String> {
+        let text = buffer.text();
+        for layer in buffer.syntax_layers() {
+            let root = layer.node();
+            if root.start_byte() > range.start || root.end_byte() < range.end {
+                continue;
+            }
+
+            let (tokens, node_ends) = syntax_tokens(&layer, &text, range.clone());
+            let matcher = Matcher {
+                pattern: &self.tokens,
+                text: &text,
+                tokens: &tokens,
+                node_ends: Some(&node_ends),
+                anchored: true,
+            };
+            let mut captures = Captures::new();
+            if matcher.match_from(0, 0, &mut captures) == Some(tokens.len()) {
+                return Some(matcher.expand(&captures, replacement));
+            }
+        }
+        self.replace(&text[range], replacement)
+    }
+
+    /// Expands the metavariables of the replacement with the code they captured in the text,
+    /// if the text is matched by the pattern as a whole. Without syntax trees, any code with
+    /// balanced brackets is considered a node, so prefer [`Self::replace_in_buffer`] for matches
+    /// found in buffers.
+    pub fn replace(&self, text: &str, replacement: &str) -> Option<String> {
+        let tokens = tokenize(text, 0..text.len()).collect::<Vec<_>>();
+        let matcher = Matcher {
+            pattern: &self.tokens,
+            text,
+            tokens: &tokens,
+            node_ends: None,
+            anchored: true,
+        };
+        let mut captures = Captures::new();
+        if matcher.match_from(0, 0, &mut captures) != Some(tokens.len()) {
+            return None;
+        }
+        Some(matcher.expand(&captures, replacement))
+    }
+}
+
+/// Splits the code of a syntax tree in the given range into tokens, skipping comments, along with
+/// the indices of the tokens right after the named nodes starting with each token.
+fn syntax_tokens(
+    layer: &SyntaxLayer,
+    text: &str,
+    range: Range<usize>,
+) -> (Vec<Range<usize>>, Vec<Vec<usize>>) {
+    let root = layer.node();
+    let mut comments = Vec::new();
+    let mut nodes = Vec::new();
+    let mut cursor = root.walk();
+    loop {
+        let node = cursor.node();
+        let node_range = node.byte_range();
+        if node_range.start < range.end && node_range.end > range.start {
+            if node.kind().contains("comment") {
+                comments.push(node_range);
+            } else {
+                if node.is_named() && !node_range.is_empty() {
+                    nodes.push(node_range);
+                }
+                if cursor.goto_first_child() {
+                    continue;
+                }
+            }
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                break;
+            }
+        }
+        if cursor.node() == root {
+            break;
+        }
+    }
+
+    let mut tokens = Vec::new();
+    let mut token_start = range.start;
+    for comment in comments {
+        tokens.extend(tokenize(text, token_start..comment.start.max(token_start)));
+        token_start = token_start.max(comment.end);
+    }
+    tokens.extend(tokenize(text, token_start..range.end.max(token_start)));
+
+    let mut node_ends = vec![Vec::new(); tokens.len()];
+    for node in nodes {
+        let start = tokens.binary_search_by_key(&node.start, |token| token.start);
+        let end = tokens.binary_search_by_key(&node.end, |token| token.end);
+        if let (Ok(start), Ok(end)) = (start, end) {
+            node_ends[start].push(end + 1);
+        }
+    }
+    for ends in &mut node_ends {
+        ends.sort_unstable();
+        ends.dedup();
+    }
+    (tokens, node_ends)
+}
+
+struct Metavariable {
+    token: PatternToken,
+    len: usize,
+}
+
+fn metavariable_at(text: &str, offset: usize) -> Option<Metavariable> {
+    let rest = &text[offset..];
+    let (sequence, name_start) = if rest.starts_with("$$$") {
+        (true, 3)
+    } else if rest.starts_with('$') {
+        (false, 1)
+    } else {
+        return None;
+    };
+    let name_len = rest[name_start..]
+        .find(|c: char| !is_word_char(c))
+        .unwrap_or(rest.len() - name_start);
+    let name = &rest[name_start..name_start + name_len];
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let name = (name != "_").then(|| name.to_string());
+    Some(Metavariable {
+        token: if sequence {
+            PatternToken::Nodes(name)
+        } else {
+            PatternToken::Node(name)
+        },
+        len: name_start + name_len,
+    })
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Splits the text into words and single punctuation characters, skipping whitespace.
+fn tokenize(text: &str, range: Range<usize>) -> impl Iterator<Item = Range<usize>> + '_ {
+    let offset = range.start;
+    let mut chars = text[range].char_indices().peekable();
+    std::iter::from_fn(move || loop {
+        let (start, c) = chars.next()?;
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        if is_word_char(c) {
+            while let Some((ix, c)) = chars.next_if(|(_, c)| is_word_char(*c)) {
+                end = ix + c.len_utf8();
+            }
+        }
+        return Some(offset + start..offset + end);
+    })
+}
+
+struct Matcher<'a> {
+    pattern: &'a [PatternToken],
+    text: &'a str,
+    tokens: &'a [Range<usize>],
+    /// For every token, the indices of the tokens right after the syntax nodes starting with it, shortest nodes first.
+    /// Without syntax trees, any code with balanced brackets is considered a node.
+    node_ends: Option<&'a [Vec<usize>]>,
+    /// Whether the match has to span all the tokens.
+    anchored: bool,
+}
+
+impl Matcher<'_> {
+    /// Matches the pattern, starting with its `pattern_ix`-th token, against the tokens starting at `token_ix`,
+    /// returning the index of the token after the match.
+    fn match_from(
+        &self,
+        pattern_ix: usize,
+        token_ix: usize,
+        captures: &mut Captures,
+    ) -> Option<usize> {
+        let Some(pattern_token) = self.pattern.get(pattern_ix) else {
+            return (!self.anchored || token_ix == self.tokens.len()).then_some(token_ix);
+        };
+        match pattern_token {
+            PatternToken::Literal(literal) => {
+                let token = self.tokens.get(token_ix)?;
+                if self.text[token.clone()] != *literal {
+                    return None;
+                }
+                self.match_from(pattern_ix + 1, token_ix + 1, captures)
+            }
+            PatternToken::Node(name) => {
+                match self.node_ends {
+                    Some(node_ends) => node_ends.get(token_ix)?.iter().find_map(|&end| {
+                        self.match_capture(pattern_ix, name, token_ix..end, captures)
+                    }),
+                    None => self.balanced_ends(token_ix).skip(1).find_map(|end| {
+                        self.match_capture(pattern_ix, name, token_ix..end, captures)
+                    }),
+                }
+            }
+            PatternToken::Nodes(name) => self
+                .balanced_ends(token_ix)
+                .find_map(|end| self.match_capture(pattern_ix, name, token_ix..end, captures)),
+        }
+    }
+
+    fn match_capture(
+        &self,
+        pattern_ix: usize,
+        name: &Option<String>,
+        token_range: Range<usize>,
+        captures: &mut Captures,
+    ) -> Option<usize> {
+        let end = token_range.end;
+        let mut captured = false;
+        if let Some(name) = name {
+            match captures.iter().find(|(captured, _)| captured == name) {
+                Some((_, previous)) => {
+                    let previous = &self.tokens[previous.clone()];
+                    let current = &self.tokens[token_range.clone()];
+                    if previous.len() != current.len()
+                        || previous
+                            .iter()
+                            .zip(current)
+                            .any(|(a, b)| self.text[a.clone()] != self.text[b.clone()])
+                    {
+                        return None;
+                    }
+                }
+                None => {
+                    captures.push((name.clone(), token_range));
+                    captured = true;
+                }
+            }
+        }
+        let result = self.match_from(pattern_ix + 1, end, captures);
+        if result.is_none() && captured {
+            captures.pop();
+        }
+        result
+    }
+
+    /// Indices of the tokens after every sequence with balanced brackets starting at the given token, shortest first.
+    fn balanced_ends(&self, token_ix: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut depth = 0_usize;
+        let mut end = token_ix;
+        let mut finished = false;
+        std::iter::from_fn(move || {
+            while !finished {
+                let balanced_end = (depth == 0).then_some(end);
+                match self.tokens.get(end).map(|token| &self.text[token.clone()]) {
+                    None => finished = true,
+                    Some("(" | "[" | "{") => {
+                        depth += 1;
+                        end += 1;
+                    }
+                    Some(")" | "]" | "}") => {
+                        if depth == 0 {
+                            finished = true;
+                        } else {
+                            depth -= 1;
+                            end += 1;
+                        }
+                    }
+                    Some(_) => end += 1,
+                }
+                if balanced_end.is_some() {
+                    return balanced_end;
+                }
+            }
+            None
+        })
+    }
+
+    /// Expands the metavariables of the replacement with the code they captured.
+    fn expand(&self, captures: &Captures, replacement: &str) -> String {
+        let mut result = String::with_capacity(replacement.len());
+        let mut offset = 0;
+        while offset < replacement.len() {
+            let captured = metavariable_at(replacement, offset).and_then(|metavariable| {
+                let (PatternToken::Node(Some(name)) | PatternToken::Nodes(Some(name))) =
+                    &metavariable.token
+                else {
+                    return None;
+                };
+                let (_, range) = captures.iter().find(|(captured, _)| captured == name)?;
+                Some((self.text_for(range.clone()), metavariable.len))
+            });
+            match captured {
+                Some((captured, len)) => {
+                    result.push_str(captured);
+                    offset += len;
+                }
+                None => {
+                    let c = replacement[offset..].chars().next().unwrap();
+                    result.push(c);
+                    offset += c.len_utf8();
+                }
+            }
+        }
+        result
+    }
+
+    /// The code spanned by the given tokens.
+    fn text_for(&self, token_range: Range<usize>) -> &str {
+        if token_range.is_empty() {
+            return "";
+        }
+        &self.text[self.tokens[token_range.start].start..self.tokens[token_range.end - 1].end]
+    }
+}
@@ -0,0 +1,166 @@
+use std::{cmp, ops::Range, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use language::{
+    proto::{deserialize_anchor, serialize_anchor, serialize_version},
+    Anchor, Bias, BufferSnapshot, PointUtf16, Unclipped,
+};
+use rpc::proto;
+
+/// The semantic tokens a language server reported for a buffer, anchored to
+/// the buffer contents at the time of the request.
+#[derive(Clone, Debug, Default)]
+pub struct BufferSemanticTokens {
+    pub tokens: Vec<SemanticToken>,
+    /// The names of the token types, indexed by [`SemanticToken::token_type`].
+    pub token_types: Arc<[String]>,
+    /// The names of the token modifiers, indexed by the bits set in [`SemanticToken::token_modifiers`].
+    pub token_modifiers: Arc<[String]>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub range: Range<Anchor>,
+    pub token_type: u32,
+    pub token_modifiers: u32,
+}
+
+impl BufferSemanticTokens {
+    pub fn token_type(&self, token: &SemanticToken) -> Option<&str> {
+        self.token_types
+            .get(token.token_type as usize)
+            .map(String::as_str)
+    }
+
+    pub fn token_modifiers<'a>(
+        &'a self,
+        token: &SemanticToken,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        let bits = token.token_modifiers;
+        self.token_modifiers
+            .iter()
+            .enumerate()
+            .take(u32::BITS as usize)
+            .filter(move |(ix, _)| bits & (1 << ix) != 0)
+            .map(|(_, modifier)| modifier.as_str())
+    }
+
+    pub(crate) fn to_proto(&self, version: &clock::Global) -> proto::GetSemanticTokensResponse {
+        proto::GetSemanticTokensResponse {
+            tokens: self
+                .tokens
+                .iter()
+                .map(|token| proto::SemanticToken {
+                    start: Some(serialize_anchor(&token.range.start)),
+                    end: Some(serialize_anchor(&token.range.end)),
+                    token_type: token.token_type,
+                    token_modifiers: token.token_modifiers,
+                })
+                .collect(),
+            token_types: self.token_types.to_vec(),
+            token_modifiers: self.token_modifiers.to_vec(),
+            version: serialize_version(version),
+        }
+    }
+
+    pub(crate) fn from_proto(response: proto::GetSemanticTokensResponse) -> Result<Self> {
+        let tokens = response
+            .tokens
+            .into_iter()
+            .map(|token| {
+                let start = token
+                    .start
+                    .and_then(deserialize_anchor)
+                    .context("missing semantic token start")?;
+                let end = token
+                    .end
+                    .and_then(deserialize_anchor)
+                    .context("missing semantic token end")?;
+                Ok(SemanticToken {
+                    range: start..end,
+                    token_type: token.token_type,
+                    token_modifiers: token.token_modifiers,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            tokens,
+            token_types: response.token_types.into(),
+            token_modifiers: response.token_modifiers.into(),
+        })
+    }
+}
+
+/// The last token data received from a language server for a buffer, in the
+/// relative encoding of the LSP spec. Kept so that the next request can ask
+/// the server for a delta instead of the full set of tokens.
+#[derive(Debug, Default)]
+pub(crate) struct CachedSemanticTokens {
+    pub result_id: Option<String>,
+    pub data: Vec<u32>,
+}
+
+pub(crate) fn flatten_lsp_tokens(tokens: &[lsp::SemanticToken]) -> Vec<u32> {
+    tokens
+        .iter()
+        .flat_map(|token| {
+            [
+                token.delta_line,
+                token.delta_start,
+                token.length,
+                token.token_type,
+                token.token_modifiers_bitset,
+            ]
+        })
+        .collect()
+}
+
+/// Applies the edits of a `textDocument/semanticTokens/full/delta` response.
+/// All edits refer to offsets in the previous data, so they are applied from
+/// the last one to the first.
+pub(crate) fn apply_semantic_token_edits(
+    data: &mut Vec<u32>,
+    mut edits: Vec<lsp::SemanticTokensEdit>,
+) {
+    edits.sort_by_key(|edit| edit.start);
+    for edit in edits.into_iter().rev() {
+        let start = cmp::min(edit.start as usize, data.len());
+        let end = cmp::min(start + edit.delete_count as usize, data.len());
+        let inserted = edit
+            .data
+            .as_deref()
+            .map(flatten_lsp_tokens)
+            .unwrap_or_default();
+        data.splice(start..end, inserted);
+    }
+}
+
+/// Decodes relative token data against the buffer contents the server saw.
+pub(crate) fn decode_semantic_tokens(data: &[u32], buffer: &BufferSnapshot) -> Vec<SemanticToken> {
+    let mut tokens = Vec::with_capacity(data.len() / 5);
+    let mut row = 0;
+    let mut column = 0;
+    for token in data.chunks_exact(5) {
+        let delta_line = token[0];
+        let delta_start = token[1];
+        let length = token[2];
+        if delta_line == 0 {
+            column += delta_start;
+        } else {
+            row += delta_line;
+            column = delta_start;
+        }
+
+        let start = buffer.clip_point_utf16(Unclipped(PointUtf16::new(row, column)), Bias::Left);
+        let end =
+            buffer.clip_point_utf16(Unclipped(PointUtf16::new(row, column + length)), Bias::Left);
+        if start < end {
+            tokens.push(SemanticToken {
+                range: buffer.anchor_after(start)..buffer.anchor_before(end),
+                token_type: token[3],
+                token_modifiers: token[4],
+            });
+        }
+    }
+    tokens
+}
@@ -6,10 +6,13 @@ use std::{
     sync::Arc,
 };
 
+use anyhow::Context as _;
 use collections::{HashMap, VecDeque};
 use gpui::{AppContext, Context, Model, ModelContext, Subscription};
 use itertools::Itertools;
-use task::{Task, TaskContext, TaskId, TaskSource};
+use task::{
+    discovered_source::DiscoveredFile, RunMode, Task, TaskContext, TaskId, TaskSource, TaskStatus,
+};
 use util::{post_inc, NumericPrefixWithSuffix};
 use worktree::WorktreeId;
 
@@ -17,6 +20,18 @@ use worktree::WorktreeId;
 pub struct Inventory {
     sources: Vec<SourceInInventory>,
     last_scheduled_tasks: VecDeque<(TaskId, TaskContext)>,
+    task_statuses: HashMap<TaskId, TaskStatus>,
+}
+
+/// A task with the other tasks to run around it, resolved from their labels.
+#[derive(Clone)]
+pub struct ResolvedTask {
+    pub task: Arc<dyn Task>,
+    /// Tasks to run before this one, in the order they are listed.
+    pub dependencies: Vec<ResolvedTask>,
+    pub run_mode: RunMode,
+    pub on_success: Option<Box<ResolvedTask>>,
+    pub on_failure: Option<Box<ResolvedTask>>,
 }
 
 struct SourceInInventory {
@@ -31,23 +46,33 @@ struct SourceInInventory {
 pub enum TaskSourceKind {
     /// bash-like commands spawned by users, not associated with any path
     UserInput,
+    /// Tests, binaries and other runnables detected in the code of the open files by their languages
+    Runnables,
     /// ~/.config/zed/task.json - like global files with task definitions, applicable to any path
     AbsPath(PathBuf),
     /// Worktree-specific task definitions, e.g. dynamic tasks from open worktree file, or tasks from the worktree's .zed/task.json
     Worktree { id: WorktreeId, abs_path: PathBuf },
+    /// Tasks discovered in a package manifest or a build file of the worktree, e.g. `package.json` scripts or `Makefile` targets
+    Discovered {
+        id: WorktreeId,
+        abs_path: PathBuf,
+        file: DiscoveredFile,
+    },
 }
 
 impl TaskSourceKind {
     fn abs_path(&self) -> Option<&Path> {
         match self {
-            Self::AbsPath(abs_path) | Self::Worktree { abs_path, .. } => Some(abs_path),
-            Self::UserInput => None,
+            Self::AbsPath(abs_path)
+            | Self::Worktree { abs_path, .. }
+            | Self::Discovered { abs_path, .. } => Some(abs_path),
+            Self::UserInput | Self::Runnables => None,
         }
     }
 
     fn worktree(&self) -> Option<WorktreeId> {
         match self {
-            Self::Worktree { id, .. } => Some(*id),
+            Self::Worktree { id, .. } | Self::Discovered { id, .. } => Some(*id),
             _ => None,
         }
     }
@@ -58,6 +83,7 @@ impl Inventory {
         cx.new_model(|_| Self {
             sources: Vec::new(),
             last_scheduled_tasks: VecDeque::new(),
+            task_statuses: HashMap::default(),
         })
     }
 
@@ -216,6 +242,33 @@ impl Inventory {
             })
     }
 
+    /// Resolves the tasks to run before and after the task given, looking them up by their labels among the tasks available for the path.
+    /// Fails if a label does not match any task, or if the tasks form a cycle.
+    pub fn resolve_task(
+        &self,
+        task: Arc<dyn Task>,
+        path: Option<&Path>,
+        worktree: Option<WorktreeId>,
+        cx: &mut AppContext,
+    ) -> anyhow::Result<ResolvedTask> {
+        let tasks_by_label = self
+            .list_tasks(path, worktree, false, cx)
+            .into_iter()
+            .map(|(_, task)| (task.name().to_string(), task))
+            .collect::<HashMap<_, _>>();
+        resolve_task(task, &tasks_by_label, &mut Vec::new())
+    }
+
+    /// Returns the state of the latest run of the task, if it was run with its dependencies resolved.
+    pub fn task_status(&self, id: &TaskId) -> Option<TaskStatus> {
+        self.task_statuses.get(id).copied()
+    }
+
+    pub fn set_task_status(&mut self, id: TaskId, status: TaskStatus, cx: &mut ModelContext<Self>) {
+        self.task_statuses.insert(id, status);
+        cx.notify();
+    }
+
     /// Registers task "usage" as being scheduled – to be used for LRU sorting when listing all tasks.
     pub fn task_scheduled(&mut self, id: TaskId, task_context: TaskContext) {
         self.last_scheduled_tasks.push_back((id, task_context));
@@ -225,6 +278,66 @@ impl Inventory {
     }
 }
 
+fn resolve_task(
+    task: Arc<dyn Task>,
+    tasks_by_label: &HashMap<String, Arc<dyn Task>>,
+    resolving: &mut Vec<String>,
+) -> anyhow::Result<ResolvedTask> {
+    let label = task.name().to_string();
+    if let Some(cycle_start) = resolving.iter().position(|resolving| resolving == &label) {
+        let mut cycle = resolving[cycle_start..].to_vec();
+        cycle.push(label);
+        anyhow::bail!("Tasks depend on each other: {}", cycle.join(" -> "));
+    }
+    let Some(dependencies) = task
+        .dependencies()
+        .filter(|dependencies| !dependencies.is_empty())
+    else {
+        return Ok(ResolvedTask {
+            task,
+            dependencies: Vec::new(),
+            run_mode: RunMode::default(),
+            on_success: None,
+            on_failure: None,
+        });
+    };
+
+    resolving.push(label);
+    let mut resolve_label = |dependency_label: &str| {
+        let dependency = tasks_by_label.get(dependency_label).with_context(|| {
+            format!(
+                "Task \"{}\" refers to an unknown task \"{dependency_label}\"",
+                task.name()
+            )
+        })?;
+        resolve_task(dependency.clone(), tasks_by_label, resolving)
+    };
+    let resolved_dependencies = dependencies
+        .depends_on
+        .iter()
+        .map(|label| resolve_label(label))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let on_success = dependencies
+        .on_success
+        .as_deref()
+        .map(|label| resolve_label(label).map(Box::new))
+        .transpose()?;
+    let on_failure = dependencies
+        .on_failure
+        .as_deref()
+        .map(|label| resolve_label(label).map(Box::new))
+        .transpose()?;
+    resolving.pop();
+
+    Ok(ResolvedTask {
+        run_mode: dependencies.run_mode,
+        task,
+        dependencies: resolved_dependencies,
+        on_success,
+        on_failure,
+    })
+}
+
 #[cfg(any(test, feature = "test-support"))]
 pub mod test_inventory {
     use std::{path::Path, sync::Arc};
@@ -663,4 +776,85 @@ mod tests {
             );
         }
     }
+
+    #[gpui::test]
+    fn test_resolve_task_dependencies(cx: &mut TestAppContext) {
+        let inventory = cx.update(Inventory::new);
+        let (tasks_tx, tasks_rx) = futures::channel::mpsc::unbounded();
+        inventory.update(cx, |inventory, cx| {
+            inventory.add_source(
+                TaskSourceKind::AbsPath(PathBuf::from("/tasks.json")),
+                |cx| task::static_source::StaticSource::new("test", tasks_rx, cx),
+                cx,
+            );
+        });
+        let set_tasks = |tasks: &str, cx: &mut TestAppContext| {
+            tasks_tx.unbounded_send(tasks.to_string()).unwrap();
+            cx.run_until_parked();
+        };
+        let resolve = |label: &str, cx: &mut TestAppContext| {
+            inventory.update(cx, |inventory, cx| {
+                let (_, task) = inventory
+                    .list_tasks(None, None, false, cx)
+                    .into_iter()
+                    .find(|(_, task)| task.name() == label)
+                    .unwrap();
+                inventory.resolve_task(task, None, None, cx)
+            })
+        };
+        fn labels(task: &ResolvedTask) -> Vec<String> {
+            task.dependencies
+                .iter()
+                .map(|dependency| dependency.task.name().to_string())
+                .collect()
+        }
+
+        set_tasks(
+            r#"[
+                { "label": "build", "command": "cargo build" },
+                { "label": "migrate", "command": "diesel migration run", "depends_on": ["build"] },
+                { "label": "lint", "command": "cargo clippy" },
+                {
+                    "label": "serve",
+                    "command": "cargo run",
+                    "depends_on": ["migrate", "lint"],
+                    "run_mode": "parallel",
+                    "on_failure": "notify"
+                },
+                { "label": "notify", "command": "echo failed" }
+            ]"#,
+            cx,
+        );
+        let serve = resolve("serve", cx).unwrap();
+        assert_eq!(labels(&serve), vec!["migrate", "lint"]);
+        assert_eq!(serve.run_mode, RunMode::Parallel);
+        assert_eq!(labels(&serve.dependencies[0]), vec!["build"]);
+        assert_eq!(serve.dependencies[0].run_mode, RunMode::Sequential);
+        assert!(serve.on_success.is_none());
+        assert_eq!(serve.on_failure.unwrap().task.name(), "notify");
+
+        set_tasks(
+            r#"[
+                { "label": "build", "command": "cargo build", "on_success": "migrate" },
+                { "label": "migrate", "command": "diesel migration run", "depends_on": ["build"] },
+                { "label": "serve", "command": "cargo run", "depends_on": ["migrate", "missing"] }
+            ]"#,
+            cx,
+        );
+        assert_eq!(
+            resolve("migrate", cx).err().unwrap().to_string(),
+            "Tasks depend on each other: migrate -> build -> migrate"
+        );
+        set_tasks(
+            r#"[
+                { "label": "build", "command": "cargo build" },
+                { "label": "serve", "command": "cargo run", "depends_on": ["build", "missing"] }
+            ]"#,
+            cx,
+        );
+        assert_eq!(
+            resolve("serve", cx).err().unwrap().to_string(),
+            "Task \"serve\" refers to an unknown task \"missing\""
+        );
+    }
 }
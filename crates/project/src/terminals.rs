@@ -1,18 +1,31 @@
 use crate::Project;
+use collections::HashMap;
+use futures::channel::oneshot;
 use gpui::{AnyWindowHandle, Context, Entity, Model, ModelContext, WeakModel};
+use language::{Diagnostic, DiagnosticEntry, PointUtf16, Unclipped};
+use lsp::{DiagnosticSeverity, LanguageServerId};
 use settings::Settings;
 use smol::channel::bounded;
 use std::path::{Path, PathBuf};
+use task::{
+    problem_matcher::{Problem, ProblemMatcher, ProblemSeverity},
+    TaskId,
+};
 use terminal::{
     terminal_settings::{self, Shell, TerminalSettings, VenvSettingsContent},
     SpawnTask, TaskState, Terminal, TerminalBuilder,
 };
+use util::{post_inc, ResultExt};
 
 // #[cfg(target_os = "macos")]
 // use std::os::unix::ffi::OsStrExt;
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    /// The diagnostics source of each task with a problem matcher, under which the problems found in its output are reported.
+    pub(crate) task_diagnostic_sources: HashMap<TaskId, LanguageServerId>,
+    /// Receivers of the exit codes of the tasks that are about to be spawned.
+    pub(crate) task_completion_waiters: HashMap<TaskId, Vec<oneshot::Sender<Option<i32>>>>,
 }
 
 impl Project {
@@ -23,22 +36,30 @@ impl Project {
         window: AnyWindowHandle,
         cx: &mut ModelContext<Self>,
     ) -> anyhow::Result<Model<Terminal>> {
-        anyhow::ensure!(
-            !self.is_remote(),
-            "creating terminals as a guest is not supported yet"
-        );
+        let task_id = spawn_task.as_ref().map(|spawn_task| spawn_task.id.clone());
+        if self.is_remote() {
+            if let Some(task_id) = &task_id {
+                self.cancel_task_completion(task_id);
+            }
+            anyhow::bail!("creating terminals as a guest is not supported yet");
+        }
 
         let settings = TerminalSettings::get_global(cx);
         let python_settings = settings.detect_venv.clone();
         let (completion_tx, completion_rx) = bounded(1);
         let mut env = settings.env.clone();
+        let mut problem_matcher = None;
         let (spawn_task, shell) = if let Some(spawn_task) = spawn_task {
             env.extend(spawn_task.env);
+            problem_matcher = spawn_task
+                .problem_matcher
+                .map(|matcher| (spawn_task.id.clone(), spawn_task.label.clone(), matcher));
             (
                 Some(TaskState {
                     id: spawn_task.id,
                     label: spawn_task.label,
                     completed: false,
+                    exit_code: None,
                     completion_rx,
                 }),
                 Shell::WithArguments {
@@ -82,6 +103,21 @@ impl Project {
             })
             .detach();
 
+            if let Some(task_id) = terminal_handle.read(cx).task().map(|task| task.id.clone()) {
+                self.report_task_completion(task_id, &terminal_handle, cx);
+            }
+
+            if let Some((task_id, task_label, problem_matcher)) = problem_matcher {
+                self.report_task_problems(
+                    task_id,
+                    task_label,
+                    problem_matcher,
+                    working_directory.clone(),
+                    &terminal_handle,
+                    cx,
+                );
+            }
+
             if let Some(python_settings) = &python_settings.as_option() {
                 let activate_command = Project::get_activate_command(python_settings);
                 let activate_script_path =
@@ -96,9 +132,160 @@ impl Project {
             terminal_handle
         });
 
+        if let (Err(_), Some(task_id)) = (&terminal, &task_id) {
+            self.cancel_task_completion(task_id);
+        }
         terminal
     }
 
+    /// Returns the exit code of the task with the given id, once the next run of it completes.
+    /// Resolves to `None` if the exit code cannot be determined, and is canceled if no terminal gets spawned for the task.
+    pub fn task_completion(&mut self, task_id: TaskId) -> oneshot::Receiver<Option<i32>> {
+        let (tx, rx) = oneshot::channel();
+        self.terminals
+            .task_completion_waiters
+            .entry(task_id)
+            .or_default()
+            .push(tx);
+        rx
+    }
+
+    /// Drops the receivers of the exit code of the task with the given id, for when it is not going to be run,
+    /// so that they resolve instead of waiting forever.
+    pub fn cancel_task_completion(&mut self, task_id: &TaskId) {
+        self.terminals.task_completion_waiters.remove(task_id);
+    }
+
+    fn report_task_completion(
+        &mut self,
+        task_id: TaskId,
+        terminal: &Model<Terminal>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(waiters) = self.terminals.task_completion_waiters.remove(&task_id) else {
+            return;
+        };
+        let task_completed =
+            terminal.update(cx, |terminal, cx| terminal.wait_for_completed_task(cx));
+        let terminal = terminal.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            task_completed.await;
+            let exit_code = terminal
+                .update(&mut cx, |terminal, _| {
+                    terminal.task().and_then(|task| task.exit_code)
+                })
+                .ok()
+                .flatten();
+            for waiter in waiters {
+                waiter.send(exit_code).ok();
+            }
+        })
+        .detach();
+    }
+
+    /// Clears the problems reported by the previous run of the task, and reports the ones
+    /// found in the output of this run as diagnostics once the task completes.
+    fn report_task_problems(
+        &mut self,
+        task_id: TaskId,
+        task_label: String,
+        problem_matcher: ProblemMatcher,
+        working_directory: Option<PathBuf>,
+        terminal: &Model<Terminal>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let languages = &self.languages;
+        let server_id = *self
+            .terminals
+            .task_diagnostic_sources
+            .entry(task_id)
+            .or_insert_with(|| languages.next_language_server_id());
+        self.clear_task_problems(server_id, cx);
+
+        let task_completed =
+            terminal.update(cx, |terminal, cx| terminal.wait_for_completed_task(cx));
+        let terminal = terminal.downgrade();
+        cx.spawn(|project, mut cx| async move {
+            task_completed.await;
+            let Ok(output) = terminal.update(&mut cx, |terminal, _| terminal.text()) else {
+                return Ok(());
+            };
+            let problems = cx
+                .background_executor()
+                .spawn(async move { problem_matcher.find_problems(&output) })
+                .await?;
+            project.update(&mut cx, |project, cx| {
+                project.update_task_problems(server_id, task_label, working_directory, problems, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn clear_task_problems(&mut self, server_id: LanguageServerId, cx: &mut ModelContext<Self>) {
+        let reported_paths = self
+            .diagnostic_summaries(true, cx)
+            .filter(|(_, path_server_id, _)| *path_server_id == server_id)
+            .filter_map(|(project_path, _, _)| self.absolute_path(&project_path, cx))
+            .collect::<Vec<_>>();
+        for abs_path in reported_paths {
+            self.update_diagnostic_entries(server_id, abs_path, None, Vec::new(), cx)
+                .log_err();
+        }
+    }
+
+    fn update_task_problems(
+        &mut self,
+        server_id: LanguageServerId,
+        task_label: String,
+        working_directory: Option<PathBuf>,
+        problems: Vec<Problem>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let working_directory = working_directory.or_else(|| {
+            self.visible_worktrees(cx)
+                .find_map(|worktree| Some(worktree.read(cx).as_local()?.abs_path().to_path_buf()))
+        });
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<_>>::default();
+        for problem in problems {
+            let abs_path = match &working_directory {
+                Some(working_directory) => working_directory.join(&problem.path),
+                None => problem.path,
+            };
+            // Compilers also point into the files outside of the project, e.g. to the standard library.
+            if self.find_local_worktree(&abs_path, cx).is_none() {
+                continue;
+            }
+            let position = Unclipped(PointUtf16::new(problem.row, problem.column));
+            diagnostics_by_path
+                .entry(abs_path)
+                .or_default()
+                .push(DiagnosticEntry {
+                    range: position..position,
+                    diagnostic: Diagnostic {
+                        source: Some(task_label.clone()),
+                        code: problem.code,
+                        severity: match problem.severity {
+                            ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                            ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                            ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                            ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+                        },
+                        message: problem.message,
+                        group_id: post_inc(&mut self.next_diagnostic_group_id),
+                        is_primary: true,
+                        is_disk_based: true,
+                        is_unnecessary: false,
+                    },
+                });
+        }
+
+        for (abs_path, diagnostics) in diagnostics_by_path {
+            self.update_diagnostic_entries(server_id, abs_path, None, diagnostics, cx)
+                .log_err();
+        }
+        self.disk_based_diagnostics_finished(server_id, cx);
+    }
+
     pub fn find_activate_script_path(
         &mut self,
         settings: &VenvSettingsContent,
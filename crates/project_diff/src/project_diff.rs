@@ -0,0 +1,873 @@
+mod toolbar_controls;
+
+use anyhow::Result;
+use collections::HashMap;
+use editor::{Editor, EditorEvent, ExcerptId, ExcerptRange, MultiBuffer};
+use futures::future::join_all;
+use gpui::{
+    actions, div, AnyElement, AnyView, AppContext, Context, EventEmitter, FocusHandle,
+    FocusableView, InteractiveElement, IntoElement, Model, ParentElement, Render, SharedString,
+    Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use language::{Buffer, Capability, Point, ToPoint as _};
+use project::{repository::GitFileStatus, Project, ProjectPath};
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use theme::ActiveTheme;
+pub use toolbar_controls::ToolbarControls;
+use ui::{h_flex, prelude::*, Icon, IconName, Label};
+use util::ResultExt;
+use workspace::{
+    item::{BreadcrumbText, Item, ItemEvent, ItemHandle},
+    ItemNavHistory, ToolbarItemLocation, Workspace,
+};
+
+actions!(project_diff, [Deploy, ToggleSortOrder]);
+
+const CONTEXT_LINE_COUNT: u32 = 2;
+const UPDATE_PATHS_DEBOUNCE: Duration = Duration::from_millis(50);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(ProjectDiffEditor::register).detach();
+}
+
+/// The order of the files in the [`ProjectDiffEditor`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Path,
+    /// Conflicted files first, then the modified and the added ones, each sorted by path.
+    Status,
+}
+
+/// A multibuffer with an excerpt for every changed hunk of the files of the project's
+/// git repositories, with respect to their index.
+pub struct ProjectDiffEditor {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    focus_handle: FocusHandle,
+    editor: View<Editor>,
+    excerpts: Model<MultiBuffer>,
+    sort_order: SortOrder,
+    path_states: Vec<PathState>,
+    update_paths_task: Task<()>,
+    open_buffers_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// A file with a git status, along with the text it had in the index or in the HEAD commit
+/// if it was deleted from the working directory.
+struct ChangedPath {
+    status: GitFileStatus,
+    deleted_text: Option<String>,
+}
+
+struct PathState {
+    path: ProjectPath,
+    status: GitFileStatus,
+    /// Whether the file was deleted from the working directory, its buffer holding the text
+    /// it had in the index or in the HEAD commit.
+    deleted: bool,
+    buffer: Model<Buffer>,
+    hunk_count: usize,
+    /// The number of the buffer's diff updates that the excerpts reflect.
+    diff_update_count: usize,
+    _subscriptions: [Subscription; 2],
+}
+
+impl EventEmitter<EditorEvent> for ProjectDiffEditor {}
+
+impl Render for ProjectDiffEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl Element {
+        let child = if self.hunk_count() == 0 {
+            div()
+                .bg(cx.theme().colors().editor_background)
+                .flex()
+                .items_center()
+                .justify_center()
+                .size_full()
+                .child(Label::new("No changes"))
+        } else {
+            div().size_full().child(self.editor.clone())
+        };
+
+        div()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::toggle_sort_order))
+            .child(child)
+    }
+}
+
+impl ProjectDiffEditor {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::deploy);
+    }
+
+    fn new(
+        project_handle: Model<Project>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let project_event_subscription =
+            cx.subscribe(&project_handle, |this, _, event, cx| match event {
+                project::Event::WorktreeAdded
+                | project::Event::WorktreeRemoved(_)
+                | project::Event::WorktreeUpdatedEntries(_, _)
+                | project::Event::WorktreeUpdatedGitRepositories(_) => this.update_paths(true, cx),
+                _ => {}
+            });
+
+        let focus_handle = cx.focus_handle();
+        let focus_in_subscription =
+            cx.on_focus_in(&focus_handle, |project_diff, cx| project_diff.focus_in(cx));
+
+        let excerpts = cx.new_model(|cx| {
+            MultiBuffer::new(
+                project_handle.read(cx).replica_id(),
+                project_handle.read(cx).capability(),
+            )
+        });
+        let editor = cx.new_view(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(excerpts.clone(), Some(project_handle.clone()), cx);
+            editor.set_vertical_scroll_margin(5, cx);
+            editor
+        });
+        let editor_event_subscription =
+            cx.subscribe(&editor, |this, _editor, event: &EditorEvent, cx| {
+                cx.emit(event.clone());
+                if event == &EditorEvent::Focused && this.hunk_count() == 0 {
+                    cx.focus(&this.focus_handle);
+                }
+            });
+
+        let mut this = Self {
+            project: project_handle,
+            workspace,
+            focus_handle,
+            editor,
+            excerpts,
+            sort_order: SortOrder::default(),
+            path_states: Vec::new(),
+            update_paths_task: Task::ready(()),
+            open_buffers_task: Task::ready(()),
+            _subscriptions: vec![
+                project_event_subscription,
+                editor_event_subscription,
+                focus_in_subscription,
+            ],
+        };
+        this.update_paths(false, cx);
+        this
+    }
+
+    fn deploy(workspace: &mut Workspace, _: &Deploy, cx: &mut ViewContext<Workspace>) {
+        if let Some(existing) = workspace.item_of_type::<ProjectDiffEditor>(cx) {
+            workspace.activate_item(&existing, cx);
+        } else {
+            let workspace_handle = cx.view().downgrade();
+            let project_diff = cx.new_view(|cx| {
+                ProjectDiffEditor::new(workspace.project().clone(), workspace_handle, cx)
+            });
+            workspace.add_item_to_active_pane(Box::new(project_diff), cx);
+        }
+    }
+
+    pub fn sort_order(&self) -> SortOrder {
+        self.sort_order
+    }
+
+    fn toggle_sort_order(&mut self, _: &ToggleSortOrder, cx: &mut ViewContext<Self>) {
+        self.sort_order = match self.sort_order {
+            SortOrder::Path => SortOrder::Status,
+            SortOrder::Status => SortOrder::Path,
+        };
+        self.reorder_paths(cx);
+    }
+
+    fn focus_in(&mut self, cx: &mut ViewContext<Self>) {
+        if self.focus_handle.is_focused(cx) && self.hunk_count() > 0 {
+            self.editor.focus_handle(cx).focus(cx)
+        }
+    }
+
+    fn hunk_count(&self) -> usize {
+        self.path_states.iter().map(|state| state.hunk_count).sum()
+    }
+
+    fn sort_key<'a>(&self, path: &'a ProjectPath, status: GitFileStatus) -> (u8, &'a ProjectPath) {
+        let rank = match self.sort_order {
+            SortOrder::Path => 0,
+            SortOrder::Status => match status {
+                GitFileStatus::Conflict => 0,
+                GitFileStatus::Modified => 1,
+                GitFileStatus::Added => 2,
+            },
+        };
+        (rank, path)
+    }
+
+    /// Reads the statuses of the files of the repositories in the project's local worktrees on
+    /// the background, debouncing the updates caused by file system events.
+    fn update_paths(&mut self, debounce: bool, cx: &mut ViewContext<Self>) {
+        let mut repositories = Vec::new();
+        for worktree in self.project.read(cx).visible_worktrees(cx) {
+            let Some(local_worktree) = worktree.read(cx).as_local() else {
+                continue;
+            };
+            let snapshot = local_worktree.snapshot();
+            for (work_directory, _) in snapshot.repositories() {
+                if let Some(repo) = snapshot.local_git_repo(work_directory) {
+                    repositories.push((snapshot.clone(), work_directory.clone(), repo));
+                }
+            }
+        }
+
+        self.update_paths_task = cx.spawn(|this, mut cx| async move {
+            if debounce {
+                cx.background_executor().timer(UPDATE_PATHS_DEBOUNCE).await;
+            }
+            let changed_paths = cx
+                .background_executor()
+                .spawn(async move {
+                    let mut changed_paths = HashMap::default();
+                    for (snapshot, work_directory, repo) in repositories {
+                        let repo = repo.lock();
+                        let Some(statuses) = repo.file_statuses().log_err() else {
+                            continue;
+                        };
+                        for entry in statuses {
+                            let Some(status) =
+                                GitFileStatus::merge(entry.staged, entry.unstaged, false)
+                            else {
+                                continue;
+                            };
+                            let path: Arc<Path> = work_directory.join(&entry.repo_path).into();
+                            let deleted_text = if snapshot.entry_for_path(&path).is_some() {
+                                None
+                            } else if let Some(text) = repo
+                                .load_index_text(&entry.repo_path)
+                                .or_else(|| repo.load_head_text(&entry.repo_path))
+                            {
+                                Some(text)
+                            } else {
+                                continue;
+                            };
+                            let path = ProjectPath {
+                                worktree_id: snapshot.id(),
+                                path,
+                            };
+                            changed_paths.insert(
+                                path,
+                                ChangedPath {
+                                    status,
+                                    deleted_text,
+                                },
+                            );
+                        }
+                    }
+                    changed_paths
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.set_changed_paths(changed_paths, cx)
+            })
+            .ok();
+        });
+    }
+
+    /// Forgets the files that don't have a git status anymore and opens the buffers of the new
+    /// ones, the deleted files being shown with the text they had in the index.
+    fn set_changed_paths(
+        &mut self,
+        mut changed_paths: HashMap<ProjectPath, ChangedPath>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let mut removed_excerpts = Vec::new();
+        let mut status_changed = false;
+        self.path_states.retain_mut(|state| {
+            let status = changed_paths
+                .get(&state.path)
+                .filter(|changed| changed.deleted_text.is_some() == state.deleted)
+                .map(|changed| changed.status);
+            match status {
+                Some(status) => {
+                    changed_paths.remove(&state.path);
+                    status_changed |= state.status != status;
+                    state.status = status;
+                    true
+                }
+                None => {
+                    removed_excerpts.extend(
+                        self.excerpts
+                            .read(cx)
+                            .excerpts_for_buffer(&state.buffer, cx)
+                            .into_iter()
+                            .map(|(excerpt_id, _)| excerpt_id),
+                    );
+                    false
+                }
+            }
+        });
+        if !removed_excerpts.is_empty() {
+            self.excerpts.update(cx, |excerpts, cx| {
+                excerpts.remove_excerpts(removed_excerpts, cx)
+            });
+            self.refresh_selections(cx);
+        }
+        if status_changed && self.sort_order == SortOrder::Status {
+            self.reorder_paths(cx);
+        }
+
+        let new_paths = changed_paths;
+        if new_paths.is_empty() {
+            return;
+        }
+        let project = self.project.clone();
+        self.open_buffers_task = cx.spawn(|this, mut cx| async move {
+            let Some(open_buffers) = project
+                .update(&mut cx, |project, cx| {
+                    new_paths
+                        .into_iter()
+                        .map(|(path, changed)| {
+                            let deleted = changed.deleted_text.is_some();
+                            let open_buffer = match changed.deleted_text {
+                                Some(text) => Task::ready(
+                                    project.create_buffer(&text, None, cx).map(|buffer| {
+                                        buffer.update(cx, |buffer, cx| {
+                                            buffer.set_capability(Capability::ReadOnly, cx)
+                                        });
+                                        buffer
+                                    }),
+                                ),
+                                None => project.open_buffer(path.clone(), cx),
+                            };
+                            let status = changed.status;
+                            async move {
+                                let buffer = open_buffer.await.log_err()?;
+                                Some((path, status, deleted, buffer))
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .ok()
+            else {
+                return;
+            };
+            let buffers = join_all(open_buffers).await;
+
+            this.update(&mut cx, |this, cx| {
+                for (path, status, deleted, buffer) in buffers.into_iter().flatten() {
+                    this.insert_path(path, status, deleted, buffer, cx);
+                }
+            })
+            .ok();
+        });
+        cx.notify();
+    }
+
+    fn insert_path(
+        &mut self,
+        path: ProjectPath,
+        status: GitFileStatus,
+        deleted: bool,
+        buffer: Model<Buffer>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.path_states.iter().any(|state| state.path == path) {
+            return;
+        }
+
+        let key = self.sort_key(&path, status);
+        let ix = self
+            .path_states
+            .partition_point(|state| self.sort_key(&state.path, state.status) < key);
+        // The buffer's diff is recomputed after edits, and when its diff base changes.
+        let subscriptions = [
+            cx.observe(&buffer, |this, buffer, cx| {
+                this.buffer_diff_changed(&buffer, cx)
+            }),
+            cx.subscribe(&buffer, |this, buffer, event, cx| {
+                if let language::Event::DiffBaseChanged = event {
+                    this.buffer_diff_changed(&buffer, cx);
+                }
+            }),
+        ];
+        self.path_states.insert(
+            ix,
+            PathState {
+                path,
+                status,
+                deleted,
+                buffer,
+                hunk_count: 0,
+                diff_update_count: 0,
+                _subscriptions: subscriptions,
+            },
+        );
+        self.populate_excerpts(ix, cx);
+    }
+
+    fn buffer_diff_changed(&mut self, buffer: &Model<Buffer>, cx: &mut ViewContext<Self>) {
+        let diff_update_count = buffer.read(cx).git_diff_update_count();
+        if let Some(ix) = self.path_states.iter().position(|state| {
+            state.buffer == *buffer && state.diff_update_count != diff_update_count
+        }) {
+            self.populate_excerpts(ix, cx);
+        }
+    }
+
+    fn reorder_paths(&mut self, cx: &mut ViewContext<Self>) {
+        let mut path_states = std::mem::take(&mut self.path_states);
+        path_states.sort_by(|a, b| {
+            self.sort_key(&a.path, a.status)
+                .cmp(&self.sort_key(&b.path, b.status))
+        });
+        self.path_states = path_states;
+
+        self.excerpts.update(cx, |excerpts, cx| excerpts.clear(cx));
+        for ix in 0..self.path_states.len() {
+            self.populate_excerpts(ix, cx);
+        }
+        self.refresh_selections(cx);
+    }
+
+    /// Updates the excerpts of the file at the given index to show its current diff hunks,
+    /// keeping the excerpts whose range did not change.
+    fn populate_excerpts(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let mut prev_excerpt_id = self.path_states[..ix]
+            .iter()
+            .rev()
+            .find_map(|state| {
+                self.excerpts
+                    .read(cx)
+                    .excerpts_for_buffer(&state.buffer, cx)
+                    .last()
+                    .map(|(excerpt_id, _)| *excerpt_id)
+            })
+            .unwrap_or_else(ExcerptId::min);
+
+        let state = &mut self.path_states[ix];
+        let buffer = state.buffer.read(cx);
+        let snapshot = buffer.snapshot();
+        state.diff_update_count = buffer.git_diff_update_count();
+        let ranges = if buffer.diff_base().is_some() {
+            state.hunk_count = snapshot
+                .git_diff_hunks_in_row_range(0..snapshot.max_point().row + 1)
+                .count();
+            hunk_context_ranges(&snapshot)
+        } else {
+            // Untracked and deleted files have nothing to be diffed against, and are shown whole.
+            state.hunk_count = 1;
+            vec![Point::zero()..snapshot.max_point()]
+        };
+
+        let buffer = state.buffer.clone();
+        self.excerpts.update(cx, |excerpts, cx| {
+            let mut old_excerpts = excerpts
+                .excerpts_for_buffer(&buffer, cx)
+                .into_iter()
+                .map(|(excerpt_id, range)| {
+                    let range = range.context.start.to_point(&snapshot)
+                        ..range.context.end.to_point(&snapshot);
+                    (excerpt_id, range)
+                })
+                .peekable();
+            let mut excerpts_to_remove = Vec::new();
+            for range in ranges {
+                let mut kept = false;
+                while let Some((_, old_range)) = old_excerpts.peek() {
+                    if old_range.start > range.start {
+                        break;
+                    }
+                    let (excerpt_id, old_range) = old_excerpts.next().unwrap();
+                    if old_range == range {
+                        prev_excerpt_id = excerpt_id;
+                        kept = true;
+                        break;
+                    }
+                    excerpts_to_remove.push(excerpt_id);
+                }
+                if !kept {
+                    let excerpt_ids = excerpts.insert_excerpts_after(
+                        prev_excerpt_id,
+                        buffer.clone(),
+                        [ExcerptRange {
+                            context: range,
+                            primary: None,
+                        }],
+                        cx,
+                    );
+                    prev_excerpt_id = excerpt_ids.last().copied().unwrap_or(prev_excerpt_id);
+                }
+            }
+            excerpts_to_remove.extend(old_excerpts.map(|(excerpt_id, _)| excerpt_id));
+            excerpts.remove_excerpts(excerpts_to_remove, cx);
+        });
+        self.refresh_selections(cx);
+
+        if self.hunk_count() == 0 {
+            if self.editor.focus_handle(cx).is_focused(cx) {
+                cx.focus(&self.focus_handle);
+            }
+        } else if self.focus_handle.is_focused(cx) {
+            let focus_handle = self.editor.focus_handle(cx);
+            cx.focus(&focus_handle);
+        }
+        cx.emit(EditorEvent::TitleChanged);
+        cx.notify();
+    }
+
+    /// Moves the selections that were in removed excerpts.
+    fn refresh_selections(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |selections| {
+                selections.refresh();
+            });
+        });
+    }
+}
+
+/// The ranges of the buffer's diff hunks, extended by [`CONTEXT_LINE_COUNT`] lines in each
+/// direction, with the overlapping or adjacent ones merged.
+fn hunk_context_ranges(snapshot: &language::BufferSnapshot) -> Vec<Range<Point>> {
+    let max_row = snapshot.max_point().row;
+    let mut ranges = Vec::<Range<Point>>::new();
+    for hunk in snapshot.git_diff_hunks_in_row_range(0..max_row + 1) {
+        let rows = hunk.associated_range;
+        let start_row = rows.start.saturating_sub(CONTEXT_LINE_COUNT);
+        let end_row =
+            (rows.end.saturating_sub(1).max(rows.start) + CONTEXT_LINE_COUNT).min(max_row);
+        let range = Point::new(start_row, 0)..Point::new(end_row, snapshot.line_len(end_row));
+        match ranges.last_mut() {
+            Some(last) if last.end.row + 1 >= range.start.row => {
+                last.end = last.end.max(range.end);
+            }
+            _ => ranges.push(range),
+        }
+    }
+    ranges
+}
+
+impl FocusableView for ProjectDiffEditor {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ProjectDiffEditor {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| editor.deactivated(cx));
+    }
+
+    fn navigate(&mut self, data: Box<dyn Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some("Project Diff".into())
+    }
+
+    fn tab_content(&self, _detail: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        let color = if selected {
+            Color::Default
+        } else {
+            Color::Muted
+        };
+        let changed_files = self
+            .path_states
+            .iter()
+            .filter(|state| state.hunk_count > 0)
+            .count();
+        if changed_files == 0 {
+            Label::new("No changes").color(color).into_any_element()
+        } else {
+            h_flex()
+                .gap_1()
+                .child(Icon::new(IconName::FileGit).color(color))
+                .child(Label::new(changed_files.to_string()).color(color))
+                .into_any_element()
+        }
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("project diff")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        false
+    }
+
+    fn set_nav_history(&mut self, nav_history: ItemNavHistory, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: workspace::WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new_view(|cx| {
+            ProjectDiffEditor::new(self.project.clone(), self.workspace.clone(), cx)
+        }))
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.excerpts.read(cx).is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.excerpts.read(cx).has_conflict(cx)
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.save(format, project, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        _: Model<Project>,
+        _: PathBuf,
+        _: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        unreachable!()
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.editor.reload(project, cx)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn breadcrumb_location(&self) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::actions::{SelectAll, StageSelectedHunks};
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{repository::RepoPath, FakeFs};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+
+    #[gpui::test]
+    async fn test_project_diff(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                ".git": {},
+                "a.txt": "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n",
+                "b.txt": "a\nb\nc\n",
+                "c.txt": "unchanged\n",
+            }),
+        )
+        .await;
+        let dot_git = Path::new("/root/.git");
+        fs.set_index_for_repo(
+            dot_git,
+            &[
+                (
+                    Path::new("a.txt"),
+                    "1\n2\n3\n4\nfive\n6\n7\n8\n9\n10\n".into(),
+                ),
+                (Path::new("b.txt"), "a\nc\n".into()),
+                (Path::new("c.txt"), "unchanged\n".into()),
+            ],
+        );
+        fs.with_git_state(dot_git, true, |state| {
+            state
+                .worktree_statuses
+                .insert(Path::new("a.txt").into(), GitFileStatus::Added);
+            state
+                .unstaged_statuses
+                .insert(Path::new("b.txt").into(), GitFileStatus::Modified);
+        });
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let workspace = window.root(cx).unwrap();
+
+        let project_diff =
+            cx.new_view(|cx| ProjectDiffEditor::new(project.clone(), workspace.downgrade(), cx));
+        cx.run_until_parked();
+        project_diff.update(cx, |project_diff, cx| {
+            assert_eq!(project_diff.hunk_count(), 2);
+            assert_eq!(
+                project_diff.editor.read(cx).text(cx),
+                "3\n4\n5\n6\n7\na\nb\nc\n"
+            );
+        });
+
+        // The added files come after the modified ones when sorting by status.
+        project_diff.update(cx, |project_diff, cx| {
+            project_diff.toggle_sort_order(&ToggleSortOrder, cx);
+            assert_eq!(project_diff.sort_order(), SortOrder::Status);
+            assert_eq!(
+                project_diff.editor.read(cx).text(cx),
+                "a\nb\nc\n\n3\n4\n5\n6\n7"
+            );
+            project_diff.toggle_sort_order(&ToggleSortOrder, cx);
+        });
+
+        // The excerpts follow the edits of the files.
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/a.txt", cx)
+            })
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(Point::new(0, 0)..Point::new(0, 1), "one")], None, cx)
+        });
+        cx.run_until_parked();
+        project_diff.update(cx, |project_diff, cx| {
+            assert_eq!(project_diff.hunk_count(), 3);
+            assert_eq!(
+                project_diff.editor.read(cx).text(cx),
+                "one\n2\n3\n4\n5\n6\n7\na\nb\nc\n"
+            );
+        });
+
+        // The staged hunks are not shown anymore.
+        project_diff.update(cx, |project_diff, cx| {
+            project_diff.editor.update(cx, |editor, cx| {
+                editor.select_all(&SelectAll, cx);
+                editor.stage_selected_hunks(&StageSelectedHunks, cx);
+            });
+        });
+        cx.run_until_parked();
+        project_diff.update(cx, |project_diff, cx| {
+            assert_eq!(project_diff.hunk_count(), 0);
+            assert_eq!(project_diff.editor.read(cx).text(cx), "");
+        });
+    }
+
+    #[gpui::test]
+    async fn test_project_diff_untracked_and_deleted_files(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                ".git": {},
+                "new.txt": "new\n",
+            }),
+        )
+        .await;
+        let dot_git = Path::new("/root/.git");
+        fs.set_index_for_repo(dot_git, &[(Path::new("gone.txt"), "old\n".into())]);
+        fs.with_git_state(dot_git, true, |state| {
+            state
+                .unstaged_statuses
+                .insert(Path::new("gone.txt").into(), GitFileStatus::Modified);
+            state
+                .unstaged_statuses
+                .insert(Path::new("new.txt").into(), GitFileStatus::Added);
+        });
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let workspace = window.root(cx).unwrap();
+
+        let project_diff =
+            cx.new_view(|cx| ProjectDiffEditor::new(project.clone(), workspace.downgrade(), cx));
+        cx.run_until_parked();
+        project_diff.update(cx, |project_diff, cx| {
+            assert_eq!(project_diff.hunk_count(), 2);
+            assert_eq!(project_diff.editor.read(cx).text(cx), "old\n\nnew\n");
+        });
+
+        // The files are forgotten once they don't have a status anymore.
+        fs.with_git_state(dot_git, true, |state| {
+            state
+                .unstaged_statuses
+                .remove(&RepoPath::new("new.txt".into()));
+        });
+        cx.executor().advance_clock(UPDATE_PATHS_DEBOUNCE);
+        cx.run_until_parked();
+        project_diff.update(cx, |project_diff, cx| {
+            assert_eq!(project_diff.hunk_count(), 1);
+            assert_eq!(project_diff.editor.read(cx).text(cx), "old\n");
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+            editor::init(cx);
+        });
+    }
+}
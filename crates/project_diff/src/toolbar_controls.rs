@@ -0,0 +1,110 @@
+use crate::{ProjectDiffEditor, SortOrder};
+use editor::actions::{StageSelectedHunks, UnstageSelectedHunks};
+use gpui::{div, EventEmitter, ParentElement, Render, ViewContext, WeakView};
+use ui::prelude::*;
+use ui::{IconButton, IconName, Tooltip};
+use workspace::{item::ItemHandle, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
+
+pub struct ToolbarControls {
+    project_diff: Option<WeakView<ProjectDiffEditor>>,
+}
+
+impl Render for ToolbarControls {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let sort_order = self
+            .project_diff
+            .as_ref()
+            .and_then(|project_diff| project_diff.upgrade())
+            .map(|project_diff| project_diff.read(cx).sort_order())
+            .unwrap_or_default();
+
+        let sort_tooltip = match sort_order {
+            SortOrder::Path => "Sort by Status",
+            SortOrder::Status => "Sort by Path",
+        };
+
+        h_flex()
+            .gap_1()
+            .child(
+                IconButton::new("stage-hunks", IconName::Plus)
+                    .tooltip(|cx| {
+                        Tooltip::for_action("Stage Selected Hunks", &StageSelectedHunks, cx)
+                    })
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_editor(cx, |editor, cx| {
+                            editor.stage_selected_hunks(&StageSelectedHunks, cx)
+                        });
+                    })),
+            )
+            .child(
+                IconButton::new("unstage-hunks", IconName::Dash)
+                    .tooltip(|cx| {
+                        Tooltip::for_action("Unstage Selected Hunks", &UnstageSelectedHunks, cx)
+                    })
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.update_editor(cx, |editor, cx| {
+                            editor.unstage_selected_hunks(&UnstageSelectedHunks, cx)
+                        });
+                    })),
+            )
+            .child(
+                div().child(
+                    IconButton::new("toggle-sort-order", IconName::Filter)
+                        .tooltip(move |cx| Tooltip::text(sort_tooltip, cx))
+                        .on_click(cx.listener(|this, _, cx| {
+                            if let Some(project_diff) = this
+                                .project_diff
+                                .as_ref()
+                                .and_then(|project_diff| project_diff.upgrade())
+                            {
+                                project_diff.update(cx, |project_diff, cx| {
+                                    project_diff.toggle_sort_order(&Default::default(), cx);
+                                });
+                            }
+                        })),
+                ),
+            )
+    }
+}
+
+impl EventEmitter<ToolbarItemEvent> for ToolbarControls {}
+
+impl ToolbarItemView for ToolbarControls {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        _: &mut ViewContext<Self>,
+    ) -> ToolbarItemLocation {
+        if let Some(pane_item) = active_pane_item.as_ref() {
+            if let Some(project_diff) = pane_item.downcast::<ProjectDiffEditor>() {
+                self.project_diff = Some(project_diff.downgrade());
+                ToolbarItemLocation::PrimaryRight
+            } else {
+                ToolbarItemLocation::Hidden
+            }
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+}
+
+impl ToolbarControls {
+    pub fn new() -> Self {
+        ToolbarControls { project_diff: None }
+    }
+
+    fn update_editor(
+        &self,
+        cx: &mut ViewContext<Self>,
+        f: impl FnOnce(&mut editor::Editor, &mut ViewContext<editor::Editor>),
+    ) {
+        if let Some(project_diff) = self
+            .project_diff
+            .as_ref()
+            .and_then(|project_diff| project_diff.upgrade())
+        {
+            let editor = project_diff.read(cx).editor.clone();
+            editor.update(cx, f);
+        }
+    }
+}
@@ -1,12 +1,13 @@
 mod registrar;
 
 use crate::{
-    history::SearchHistory,
+    history::{SearchHistory, SearchHistoryCursor, SearchHistoryEntry},
     mode::{next_mode, SearchMode},
     search_bar::render_nav_button,
-    ActivateRegexMode, ActivateTextMode, CycleMode, NextHistoryQuery, PreviousHistoryQuery,
-    ReplaceAll, ReplaceNext, SearchOptions, SelectAllMatches, SelectNextMatch, SelectPrevMatch,
-    ToggleCaseSensitive, ToggleReplace, ToggleWholeWord,
+    ActivateFuzzyMode, ActivateRegexMode, ActivateTextMode, CycleMode, NextHistoryQuery,
+    PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions, SelectAllMatches,
+    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleReplace, ToggleSelection,
+    ToggleWholeWord,
 };
 use collections::HashMap;
 use editor::{
@@ -39,6 +40,7 @@ use registrar::{ForDeployed, ForDismissed, SearchActionsRegistrar, WithResults};
 
 const MIN_INPUT_WIDTH_REMS: f32 = 15.;
 const MAX_INPUT_WIDTH_REMS: f32 = 30.;
+const MAX_QUERY_LINES: usize = 8;
 
 #[derive(PartialEq, Clone, Deserialize)]
 pub struct Deploy {
@@ -74,9 +76,10 @@ pub struct BufferSearchBar {
     default_options: SearchOptions,
     query_contains_error: bool,
     dismissed: bool,
-    search_history: SearchHistory,
+    search_history_cursor: SearchHistoryCursor,
     current_mode: SearchMode,
     replace_enabled: bool,
+    selection_search_enabled: bool,
 }
 
 impl BufferSearchBar {
@@ -238,7 +241,23 @@ impl Render for BufferSearchBar {
                             SearchOptions::WHOLE_WORD,
                             cx.listener(|this, _, cx| this.toggle_whole_word(&ToggleWholeWord, cx)),
                         )
-                    })),
+                    }))
+                    .when(supported_options.selection, |this| {
+                        this.child(
+                            IconButton::new(
+                                "buffer-search-bar-toggle-search-selection-button",
+                                IconName::TextSelect,
+                            )
+                            .style(ButtonStyle::Subtle)
+                            .selected(self.selection_search_enabled)
+                            .on_click(cx.listener(|this, _: &ClickEvent, cx| {
+                                this.toggle_selection(&ToggleSelection, cx);
+                            }))
+                            .tooltip(|cx| {
+                                Tooltip::for_action("Toggle Search Selection", &ToggleSelection, cx)
+                            }),
+                        )
+                    }),
             )
             .child(
                 h_flex()
@@ -278,8 +297,36 @@ impl Render for BufferSearchBar {
                                             cx,
                                         )
                                     })
+                                    .map(|this| {
+                                        if supported_options.fuzzy {
+                                            this.middle()
+                                        } else {
+                                            this.last()
+                                        }
+                                    }),
+                            )
+                            .when(supported_options.fuzzy, |this| {
+                                this.child(
+                                    ToggleButton::new(
+                                        "search-mode-fuzzy",
+                                        SearchMode::Fuzzy.label(),
+                                    )
+                                    .style(ButtonStyle::Filled)
+                                    .size(ButtonSize::Large)
+                                    .selected(self.current_mode == SearchMode::Fuzzy)
+                                    .on_click(cx.listener(move |_, _event, cx| {
+                                        cx.dispatch_action(SearchMode::Fuzzy.action())
+                                    }))
+                                    .tooltip(|cx| {
+                                        Tooltip::for_action(
+                                            SearchMode::Fuzzy.tooltip(),
+                                            &*SearchMode::Fuzzy.action(),
+                                            cx,
+                                        )
+                                    })
                                     .last(),
-                            ),
+                                )
+                            }),
                     )
                     .when(supported_options.replacement, |this| {
                         this.child(
@@ -388,6 +435,11 @@ impl Render for BufferSearchBar {
             .on_action(cx.listener(|this, _: &ActivateTextMode, cx| {
                 this.activate_search_mode(SearchMode::Text, cx);
             }))
+            .when(self.supported_options().fuzzy, |this| {
+                this.on_action(cx.listener(|this, _: &ActivateFuzzyMode, cx| {
+                    this.activate_search_mode(SearchMode::Fuzzy, cx);
+                }))
+            })
             .when(self.supported_options().replacement, |this| {
                 this.on_action(cx.listener(Self::toggle_replace))
                     .when(in_replace, |this| {
@@ -401,6 +453,9 @@ impl Render for BufferSearchBar {
             .when(self.supported_options().word, |this| {
                 this.on_action(cx.listener(Self::toggle_whole_word))
             })
+            .when(self.supported_options().selection, |this| {
+                this.on_action(cx.listener(Self::toggle_selection))
+            })
             .gap_2()
             .child(
                 h_flex().child(search_line.w_full()).child(
@@ -429,7 +484,12 @@ impl ToolbarItemView for BufferSearchBar {
     ) -> ToolbarItemLocation {
         cx.notify();
         self.active_searchable_item_subscription.take();
-        self.active_searchable_item.take();
+        if let Some(previous_item) = self.active_searchable_item.take() {
+            if self.selection_search_enabled {
+                previous_item.toggle_filtered_search_ranges(false, cx);
+            }
+        }
+        self.selection_search_enabled = false;
 
         self.pending_search.take();
 
@@ -486,9 +546,19 @@ impl BufferSearchBar {
                 this.activate_search_mode(SearchMode::Regex, cx);
             }
         }));
+        registrar.register_handler(ForDeployed(|this, action: &ToggleSelection, cx| {
+            if this.supported_options().selection {
+                this.toggle_selection(action, cx);
+            }
+        }));
         registrar.register_handler(ForDeployed(|this, _: &ActivateTextMode, cx| {
             this.activate_search_mode(SearchMode::Text, cx);
         }));
+        registrar.register_handler(ForDeployed(|this, _: &ActivateFuzzyMode, cx| {
+            if this.supported_options().fuzzy {
+                this.activate_search_mode(SearchMode::Fuzzy, cx);
+            }
+        }));
         registrar.register_handler(ForDeployed(|this, action: &CycleMode, cx| {
             if this.supported_options().regex {
                 // If regex is not supported then search has just one mode (text) - in that case there's no point in supporting
@@ -520,7 +590,7 @@ impl BufferSearchBar {
     }
 
     pub fn new(cx: &mut ViewContext<Self>) -> Self {
-        let query_editor = cx.new_view(|cx| Editor::single_line(cx));
+        let query_editor = cx.new_view(|cx| Editor::auto_height(MAX_QUERY_LINES, cx));
         cx.subscribe(&query_editor, Self::on_query_editor_event)
             .detach();
         let replacement_editor = cx.new_view(|cx| Editor::single_line(cx));
@@ -540,10 +610,11 @@ impl BufferSearchBar {
             pending_search: None,
             query_contains_error: false,
             dismissed: true,
-            search_history: SearchHistory::default(),
+            search_history_cursor: SearchHistoryCursor::default(),
             current_mode: SearchMode::default(),
             active_search: None,
             replace_enabled: false,
+            selection_search_enabled: false,
         }
     }
 
@@ -561,9 +632,13 @@ impl BufferSearchBar {
             }
         }
         if let Some(active_editor) = self.active_searchable_item.as_ref() {
+            if self.selection_search_enabled {
+                active_editor.toggle_filtered_search_ranges(false, cx);
+            }
             let handle = active_editor.focus_handle(cx);
             cx.focus(&handle);
         }
+        self.selection_search_enabled = false;
         cx.emit(Event::UpdateLocation);
         cx.emit(ToolbarItemEvent::ChangeLocation(
             ToolbarItemLocation::Hidden,
@@ -846,6 +921,14 @@ impl BufferSearchBar {
     fn toggle_whole_word(&mut self, _: &ToggleWholeWord, cx: &mut ViewContext<Self>) {
         self.toggle_search_option(SearchOptions::WHOLE_WORD, cx)
     }
+    fn toggle_selection(&mut self, _: &ToggleSelection, cx: &mut ViewContext<Self>) {
+        if let Some(active_item) = self.active_searchable_item.as_ref() {
+            self.selection_search_enabled = !self.selection_search_enabled;
+            active_item.toggle_filtered_search_ranges(self.selection_search_enabled, cx);
+            let _ = self.update_matches(cx);
+            cx.notify();
+        }
+    }
 
     fn clear_active_searchable_item_matches(&mut self, cx: &mut WindowContext) {
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
@@ -886,7 +969,17 @@ impl BufferSearchBar {
                 let _ = done_tx.send(());
                 cx.notify();
             } else {
-                let query: Arc<_> = if self.current_mode == SearchMode::Regex {
+                let query: Arc<_> = if self.current_mode == SearchMode::Fuzzy {
+                    match SearchQuery::fuzzy(query, false, Vec::new(), Vec::new()) {
+                        Ok(query) => query,
+                        Err(_) => {
+                            self.query_contains_error = true;
+                            self.clear_active_searchable_item_matches(cx);
+                            cx.notify();
+                            return done_rx;
+                        }
+                    }
+                } else if self.current_mode == SearchMode::Regex {
                     match SearchQuery::regex(
                         query,
                         self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -923,7 +1016,11 @@ impl BufferSearchBar {
                 }
                 .into();
                 self.active_search = Some(query.clone());
-                let query_text = query.as_str().to_string();
+                let history_entry = SearchHistoryEntry::new(
+                    query.as_str().to_string(),
+                    self.current_mode,
+                    self.search_options,
+                );
 
                 let matches = active_searchable_item.find_matches(query, cx);
 
@@ -935,11 +1032,21 @@ impl BufferSearchBar {
                         if let Some(active_searchable_item) =
                             WeakSearchableItemHandle::upgrade(active_searchable_item.as_ref(), cx)
                         {
+                            let has_matches = !matches.is_empty();
                             this.searchable_items_with_matches
                                 .insert(active_searchable_item.downgrade(), matches);
 
-                            this.update_match_index(cx);
-                            this.search_history.add(query_text);
+                            // Fuzzy matches come best first, and navigating them starts at the best one.
+                            if this.current_mode == SearchMode::Fuzzy {
+                                this.active_match_index = has_matches.then_some(0);
+                            } else {
+                                this.update_match_index(cx);
+                            }
+                            SearchHistory::record(
+                                &mut this.search_history_cursor,
+                                history_entry,
+                                cx,
+                            );
                             if !this.dismissed {
                                 let matches = this
                                     .searchable_items_with_matches
@@ -1001,28 +1108,41 @@ impl BufferSearchBar {
     }
 
     fn next_history_query(&mut self, _: &NextHistoryQuery, cx: &mut ViewContext<Self>) {
-        if let Some(new_query) = self.search_history.next().map(str::to_string) {
+        if let Some(new_query) = SearchHistory::global(cx)
+            .next(&mut self.search_history_cursor)
+            .map(|entry| entry.query.clone())
+        {
             let _ = self.search(&new_query, Some(self.search_options), cx);
         } else {
-            self.search_history.reset_selection();
+            self.search_history_cursor.reset();
             let _ = self.search("", Some(self.search_options), cx);
         }
     }
 
     fn previous_history_query(&mut self, _: &PreviousHistoryQuery, cx: &mut ViewContext<Self>) {
         if self.query(cx).is_empty() {
-            if let Some(new_query) = self.search_history.current().map(str::to_string) {
+            if let Some(new_query) = SearchHistory::global(cx)
+                .current(&self.search_history_cursor)
+                .map(|entry| entry.query.clone())
+            {
                 let _ = self.search(&new_query, Some(self.search_options), cx);
                 return;
             }
         }
 
-        if let Some(new_query) = self.search_history.previous().map(str::to_string) {
+        if let Some(new_query) = SearchHistory::global(cx)
+            .previous(&mut self.search_history_cursor)
+            .map(|entry| entry.query.clone())
+        {
             let _ = self.search(&new_query, Some(self.search_options), cx);
         }
     }
     fn cycle_mode(&mut self, _: &CycleMode, cx: &mut ViewContext<Self>) {
-        self.activate_search_mode(next_mode(&self.current_mode, false), cx);
+        let mut available_modes = vec![SearchMode::Text, SearchMode::Regex];
+        if self.supported_options().fuzzy {
+            available_modes.push(SearchMode::Fuzzy);
+        }
+        self.activate_search_mode(next_mode(&self.current_mode, &available_modes), cx);
     }
     fn toggle_replace(&mut self, _: &ToggleReplace, cx: &mut ViewContext<Self>) {
         if let Some(_) = &self.active_searchable_item {
@@ -1923,6 +2043,144 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_search_multiline_query(cx: &mut TestAppContext) {
+        let (editor, search_bar, cx) = init_test(cx);
+
+        search_bar
+            .update(cx, |search_bar, cx| {
+                search_bar.search("referred to as\nrational", None, cx)
+            })
+            .await
+            .unwrap();
+        editor.update(cx, |editor, cx| {
+            assert_eq!(
+                editor
+                    .all_text_background_highlights(cx)
+                    .into_iter()
+                    .map(|(range, _)| range)
+                    .collect::<Vec<_>>(),
+                &[DisplayPoint::new(0, 60)..DisplayPoint::new(1, 8)]
+            );
+        });
+
+        search_bar
+            .update(cx, |search_bar, cx| {
+                search_bar.activate_search_mode(SearchMode::Regex, cx);
+                search_bar.search(r"as\n\w+", None, cx)
+            })
+            .await
+            .unwrap();
+        editor.update(cx, |editor, cx| {
+            assert_eq!(
+                editor
+                    .all_text_background_highlights(cx)
+                    .into_iter()
+                    .map(|(range, _)| range)
+                    .collect::<Vec<_>>(),
+                &[DisplayPoint::new(0, 72)..DisplayPoint::new(1, 8)]
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_search_fuzzy(cx: &mut TestAppContext) {
+        let (editor, search_bar, cx) = init_test(cx);
+
+        search_bar
+            .update(cx, |search_bar, cx| {
+                search_bar.activate_search_mode(SearchMode::Fuzzy, cx);
+                search_bar.search("srchalg", None, cx)
+            })
+            .await
+            .unwrap();
+        editor.update(cx, |editor, cx| {
+            let highlights = editor.all_text_background_highlights(cx);
+            assert_eq!(highlights.len(), 1);
+            let (range, _) = &highlights[0];
+            assert_eq!(range.start.row(), 2);
+            assert_eq!(range.end.row(), 2);
+        });
+
+        search_bar
+            .update(cx, |search_bar, cx| search_bar.search("xyzzy", None, cx))
+            .await
+            .unwrap();
+        search_bar.update(cx, |search_bar, _| {
+            assert_eq!(search_bar.active_match_index, None);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_search_within_selection(cx: &mut TestAppContext) {
+        let (editor, search_bar, cx) = init_test(cx);
+
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_display_ranges([DisplayPoint::new(1, 0)..DisplayPoint::new(2, 0)])
+            });
+        });
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.toggle_selection(&ToggleSelection, cx);
+        });
+        search_bar
+            .update(cx, |search_bar, cx| {
+                search_bar.search("expression", None, cx)
+            })
+            .await
+            .unwrap();
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.select_all_matches(&SelectAllMatches, cx);
+        });
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.selections.display_ranges(cx)),
+            [DisplayPoint::new(1, 9)..DisplayPoint::new(1, 19)]
+        );
+
+        // The search stays within the selected text when edits move it around.
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_display_ranges([DisplayPoint::new(0, 0)..DisplayPoint::new(0, 0)])
+            });
+            editor.insert("expression\n", cx);
+        });
+        search_bar
+            .update(cx, |search_bar, cx| {
+                search_bar.search("expression", None, cx)
+            })
+            .await
+            .unwrap();
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.select_all_matches(&SelectAllMatches, cx);
+        });
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.selections.display_ranges(cx)),
+            [DisplayPoint::new(2, 9)..DisplayPoint::new(2, 19)]
+        );
+
+        // Turning the option off searches the whole buffer again.
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.toggle_selection(&ToggleSelection, cx);
+        });
+        search_bar
+            .update(cx, |search_bar, cx| {
+                search_bar.search("expression", None, cx)
+            })
+            .await
+            .unwrap();
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.select_all_matches(&SelectAllMatches, cx);
+        });
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.selections.display_ranges(cx)),
+            [
+                DisplayPoint::new(0, 0)..DisplayPoint::new(0, 10),
+                DisplayPoint::new(1, 10)..DisplayPoint::new(1, 20),
+                DisplayPoint::new(2, 9)..DisplayPoint::new(2, 19),
+            ]
+        );
+    }
+
     #[gpui::test]
     async fn test_invalid_regexp_search_after_valid(cx: &mut TestAppContext) {
         let (editor, search_bar, cx) = init_test(cx);
@@ -1,77 +1,237 @@
+use crate::{SearchMode, SearchOptions};
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{AppContext, Global};
+use project::search::SearchInputs;
+use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
+use util::{paths::PathMatcher, ResultExt};
+
 const SEARCH_HISTORY_LIMIT: usize = 20;
+const SEARCH_HISTORY_KEY: &str = "search_history";
+
+/// A search that was run, with everything needed to run it again.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SearchHistoryEntry {
+    pub query: String,
+    #[serde(default)]
+    pub mode: SearchMode,
+    #[serde(default, with = "serialized_options")]
+    pub options: SearchOptions,
+    #[serde(default)]
+    pub files_to_include: String,
+    #[serde(default)]
+    pub files_to_exclude: String,
+}
+
+impl SearchHistoryEntry {
+    pub fn new(query: String, mode: SearchMode, options: SearchOptions) -> Self {
+        Self {
+            query,
+            mode,
+            options,
+            files_to_include: String::new(),
+            files_to_exclude: String::new(),
+        }
+    }
+
+    pub fn for_inputs(inputs: &SearchInputs, mode: SearchMode, options: SearchOptions) -> Self {
+        fn join(path_matchers: &[PathMatcher]) -> String {
+            path_matchers
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        Self {
+            files_to_include: join(inputs.files_to_include()),
+            files_to_exclude: join(inputs.files_to_exclude()),
+            ..Self::new(inputs.as_str().to_string(), mode, options)
+        }
+    }
+}
+
+mod serialized_options {
+    use crate::SearchOptions;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+    pub fn serialize<S: Serializer>(
+        options: &SearchOptions,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        options.bits().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<SearchOptions, D::Error> {
+        Ok(SearchOptions::from_bits_truncate(u8::deserialize(
+            deserializer,
+        )?))
+    }
+}
+
+/// The searches run in all the search bars, most recent last, persisted across restarts.
 #[derive(Default, Debug, Clone)]
 pub struct SearchHistory {
-    history: SmallVec<[String; SEARCH_HISTORY_LIMIT]>,
+    /// The entries along with their ids, which stay the same when older entries are evicted.
+    history: SmallVec<[(usize, SearchHistoryEntry); SEARCH_HISTORY_LIMIT]>,
+    next_entry_id: usize,
+    /// Whether the persisted history was merged in, before which it must not be overwritten.
+    loaded: bool,
+}
+
+impl Global for SearchHistory {}
+
+/// The position of a single search bar in the shared [`SearchHistory`], moved by browsing through it.
+#[derive(Default, Debug, Clone)]
+pub struct SearchHistoryCursor {
+    /// The id of the selected entry.
     selected: Option<usize>,
 }
 
+impl SearchHistoryCursor {
+    pub fn reset(&mut self) {
+        self.selected = None;
+    }
+}
+
 impl SearchHistory {
-    pub fn add(&mut self, search_string: String) {
-        if let Some(i) = self.selected {
-            if search_string == self.history[i] {
+    pub(crate) fn init(cx: &mut AppContext) {
+        cx.set_global(Self::default());
+        cx.spawn(|mut cx| async move {
+            let persisted = cx
+                .background_executor()
+                .spawn(async move {
+                    let persisted = KEY_VALUE_STORE.read_kvp(SEARCH_HISTORY_KEY)?;
+                    let persisted = persisted
+                        .map(|persisted| {
+                            serde_json::from_str::<Vec<SearchHistoryEntry>>(&persisted)
+                        })
+                        .transpose()?;
+                    anyhow::Ok(persisted)
+                })
+                .await
+                .log_err()
+                .flatten()
+                .unwrap_or_default();
+            cx.update_global(|history: &mut Self, cx| {
+                let recorded_before_load = !history.history.is_empty();
+                history.merge_persisted(persisted);
+                history.loaded = true;
+                if recorded_before_load {
+                    history.persist(cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Puts the persisted entries before the ones recorded since startup, keeping the ids of the
+    /// latter so that the search bars' cursors still point at them.
+    fn merge_persisted(&mut self, persisted: Vec<SearchHistoryEntry>) {
+        let mut entries = Vec::with_capacity(persisted.len() + self.history.len());
+        for entry in persisted {
+            entries.push((self.next_entry_id, entry));
+            self.next_entry_id += 1;
+        }
+        entries.extend(self.history.drain(..));
+        let excess = entries.len().saturating_sub(SEARCH_HISTORY_LIMIT);
+        self.history = entries.into_iter().skip(excess).collect();
+    }
+
+    fn persist(&self, cx: &AppContext) {
+        if !self.loaded {
+            return;
+        }
+        let entries = self
+            .history
+            .iter()
+            .map(|(_, entry)| entry)
+            .collect::<Vec<_>>();
+        let Some(serialized) = serde_json::to_string(&entries).log_err() else {
+            return;
+        };
+        cx.background_executor()
+            .spawn(async move {
+                KEY_VALUE_STORE
+                    .write_kvp(SEARCH_HISTORY_KEY.into(), serialized)
+                    .await
+            })
+            .detach_and_log_err(cx);
+    }
+
+    /// Adds the search to the history shared by all search bars, and persists the history once
+    /// the persisted one was merged in.
+    pub(crate) fn record(
+        cursor: &mut SearchHistoryCursor,
+        entry: SearchHistoryEntry,
+        cx: &mut AppContext,
+    ) {
+        cx.default_global::<Self>().add(cursor, entry);
+        cx.global::<Self>().persist(cx);
+    }
+
+    pub(crate) fn global(cx: &mut AppContext) -> &mut Self {
+        cx.default_global::<Self>()
+    }
+
+    pub fn add(&mut self, cursor: &mut SearchHistoryCursor, entry: SearchHistoryEntry) {
+        if let Some(ix) = self.selected_ix(cursor) {
+            let selected = &mut self.history[ix].1;
+            if entry.query == selected.query {
+                *selected = entry;
                 return;
             }
         }
 
-        if let Some(previously_searched) = self.history.last_mut() {
-            if search_string.contains(previously_searched.as_str()) {
-                *previously_searched = search_string;
-                self.selected = Some(self.history.len() - 1);
+        if let Some((id, previously_searched)) = self.history.last_mut() {
+            if entry.query.contains(previously_searched.query.as_str()) {
+                *previously_searched = entry;
+                cursor.selected = Some(*id);
                 return;
             }
         }
 
-        self.history.push(search_string);
+        let id = self.next_entry_id;
+        self.next_entry_id += 1;
+        self.history.push((id, entry));
         if self.history.len() > SEARCH_HISTORY_LIMIT {
             self.history.remove(0);
         }
-        self.selected = Some(self.history.len() - 1);
+        cursor.selected = Some(id);
     }
 
-    pub fn next(&mut self) -> Option<&str> {
-        let history_size = self.history.len();
-        if history_size == 0 {
-            return None;
-        }
-
-        let selected = self.selected?;
-        if selected == history_size - 1 {
-            return None;
-        }
-        let next_index = selected + 1;
-        self.selected = Some(next_index);
-        Some(&self.history[next_index])
+    pub fn next(&self, cursor: &mut SearchHistoryCursor) -> Option<&SearchHistoryEntry> {
+        let selected = cursor.selected?;
+        // An evicted entry was older than all the remaining ones.
+        let next_ix = self
+            .history
+            .iter()
+            .position(|(id, _)| *id == selected)
+            .map_or(0, |ix| ix + 1);
+        let (id, entry) = self.history.get(next_ix)?;
+        cursor.selected = Some(*id);
+        Some(entry)
     }
 
-    pub fn current(&self) -> Option<&str> {
-        Some(&self.history[self.selected?])
+    pub fn current(&self, cursor: &SearchHistoryCursor) -> Option<&SearchHistoryEntry> {
+        Some(&self.history[self.selected_ix(cursor)?].1)
     }
 
-    pub fn previous(&mut self) -> Option<&str> {
-        let history_size = self.history.len();
-        if history_size == 0 {
-            return None;
-        }
-
-        let prev_index = match self.selected {
-            Some(selected_index) => {
-                if selected_index == 0 {
-                    return None;
-                } else {
-                    selected_index - 1
-                }
-            }
-            None => history_size - 1,
+    pub fn previous(&self, cursor: &mut SearchHistoryCursor) -> Option<&SearchHistoryEntry> {
+        let prev_ix = match cursor.selected {
+            Some(_) => self.selected_ix(cursor)?.checked_sub(1)?,
+            None => self.history.len().checked_sub(1)?,
         };
-
-        self.selected = Some(prev_index);
-        Some(&self.history[prev_index])
+        let (id, entry) = &self.history[prev_ix];
+        cursor.selected = Some(*id);
+        Some(entry)
     }
 
-    pub fn reset_selection(&mut self) {
-        self.selected = None;
+    fn selected_ix(&self, cursor: &SearchHistoryCursor) -> Option<usize> {
+        let selected = cursor.selected?;
+        self.history.iter().position(|(id, _)| *id == selected)
     }
 }
 
@@ -79,43 +239,52 @@ impl SearchHistory {
 mod tests {
     use super::*;
 
+    fn entry(query: &str) -> SearchHistoryEntry {
+        SearchHistoryEntry::new(query.to_string(), SearchMode::Text, SearchOptions::NONE)
+    }
+
+    fn query(entry: Option<&SearchHistoryEntry>) -> Option<&str> {
+        entry.map(|entry| entry.query.as_str())
+    }
+
     #[test]
     fn test_add() {
         let mut search_history = SearchHistory::default();
+        let mut cursor = SearchHistoryCursor::default();
         assert_eq!(
-            search_history.current(),
+            query(search_history.current(&cursor)),
             None,
             "No current selection should be set for the default search history"
         );
 
-        search_history.add("rust".to_string());
+        search_history.add(&mut cursor, entry("rust"));
         assert_eq!(
-            search_history.current(),
+            query(search_history.current(&cursor)),
             Some("rust"),
             "Newly added item should be selected"
         );
 
         // check if duplicates are not added
-        search_history.add("rust".to_string());
+        search_history.add(&mut cursor, entry("rust"));
         assert_eq!(
             search_history.history.len(),
             1,
             "Should not add a duplicate"
         );
-        assert_eq!(search_history.current(), Some("rust"));
+        assert_eq!(query(search_history.current(&cursor)), Some("rust"));
 
         // check if new string containing the previous string replaces it
-        search_history.add("rustlang".to_string());
+        search_history.add(&mut cursor, entry("rustlang"));
         assert_eq!(
             search_history.history.len(),
             1,
             "Should replace previous item if it's a substring"
         );
-        assert_eq!(search_history.current(), Some("rustlang"));
+        assert_eq!(query(search_history.current(&cursor)), Some("rustlang"));
 
         // push enough items to test SEARCH_HISTORY_LIMIT
         for i in 0..SEARCH_HISTORY_LIMIT * 2 {
-            search_history.add(format!("item{i}"));
+            search_history.add(&mut cursor, entry(&format!("item{i}")));
         }
         assert!(search_history.history.len() <= SEARCH_HISTORY_LIMIT);
     }
@@ -123,62 +292,173 @@ mod tests {
     #[test]
     fn test_next_and_previous() {
         let mut search_history = SearchHistory::default();
+        let mut cursor = SearchHistoryCursor::default();
         assert_eq!(
-            search_history.next(),
+            query(search_history.next(&mut cursor)),
             None,
             "Default search history should not have a next item"
         );
 
-        search_history.add("Rust".to_string());
-        assert_eq!(search_history.next(), None);
-        search_history.add("JavaScript".to_string());
-        assert_eq!(search_history.next(), None);
-        search_history.add("TypeScript".to_string());
-        assert_eq!(search_history.next(), None);
+        search_history.add(&mut cursor, entry("Rust"));
+        assert_eq!(query(search_history.next(&mut cursor)), None);
+        search_history.add(&mut cursor, entry("JavaScript"));
+        assert_eq!(query(search_history.next(&mut cursor)), None);
+        search_history.add(&mut cursor, entry("TypeScript"));
+        assert_eq!(query(search_history.next(&mut cursor)), None);
 
-        assert_eq!(search_history.current(), Some("TypeScript"));
+        assert_eq!(query(search_history.current(&cursor)), Some("TypeScript"));
 
-        assert_eq!(search_history.previous(), Some("JavaScript"));
-        assert_eq!(search_history.current(), Some("JavaScript"));
+        assert_eq!(
+            query(search_history.previous(&mut cursor)),
+            Some("JavaScript")
+        );
+        assert_eq!(query(search_history.current(&cursor)), Some("JavaScript"));
 
-        assert_eq!(search_history.previous(), Some("Rust"));
-        assert_eq!(search_history.current(), Some("Rust"));
+        assert_eq!(query(search_history.previous(&mut cursor)), Some("Rust"));
+        assert_eq!(query(search_history.current(&cursor)), Some("Rust"));
 
-        assert_eq!(search_history.previous(), None);
-        assert_eq!(search_history.current(), Some("Rust"));
+        assert_eq!(query(search_history.previous(&mut cursor)), None);
+        assert_eq!(query(search_history.current(&cursor)), Some("Rust"));
 
-        assert_eq!(search_history.next(), Some("JavaScript"));
-        assert_eq!(search_history.current(), Some("JavaScript"));
+        assert_eq!(query(search_history.next(&mut cursor)), Some("JavaScript"));
+        assert_eq!(query(search_history.current(&cursor)), Some("JavaScript"));
 
-        assert_eq!(search_history.next(), Some("TypeScript"));
-        assert_eq!(search_history.current(), Some("TypeScript"));
+        assert_eq!(query(search_history.next(&mut cursor)), Some("TypeScript"));
+        assert_eq!(query(search_history.current(&cursor)), Some("TypeScript"));
 
-        assert_eq!(search_history.next(), None);
-        assert_eq!(search_history.current(), Some("TypeScript"));
+        assert_eq!(query(search_history.next(&mut cursor)), None);
+        assert_eq!(query(search_history.current(&cursor)), Some("TypeScript"));
     }
 
     #[test]
     fn test_reset_selection() {
         let mut search_history = SearchHistory::default();
-        search_history.add("Rust".to_string());
-        search_history.add("JavaScript".to_string());
-        search_history.add("TypeScript".to_string());
+        let mut cursor = SearchHistoryCursor::default();
+        search_history.add(&mut cursor, entry("Rust"));
+        search_history.add(&mut cursor, entry("JavaScript"));
+        search_history.add(&mut cursor, entry("TypeScript"));
 
-        assert_eq!(search_history.current(), Some("TypeScript"));
-        search_history.reset_selection();
-        assert_eq!(search_history.current(), None);
+        assert_eq!(query(search_history.current(&cursor)), Some("TypeScript"));
+        cursor.reset();
+        assert_eq!(query(search_history.current(&cursor)), None);
         assert_eq!(
-            search_history.previous(),
+            query(search_history.previous(&mut cursor)),
             Some("TypeScript"),
             "Should start from the end after reset on previous item query"
         );
 
-        search_history.previous();
-        assert_eq!(search_history.current(), Some("JavaScript"));
-        search_history.previous();
-        assert_eq!(search_history.current(), Some("Rust"));
+        search_history.previous(&mut cursor);
+        assert_eq!(query(search_history.current(&cursor)), Some("JavaScript"));
+        search_history.previous(&mut cursor);
+        assert_eq!(query(search_history.current(&cursor)), Some("Rust"));
+
+        cursor.reset();
+        assert_eq!(query(search_history.current(&cursor)), None);
+    }
+
+    #[test]
+    fn test_shared_history() {
+        let mut search_history = SearchHistory::default();
+        let mut buffer_search_cursor = SearchHistoryCursor::default();
+        let mut project_search_cursor = SearchHistoryCursor::default();
+
+        search_history.add(&mut buffer_search_cursor, entry("Rust"));
+        search_history.add(
+            &mut project_search_cursor,
+            SearchHistoryEntry {
+                files_to_include: "crates/**/*.rs".to_string(),
+                ..SearchHistoryEntry::new(
+                    "fn \\w+".to_string(),
+                    SearchMode::Regex,
+                    SearchOptions::CASE_SENSITIVE | SearchOptions::WHOLE_WORD,
+                )
+            },
+        );
+        assert_eq!(
+            query(search_history.previous(&mut buffer_search_cursor)),
+            None,
+            "Each search bar should browse the history on its own"
+        );
+        assert_eq!(
+            query(search_history.previous(&mut project_search_cursor)),
+            Some("Rust"),
+            "Searches from all search bars should be in the history"
+        );
+
+        let entries = search_history
+            .history
+            .iter()
+            .map(|(_, entry)| entry.clone())
+            .collect::<Vec<_>>();
+        let serialized = serde_json::to_string(&entries).unwrap();
+        let deserialized: Vec<SearchHistoryEntry> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, entries);
+        assert_eq!(deserialized[1].mode, SearchMode::Regex);
+        assert_eq!(
+            deserialized[1].options,
+            SearchOptions::CASE_SENSITIVE | SearchOptions::WHOLE_WORD
+        );
+    }
+
+    #[test]
+    fn test_cursors_survive_eviction() {
+        let mut search_history = SearchHistory::default();
+        let mut buffer_search_cursor = SearchHistoryCursor::default();
+        let mut project_search_cursor = SearchHistoryCursor::default();
 
-        search_history.reset_selection();
-        assert_eq!(search_history.current(), None);
+        search_history.add(&mut buffer_search_cursor, entry("Rust"));
+        search_history.add(&mut project_search_cursor, entry("JavaScript"));
+        assert_eq!(
+            query(search_history.current(&buffer_search_cursor)),
+            Some("Rust")
+        );
+
+        for i in 0..SEARCH_HISTORY_LIMIT - 2 {
+            search_history.add(&mut project_search_cursor, entry(&format!("item{i}")));
+        }
+        assert_eq!(
+            query(search_history.current(&buffer_search_cursor)),
+            Some("Rust")
+        );
+
+        // Evicting an entry doesn't move the cursors pointing at other entries.
+        search_history.add(&mut project_search_cursor, entry("TypeScript"));
+        assert_eq!(query(search_history.current(&buffer_search_cursor)), None);
+        assert_eq!(
+            query(search_history.previous(&mut project_search_cursor)),
+            Some(format!("item{}", SEARCH_HISTORY_LIMIT - 3).as_str())
+        );
+        assert_eq!(
+            query(search_history.next(&mut buffer_search_cursor)),
+            Some("JavaScript"),
+            "A cursor on an evicted entry should continue from the oldest remaining one"
+        );
+        assert_eq!(
+            query(search_history.previous(&mut buffer_search_cursor)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_merge_persisted() {
+        let mut search_history = SearchHistory::default();
+        let mut cursor = SearchHistoryCursor::default();
+        search_history.add(&mut cursor, entry("Rust"));
+
+        search_history.merge_persisted(vec![entry("JavaScript"), entry("TypeScript")]);
+        assert_eq!(
+            query(search_history.current(&cursor)),
+            Some("Rust"),
+            "Merging the persisted history should keep the cursors on their entries"
+        );
+        assert_eq!(
+            query(search_history.previous(&mut cursor)),
+            Some("TypeScript")
+        );
+        assert_eq!(
+            query(search_history.previous(&mut cursor)),
+            Some("JavaScript")
+        );
+        assert_eq!(query(search_history.previous(&mut cursor)), None);
     }
 }
@@ -1,14 +1,21 @@
 use gpui::{Action, SharedString};
+use serde::{Deserialize, Serialize};
 
-use crate::{ActivateRegexMode, ActivateSemanticMode, ActivateTextMode};
+use crate::{
+    ActivateFuzzyMode, ActivateRegexMode, ActivateSemanticMode, ActivateStructuralMode,
+    ActivateTextMode,
+};
 
 // TODO: Update the default search mode to get from config
-#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SearchMode {
     #[default]
     Text,
     Semantic,
     Regex,
+    Structural,
+    Fuzzy,
 }
 
 impl SearchMode {
@@ -17,6 +24,8 @@ impl SearchMode {
             SearchMode::Text => "Text",
             SearchMode::Semantic => "Semantic",
             SearchMode::Regex => "Regex",
+            SearchMode::Structural => "Structural",
+            SearchMode::Fuzzy => "Fuzzy",
         }
     }
     pub(crate) fn tooltip(&self) -> SharedString {
@@ -27,20 +36,19 @@ impl SearchMode {
             SearchMode::Text => ActivateTextMode.boxed_clone(),
             SearchMode::Semantic => ActivateSemanticMode.boxed_clone(),
             SearchMode::Regex => ActivateRegexMode.boxed_clone(),
+            SearchMode::Structural => ActivateStructuralMode.boxed_clone(),
+            SearchMode::Fuzzy => ActivateFuzzyMode.boxed_clone(),
         }
     }
 }
 
-pub(crate) fn next_mode(mode: &SearchMode, semantic_enabled: bool) -> SearchMode {
-    match mode {
-        SearchMode::Text => SearchMode::Regex,
-        SearchMode::Regex => {
-            if semantic_enabled {
-                SearchMode::Semantic
-            } else {
-                SearchMode::Text
-            }
-        }
-        SearchMode::Semantic => SearchMode::Text,
-    }
+/// The mode following the given one among the modes available to the search, wrapping around.
+pub(crate) fn next_mode(mode: &SearchMode, available_modes: &[SearchMode]) -> SearchMode {
+    available_modes
+        .iter()
+        .position(|available_mode| available_mode == mode)
+        .and_then(|ix| available_modes.get(ix + 1))
+        .or_else(|| available_modes.first())
+        .copied()
+        .unwrap_or_default()
 }
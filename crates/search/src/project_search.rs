@@ -1,8 +1,10 @@
 use crate::{
-    history::SearchHistory, mode::SearchMode, ActivateRegexMode, ActivateSemanticMode,
-    ActivateTextMode, CycleMode, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext,
-    SearchOptions, SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
-    ToggleReplace, ToggleWholeWord,
+    history::{SearchHistory, SearchHistoryCursor, SearchHistoryEntry},
+    mode::SearchMode,
+    ActivateRegexMode, ActivateSemanticMode, ActivateStructuralMode, ActivateTextMode, CycleMode,
+    NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
+    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
+    TogglePreserveCase, ToggleReplace, ToggleWholeWord,
 };
 use anyhow::{Context as _, Result};
 use collections::HashMap;
@@ -20,6 +22,7 @@ use gpui::{
     PromptLevel, Render, SharedString, Styled, Subscription, Task, TextStyle, View, ViewContext,
     VisualContext, WeakModel, WeakView, WhiteSpace, WindowContext,
 };
+use language::{Buffer, OffsetRangeExt as _};
 use menu::Confirm;
 use project::{
     search::{SearchInputs, SearchQuery},
@@ -57,7 +60,13 @@ const MAX_INPUT_WIDTH_REMS: f32 = 30.;
 
 actions!(
     project_search,
-    [SearchInNew, ToggleFocus, NextField, ToggleFilters]
+    [
+        SearchInNew,
+        ToggleFocus,
+        NextField,
+        ToggleFilters,
+        PreviewReplaceAll
+    ]
 );
 
 #[derive(Default)]
@@ -80,12 +89,21 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action(workspace, move |search_bar, action: &ToggleReplace, cx| {
             search_bar.toggle_replace(action, cx)
         });
+        register_workspace_action(workspace, move |search_bar, _: &TogglePreserveCase, cx| {
+            search_bar.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+        });
         register_workspace_action(workspace, move |search_bar, _: &ActivateRegexMode, cx| {
             search_bar.activate_search_mode(SearchMode::Regex, cx)
         });
         register_workspace_action(workspace, move |search_bar, _: &ActivateTextMode, cx| {
             search_bar.activate_search_mode(SearchMode::Text, cx)
         });
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ActivateStructuralMode, cx| {
+                search_bar.activate_search_mode(SearchMode::Structural, cx)
+            },
+        );
         register_workspace_action(
             workspace,
             move |search_bar, _: &ActivateSemanticMode, cx| {
@@ -112,6 +130,9 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action_for_present_search(workspace, |workspace, action, cx| {
             ProjectSearchView::search_in_new(workspace, action, cx)
         });
+        register_workspace_action_for_present_search(workspace, |workspace, action, cx| {
+            ProjectSearchView::preview_replace_all(workspace, action, cx)
+        });
 
         // Both on present and dismissed search, we need to unconditionally handle those actions to focus from the editor.
         workspace.register_action(move |workspace, action: &DeploySearch, cx| {
@@ -141,7 +162,7 @@ struct ProjectSearch {
     match_ranges: Vec<Range<Anchor>>,
     active_query: Option<SearchQuery>,
     search_id: usize,
-    search_history: SearchHistory,
+    search_history_cursor: SearchHistoryCursor,
     no_results: Option<bool>,
 }
 
@@ -203,7 +224,7 @@ impl ProjectSearch {
             match_ranges: Default::default(),
             active_query: None,
             search_id: 0,
-            search_history: SearchHistory::default(),
+            search_history_cursor: SearchHistoryCursor::default(),
             no_results: None,
         }
     }
@@ -218,7 +239,7 @@ impl ProjectSearch {
             match_ranges: self.match_ranges.clone(),
             active_query: self.active_query.clone(),
             search_id: self.search_id,
-            search_history: self.search_history.clone(),
+            search_history_cursor: self.search_history_cursor.clone(),
             no_results: self.no_results,
         })
     }
@@ -228,7 +249,22 @@ impl ProjectSearch {
             .project
             .update(cx, |project, cx| project.search(query.clone(), cx));
         self.search_id += 1;
-        self.search_history.add(query.as_str().to_string());
+        let mode = if query.is_regex() {
+            SearchMode::Regex
+        } else if query.is_structural() {
+            SearchMode::Structural
+        } else {
+            SearchMode::Text
+        };
+        SearchHistory::record(
+            &mut self.search_history_cursor,
+            SearchHistoryEntry::for_inputs(
+                query.as_inner(),
+                mode,
+                SearchOptions::from_query(&query),
+            ),
+            cx,
+        );
         self.active_query = Some(query);
         self.match_ranges.clear();
         self.pending_search = Some(cx.spawn(|this, mut cx| async move {
@@ -284,7 +320,11 @@ impl ProjectSearch {
         });
         self.search_id += 1;
         self.match_ranges.clear();
-        self.search_history.add(inputs.as_str().to_string());
+        SearchHistory::record(
+            &mut self.search_history_cursor,
+            SearchHistoryEntry::for_inputs(inputs, SearchMode::Semantic, SearchOptions::NONE),
+            cx,
+        );
         self.no_results = None;
         self.pending_search = Some(cx.spawn(|this, mut cx| async move {
             let results = search?.await.log_err()?;
@@ -480,14 +520,11 @@ impl Item for ProjectSearchView {
     }
 
     fn tab_content(&self, _: Option<usize>, selected: bool, cx: &WindowContext<'_>) -> AnyElement {
-        let last_query: Option<SharedString> = self
-            .model
-            .read(cx)
-            .search_history
-            .current()
-            .as_ref()
-            .map(|query| {
-                let query = query.replace('\n', "");
+        let last_query: Option<SharedString> = cx
+            .try_global::<SearchHistory>()
+            .and_then(|history| history.current(&self.model.read(cx).search_history_cursor))
+            .map(|entry| {
+                let query = entry.query.replace('\n', "");
                 let query_text = util::truncate_and_trailoff(&query, MAX_TAB_TITLE_LEN);
                 query_text.into()
             });
@@ -786,7 +823,7 @@ impl ProjectSearchView {
                     anyhow::Ok(())
                 }).detach_and_log_err(cx);
             }
-            SearchMode::Regex | SearchMode::Text => {
+            SearchMode::Regex | SearchMode::Text | SearchMode::Structural | SearchMode::Fuzzy => {
                 self.semantic_state = None;
                 self.active_match_index = None;
                 self.search(cx);
@@ -822,24 +859,97 @@ impl ProjectSearchView {
     pub fn replacement(&self, cx: &AppContext) -> String {
         self.replacement_editor.read(cx).text(cx)
     }
-    fn replace_all(&mut self, _: &ReplaceAll, cx: &mut ViewContext<Self>) {
+    /// The matches of the active query, grouped by the buffers they are in.
+    fn matches_by_buffer(
+        &self,
+        cx: &AppContext,
+    ) -> Vec<(Model<Buffer>, Vec<Range<language::Anchor>>)> {
         let model = self.model.read(cx);
-        if let Some(query) = model.active_query.as_ref() {
-            if model.match_ranges.is_empty() {
-                return;
-            }
-            if self.active_match_index.is_some() {
-                let query = query.clone().with_replacement(self.replacement(cx));
-                let matches = model
-                    .match_ranges
-                    .iter()
-                    .map(|item| Box::new(item.clone()) as _)
-                    .collect::<Vec<_>>();
-                for item in matches {
-                    self.results_editor.replace(&item, &query, cx);
-                }
+        let excerpts = model.excerpts.read(cx);
+        let mut matches = Vec::<(Model<Buffer>, Vec<Range<language::Anchor>>)>::new();
+        for range in &model.match_ranges {
+            let Some(buffer) = range
+                .start
+                .buffer_id
+                .and_then(|buffer_id| excerpts.buffer(buffer_id))
+            else {
+                continue;
+            };
+            let range = range.start.text_anchor..range.end.text_anchor;
+            match matches.last_mut() {
+                Some((last_buffer, ranges)) if *last_buffer == buffer => ranges.push(range),
+                _ => matches.push((buffer, vec![range])),
             }
         }
+        matches
+    }
+    fn replace_all(&mut self, _: &ReplaceAll, cx: &mut ViewContext<Self>) {
+        let model = self.model.read(cx);
+        let Some(query) = model.active_query.clone() else {
+            return;
+        };
+        if model.match_ranges.is_empty() || self.active_match_index.is_none() {
+            return;
+        }
+        let project = model.project.clone();
+        let query = query.with_replacement(self.replacement(cx));
+        let matches = self.matches_by_buffer(cx);
+        let transaction = project.update(cx, |project, cx| {
+            project.replace_search_matches(&query, matches, true, cx)
+        });
+        // Group the edits of all the buffers, to undo the whole replacement at once from the results.
+        self.results_editor.update(cx, |editor, cx| {
+            editor.buffer().update(cx, |buffer, cx| {
+                buffer.push_transaction(&transaction.0, cx);
+            });
+        });
+    }
+
+    fn preview_replace_all(
+        workspace: &mut Workspace,
+        _: &PreviewReplaceAll,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(search_view) = workspace
+            .active_item(cx)
+            .and_then(|item| item.downcast::<ProjectSearchView>())
+        else {
+            return;
+        };
+        let search_view = search_view.read(cx);
+        let Some(query) = search_view.model.read(cx).active_query.clone() else {
+            return;
+        };
+        let query = query.with_replacement(search_view.replacement(cx));
+        let mut preview = String::new();
+        for (buffer, ranges) in search_view.matches_by_buffer(cx) {
+            let buffer = buffer.read(cx);
+            let path = buffer.file().map_or_else(
+                || "untitled".to_string(),
+                |file| file.full_path(cx).to_string_lossy().into_owned(),
+            );
+            preview.push_str(&replacement_diff(&query, buffer, &ranges, &path));
+        }
+        if preview.is_empty() {
+            return;
+        }
+
+        let project = workspace.project().clone();
+        let Some(buffer) = project
+            .update(cx, |project, cx| project.create_buffer(&preview, None, cx))
+            .log_err()
+        else {
+            return;
+        };
+        let excerpts = cx.new_model(|cx| {
+            MultiBuffer::singleton(buffer, cx).with_title("Replace Preview".to_string())
+        });
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_multibuffer(excerpts, Some(project), cx);
+            editor.set_read_only(true);
+            editor
+        });
+        workspace.add_item_to_active_pane(Box::new(editor), cx);
     }
 
     fn new(
@@ -1009,6 +1119,65 @@ impl ProjectSearchView {
         });
     }
 
+    /// The search currently set up in the view, to be saved and run again later.
+    pub(crate) fn history_entry(&self, cx: &AppContext) -> SearchHistoryEntry {
+        SearchHistoryEntry {
+            query: self.query_editor.read(cx).text(cx),
+            mode: self.current_mode,
+            options: self.search_options,
+            files_to_include: self.included_files_editor.read(cx).text(cx),
+            files_to_exclude: self.excluded_files_editor.read(cx).text(cx),
+        }
+    }
+
+    /// Runs the given search in the project search of the active pane, creating one if there is none.
+    pub(crate) fn run_history_entry(
+        workspace: &mut Workspace,
+        entry: &SearchHistoryEntry,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let existing = workspace
+            .active_pane()
+            .read(cx)
+            .items()
+            .find_map(|item| item.downcast::<ProjectSearchView>());
+        let search = if let Some(existing) = existing {
+            workspace.activate_item(&existing, cx);
+            existing
+        } else {
+            let settings = cx
+                .global::<ActiveSettings>()
+                .0
+                .get(&workspace.project().downgrade())
+                .cloned();
+            let model = cx.new_model(|cx| ProjectSearch::new(workspace.project().clone(), cx));
+            let view = cx.new_view(|cx| ProjectSearchView::new(model, cx, settings));
+            workspace.add_item_to_active_pane(Box::new(view.clone()), cx);
+            view
+        };
+
+        search.update(cx, |search, cx| {
+            search.set_query(&entry.query, cx);
+            search.search_options = entry.options;
+            search.included_files_editor.update(cx, |editor, cx| {
+                editor.set_text(entry.files_to_include.as_str(), cx)
+            });
+            search.excluded_files_editor.update(cx, |editor, cx| {
+                editor.set_text(entry.files_to_exclude.as_str(), cx)
+            });
+            if !entry.files_to_include.is_empty() || !entry.files_to_exclude.is_empty() {
+                search.filters_enabled = true;
+            }
+            if search.current_mode == entry.mode {
+                search.search(cx);
+            } else {
+                search.activate_search_mode(entry.mode, cx);
+            }
+            search.focus_results_editor(cx);
+            cx.notify();
+        });
+    }
+
     // Re-activate the most recently activated search in this pane or the most recent if it has been closed.
     // If no search exists in the workspace, create a new one.
     fn deploy_search(
@@ -1199,6 +1368,29 @@ impl ProjectSearchView {
                     }
                 }
             }
+            SearchMode::Structural => match SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+            ) {
+                Ok(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error {
+                        cx.notify();
+                    }
+
+                    Some(query)
+                }
+                Err(_e) => {
+                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                    if should_mark_error {
+                        cx.notify();
+                    }
+
+                    None
+                }
+            },
             _ => match SearchQuery::text(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -1206,7 +1398,10 @@ impl ProjectSearchView {
                 self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
                 included_files,
                 excluded_files,
-            ) {
+            )
+            .and_then(|query| {
+                query.with_preserve_case(self.search_options.contains(SearchOptions::PRESERVE_CASE))
+            }) {
                 Ok(query) => {
                     let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
                     if should_unmark_error {
@@ -1340,7 +1535,8 @@ impl ProjectSearchView {
 
     fn landing_text_minor(&self) -> SharedString {
         match self.current_mode {
-            SearchMode::Text | SearchMode::Regex => "Include/exclude specific paths with the filter option. Matching exact word and/or casing is available too.".into(),
+            SearchMode::Text | SearchMode::Regex | SearchMode::Fuzzy => "Include/exclude specific paths with the filter option. Matching exact word and/or casing is available too.".into(),
+            SearchMode::Structural => "\nSearch for code patterns like `$X.unwrap()`, where `$X` matches any expression and `$$$ARGS` any arguments. Use them in the replacement to keep the matched code.".into(),
             SearchMode::Semantic => "\nSimply explain the code you are looking to find. ex. 'prompt user for permissions to index their project'".into()
         }
     }
@@ -1372,8 +1568,12 @@ impl ProjectSearchBar {
     fn cycle_mode(&self, _: &CycleMode, cx: &mut ViewContext<Self>) {
         if let Some(view) = self.active_project_search.as_ref() {
             view.update(cx, |this, cx| {
-                let new_mode =
-                    crate::mode::next_mode(&this.current_mode, SemanticIndex::enabled(cx));
+                let mut available_modes =
+                    vec![SearchMode::Text, SearchMode::Regex, SearchMode::Structural];
+                if SemanticIndex::enabled(cx) {
+                    available_modes.push(SearchMode::Semantic);
+                }
+                let new_mode = crate::mode::next_mode(&this.current_mode, &available_modes);
                 this.activate_search_mode(new_mode, cx);
                 let editor_handle = this.query_editor.focus_handle(cx);
                 cx.focus(&editor_handle);
@@ -1523,11 +1723,14 @@ impl ProjectSearchBar {
     fn next_history_query(&mut self, _: &NextHistoryQuery, cx: &mut ViewContext<Self>) {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
-                let new_query = search_view.model.update(cx, |model, _| {
-                    if let Some(new_query) = model.search_history.next().map(str::to_string) {
+                let new_query = search_view.model.update(cx, |model, cx| {
+                    if let Some(new_query) = SearchHistory::global(cx)
+                        .next(&mut model.search_history_cursor)
+                        .map(|entry| entry.query.clone())
+                    {
                         new_query
                     } else {
-                        model.search_history.reset_selection();
+                        model.search_history_cursor.reset();
                         String::new()
                     }
                 });
@@ -1540,20 +1743,21 @@ impl ProjectSearchBar {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
                 if search_view.query_editor.read(cx).text(cx).is_empty() {
-                    if let Some(new_query) = search_view
-                        .model
-                        .read(cx)
-                        .search_history
-                        .current()
-                        .map(str::to_string)
+                    let search_history_cursor = &search_view.model.read(cx).search_history_cursor;
+                    if let Some(new_query) = cx
+                        .try_global::<SearchHistory>()
+                        .and_then(|history| history.current(search_history_cursor))
+                        .map(|entry| entry.query.clone())
                     {
                         search_view.set_query(&new_query, cx);
                         return;
                     }
                 }
 
-                if let Some(new_query) = search_view.model.update(cx, |model, _| {
-                    model.search_history.previous().map(str::to_string)
+                if let Some(new_query) = search_view.model.update(cx, |model, cx| {
+                    SearchHistory::global(cx)
+                        .previous(&mut model.search_history_cursor)
+                        .map(|entry| entry.query.clone())
                 }) {
                     search_view.set_query(&new_query, cx);
                 }
@@ -1696,35 +1900,50 @@ impl Render for ProjectSearchBar {
                                     .unwrap_or_default(),
                             ),
                     )
-                    .when(search.current_mode != SearchMode::Semantic, |this| {
-                        this.child(
-                            IconButton::new(
-                                "project-search-case-sensitive",
-                                IconName::CaseSensitive,
-                            )
-                            .tooltip(|cx| {
-                                Tooltip::for_action(
-                                    "Toggle case sensitive",
-                                    &ToggleCaseSensitive,
-                                    cx,
+                    .when(
+                        !matches!(
+                            search.current_mode,
+                            SearchMode::Semantic | SearchMode::Structural
+                        ),
+                        |this| {
+                            this.child(
+                                IconButton::new(
+                                    "project-search-case-sensitive",
+                                    IconName::CaseSensitive,
                                 )
-                            })
-                            .selected(self.is_option_enabled(SearchOptions::CASE_SENSITIVE, cx))
-                            .on_click(cx.listener(|this, _, cx| {
-                                this.toggle_search_option(SearchOptions::CASE_SENSITIVE, cx);
-                            })),
-                        )
-                        .child(
-                            IconButton::new("project-search-whole-word", IconName::WholeWord)
                                 .tooltip(|cx| {
-                                    Tooltip::for_action("Toggle whole word", &ToggleWholeWord, cx)
+                                    Tooltip::for_action(
+                                        "Toggle case sensitive",
+                                        &ToggleCaseSensitive,
+                                        cx,
+                                    )
                                 })
-                                .selected(self.is_option_enabled(SearchOptions::WHOLE_WORD, cx))
-                                .on_click(cx.listener(|this, _, cx| {
-                                    this.toggle_search_option(SearchOptions::WHOLE_WORD, cx);
-                                })),
-                        )
-                    }),
+                                .selected(self.is_option_enabled(SearchOptions::CASE_SENSITIVE, cx))
+                                .on_click(cx.listener(
+                                    |this, _, cx| {
+                                        this.toggle_search_option(
+                                            SearchOptions::CASE_SENSITIVE,
+                                            cx,
+                                        );
+                                    },
+                                )),
+                            )
+                            .child(
+                                IconButton::new("project-search-whole-word", IconName::WholeWord)
+                                    .tooltip(|cx| {
+                                        Tooltip::for_action(
+                                            "Toggle whole word",
+                                            &ToggleWholeWord,
+                                            cx,
+                                        )
+                                    })
+                                    .selected(self.is_option_enabled(SearchOptions::WHOLE_WORD, cx))
+                                    .on_click(cx.listener(|this, _, cx| {
+                                        this.toggle_search_option(SearchOptions::WHOLE_WORD, cx);
+                                    })),
+                            )
+                        },
+                    ),
             );
 
         let mode_column = v_flex().items_start().justify_start().child(
@@ -1760,6 +1979,23 @@ impl Render for ProjectSearchBar {
                                         cx,
                                     )
                                 })
+                                .middle(),
+                        )
+                        .child(
+                            ToggleButton::new("project-search-structural-button", "Structural")
+                                .style(ButtonStyle::Filled)
+                                .size(ButtonSize::Large)
+                                .selected(search.current_mode == SearchMode::Structural)
+                                .on_click(cx.listener(|this, _, cx| {
+                                    this.activate_search_mode(SearchMode::Structural, cx)
+                                }))
+                                .tooltip(|cx| {
+                                    Tooltip::for_action(
+                                        "Toggle structural search",
+                                        &ActivateStructuralMode,
+                                        cx,
+                                    )
+                                })
                                 .map(|this| {
                                     if semantic_is_available {
                                         this.middle()
@@ -1860,7 +2096,19 @@ impl Render for ProjectSearchBar {
                 .rounded_lg()
                 .child(self.render_text_input(&search.replacement_editor, cx));
             let replace_actions = h_flex().when(search.replace_enabled, |this| {
-                this.child(
+                this.when(search.current_mode == SearchMode::Text, |this| {
+                    this.child(
+                        IconButton::new("project-search-preserve-case", IconName::CaseSensitive)
+                            .tooltip(|cx| {
+                                Tooltip::for_action("Toggle preserve case", &TogglePreserveCase, cx)
+                            })
+                            .selected(self.is_option_enabled(SearchOptions::PRESERVE_CASE, cx))
+                            .on_click(cx.listener(|this, _, cx| {
+                                this.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+                            })),
+                    )
+                })
+                .child(
                     IconButton::new("project-search-replace-next", IconName::ReplaceNext)
                         .on_click(cx.listener(|this, _, cx| {
                             if let Some(search) = this.active_project_search.as_ref() {
@@ -1882,6 +2130,17 @@ impl Render for ProjectSearchBar {
                         }))
                         .tooltip(|cx| Tooltip::for_action("Replace all matches", &ReplaceAll, cx)),
                 )
+                .child(
+                    IconButton::new("project-search-preview-replace-all", IconName::FileDoc)
+                        .on_click(|_, cx| cx.dispatch_action(PreviewReplaceAll.boxed_clone()))
+                        .tooltip(|cx| {
+                            Tooltip::for_action(
+                                "Preview replacing all matches",
+                                &PreviewReplaceAll,
+                                cx,
+                            )
+                        }),
+                )
             });
             h_flex()
                 .gap_2()
@@ -1948,6 +2207,9 @@ impl Render for ProjectSearchBar {
             .on_action(cx.listener(|this, _: &ActivateRegexMode, cx| {
                 this.activate_search_mode(SearchMode::Regex, cx)
             }))
+            .on_action(cx.listener(|this, _: &ActivateStructuralMode, cx| {
+                this.activate_search_mode(SearchMode::Structural, cx)
+            }))
             .on_action(cx.listener(|this, _: &ActivateSemanticMode, cx| {
                 this.activate_search_mode(SearchMode::Semantic, cx)
             }))
@@ -1973,6 +2235,9 @@ impl Render for ProjectSearchBar {
                 .on_action(cx.listener(|this, _: &ToggleCaseSensitive, cx| {
                     this.toggle_search_option(SearchOptions::CASE_SENSITIVE, cx);
                 }))
+                .on_action(cx.listener(|this, _: &TogglePreserveCase, cx| {
+                    this.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+                }))
                 .on_action(cx.listener(|this, action, cx| {
                     if let Some(search) = this.active_project_search.as_ref() {
                         search.update(cx, |this, cx| {
@@ -2039,6 +2304,40 @@ impl ToolbarItemView for ProjectSearchBar {
     }
 }
 
+/// Unified diff of the buffer text before and after replacing the given matches of the query.
+fn replacement_diff(
+    query: &SearchQuery,
+    buffer: &Buffer,
+    ranges: &[Range<language::Anchor>],
+    path: &str,
+) -> String {
+    let snapshot = buffer.snapshot();
+    let old_text = buffer.text();
+    let mut new_text = String::with_capacity(old_text.len());
+    let mut last_end = 0;
+    for range in ranges {
+        let range = range.to_offset(buffer);
+        if range.start < last_end {
+            continue;
+        }
+        let matched_text = &old_text[range.clone()];
+        new_text.push_str(&old_text[last_end..range.start]);
+        new_text.push_str(
+            &query
+                .replacement_for_range(&snapshot, range.clone())
+                .unwrap_or_else(|| matched_text.to_string()),
+        );
+        last_end = range.end;
+    }
+    new_text.push_str(&old_text[last_end..]);
+
+    similar::TextDiff::from_lines(&old_text, &new_text)
+        .unified_diff()
+        .context_radius(3)
+        .header(path, path)
+        .to_string()
+}
+
 fn register_workspace_action<A: Action>(
     workspace: &mut Workspace,
     callback: fn(&mut ProjectSearchBar, &A, &mut ViewContext<ProjectSearchBar>),
@@ -3417,6 +3716,93 @@ pub mod tests {
             .expect("unable to update search view");
     }
 
+    #[gpui::test]
+    async fn test_run_saved_search(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                "three.txt": "ONE and TWO",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project, cx));
+        let workspace = window.root(cx).unwrap();
+
+        let saved_search = SearchHistoryEntry {
+            query: "ONE".to_string(),
+            mode: SearchMode::Text,
+            options: SearchOptions::CASE_SENSITIVE | SearchOptions::WHOLE_WORD,
+            files_to_include: "*.rs".to_string(),
+            files_to_exclude: "two.rs".to_string(),
+        };
+        window
+            .update(cx, |workspace, cx| {
+                ProjectSearchView::run_history_entry(workspace, &saved_search, cx)
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+
+        let search_view = cx.read(|cx| {
+            workspace
+                .read(cx)
+                .active_item(cx)
+                .and_then(|item| item.downcast::<ProjectSearchView>())
+                .expect("running a saved search should open a project search")
+        });
+        window
+            .update(cx, |_, cx| {
+                search_view.update(cx, |search_view, cx| {
+                    assert!(search_view.filters_enabled);
+                    assert_eq!(search_view.history_entry(cx), saved_search);
+                    assert_eq!(
+                        search_view
+                            .results_editor
+                            .update(cx, |editor, cx| editor.display_text(cx)),
+                        "\n\nconst ONE: usize = 1;"
+                    );
+                });
+            })
+            .unwrap();
+
+        window
+            .update(cx, |workspace, cx| {
+                ProjectSearchView::run_history_entry(
+                    workspace,
+                    &SearchHistoryEntry::new(
+                        "TWO".to_string(),
+                        SearchMode::Text,
+                        SearchOptions::NONE,
+                    ),
+                    cx,
+                )
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+        window
+            .update(cx, |workspace, cx| {
+                assert_eq!(
+                    workspace.active_pane().read(cx).items_len(),
+                    1,
+                    "the existing project search should be reused"
+                );
+                search_view.update(cx, |search_view, cx| {
+                    assert_eq!(
+                        search_view
+                            .results_editor
+                            .update(cx, |editor, cx| editor.display_text(cx)),
+                        "\n\nONE and TWO\n\n\nconst TWO: usize = one::ONE + one::ONE;"
+                    );
+                });
+            })
+            .unwrap();
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
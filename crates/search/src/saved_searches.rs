@@ -0,0 +1,494 @@
+use crate::{
+    history::SearchHistoryEntry, project_search::ProjectSearchView, SaveSearch, ToggleSavedSearches,
+};
+use db::kvp::KEY_VALUE_STORE;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Global, Render, Task, View,
+    ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, IconButton, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+const SAVED_SEARCHES_KEY: &str = "saved_searches";
+
+/// A project search stored under a name, to run it again later.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub search: SearchHistoryEntry,
+}
+
+/// The saved searches, shared by all the workspaces and persisted across restarts.
+#[derive(Default, Debug)]
+pub struct SavedSearches {
+    searches: Vec<SavedSearch>,
+    /// Whether the persisted searches were merged in, before which they must not be overwritten.
+    loaded: bool,
+    /// The names of the searches deleted before the persisted ones were merged in.
+    deleted_before_load: Vec<String>,
+}
+
+impl Global for SavedSearches {}
+
+impl SavedSearches {
+    fn load(cx: &mut AppContext) {
+        cx.set_global(Self::default());
+        cx.spawn(|mut cx| async move {
+            let persisted = cx
+                .background_executor()
+                .spawn(async move {
+                    let persisted = KEY_VALUE_STORE.read_kvp(SAVED_SEARCHES_KEY)?;
+                    let persisted = persisted
+                        .map(|persisted| serde_json::from_str::<Vec<SavedSearch>>(&persisted))
+                        .transpose()?;
+                    anyhow::Ok(persisted)
+                })
+                .await
+                .log_err()
+                .flatten()
+                .unwrap_or_default();
+            cx.update_global(|saved_searches: &mut Self, cx| {
+                let changed_before_load = !saved_searches.searches.is_empty()
+                    || !saved_searches.deleted_before_load.is_empty();
+                saved_searches.merge_persisted(persisted);
+                saved_searches.loaded = true;
+                if changed_before_load {
+                    saved_searches.persist(cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Adds the persisted searches, except for the ones saved or deleted since startup.
+    fn merge_persisted(&mut self, persisted: Vec<SavedSearch>) {
+        let deleted_before_load = std::mem::take(&mut self.deleted_before_load);
+        for saved_search in persisted {
+            let changed_before_load = deleted_before_load.contains(&saved_search.name)
+                || self
+                    .searches
+                    .iter()
+                    .any(|existing| existing.name == saved_search.name);
+            if !changed_before_load {
+                self.insert(saved_search);
+            }
+        }
+    }
+
+    pub fn searches(&self) -> &[SavedSearch] {
+        &self.searches
+    }
+
+    /// Saves the search under the given name, replacing the search saved under it before.
+    pub fn save(name: String, search: SearchHistoryEntry, cx: &mut AppContext) {
+        cx.default_global::<Self>()
+            .insert(SavedSearch { name, search });
+        cx.global::<Self>().persist(cx);
+    }
+
+    pub fn delete(name: &str, cx: &mut AppContext) {
+        let saved_searches = cx.default_global::<Self>();
+        saved_searches
+            .searches
+            .retain(|saved_search| saved_search.name != name);
+        if !saved_searches.loaded {
+            saved_searches.deleted_before_load.push(name.to_string());
+        }
+        cx.global::<Self>().persist(cx);
+    }
+
+    fn insert(&mut self, saved_search: SavedSearch) {
+        match self
+            .searches
+            .iter_mut()
+            .find(|existing| existing.name == saved_search.name)
+        {
+            Some(existing) => *existing = saved_search,
+            None => {
+                self.searches.push(saved_search);
+                self.searches
+                    .sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+            }
+        }
+    }
+
+    fn persist(&self, cx: &AppContext) {
+        if !self.loaded {
+            return;
+        }
+        let Some(serialized) = serde_json::to_string(&self.searches).log_err() else {
+            return;
+        };
+        cx.background_executor()
+            .spawn(async move {
+                KEY_VALUE_STORE
+                    .write_kvp(SAVED_SEARCHES_KEY.into(), serialized)
+                    .await
+            })
+            .detach_and_log_err(cx);
+    }
+}
+
+pub(crate) fn init(cx: &mut AppContext) {
+    SavedSearches::load(cx);
+    cx.observe_new_views(SavedSearchesModal::register).detach();
+}
+
+pub struct SavedSearchesModal {
+    picker: View<Picker<SavedSearchesDelegate>>,
+}
+
+impl SavedSearchesModal {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &ToggleSavedSearches, cx| {
+            Self::toggle(workspace, None, cx);
+        });
+        workspace.register_action(|workspace, _: &SaveSearch, cx| {
+            let Some(search_view) = workspace
+                .active_item(cx)
+                .and_then(|item| item.downcast::<ProjectSearchView>())
+            else {
+                cx.propagate();
+                return;
+            };
+            let search = search_view.read(cx).history_entry(cx);
+            if search.query.is_empty() {
+                return;
+            }
+            Self::toggle(workspace, Some(search), cx);
+        });
+    }
+
+    fn toggle(
+        workspace: &mut Workspace,
+        search_to_save: Option<SearchHistoryEntry>,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let workspace_handle = cx.view().downgrade();
+        workspace.toggle_modal(cx, move |cx| {
+            let delegate = SavedSearchesDelegate::new(
+                cx.view().downgrade(),
+                workspace_handle,
+                search_to_save,
+                cx,
+            );
+            let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+            Self { picker }
+        });
+    }
+}
+
+impl Render for SavedSearchesModal {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for SavedSearchesModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SavedSearchesModal {}
+impl ModalView for SavedSearchesModal {}
+
+pub struct SavedSearchesDelegate {
+    modal: WeakView<SavedSearchesModal>,
+    workspace: WeakView<Workspace>,
+    /// The search to save under the name typed in the picker, or `None` to pick a saved search to run.
+    search_to_save: Option<SearchHistoryEntry>,
+    saved_searches: Vec<SavedSearch>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    last_query: String,
+}
+
+impl SavedSearchesDelegate {
+    fn new(
+        modal: WeakView<SavedSearchesModal>,
+        workspace: WeakView<Workspace>,
+        search_to_save: Option<SearchHistoryEntry>,
+        cx: &mut AppContext,
+    ) -> Self {
+        Self {
+            modal,
+            workspace,
+            search_to_save,
+            saved_searches: cx.default_global::<SavedSearches>().searches().to_vec(),
+            matches: Vec::new(),
+            selected_index: 0,
+            last_query: String::new(),
+        }
+    }
+
+    /// The name typed in the picker, if the search can be saved under it as a new saved search.
+    fn new_name(&self) -> Option<&str> {
+        self.search_to_save.as_ref()?;
+        let name = self.last_query.trim();
+        let is_new = !name.is_empty()
+            && self
+                .saved_searches
+                .iter()
+                .all(|saved_search| saved_search.name != name);
+        is_new.then_some(name)
+    }
+
+    fn saved_search_at(&self, ix: usize) -> Option<&SavedSearch> {
+        let ix = ix.checked_sub(usize::from(self.new_name().is_some()))?;
+        let string_match = self.matches.get(ix)?;
+        self.saved_searches.get(string_match.candidate_id)
+    }
+}
+
+impl PickerDelegate for SavedSearchesDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        if self.search_to_save.is_some() {
+            "Name the search...".into()
+        } else {
+            "Run a saved search...".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len() + usize::from(self.new_name().is_some())
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .saved_searches
+            .iter()
+            .enumerate()
+            .map(|(id, saved_search)| StringMatchCandidate::new(id, saved_search.name.clone()))
+            .collect::<Vec<_>>();
+        cx.spawn(|picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            picker
+                .update(&mut cx, |picker, cx| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.last_query = query;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.match_count().saturating_sub(1));
+                    cx.notify();
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        match self.search_to_save.clone() {
+            Some(search) => {
+                let name = if self.selected_index == 0 {
+                    self.new_name().map(ToOwned::to_owned)
+                } else {
+                    None
+                };
+                let name = name.or_else(|| {
+                    self.saved_search_at(self.selected_index)
+                        .map(|saved_search| saved_search.name.clone())
+                });
+                let Some(name) = name else {
+                    return;
+                };
+                SavedSearches::save(name, search, cx);
+            }
+            None => {
+                let Some(saved_search) = self.saved_search_at(self.selected_index).cloned() else {
+                    return;
+                };
+                self.workspace
+                    .update(cx, |workspace, cx| {
+                        ProjectSearchView::run_history_entry(workspace, &saved_search.search, cx)
+                    })
+                    .log_err();
+            }
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.modal
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        if ix == 0 {
+            if let Some(name) = self.new_name() {
+                return Some(
+                    ListItem::new(ix)
+                        .inset(true)
+                        .spacing(ListItemSpacing::Sparse)
+                        .selected(selected)
+                        .child(Label::new(format!("Save as \"{name}\""))),
+                );
+            }
+        }
+
+        let saved_search = self.saved_search_at(ix)?;
+        let string_match = &self.matches[ix - usize::from(self.new_name().is_some())];
+        let action_label = if self.search_to_save.is_some() {
+            "Replace"
+        } else {
+            saved_search.search.mode.label()
+        };
+        let name = saved_search.name.clone();
+        let delete_button = IconButton::new("delete", IconName::Close)
+            .icon_size(IconSize::Small)
+            .on_click(cx.listener(move |picker, _, cx| {
+                cx.stop_propagation();
+                SavedSearches::delete(&name, cx);
+                picker
+                    .delegate
+                    .saved_searches
+                    .retain(|saved_search| saved_search.name != name);
+                picker.refresh(cx);
+            }))
+            .tooltip(|cx| Tooltip::text("Delete Saved Search", cx));
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            saved_search.name.clone(),
+                            string_match.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(util::truncate_and_trailoff(
+                                &saved_search.search.query.replace('\n', " "),
+                                40,
+                            ))
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                        )
+                        .child(
+                            Label::new(action_label)
+                                .color(Color::Muted)
+                                .size(LabelSize::XSmall),
+                        ),
+                )
+                .end_hover_slot(delete_button),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_searches_are_kept_by_name() {
+        let mut saved_searches = SavedSearches::default();
+        let search = |query: &str| {
+            SearchHistoryEntry::new(query.to_string(), Default::default(), Default::default())
+        };
+        saved_searches.insert(SavedSearch {
+            name: "todos".to_string(),
+            search: search("TODO"),
+        });
+        saved_searches.insert(SavedSearch {
+            name: "Fixmes".to_string(),
+            search: search("FIXME"),
+        });
+        saved_searches.insert(SavedSearch {
+            name: "todos".to_string(),
+            search: search("TODO|XXX"),
+        });
+
+        assert_eq!(
+            saved_searches
+                .searches()
+                .iter()
+                .map(|saved_search| (
+                    saved_search.name.as_str(),
+                    saved_search.search.query.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            [("Fixmes", "FIXME"), ("todos", "TODO|XXX")]
+        );
+    }
+
+    #[test]
+    fn test_merge_persisted_saved_searches() {
+        let mut saved_searches = SavedSearches::default();
+        let saved_search = |name: &str, query: &str| SavedSearch {
+            name: name.to_string(),
+            search: SearchHistoryEntry::new(
+                query.to_string(),
+                Default::default(),
+                Default::default(),
+            ),
+        };
+        saved_searches.insert(saved_search("todos", "TODO|XXX"));
+        saved_searches
+            .deleted_before_load
+            .push("fixmes".to_string());
+
+        saved_searches.merge_persisted(vec![
+            saved_search("todos", "TODO"),
+            saved_search("fixmes", "FIXME"),
+            saved_search("hacks", "HACK"),
+        ]);
+        assert_eq!(
+            saved_searches
+                .searches()
+                .iter()
+                .map(|saved_search| (
+                    saved_search.name.as_str(),
+                    saved_search.search.query.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            [("hacks", "HACK"), ("todos", "TODO|XXX")],
+            "Searches saved or deleted before loading should win over the persisted ones"
+        );
+    }
+}
@@ -11,12 +11,15 @@ pub mod buffer_search;
 mod history;
 mod mode;
 pub mod project_search;
+mod saved_searches;
 pub(crate) mod search_bar;
 
 pub fn init(cx: &mut AppContext) {
     menu::init();
     buffer_search::init(cx);
     project_search::init(cx);
+    history::SearchHistory::init(cx);
+    saved_searches::init(cx);
 }
 
 actions!(
@@ -26,7 +29,9 @@ actions!(
         ToggleWholeWord,
         ToggleCaseSensitive,
         ToggleIncludeIgnored,
+        TogglePreserveCase,
         ToggleReplace,
+        ToggleSelection,
         SelectNextMatch,
         SelectPrevMatch,
         SelectAllMatches,
@@ -35,8 +40,12 @@ actions!(
         ActivateTextMode,
         ActivateSemanticMode,
         ActivateRegexMode,
+        ActivateStructuralMode,
+        ActivateFuzzyMode,
         ReplaceAll,
         ReplaceNext,
+        SaveSearch,
+        ToggleSavedSearches,
     ]
 );
 
@@ -47,6 +56,7 @@ bitflags! {
         const WHOLE_WORD = 0b001;
         const CASE_SENSITIVE = 0b010;
         const INCLUDE_IGNORED = 0b100;
+        const PRESERVE_CASE = 0b1000;
     }
 }
 
@@ -56,6 +66,7 @@ impl SearchOptions {
             SearchOptions::WHOLE_WORD => "Match Whole Word",
             SearchOptions::CASE_SENSITIVE => "Match Case",
             SearchOptions::INCLUDE_IGNORED => "Include ignored",
+            SearchOptions::PRESERVE_CASE => "Preserve Case",
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -65,6 +76,7 @@ impl SearchOptions {
             SearchOptions::WHOLE_WORD => ui::IconName::WholeWord,
             SearchOptions::CASE_SENSITIVE => ui::IconName::CaseSensitive,
             SearchOptions::INCLUDE_IGNORED => ui::IconName::FileGit,
+            SearchOptions::PRESERVE_CASE => ui::IconName::CaseSensitive,
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -74,6 +86,7 @@ impl SearchOptions {
             SearchOptions::WHOLE_WORD => Box::new(ToggleWholeWord),
             SearchOptions::CASE_SENSITIVE => Box::new(ToggleCaseSensitive),
             SearchOptions::INCLUDE_IGNORED => Box::new(ToggleIncludeIgnored),
+            SearchOptions::PRESERVE_CASE => Box::new(TogglePreserveCase),
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -87,6 +100,7 @@ impl SearchOptions {
         options.set(SearchOptions::WHOLE_WORD, query.whole_word());
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
+        options.set(SearchOptions::PRESERVE_CASE, query.preserve_case());
         options
     }
 
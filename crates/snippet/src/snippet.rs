@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Context, Result};
+use regex::{Captures, Regex, RegexBuilder};
 use smallvec::SmallVec;
 use std::{collections::BTreeMap, ops::Range};
 
@@ -6,22 +7,141 @@ use std::{collections::BTreeMap, ops::Range};
 pub struct Snippet {
     pub text: String,
     pub tabstops: Vec<TabStop>,
+    /// The values to choose from for the tabstops written as `${1|one,two|}`, by index in `tabstops`.
+    pub choices: BTreeMap<usize, Vec<String>>,
+    /// The ranges written as `${1/regex/format/flags}`, mirroring a tabstop with its text transformed.
+    pub transforms: Vec<TabStopTransform>,
 }
 
 type TabStop = SmallVec<[Range<isize>; 2]>;
 
+/// A range of the snippet's text, to be replaced with the transformed text of a tabstop once it is edited.
+#[derive(Clone, Debug)]
+pub struct TabStopTransform {
+    /// The index of the mirrored tabstop in `tabstops`.
+    pub tabstop: usize,
+    pub range: Range<isize>,
+    pub transform: Transform,
+}
+
+/// A regex replacement applied to a variable or a tabstop, e.g. `${TM_FILENAME/(.*)\..+$/$1/}`.
+///
+/// The format may refer to the captured groups as `$1` or `${1}`, change their case with
+/// `${1:/upcase}`, `${1:/downcase}`, `${1:/capitalize}`, `${1:/camelcase}` or `${1:/pascalcase}`,
+/// and insert text depending on whether they matched with `${1:+if}`, `${1:-else}` or `${1:?if:else}`.
+#[derive(Clone, Debug)]
+pub struct Transform {
+    regex: Regex,
+    format: Vec<FormatItem>,
+    global: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FormatItem {
+    Text(String),
+    Group(usize),
+    ChangeCase(usize, CaseChange),
+    Conditional {
+        group: usize,
+        /// The text to insert if the group matched, or `None` to insert the group itself.
+        if_matched: Option<String>,
+        otherwise: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CaseChange {
+    Upcase,
+    Downcase,
+    Capitalize,
+    Camelcase,
+    Pascalcase,
+}
+
 impl Snippet {
     pub fn parse(source: &str) -> Result<Self> {
-        let mut text = String::with_capacity(source.len());
-        let mut tabstops = BTreeMap::new();
-        parse_snippet(source, false, &mut text, &mut tabstops)
+        Self::parse_with_variables(source, &|_| None)
+    }
+
+    /// Parses the snippet, expanding the variables like `$TM_FILENAME` or `${CLIPBOARD:default}` with the given values.
+    ///
+    /// The variables without a value are replaced with their default, or their name if they have none.
+    pub fn parse_with_variables(
+        source: &str,
+        variables: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let mut parser = Parser::new(variables, BTreeMap::new());
+        parser
+            .parse_snippet(source, false)
             .context("failed to parse snippet")?;
+        if !parser.transforms.is_empty() {
+            // Transformed tabstops mirror the placeholders, which may come after them in the snippet.
+            let placeholders = parser.placeholders();
+            parser = Parser::new(variables, placeholders);
+            parser
+                .parse_snippet(source, false)
+                .context("failed to parse snippet")?;
+        }
+        Ok(parser.finish())
+    }
+}
+
+struct Parser<'a> {
+    text: String,
+    tabstops: BTreeMap<usize, TabStop>,
+    choices: BTreeMap<usize, Vec<String>>,
+    transforms: Vec<(usize, Range<isize>, Transform)>,
+    variables: &'a dyn Fn(&str) -> Option<String>,
+    /// The text of the first placeholder of every tabstop, to fill the transformed tabstops with.
+    placeholders: BTreeMap<usize, String>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(
+        variables: &'a dyn Fn(&str) -> Option<String>,
+        placeholders: BTreeMap<usize, String>,
+    ) -> Self {
+        Self {
+            text: String::new(),
+            tabstops: BTreeMap::new(),
+            choices: BTreeMap::new(),
+            transforms: Vec::new(),
+            variables,
+            placeholders,
+        }
+    }
+
+    fn placeholders(&self) -> BTreeMap<usize, String> {
+        self.tabstops
+            .iter()
+            .filter_map(|(index, ranges)| {
+                let range = ranges.first()?;
+                let text = &self.text[range.start as usize..range.end as usize];
+                Some((*index, text.to_string()))
+            })
+            .collect()
+    }
+
+    fn finish(self) -> Snippet {
+        let Parser {
+            text,
+            mut tabstops,
+            choices,
+            transforms,
+            ..
+        } = self;
 
         let len = text.len() as isize;
         let final_tabstop = tabstops.remove(&0);
+        let mut positions = tabstops
+            .keys()
+            .enumerate()
+            .map(|(position, index)| (*index, position))
+            .collect::<BTreeMap<_, _>>();
         let mut tabstops = tabstops.into_values().collect::<Vec<_>>();
 
         if let Some(final_tabstop) = final_tabstop {
+            positions.insert(0, tabstops.len());
             tabstops.push(final_tabstop);
         } else {
             let end_tabstop = [len..len].into_iter().collect();
@@ -30,79 +150,160 @@ impl Snippet {
             }
         }
 
-        Ok(Snippet { text, tabstops })
+        let choices = choices
+            .into_iter()
+            .filter_map(|(index, choices)| Some((*positions.get(&index)?, choices)))
+            .collect();
+        let transforms = transforms
+            .into_iter()
+            .filter_map(|(index, range, transform)| {
+                Some(TabStopTransform {
+                    tabstop: *positions.get(&index)?,
+                    range,
+                    transform,
+                })
+            })
+            .collect();
+
+        Snippet {
+            text,
+            tabstops,
+            choices,
+            transforms,
+        }
     }
-}
 
-fn parse_snippet<'a>(
-    mut source: &'a str,
-    nested: bool,
-    text: &mut String,
-    tabstops: &mut BTreeMap<usize, TabStop>,
-) -> Result<&'a str> {
-    loop {
-        match source.chars().next() {
-            None => return Ok(""),
-            Some('$') => {
-                source = parse_tabstop(&source[1..], text, tabstops)?;
-            }
-            Some('\\') => {
-                source = &source[1..];
-                if let Some(c) = source.chars().next() {
-                    text.push(c);
-                    source = &source[c.len_utf8()..];
+    fn parse_snippet<'s>(&mut self, mut source: &'s str, nested: bool) -> Result<&'s str> {
+        loop {
+            match source.chars().next() {
+                None => return Ok(""),
+                Some('$') => {
+                    source = self.parse_tabstop(&source[1..])?;
                 }
-            }
-            Some('}') => {
-                if nested {
-                    return Ok(source);
-                } else {
-                    text.push('}');
+                Some('\\') => {
                     source = &source[1..];
+                    if let Some(c) = source.chars().next() {
+                        self.text.push(c);
+                        source = &source[c.len_utf8()..];
+                    }
                 }
+                Some('}') => {
+                    if nested {
+                        return Ok(source);
+                    } else {
+                        self.text.push('}');
+                        source = &source[1..];
+                    }
+                }
+                Some(_) => {
+                    let chunk_end = source.find(['}', '$', '\\']).unwrap_or(source.len());
+                    let (chunk, rest) = source.split_at(chunk_end);
+                    self.text.push_str(chunk);
+                    source = rest;
+                }
+            }
+        }
+    }
+
+    fn parse_tabstop<'s>(&mut self, mut source: &'s str) -> Result<&'s str> {
+        let tabstop_start = self.text.len();
+        let tabstop_index;
+        if let Some(braced) = source.strip_prefix('{') {
+            if !braced.starts_with(|c: char| c.is_ascii_digit()) {
+                return self.parse_variable(braced, true);
             }
-            Some(_) => {
-                let chunk_end = source.find(['}', '$', '\\']).unwrap_or(source.len());
-                let (chunk, rest) = source.split_at(chunk_end);
-                text.push_str(chunk);
+
+            let (index, rest) = parse_int(braced)?;
+            tabstop_index = index;
+            source = rest;
+
+            if let Some(placeholder) = source.strip_prefix(':') {
+                source = self.parse_snippet(placeholder, true)?;
+            } else if let Some(choices) = source.strip_prefix('|') {
+                let (choices, rest) = parse_choices(choices)?;
+                self.text.push_str(&choices[0]);
+                self.choices.insert(tabstop_index, choices);
                 source = rest;
+            } else if let Some(transform) = source.strip_prefix('/') {
+                let (transform, rest) = Transform::parse(transform)?;
+                if let Some(placeholder) = self.placeholders.get(&tabstop_index) {
+                    self.text.push_str(&transform.apply(placeholder));
+                }
+                let range = tabstop_start as isize..self.text.len() as isize;
+                self.transforms.push((tabstop_index, range, transform));
+                return expect_closing_brace(rest);
             }
+
+            source = expect_closing_brace(source)?;
+        } else if source.starts_with(|c: char| c.is_ascii_digit()) {
+            let (index, rest) = parse_int(source)?;
+            tabstop_index = index;
+            source = rest;
+        } else {
+            return self.parse_variable(source, false);
         }
+
+        self.tabstops
+            .entry(tabstop_index)
+            .or_default()
+            .push(tabstop_start as isize..self.text.len() as isize);
+        Ok(source)
     }
-}
 
-fn parse_tabstop<'a>(
-    mut source: &'a str,
-    text: &mut String,
-    tabstops: &mut BTreeMap<usize, TabStop>,
-) -> Result<&'a str> {
-    let tabstop_start = text.len();
-    let tabstop_index;
-    if source.starts_with('{') {
-        let (index, rest) = parse_int(&source[1..])?;
-        tabstop_index = index;
-        source = rest;
+    fn parse_variable<'s>(&mut self, source: &'s str, braced: bool) -> Result<&'s str> {
+        let name_len = source
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(source.len());
+        let (name, mut source) = source.split_at(name_len);
+        if name.is_empty() {
+            if braced {
+                return Err(anyhow!("expected a tabstop index or a variable name"));
+            }
+            self.text.push('$');
+            return Ok(source);
+        }
 
-        if source.starts_with(':') {
-            source = parse_snippet(&source[1..], true, text, tabstops)?;
+        let variable_start = self.text.len();
+        let value = (self.variables)(name);
+        if !braced {
+            self.text.push_str(value.as_deref().unwrap_or(name));
+            return Ok(source);
         }
 
-        if source.starts_with('}') {
-            source = &source[1..];
+        if let Some(default) = source.strip_prefix(':') {
+            source = expect_closing_brace(self.parse_snippet(default, true)?)?;
+            if let Some(value) = value.filter(|value| !value.is_empty()) {
+                self.discard_from(variable_start);
+                self.text.push_str(&value);
+            }
+        } else if let Some(transform) = source.strip_prefix('/') {
+            let (transform, rest) = Transform::parse(transform)?;
+            self.text
+                .push_str(&transform.apply(value.as_deref().unwrap_or_default()));
+            source = expect_closing_brace(rest)?;
         } else {
-            return Err(anyhow!("expected a closing brace"));
+            self.text.push_str(value.as_deref().unwrap_or(name));
+            source = expect_closing_brace(source)?;
         }
-    } else {
-        let (index, rest) = parse_int(source)?;
-        tabstop_index = index;
-        source = rest;
+        Ok(source)
     }
 
-    tabstops
-        .entry(tabstop_index)
-        .or_default()
-        .push(tabstop_start as isize..text.len() as isize);
-    Ok(source)
+    /// Removes the text after the given offset, with the tabstops it contains.
+    fn discard_from(&mut self, offset: usize) {
+        let offset = offset as isize;
+        self.text.truncate(offset as usize);
+        for ranges in self.tabstops.values_mut() {
+            ranges.retain(|range| range.start < offset);
+        }
+        self.tabstops.retain(|_, ranges| !ranges.is_empty());
+        self.transforms.retain(|(_, range, _)| range.start < offset);
+    }
+}
+
+fn expect_closing_brace(source: &str) -> Result<&str> {
+    source
+        .strip_prefix('}')
+        .ok_or_else(|| anyhow!("expected a closing brace"))
 }
 
 fn parse_int(source: &str) -> Result<(usize, &str)> {
@@ -116,6 +317,265 @@ fn parse_int(source: &str) -> Result<(usize, &str)> {
     Ok((prefix.parse()?, suffix))
 }
 
+/// Parses the comma-separated values of `${1|one,two|}`, returning the source after the closing `|`.
+fn parse_choices(mut source: &str) -> Result<(Vec<String>, &str)> {
+    let mut choices = vec![String::new()];
+    loop {
+        let mut chars = source.chars();
+        match chars.next() {
+            None => return Err(anyhow!("expected the end of the choices")),
+            Some('|') => return Ok((choices, chars.as_str())),
+            Some(',') => choices.push(String::new()),
+            Some('\\') => {
+                if let Some(c) = chars.next() {
+                    if !matches!(c, ',' | '|' | '\\') {
+                        choices.last_mut().unwrap().push('\\');
+                    }
+                    choices.last_mut().unwrap().push(c);
+                }
+            }
+            Some(c) => choices.last_mut().unwrap().push(c),
+        }
+        source = chars.as_str();
+    }
+}
+
+/// Splits the source at the first unescaped delimiter, unescaping the delimiters before it.
+fn split_at_delimiter(source: &str, delimiter: char) -> Result<(String, &str)> {
+    let mut result = String::new();
+    let mut chars = source.chars();
+    loop {
+        match chars.next() {
+            None => return Err(anyhow!("expected '{delimiter}'")),
+            Some('\\') => match chars.next() {
+                Some(c) if c == delimiter => result.push(c),
+                Some(c) => {
+                    result.push('\\');
+                    result.push(c);
+                }
+                None => result.push('\\'),
+            },
+            Some(c) if c == delimiter => return Ok((result, chars.as_str())),
+            Some(c) => result.push(c),
+        }
+    }
+}
+
+impl Transform {
+    /// Parses the `regex/format/flags` part of a transform, returning the source after the flags.
+    fn parse(source: &str) -> Result<(Self, &str)> {
+        let (regex, rest) = split_at_delimiter(source, '/')?;
+        // The format's groups may contain slashes, as in `${1:/upcase}`.
+        let mut group_depth = 0;
+        let mut chars = rest.char_indices();
+        let format_len = loop {
+            match chars.next() {
+                None => return Err(anyhow!("expected '/'")),
+                Some((_, '\\')) => {
+                    chars.next();
+                }
+                Some((_, '$')) if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    group_depth += 1;
+                }
+                Some((_, '}')) if group_depth > 0 => group_depth -= 1,
+                Some((ix, '/')) if group_depth == 0 => break ix,
+                Some(_) => {}
+            }
+        };
+        let (format, rest) = (&rest[..format_len], &rest[format_len + 1..]);
+        let flags_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let (flags, rest) = rest.split_at(flags_len);
+
+        let regex = RegexBuilder::new(&regex)
+            .case_insensitive(flags.contains('i'))
+            .multi_line(flags.contains('m'))
+            .dot_matches_new_line(flags.contains('s'))
+            .build()
+            .context("invalid transform regex")?;
+        let transform = Self {
+            regex,
+            format: parse_format(format)?,
+            global: flags.contains('g'),
+        };
+        Ok((transform, rest))
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut last_match_end = 0;
+        for captures in self.regex.captures_iter(text) {
+            let Some(matched) = captures.get(0) else {
+                continue;
+            };
+            result.push_str(&text[last_match_end..matched.start()]);
+            for item in &self.format {
+                item.expand(&captures, &mut result);
+            }
+            last_match_end = matched.end();
+            if !self.global {
+                break;
+            }
+        }
+        result.push_str(&text[last_match_end..]);
+        result
+    }
+}
+
+fn parse_format(mut source: &str) -> Result<Vec<FormatItem>> {
+    let mut items = Vec::new();
+    let mut text = String::new();
+    while let Some(c) = source.chars().next() {
+        source = &source[c.len_utf8()..];
+        let group_item = match c {
+            '\\' => {
+                if let Some(c) = source.chars().next() {
+                    text.push(c);
+                    source = &source[c.len_utf8()..];
+                }
+                continue;
+            }
+            '$' if source.starts_with(|c: char| c.is_ascii_digit()) => {
+                let (group, rest) = parse_int(source)?;
+                source = rest;
+                FormatItem::Group(group)
+            }
+            '$' if source.starts_with('{') => {
+                let (item, rest) = parse_format_group(&source[1..])?;
+                source = rest;
+                item
+            }
+            c => {
+                text.push(c);
+                continue;
+            }
+        };
+        if !text.is_empty() {
+            items.push(FormatItem::Text(std::mem::take(&mut text)));
+        }
+        items.push(group_item);
+    }
+    if !text.is_empty() {
+        items.push(FormatItem::Text(text));
+    }
+    Ok(items)
+}
+
+/// Parses the inside of `${1}`, `${1:/upcase}`, `${1:+if}`, `${1:-else}`, `${1:else}` or `${1:?if:else}`.
+fn parse_format_group(source: &str) -> Result<(FormatItem, &str)> {
+    let (group, rest) = parse_int(source)?;
+    if let Some(rest) = rest.strip_prefix('}') {
+        return Ok((FormatItem::Group(group), rest));
+    }
+    let rest = rest
+        .strip_prefix(':')
+        .ok_or_else(|| anyhow!("expected ':' or a closing brace"))?;
+    let item = if let Some(case_change) = rest.strip_prefix('/') {
+        let (case_change, rest) = split_at_delimiter(case_change, '}')?;
+        let case_change = match case_change.as_str() {
+            "upcase" => CaseChange::Upcase,
+            "downcase" => CaseChange::Downcase,
+            "capitalize" => CaseChange::Capitalize,
+            "camelcase" => CaseChange::Camelcase,
+            "pascalcase" => CaseChange::Pascalcase,
+            _ => return Err(anyhow!("unknown case change '{case_change}'")),
+        };
+        return Ok((FormatItem::ChangeCase(group, case_change), rest));
+    } else if let Some(if_matched) = rest.strip_prefix('+') {
+        let (if_matched, rest) = split_at_delimiter(if_matched, '}')?;
+        (Some(unescape(&if_matched)), String::new(), rest)
+    } else if let Some(if_matched) = rest.strip_prefix('?') {
+        let (if_matched, rest) = split_at_delimiter(if_matched, ':')?;
+        let (otherwise, rest) = split_at_delimiter(rest, '}')?;
+        (Some(unescape(&if_matched)), unescape(&otherwise), rest)
+    } else {
+        let otherwise = rest.strip_prefix('-').unwrap_or(rest);
+        let (otherwise, rest) = split_at_delimiter(otherwise, '}')?;
+        (None, unescape(&otherwise), rest)
+    };
+    let (if_matched, otherwise, rest) = item;
+    Ok((
+        FormatItem::Conditional {
+            group,
+            if_matched,
+            otherwise,
+        },
+        rest,
+    ))
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            result.extend(chars.next());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+impl FormatItem {
+    fn expand(&self, captures: &Captures, result: &mut String) {
+        let group_text = |group: usize| captures.get(group).map(|group| group.as_str());
+        match self {
+            FormatItem::Text(text) => result.push_str(text),
+            FormatItem::Group(group) => result.push_str(group_text(*group).unwrap_or_default()),
+            FormatItem::ChangeCase(group, case_change) => {
+                result.push_str(&case_change.apply(group_text(*group).unwrap_or_default()))
+            }
+            FormatItem::Conditional {
+                group,
+                if_matched,
+                otherwise,
+            } => match group_text(*group).filter(|text| !text.is_empty()) {
+                Some(text) => result.push_str(if_matched.as_deref().unwrap_or(text)),
+                None => result.push_str(otherwise),
+            },
+        }
+    }
+}
+
+impl CaseChange {
+    fn apply(&self, text: &str) -> String {
+        match self {
+            CaseChange::Upcase => text.to_uppercase(),
+            CaseChange::Downcase => text.to_lowercase(),
+            CaseChange::Capitalize => capitalize(text),
+            CaseChange::Camelcase | CaseChange::Pascalcase => {
+                let pascal_case = text
+                    .split(|c: char| !c.is_alphanumeric())
+                    .map(capitalize)
+                    .collect::<String>();
+                if *self == CaseChange::Pascalcase {
+                    return pascal_case;
+                }
+                let mut chars = pascal_case.chars();
+                chars
+                    .next()
+                    .into_iter()
+                    .flat_map(char::to_lowercase)
+                    .chain(chars)
+                    .collect()
+            }
+        }
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .into_iter()
+        .flat_map(char::to_uppercase)
+        .chain(chars)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -199,6 +659,77 @@ mod tests {
         assert_eq!(tabstops(&snippet), &[vec![3..3]]);
     }
 
+    #[test]
+    fn test_snippet_with_choices() {
+        let snippet = Snippet::parse(r"let ${1|a,b\,c,d\|e|} = $2;").unwrap();
+        assert_eq!(snippet.text, "let a = ;");
+        assert_eq!(tabstops(&snippet), &[vec![4..5], vec![8..8], vec![9..9]]);
+        assert_eq!(
+            snippet.choices.into_iter().collect::<Vec<_>>(),
+            [(
+                0,
+                vec!["a".to_string(), "b,c".to_string(), "d|e".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_snippet_with_variables() {
+        let variables = |name: &str| match name {
+            "TM_FILENAME" => Some("main.rs".to_string()),
+            "TM_SELECTED_TEXT" => Some(String::new()),
+            _ => None,
+        };
+        let snippet = Snippet::parse_with_variables(
+            "// $TM_FILENAME ${TM_FILENAME/(.*)\\..+$/$1/}\n${TM_SELECTED_TEXT:${1:body}} $UNKNOWN ${CLIPBOARD:none}$",
+            &variables,
+        )
+        .unwrap();
+        assert_eq!(snippet.text, "// main.rs main\nbody UNKNOWN none$");
+        assert_eq!(tabstops(&snippet), &[vec![16..20], vec![34..34]]);
+
+        // The default of a variable with a value is discarded, with its tabstops.
+        let variables = |_: &str| Some("selection".to_string());
+        let snippet =
+            Snippet::parse_with_variables("(${TM_SELECTED_TEXT:$1})", &variables).unwrap();
+        assert_eq!(snippet.text, "(selection)");
+        assert_eq!(tabstops(&snippet), &[vec![11..11]]);
+    }
+
+    #[test]
+    fn test_snippet_with_transforms() {
+        let snippet =
+            Snippet::parse("${2/(.)(.*)/${1:/upcase}$2/} ${1:first} ${1/first|(other)/${1:?matched:unmatched}/g}: ${2:second}")
+                .unwrap();
+        assert_eq!(snippet.text, "Second first unmatched: second");
+        assert_eq!(
+            tabstops(&snippet),
+            &[vec![7..12], vec![24..30], vec![30..30]]
+        );
+        let transforms = snippet
+            .transforms
+            .iter()
+            .map(|transform| (transform.tabstop, transform.range.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(transforms, [(1, 0..6), (0, 13..22)]);
+        assert_eq!(snippet.transforms[0].transform.apply("value"), "Value");
+        assert_eq!(
+            snippet.transforms[1].transform.apply("other first"),
+            "matched unmatched"
+        );
+
+        let variables = |_: &str| Some("my-snippet_file.json".to_string());
+        let snippet = Snippet::parse_with_variables(
+            "${TM_FILENAME/([^.]*).*/${1:/pascalcase}/} ${TM_FILENAME/([^.]*).*/${1:/camelcase}/} ${TM_FILENAME/(\\.ts)?$/${1:-.rs}/}",
+            &variables,
+        )
+        .unwrap();
+        assert_eq!(
+            snippet.text,
+            "MySnippetFile mySnippetFile my-snippet_file.json.rs"
+        );
+    }
+
     fn tabstops(snippet: &Snippet) -> Vec<Vec<Range<isize>>> {
         snippet.tabstops.iter().map(|t| t.to_vec()).collect()
     }
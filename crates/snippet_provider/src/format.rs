@@ -0,0 +1,42 @@
+use collections::BTreeMap;
+use serde::Deserialize;
+
+/// A snippets file in the VS Code format: the snippets by name.
+#[derive(Deserialize)]
+pub(crate) struct VsSnippetsFile(pub BTreeMap<String, VsCodeSnippet>);
+
+#[derive(Deserialize)]
+pub(crate) struct VsCodeSnippet {
+    #[serde(default)]
+    pub prefix: Option<ListOrDirect>,
+    pub body: ListOrDirect,
+    #[serde(default)]
+    pub description: Option<ListOrDirect>,
+    /// The comma-separated languages of the snippet, only used in the files for all languages.
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum ListOrDirect {
+    Single(String),
+    List(Vec<String>),
+}
+
+impl ListOrDirect {
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            ListOrDirect::Single(value) => vec![value],
+            ListOrDirect::List(values) => values,
+        }
+    }
+
+    /// The value, joining the lines of a list with newlines.
+    pub fn join_lines(self) -> String {
+        match self {
+            ListOrDirect::Single(value) => value,
+            ListOrDirect::List(lines) => lines.join("\n"),
+        }
+    }
+}
@@ -0,0 +1,314 @@
+mod format;
+
+use anyhow::{Context as _, Result};
+use collections::BTreeMap;
+use format::{ListOrDirect, VsSnippetsFile};
+use fs::Fs;
+use futures::StreamExt;
+use gpui::{AppContext, Global};
+use parking_lot::RwLock;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use util::{paths, ResultExt};
+
+/// A snippet defined by the user or by an extension, offered as a completion for its prefixes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserSnippet {
+    pub name: String,
+    pub prefixes: Vec<String>,
+    /// The text to insert, in the LSP snippet syntax.
+    pub body: String,
+    pub description: Option<String>,
+    /// The VS Code identifiers of the languages the snippet applies to, or `None` for all of them.
+    pub languages: Option<Vec<String>>,
+}
+
+impl UserSnippet {
+    /// Whether the snippet applies to the language with the given name, e.g. `Rust` or `C++`.
+    pub fn applies_to(&self, language_name: Option<&str>) -> bool {
+        let Some(languages) = &self.languages else {
+            return true;
+        };
+        let Some(language_name) = language_name else {
+            return false;
+        };
+        let language_id = language_id(language_name);
+        languages.iter().any(|language| *language == language_id)
+    }
+}
+
+/// The VS Code identifier of the language with the given name.
+fn language_id(language_name: &str) -> String {
+    match language_name {
+        "C++" => "cpp".to_string(),
+        "C#" => "csharp".to_string(),
+        "TSX" => "typescriptreact".to_string(),
+        _ => language_name.to_lowercase().replace(' ', ""),
+    }
+}
+
+/// Whether the file at the given path may contain snippets.
+pub fn is_snippets_file(path: &Path) -> bool {
+    path.extension().map_or(false, |extension| {
+        extension == "json" || extension == "code-snippets"
+    })
+}
+
+/// Parses a snippets file in the VS Code format.
+///
+/// The snippets of a `<language>.json` file apply to that language, while the ones of `snippets.json`
+/// and `*.code-snippets` files apply to the languages of their `scope`, or to all languages without one.
+pub fn parse_snippets_file(path: &Path, contents: &str) -> Result<Vec<Arc<UserSnippet>>> {
+    if contents.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let is_global = path
+        .extension()
+        .map_or(false, |extension| extension == "code-snippets")
+        || path.file_stem().map_or(false, |stem| stem == "snippets");
+    let file_language = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|_| !is_global)
+        .map(|stem| stem.to_lowercase());
+
+    let file: VsSnippetsFile = serde_json_lenient::from_str(contents)
+        .with_context(|| format!("parsing snippets file {path:?}"))?;
+    Ok(file
+        .0
+        .into_iter()
+        .map(|(name, snippet)| {
+            let languages = match &file_language {
+                Some(language) => Some(vec![language.clone()]),
+                None => snippet
+                    .scope
+                    .map(|scope| {
+                        scope
+                            .split(',')
+                            .map(|language| language.trim().to_lowercase())
+                            .filter(|language| !language.is_empty())
+                            .collect::<Vec<_>>()
+                    })
+                    .filter(|languages| !languages.is_empty()),
+            };
+            Arc::new(UserSnippet {
+                prefixes: snippet
+                    .prefix
+                    .map_or_else(|| vec![name.clone()], ListOrDirect::into_vec),
+                body: snippet.body.join_lines(),
+                description: snippet.description.map(ListOrDirect::join_lines),
+                languages,
+                name,
+            })
+        })
+        .collect())
+}
+
+#[derive(Default)]
+struct GlobalSnippetRegistry(Arc<SnippetRegistry>);
+
+impl Global for GlobalSnippetRegistry {}
+
+/// The snippets of the user's config directory and of the extensions, shared by all projects.
+#[derive(Default)]
+pub struct SnippetRegistry {
+    files: RwLock<BTreeMap<PathBuf, Vec<Arc<UserSnippet>>>>,
+}
+
+impl SnippetRegistry {
+    /// Returns the global [`SnippetRegistry`].
+    pub fn global(cx: &AppContext) -> Arc<Self> {
+        cx.global::<GlobalSnippetRegistry>().0.clone()
+    }
+
+    /// Returns the global [`SnippetRegistry`], inserting an empty one if it does not exist yet.
+    pub fn default_global(cx: &mut AppContext) -> Arc<Self> {
+        cx.default_global::<GlobalSnippetRegistry>().0.clone()
+    }
+
+    pub fn register_file(&self, path: PathBuf, contents: &str) -> Result<()> {
+        let snippets = parse_snippets_file(&path, contents)?;
+        self.files.write().insert(path, snippets);
+        Ok(())
+    }
+
+    pub fn remove_files(&self, paths: &[PathBuf]) {
+        let mut files = self.files.write();
+        for path in paths {
+            files.remove(path);
+        }
+    }
+
+    /// Loads the snippets of the file at the given path, or forgets them if the file was removed.
+    pub async fn reload_file(&self, path: &Path, fs: &dyn Fs) -> Result<()> {
+        if fs
+            .metadata(path)
+            .await?
+            .map_or(true, |metadata| metadata.is_dir)
+        {
+            self.remove_files(&[path.to_path_buf()]);
+            return Ok(());
+        }
+        let contents = fs.load(path).await?;
+        self.register_file(path.to_path_buf(), &contents)
+    }
+
+    pub async fn load_dir(&self, dir: &Path, fs: &dyn Fs) -> Result<()> {
+        let mut paths = fs
+            .read_dir(dir)
+            .await
+            .with_context(|| format!("reading snippets from {dir:?}"))?;
+        while let Some(path) = paths.next().await {
+            let Some(path) = path.log_err() else {
+                continue;
+            };
+            if is_snippets_file(&path) {
+                self.reload_file(&path, fs).await.log_err();
+            }
+        }
+        Ok(())
+    }
+
+    pub fn snippets_for(&self, language_name: Option<&str>) -> Vec<Arc<UserSnippet>> {
+        self.files
+            .read()
+            .values()
+            .flatten()
+            .filter(|snippet| snippet.applies_to(language_name))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Loads the snippets of the user's config directory, reloading them when they change.
+pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+    let registry = SnippetRegistry::default_global(cx);
+    cx.spawn(|_| async move {
+        let snippets_dir = paths::SNIPPETS_DIR.as_path();
+        if fs.metadata(snippets_dir).await?.is_none() {
+            fs.create_dir(snippets_dir)
+                .await
+                .with_context(|| format!("creating snippets dir at {snippets_dir:?}"))?;
+        }
+        registry.load_dir(snippets_dir, fs.as_ref()).await?;
+
+        let mut events = fs.watch(snippets_dir, Duration::from_millis(100)).await;
+        while let Some(paths) = events.next().await {
+            for path in paths {
+                if is_snippets_file(&path) {
+                    registry.reload_file(&path, fs.as_ref()).await.log_err();
+                }
+            }
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+/// The snippets available in a project: the ones from its `.zed/snippets` directories,
+/// followed by the ones of the [`SnippetRegistry`].
+#[derive(Default)]
+pub struct SnippetProvider {
+    local_files: BTreeMap<PathBuf, Vec<Arc<UserSnippet>>>,
+}
+
+impl SnippetProvider {
+    pub fn insert_local_file(&mut self, abs_path: PathBuf, contents: &str) -> Result<()> {
+        let snippets = parse_snippets_file(&abs_path, contents)?;
+        self.local_files.insert(abs_path, snippets);
+        Ok(())
+    }
+
+    pub fn remove_local_file(&mut self, abs_path: &Path) {
+        self.local_files.remove(abs_path);
+    }
+
+    pub fn snippets_for(
+        &self,
+        language_name: Option<&str>,
+        cx: &AppContext,
+    ) -> Vec<Arc<UserSnippet>> {
+        let mut snippets = self
+            .local_files
+            .values()
+            .flatten()
+            .filter(|snippet| snippet.applies_to(language_name))
+            .cloned()
+            .collect::<Vec<_>>();
+        if let Some(registry) = cx.try_global::<GlobalSnippetRegistry>() {
+            snippets.extend(registry.0.snippets_for(language_name));
+        }
+        snippets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_loading_snippets_files(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/snippets",
+            json!({
+                "rust.json": r##"{
+                    // Comments are allowed, as in VS Code.
+                    "Test function": {
+                        "prefix": ["test", "tst"],
+                        "body": ["#[test]", "fn ${1:name}() {", "    $0", "}"],
+                        "description": "A unit test",
+                    }
+                }"##,
+                "snippets.json": r#"{
+                    "Todo": { "body": "TODO($CURRENT_YEAR): $0" },
+                    "Component": {
+                        "prefix": "comp",
+                        "body": "export const $1 = () => {}",
+                        "scope": "typescript, typescriptreact"
+                    }
+                }"#,
+                "notes.txt": "not snippets",
+            }),
+        )
+        .await;
+
+        let registry = SnippetRegistry::default();
+        registry
+            .load_dir(Path::new("/snippets"), fs.as_ref())
+            .await
+            .unwrap();
+        let names = |language_name| {
+            registry
+                .snippets_for(language_name)
+                .iter()
+                .map(|snippet| snippet.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(Some("Rust")), ["Test function", "Todo"]);
+        assert_eq!(names(Some("TSX")), ["Component", "Todo"]);
+        assert_eq!(names(None), ["Todo"]);
+
+        let rust_snippet = &registry.snippets_for(Some("Rust"))[0];
+        assert_eq!(rust_snippet.prefixes, ["test", "tst"]);
+        assert_eq!(rust_snippet.body, "#[test]\nfn ${1:name}() {\n    $0\n}");
+        assert_eq!(rust_snippet.description.as_deref(), Some("A unit test"));
+        assert_eq!(registry.snippets_for(None)[0].prefixes, ["Todo"]);
+
+        fs.remove_file(Path::new("/snippets/rust.json"), Default::default())
+            .await
+            .unwrap();
+        registry
+            .reload_file(Path::new("/snippets/rust.json"), fs.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(names(Some("Rust")), ["Todo"]);
+    }
+}
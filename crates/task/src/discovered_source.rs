@@ -0,0 +1,357 @@
+//! A source of tasks, discovered in the package manifests and build files of the project:
+//! `package.json` scripts, Cargo packages, binaries, examples and workspaces, `Makefile` targets and `justfile` recipes.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use futures::{channel::mpsc::UnboundedReceiver, StreamExt};
+use gpui::{AppContext, Context, Model, ModelContext};
+
+use crate::{
+    problem_matcher::{BuiltInProblemMatcher, ProblemMatcher},
+    static_source::RevealStrategy,
+    SpawnInTerminal, Task, TaskContext, TaskId, TaskSource,
+};
+
+/// A kind of file the tasks are discovered in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DiscoveredFile {
+    /// `package.json` of an npm package, with its `scripts`.
+    PackageJson,
+    /// `Cargo.toml` of a Cargo package or workspace.
+    CargoToml,
+    /// `Makefile` with its targets.
+    Makefile,
+    /// `justfile` with its recipes.
+    Justfile,
+}
+
+impl DiscoveredFile {
+    /// Determines the kind of file to discover tasks in by its name, if any.
+    pub fn for_file_name(file_name: &str) -> Option<Self> {
+        match file_name {
+            "package.json" => Some(Self::PackageJson),
+            "Cargo.toml" => Some(Self::CargoToml),
+            "Makefile" | "makefile" | "GNUmakefile" => Some(Self::Makefile),
+            "justfile" | "Justfile" | ".justfile" => Some(Self::Justfile),
+            _ => None,
+        }
+    }
+
+    /// Name of the tool running the tasks discovered in the file.
+    pub fn tool(&self) -> &'static str {
+        match self {
+            Self::PackageJson => "npm",
+            Self::CargoToml => "cargo",
+            Self::Makefile => "make",
+            Self::Justfile => "just",
+        }
+    }
+
+    fn commands(&self, contents: &str) -> Vec<Vec<String>> {
+        match self {
+            Self::PackageJson => npm_scripts(contents),
+            Self::CargoToml => cargo_commands(contents),
+            Self::Makefile => make_targets(contents),
+            Self::Justfile => just_recipes(contents),
+        }
+    }
+}
+
+/// A task, running a command of the tool the file it was discovered in belongs to.
+#[derive(Clone, Debug, PartialEq)]
+struct DiscoveredTask {
+    id: TaskId,
+    label: String,
+    /// Arguments of the tool, e.g. `["run", "build"]` for `npm run build`.
+    args: Vec<String>,
+    dir: PathBuf,
+    file: DiscoveredFile,
+}
+
+impl Task for DiscoveredTask {
+    fn id(&self) -> &TaskId {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.label
+    }
+
+    fn cwd(&self) -> Option<&str> {
+        self.dir.to_str()
+    }
+
+    fn exec(&self, cx: TaskContext) -> Option<SpawnInTerminal> {
+        Some(SpawnInTerminal {
+            id: self.id.clone(),
+            label: self.label.clone(),
+            command: self.file.tool().to_string(),
+            args: self.args.clone(),
+            cwd: Some(self.dir.clone()),
+            env: cx.env,
+            use_new_terminal: false,
+            allow_concurrent_runs: false,
+            reveal: RevealStrategy::default(),
+            problem_matcher: match self.file {
+                DiscoveredFile::CargoToml => {
+                    Some(ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc))
+                }
+                _ => None,
+            },
+        })
+    }
+}
+
+/// The source of tasks discovered in a single package manifest or build file, updated as the file changes.
+pub struct DiscoveredSource {
+    tasks: Vec<DiscoveredTask>,
+    _file_tracker: gpui::Task<anyhow::Result<()>>,
+}
+
+impl DiscoveredSource {
+    /// Initializes the source, re-discovering the tasks on every change of the file contents.
+    pub fn new(
+        file: DiscoveredFile,
+        abs_path: PathBuf,
+        mut file_tracker: UnboundedReceiver<String>,
+        cx: &mut AppContext,
+    ) -> Model<Box<dyn TaskSource>> {
+        cx.new_model(|cx| {
+            let _file_tracker = cx.spawn(|source, mut cx| async move {
+                while let Some(contents) = file_tracker.next().await {
+                    let tasks = discover_tasks(file, &abs_path, &contents);
+                    source.update(&mut cx, |source, cx| {
+                        if let Some(source) = source.as_any().downcast_mut::<Self>() {
+                            if source.tasks != tasks {
+                                source.tasks = tasks;
+                                cx.notify();
+                            }
+                        }
+                    })?;
+                }
+                anyhow::Ok(())
+            });
+            Box::new(Self {
+                tasks: Vec::new(),
+                _file_tracker,
+            }) as Box<dyn TaskSource>
+        })
+    }
+}
+
+impl TaskSource for DiscoveredSource {
+    fn as_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn tasks_for_path(
+        &mut self,
+        _: Option<&Path>,
+        _: &mut ModelContext<Box<dyn TaskSource>>,
+    ) -> Vec<Arc<dyn Task>> {
+        self.tasks
+            .iter()
+            .cloned()
+            .map(|task| Arc::new(task) as Arc<dyn Task>)
+            .collect()
+    }
+}
+
+fn discover_tasks(file: DiscoveredFile, abs_path: &Path, contents: &str) -> Vec<DiscoveredTask> {
+    let dir = abs_path.parent().unwrap_or(abs_path).to_path_buf();
+    file.commands(contents)
+        .into_iter()
+        .map(|args| {
+            let label = format!("{} {}", file.tool(), args.join(" "));
+            DiscoveredTask {
+                id: TaskId(format!("discovered_{}_{label}", abs_path.display())),
+                label,
+                args,
+                dir: dir.clone(),
+                file,
+            }
+        })
+        .collect()
+}
+
+fn npm_scripts(contents: &str) -> Vec<Vec<String>> {
+    let Ok(package) = serde_json_lenient::from_str::<serde_json_lenient::Value>(contents) else {
+        return Vec::new();
+    };
+    package
+        .get("scripts")
+        .and_then(|scripts| scripts.as_object())
+        .map(|scripts| {
+            scripts
+                .keys()
+                .map(|script| vec!["run".to_string(), script.clone()])
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn cargo_commands(contents: &str) -> Vec<Vec<String>> {
+    let Ok(manifest) = toml::from_str::<toml::Value>(contents) else {
+        return Vec::new();
+    };
+    let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+    let mut commands = Vec::new();
+    if manifest.get("workspace").is_some() {
+        commands.push(args(&["build", "--workspace"]));
+        commands.push(args(&["test", "--workspace"]));
+    }
+    let Some(package) = manifest
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(|name| name.as_str())
+    else {
+        return commands;
+    };
+    commands.push(args(&["build", "-p", package]));
+    commands.push(args(&["test", "-p", package]));
+    let target_names = |kind: &str| {
+        manifest
+            .get(kind)
+            .and_then(|targets| targets.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|target| target.get("name")?.as_str())
+            .collect::<Vec<_>>()
+    };
+    let bins = target_names("bin");
+    if bins.is_empty() {
+        commands.push(args(&["run", "-p", package]));
+    }
+    for bin in bins {
+        commands.push(args(&["run", "-p", package, "--bin", bin]));
+    }
+    for example in target_names("example") {
+        commands.push(args(&["run", "-p", package, "--example", example]));
+    }
+    commands
+}
+
+fn make_targets(contents: &str) -> Vec<Vec<String>> {
+    let mut targets = Vec::<String>::new();
+    for line in contents.lines() {
+        if line.starts_with(|c: char| c.is_whitespace() || c == '#') {
+            continue;
+        }
+        let Some((names, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if names.contains('=') || rest.starts_with('=') || rest.starts_with(":=") {
+            continue;
+        }
+        for name in names.split_whitespace() {
+            if !name.starts_with('.')
+                && !name.contains(['%', '$'])
+                && !targets.iter().any(|target| target == name)
+            {
+                targets.push(name.to_string());
+            }
+        }
+    }
+    targets.into_iter().map(|target| vec![target]).collect()
+}
+
+fn just_recipes(contents: &str) -> Vec<Vec<String>> {
+    let mut recipes = Vec::<String>::new();
+    for line in contents.lines() {
+        if line.starts_with(|c: char| c.is_whitespace() || matches!(c, '#' | '[')) {
+            continue;
+        }
+        let Some((signature, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if rest.starts_with('=') {
+            continue;
+        }
+        let Some(name) = signature.trim_start_matches('@').split_whitespace().next() else {
+            continue;
+        };
+        let is_identifier = name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'));
+        if is_identifier
+            && !name.starts_with('_')
+            && !matches!(name, "set" | "alias" | "export" | "import" | "mod")
+            && !recipes.iter().any(|recipe| recipe == name)
+        {
+            recipes.push(name.to_string());
+        }
+    }
+    recipes.into_iter().map(|recipe| vec![recipe]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(file: DiscoveredFile, contents: &str) -> Vec<String> {
+        discover_tasks(file, Path::new("/project/file"), contents)
+            .into_iter()
+            .map(|task| task.label)
+            .collect()
+    }
+
+    #[test]
+    fn test_discovered_tasks() {
+        assert_eq!(
+            labels(
+                DiscoveredFile::PackageJson,
+                r#"{
+                    "name": "app",
+                    "scripts": { "build": "tsc", "test": "jest", },
+                }"#
+            ),
+            vec!["npm run build", "npm run test"]
+        );
+
+        assert_eq!(
+            labels(
+                DiscoveredFile::CargoToml,
+                r#"
+                [workspace]
+                members = ["crates/*"]
+
+                [package]
+                name = "app"
+
+                [[bin]]
+                name = "cli"
+
+                [[example]]
+                name = "hello"
+                "#
+            ),
+            vec![
+                "cargo build --workspace",
+                "cargo test --workspace",
+                "cargo build -p app",
+                "cargo test -p app",
+                "cargo run -p app --bin cli",
+                "cargo run -p app --example hello",
+            ]
+        );
+
+        assert_eq!(
+            labels(
+                DiscoveredFile::Makefile,
+                "CC := gcc\nVERSION = 1\n.PHONY: all clean\n\nall: app\n\tcc -o app main.c\n%.o: %.c\n\tcc -c $<\nclean test:\n\trm -f app\n"
+            ),
+            vec!["make all", "make clean", "make test"]
+        );
+
+        assert_eq!(
+            labels(
+                DiscoveredFile::Justfile,
+                "set shell := [\"bash\", \"-c\"]\nalias b := build\n\n# Builds the app\nbuild target='debug':\n    cargo build\n\n[private]\n_setup:\n    echo setup\n@lint: build\n    cargo clippy\n"
+            ),
+            vec!["just build", "just lint"]
+        );
+    }
+}
@@ -1,11 +1,17 @@
 //! Baseline interface of Tasks in Zed: all tasks in Zed are intended to use those for implementing their own logic.
 #![deny(missing_docs)]
 
+pub mod discovered_source;
 pub mod oneshot_source;
+pub mod problem_matcher;
 pub mod static_source;
+pub mod vscode_format;
 
 use collections::HashMap;
 use gpui::ModelContext;
+use problem_matcher::ProblemMatcher;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use static_source::RevealStrategy;
 use std::any::Any;
 use std::path::{Path, PathBuf};
@@ -37,6 +43,8 @@ pub struct SpawnInTerminal {
     pub allow_concurrent_runs: bool,
     /// What to do with the terminal pane and tab, after the command was started.
     pub reveal: RevealStrategy,
+    /// How to find problems in the output of the command, to report them as diagnostics.
+    pub problem_matcher: Option<ProblemMatcher>,
 }
 
 /// Keeps track of the file associated with a task and context of tasks execution (i.e. current file or current function)
@@ -48,6 +56,58 @@ pub struct TaskContext {
     pub env: HashMap<String, String>,
 }
 
+/// Other tasks to run around a task, referred to by their labels.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TaskDependencies {
+    /// Labels of the tasks to run before the task, which is not run if any of them fails.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Whether to run the tasks the task depends on one after another, in the order listed, or all at once.
+    #[serde(default)]
+    pub run_mode: RunMode,
+    /// Label of the task to run after the task succeeds.
+    #[serde(default)]
+    pub on_success: Option<String>,
+    /// Label of the task to run after the task fails.
+    #[serde(default)]
+    pub on_failure: Option<String>,
+}
+
+impl TaskDependencies {
+    /// Whether there are no other tasks to run around the task.
+    pub fn is_empty(&self) -> bool {
+        self.depends_on.is_empty() && self.on_success.is_none() && self.on_failure.is_none()
+    }
+}
+
+/// How to run the tasks a task depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RunMode {
+    /// One after another, in the order listed, stopping at the first failure (default).
+    #[default]
+    Sequential,
+    /// All at once.
+    Parallel,
+}
+
+/// State of the latest run of a task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskStatus {
+    /// The task is waiting for the tasks it depends on, or is running.
+    Running,
+    /// The task exited with code 0.
+    Succeeded,
+    /// The task exited with a non-zero code, or its exit code is unknown.
+    Failed {
+        /// Exit code of the task, if known.
+        exit_code: Option<i32>,
+    },
+    /// The task was not run, as one of the tasks it depends on failed.
+    Skipped,
+}
+
 /// Represents a short lived recipe of a task, whose main purpose
 /// is to get spawned.
 pub trait Task {
@@ -60,6 +120,10 @@ pub trait Task {
     /// Sets up everything needed to spawn the task in the given directory (`cwd`).
     /// If a task is intended to be spawned in the terminal, it should return the corresponding struct filled with the data necessary.
     fn exec(&self, cx: TaskContext) -> Option<SpawnInTerminal>;
+    /// Other tasks to run before and after the task, if any.
+    fn dependencies(&self) -> Option<&TaskDependencies> {
+        None
+    }
 }
 
 /// [`Source`] produces tasks that can be scheduled.
@@ -0,0 +1,326 @@
+//! Problem matchers, extracting errors and warnings reported by compilers and linters out of the task output.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use regex::{Captures, RegexBuilder};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A way to find problems in the output of a task, to report them as diagnostics.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcher {
+    /// One of the matchers Zed knows the output format of, referred to by its name.
+    BuiltIn(BuiltInProblemMatcher),
+    /// A matcher for a custom output format.
+    Custom(CustomProblemMatcher),
+}
+
+/// Output formats of the common tools, with a problem matcher available out of the box.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltInProblemMatcher {
+    /// Errors and warnings of `rustc`, as printed by `cargo build` and `cargo check`.
+    Rustc,
+    /// Errors of the TypeScript compiler, with `--pretty false`.
+    Tsc,
+    /// Errors, warnings and notes of `gcc` and `clang`.
+    Gcc,
+    /// Problems reported by ESLint, with `--format compact`.
+    Eslint,
+    /// Errors of `go build` and `go vet`.
+    Go,
+}
+
+/// A problem matcher, defined by a regular expression.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CustomProblemMatcher {
+    /// Regular expression to match the problems with.
+    /// The named groups `file`, `line` and `message` are required, `column`, `severity` and `code` are optional.
+    /// `^` and `$` match at line boundaries, and the expression may span multiple lines.
+    pub pattern: String,
+    /// Severity of the problems that have no `severity` group matched.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+}
+
+/// How severe a problem is.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    /// Reported as an error (default).
+    #[default]
+    Error,
+    /// Reported as a warning.
+    Warning,
+    /// Reported as information.
+    Info,
+    /// Reported as a hint.
+    Hint,
+}
+
+impl ProblemSeverity {
+    fn from_output(severity: &str) -> Option<Self> {
+        match severity.to_lowercase().as_str() {
+            "error" | "fatal" | "fatal error" => Some(Self::Error),
+            "warning" | "warn" => Some(Self::Warning),
+            "info" | "information" => Some(Self::Info),
+            "note" | "hint" | "help" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// A single problem found in the task output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// Path of the file with the problem, as printed by the task: relative paths are relative to the task's working directory.
+    pub path: PathBuf,
+    /// Zero-based row of the problem.
+    pub row: u32,
+    /// Zero-based column of the problem.
+    pub column: u32,
+    /// How severe the problem is.
+    pub severity: ProblemSeverity,
+    /// Description of the problem.
+    pub message: String,
+    /// Code of the error or the lint that reported the problem, if any.
+    pub code: Option<String>,
+}
+
+impl BuiltInProblemMatcher {
+    fn pattern(&self) -> &'static str {
+        match self {
+            Self::Rustc => {
+                r"^(?P<severity>error|warning)(?:\[(?P<code>[^\]]+)\])?: (?P<message>.+)\n\s*--> (?P<file>[^\n]+?):(?P<line>\d+):(?P<column>\d+)"
+            }
+            Self::Tsc => {
+                r"^(?P<file>[^\s(][^(\n]*)\((?P<line>\d+),(?P<column>\d+)\): (?P<severity>error|warning|info) (?P<code>TS\d+): (?P<message>.+)$"
+            }
+            Self::Gcc => {
+                r"^(?P<file>[^:\s][^:\n]*):(?P<line>\d+):(?P<column>\d+): (?P<severity>fatal error|error|warning|note): (?P<message>.+)$"
+            }
+            Self::Eslint => {
+                r"^(?P<file>[^:\n]+): line (?P<line>\d+), col (?P<column>\d+), (?P<severity>Error|Warning|Info) - (?P<message>.+?)(?: \((?P<code>[^)\n]+)\))?$"
+            }
+            Self::Go => {
+                r"^(?:\./)?(?P<file>[^:\s][^:\n]*\.go):(?P<line>\d+)(?::(?P<column>\d+))?: (?P<message>.+)$"
+            }
+        }
+    }
+}
+
+impl ProblemMatcher {
+    /// Finds all problems reported in the given task output.
+    pub fn find_problems(&self, output: &str) -> Result<Vec<Problem>> {
+        let (pattern, default_severity) = match self {
+            Self::BuiltIn(matcher) => (matcher.pattern(), ProblemSeverity::Error),
+            Self::Custom(matcher) => (matcher.pattern.as_str(), matcher.severity),
+        };
+        let regex = RegexBuilder::new(pattern).multi_line(true).build()?;
+        Ok(regex
+            .captures_iter(output)
+            .filter_map(|captures| problem(&captures, default_severity))
+            .collect())
+    }
+}
+
+fn problem(captures: &Captures, default_severity: ProblemSeverity) -> Option<Problem> {
+    let number = |name| {
+        captures
+            .name(name)
+            .and_then(|number| number.as_str().parse::<u32>().ok())
+    };
+    let path = captures.name("file")?.as_str().trim();
+    let message = captures.name("message")?.as_str().trim();
+    if path.is_empty() || message.is_empty() {
+        return None;
+    }
+    Some(Problem {
+        path: PathBuf::from(path),
+        row: number("line")?.saturating_sub(1),
+        column: number("column").unwrap_or(1).saturating_sub(1),
+        severity: captures
+            .name("severity")
+            .and_then(|severity| ProblemSeverity::from_output(severity.as_str()))
+            .unwrap_or(default_severity),
+        message: message.to_string(),
+        code: captures.name("code").map(|code| code.as_str().to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem(
+        path: &str,
+        row: u32,
+        column: u32,
+        severity: ProblemSeverity,
+        message: &str,
+        code: Option<&str>,
+    ) -> Problem {
+        Problem {
+            path: PathBuf::from(path),
+            row,
+            column,
+            severity,
+            message: message.to_string(),
+            code: code.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn test_built_in_matchers() {
+        let rustc_output = r#"   Compiling zed v0.1.0 (/zed)
+error[E0308]: mismatched types
+  --> src/main.rs:2:18
+   |
+2  |     let x: u32 = "one";
+   |            ---   ^^^^^ expected `u32`, found `&str`
+
+warning: unused variable: `y`
+ --> src/lib.rs:10:9
+  |
+error: could not compile `zed` (bin "zed") due to 1 previous error; 1 warning emitted"#;
+        assert_eq!(
+            ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)
+                .find_problems(rustc_output)
+                .unwrap(),
+            vec![
+                problem(
+                    "src/main.rs",
+                    1,
+                    17,
+                    ProblemSeverity::Error,
+                    "mismatched types",
+                    Some("E0308")
+                ),
+                problem(
+                    "src/lib.rs",
+                    9,
+                    8,
+                    ProblemSeverity::Warning,
+                    "unused variable: `y`",
+                    None
+                ),
+            ]
+        );
+
+        let tsc_output = "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\nFound 1 error.";
+        assert_eq!(
+            ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)
+                .find_problems(tsc_output)
+                .unwrap(),
+            vec![problem(
+                "src/index.ts",
+                2,
+                6,
+                ProblemSeverity::Error,
+                "Type 'string' is not assignable to type 'number'.",
+                Some("TS2322")
+            )]
+        );
+
+        let gcc_output = "main.c: In function 'main':\nmain.c:4:5: warning: implicit declaration of function 'foo'\nmain.c:5:1: error: expected ';' before '}' token";
+        assert_eq!(
+            ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Gcc)
+                .find_problems(gcc_output)
+                .unwrap(),
+            vec![
+                problem(
+                    "main.c",
+                    3,
+                    4,
+                    ProblemSeverity::Warning,
+                    "implicit declaration of function 'foo'",
+                    None
+                ),
+                problem(
+                    "main.c",
+                    4,
+                    0,
+                    ProblemSeverity::Error,
+                    "expected ';' before '}' token",
+                    None
+                ),
+            ]
+        );
+
+        let eslint_output = "/app/src/a.js: line 1, col 10, Warning - 'a' is defined but never used. (no-unused-vars)\n\n1 problem";
+        assert_eq!(
+            ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Eslint)
+                .find_problems(eslint_output)
+                .unwrap(),
+            vec![problem(
+                "/app/src/a.js",
+                0,
+                9,
+                ProblemSeverity::Warning,
+                "'a' is defined but never used.",
+                Some("no-unused-vars")
+            )]
+        );
+
+        let go_output = "# example.com/app\n./main.go:7:2: undefined: fmt.Printn\nutil/util.go:12: missing return";
+        assert_eq!(
+            ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Go)
+                .find_problems(go_output)
+                .unwrap(),
+            vec![
+                problem(
+                    "main.go",
+                    6,
+                    1,
+                    ProblemSeverity::Error,
+                    "undefined: fmt.Printn",
+                    None
+                ),
+                problem(
+                    "util/util.go",
+                    11,
+                    0,
+                    ProblemSeverity::Error,
+                    "missing return",
+                    None
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_matcher() {
+        let matcher: ProblemMatcher = serde_json_lenient::from_str(
+            r#"{
+                "pattern": "^(?P<file>\\S+) \\[(?P<line>\\d+)\\] (?P<message>.+)$",
+                "severity": "warning"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            matcher
+                .find_problems("checking...\nlib/a.py [12] line too long\ndone")
+                .unwrap(),
+            vec![problem(
+                "lib/a.py",
+                11,
+                0,
+                ProblemSeverity::Warning,
+                "line too long",
+                None
+            )]
+        );
+
+        let matcher: ProblemMatcher = serde_json_lenient::from_str(r#""gcc""#).unwrap();
+        assert_eq!(matcher, ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Gcc));
+
+        let invalid = ProblemMatcher::Custom(CustomProblemMatcher {
+            pattern: "(?P<file>".to_string(),
+            severity: ProblemSeverity::Error,
+        });
+        assert!(invalid.find_problems("").is_err());
+    }
+}
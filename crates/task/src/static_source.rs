@@ -3,14 +3,16 @@
 use std::{borrow::Cow, path::Path, sync::Arc};
 
 use collections::HashMap;
-use futures::StreamExt;
+use futures::{Stream, StreamExt};
 use gpui::{AppContext, Context, Model, ModelContext, Subscription};
 use schemars::{gen::SchemaSettings, JsonSchema};
 use serde::{Deserialize, Serialize};
 use util::ResultExt;
 
-use crate::{SpawnInTerminal, Task, TaskContext, TaskId, TaskSource};
-use futures::channel::mpsc::UnboundedReceiver;
+use crate::{
+    problem_matcher::ProblemMatcher, SpawnInTerminal, Task, TaskContext, TaskDependencies, TaskId,
+    TaskSource,
+};
 
 /// A single config file entry with the deserialized task definition.
 #[derive(Clone, Debug, PartialEq)]
@@ -40,6 +42,7 @@ impl Task for StaticTask {
             args: self.definition.args.clone(),
             reveal: self.definition.reveal,
             env: definition_env,
+            problem_matcher: self.definition.problem_matcher.clone(),
         })
     }
 
@@ -54,6 +57,10 @@ impl Task for StaticTask {
     fn cwd(&self) -> Option<&str> {
         self.definition.cwd.as_deref()
     }
+
+    fn dependencies(&self) -> Option<&TaskDependencies> {
+        Some(&self.definition.dependencies)
+    }
 }
 
 /// The source of tasks defined in a tasks config file.
@@ -91,6 +98,16 @@ pub(crate) struct Definition {
     /// * `never` — avoid changing current terminal pane focus, but still add/reuse the task's tab there
     #[serde(default)]
     pub reveal: RevealStrategy,
+    /// How to find problems in the output of the command, to show them as diagnostics until the task is rerun.
+    /// Either the name of a built-in matcher (`rustc`, `tsc`, `gcc`, `eslint` or `go`),
+    /// or an object with the regular expression `pattern` to match the problems with.
+    #[serde(default)]
+    pub problem_matcher: Option<ProblemMatcher>,
+    /// Other tasks to run around this one: `depends_on` lists the labels of the tasks to run before it,
+    /// `run_mode` tells whether to run them `sequential`ly (default) or in `parallel`,
+    /// and `on_success` and `on_failure` name the tasks to follow it up with.
+    #[serde(flatten)]
+    pub dependencies: TaskDependencies,
 }
 
 /// What to do with the terminal pane and tab, after the command was started.
@@ -106,7 +123,7 @@ pub enum RevealStrategy {
 
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
-pub struct DefinitionProvider(Vec<Definition>);
+pub struct DefinitionProvider(pub(crate) Vec<Definition>);
 
 impl DefinitionProvider {
     /// Generates JSON schema of Tasks JSON definition format.
@@ -129,7 +146,7 @@ struct TrackedFile<T> {
 impl<T: for<'a> Deserialize<'a> + PartialEq + 'static> TrackedFile<T> {
     fn new(
         parsed_contents: T,
-        mut tracker: UnboundedReceiver<String>,
+        mut tracker: impl Stream<Item = String> + Unpin + 'static,
         cx: &mut AppContext,
     ) -> Model<Self> {
         cx.new_model(move |cx| {
@@ -165,7 +182,7 @@ impl StaticSource {
     /// Initializes the static source, reacting on tasks config changes.
     pub fn new(
         id_base: impl Into<Cow<'static, str>>,
-        tasks_file_tracker: UnboundedReceiver<String>,
+        tasks_file_tracker: impl Stream<Item = String> + Unpin + 'static,
         cx: &mut AppContext,
     ) -> Model<Box<dyn TaskSource>> {
         let definitions = TrackedFile::new(DefinitionProvider::default(), tasks_file_tracker, cx);
@@ -0,0 +1,711 @@
+//! Conversion of the VS Code `.vscode/tasks.json` tasks and `.vscode/launch.json` launch configurations into Zed tasks.
+
+use std::{borrow::Cow, collections::BTreeMap, path::Path};
+
+use anyhow::{Context, Result};
+use collections::HashMap;
+use serde::Deserialize;
+use serde_json_lenient::Value;
+use util::paths::{LOCAL_VSCODE_LAUNCH_RELATIVE_PATH, LOCAL_VSCODE_TASKS_RELATIVE_PATH};
+
+use crate::{
+    problem_matcher::{BuiltInProblemMatcher, ProblemMatcher},
+    static_source::{Definition, DefinitionProvider, RevealStrategy},
+    RunMode, TaskDependencies,
+};
+
+/// A VS Code file with the definitions of the commands to run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VsCodeFile {
+    /// `.vscode/tasks.json` with the tasks.
+    Tasks,
+    /// `.vscode/launch.json` with the launch configurations, run as tasks without a debugger.
+    Launch,
+}
+
+impl VsCodeFile {
+    /// Determines the kind of VS Code file by its path, if any.
+    pub fn for_path(abs_path: &Path) -> Option<Self> {
+        if abs_path.ends_with(&*LOCAL_VSCODE_TASKS_RELATIVE_PATH) {
+            Some(Self::Tasks)
+        } else if abs_path.ends_with(&*LOCAL_VSCODE_LAUNCH_RELATIVE_PATH) {
+            Some(Self::Launch)
+        } else {
+            None
+        }
+    }
+
+    /// Path of the file, relative to the worktree root.
+    pub fn relative_path(&self) -> &'static Path {
+        match self {
+            Self::Tasks => &LOCAL_VSCODE_TASKS_RELATIVE_PATH,
+            Self::Launch => &LOCAL_VSCODE_LAUNCH_RELATIVE_PATH,
+        }
+    }
+
+    /// Converts the contents of the file (JSON with comments and trailing commas) into Zed task definitions.
+    pub fn convert(&self, contents: &str) -> Result<ConvertedTasks> {
+        let mut converted = ConvertedTasks::default();
+        match self {
+            Self::Tasks => {
+                let file: TasksFile =
+                    serde_json_lenient::from_str(contents).context("invalid VS Code tasks file")?;
+                converted.report_unsupported("tasks.json", ignore(file.other, &["version"]));
+                for task in file.tasks {
+                    converted.add_task(task);
+                }
+            }
+            Self::Launch => {
+                let file: LaunchFile = serde_json_lenient::from_str(contents)
+                    .context("invalid VS Code launch configurations file")?;
+                converted.report_unsupported("launch.json", ignore(file.other, &["version"]));
+                for configuration in file.configurations {
+                    converted.add_launch_configuration(configuration);
+                }
+            }
+        }
+        Ok(converted)
+    }
+}
+
+/// Zed task definitions, converted from a VS Code file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConvertedTasks {
+    definitions: Vec<Definition>,
+    /// Descriptions of everything that could not be converted and was left out.
+    pub warnings: Vec<String>,
+}
+
+impl ConvertedTasks {
+    /// Whether no tasks were converted.
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    /// Appends the tasks and the warnings of another conversion.
+    pub fn extend(&mut self, other: ConvertedTasks) {
+        self.definitions.extend(other.definitions);
+        self.warnings.extend(other.warnings);
+    }
+
+    /// Serializes the tasks in the format of the Zed tasks file.
+    pub fn to_json(&self) -> String {
+        serde_json_lenient::to_string_pretty(&DefinitionProvider(self.definitions.clone())).unwrap()
+    }
+
+    fn add_task(&mut self, task: VsCodeTask) {
+        let mut warnings = Vec::new();
+        let mut unsupported = ignore(task.other, &["detail", "icon", "hide"]);
+        let kind = task.kind.as_deref().unwrap_or("process");
+        let command = task.command.map(|command| command.0);
+        let (command, args, default_label) = match (kind, command) {
+            ("shell" | "process", Some(command)) => {
+                let args = task
+                    .args
+                    .iter()
+                    .map(|arg| shell_arg(&arg.0, &mut warnings))
+                    .collect();
+                (command.clone(), args, command)
+            }
+            ("npm", _) if task.script.is_some() => {
+                let script = task.script.unwrap_or_default();
+                let label = format!("npm: {script}");
+                (
+                    "npm".to_string(),
+                    vec!["run".to_string(), shell_arg(&script, &mut warnings)],
+                    label,
+                )
+            }
+            ("cargo", Some(command)) => {
+                let mut args = vec![command.clone()];
+                args.extend(task.args.iter().map(|arg| shell_arg(&arg.0, &mut warnings)));
+                ("cargo".to_string(), args, format!("cargo {command}"))
+            }
+            (kind, _) => {
+                let label = task.label.as_deref().unwrap_or(kind);
+                self.warnings.push(format!(
+                    "task \"{label}\": tasks of type \"{kind}\" are not supported"
+                ));
+                return;
+            }
+        };
+        let label = task.label.unwrap_or(default_label);
+        if let Some(path) = task.path {
+            unsupported.push(format!("path ({path})"));
+        }
+
+        let mut cwd = None;
+        let mut env = HashMap::default();
+        if let Some(options) = task.options {
+            cwd = options
+                .cwd
+                .map(|cwd| substitute_variables(&cwd, &mut warnings));
+            env = options
+                .env
+                .into_iter()
+                .map(|(name, value)| (name, substitute_variables(&value, &mut warnings)))
+                .collect();
+            unsupported.extend(prefixed("options", options.other));
+        }
+
+        let mut reveal = RevealStrategy::default();
+        let mut use_new_terminal = false;
+        if let Some(presentation) = task.presentation {
+            match presentation.reveal.as_deref() {
+                None | Some("always") => {}
+                Some("silent" | "never") => reveal = RevealStrategy::Never,
+                Some(other) => unsupported.push(format!("presentation.reveal ({other})")),
+            }
+            match presentation.panel.as_deref() {
+                None | Some("shared" | "dedicated") => {}
+                Some("new") => use_new_terminal = true,
+                Some(other) => unsupported.push(format!("presentation.panel ({other})")),
+            }
+            unsupported.extend(prefixed("presentation", presentation.other));
+        }
+
+        let mut problem_matchers = task.problem_matcher.map(|matchers| matchers.0);
+        let problem_matcher = problem_matchers.as_mut().and_then(|matchers| {
+            let supported = matchers
+                .iter()
+                .position(|matcher| built_in_problem_matcher(matcher).is_some())?;
+            built_in_problem_matcher(&matchers.remove(supported))
+        });
+        for matcher in problem_matchers.into_iter().flatten() {
+            match matcher {
+                Value::String(name) => unsupported.push(format!("problemMatcher ({name})")),
+                _ => unsupported.push("problemMatcher (custom)".to_string()),
+            }
+        }
+
+        let depends_on = task
+            .depends_on
+            .map(|depends_on| depends_on.0)
+            .unwrap_or_default();
+        let run_mode = match task.depends_order.as_deref() {
+            Some("sequence") => RunMode::Sequential,
+            _ if depends_on.is_empty() => RunMode::default(),
+            // VS Code runs the tasks a task depends on in parallel by default.
+            _ => RunMode::Parallel,
+        };
+
+        let command = substitute_variables(&command, &mut warnings);
+        unsupported.extend(warnings);
+        self.report_unsupported(&format!("task \"{label}\""), unsupported);
+        self.definitions.push(Definition {
+            label,
+            command,
+            args,
+            env,
+            cwd,
+            use_new_terminal,
+            allow_concurrent_runs: false,
+            reveal,
+            problem_matcher,
+            dependencies: TaskDependencies {
+                depends_on,
+                run_mode,
+                on_success: None,
+                on_failure: None,
+            },
+        });
+    }
+
+    fn add_launch_configuration(&mut self, configuration: LaunchConfiguration) {
+        let name = configuration.name;
+        if configuration.request != "launch" {
+            self.warnings.push(format!(
+                "launch configuration \"{name}\": \"{}\" requests are not supported",
+                configuration.request
+            ));
+            return;
+        }
+        let mut warnings = Vec::new();
+        let program = configuration
+            .program
+            .map(|program| shell_arg(&program, &mut warnings));
+        let mut args = Vec::new();
+        let command = match (configuration.kind.as_str(), program) {
+            ("node" | "pwa-node", Some(program)) => {
+                args.extend(
+                    configuration
+                        .runtime_args
+                        .iter()
+                        .map(|arg| shell_arg(&arg.0, &mut warnings)),
+                );
+                args.push(program);
+                configuration
+                    .runtime_executable
+                    .unwrap_or_else(|| "node".to_string())
+            }
+            ("python" | "debugpy", program) => {
+                match (configuration.module, program) {
+                    (Some(module), _) => {
+                        args.extend(["-m".to_string(), shell_arg(&module, &mut warnings)])
+                    }
+                    (None, Some(program)) => args.push(program),
+                    (None, None) => {
+                        self.warnings.push(format!(
+                            "launch configuration \"{name}\": neither program nor module is set"
+                        ));
+                        return;
+                    }
+                }
+                configuration
+                    .python
+                    .unwrap_or_else(|| "python3".to_string())
+            }
+            ("go", Some(program)) => {
+                args.extend(["run".to_string(), program]);
+                "go".to_string()
+            }
+            ("lldb" | "cppdbg" | "cppvsdbg" | "codelldb", Some(program)) => program,
+            (kind, _) => {
+                self.warnings.push(format!(
+                    "launch configuration \"{name}\": configurations of type \"{kind}\" are not supported"
+                ));
+                return;
+            }
+        };
+        args.extend(
+            configuration
+                .args
+                .iter()
+                .map(|arg| shell_arg(&arg.0, &mut warnings)),
+        );
+        let cwd = configuration
+            .cwd
+            .map(|cwd| substitute_variables(&cwd, &mut warnings));
+        let env = configuration
+            .env
+            .into_iter()
+            .map(|(name, value)| (name, substitute_variables(&value, &mut warnings)))
+            .collect();
+        let mut unsupported = ignore(configuration.other, &["console", "internalConsoleOptions"]);
+        unsupported.extend(warnings);
+        self.report_unsupported(&format!("launch configuration \"{name}\""), unsupported);
+        self.definitions.push(Definition {
+            label: name,
+            command,
+            args,
+            env,
+            cwd,
+            use_new_terminal: false,
+            allow_concurrent_runs: false,
+            reveal: RevealStrategy::default(),
+            problem_matcher: None,
+            dependencies: TaskDependencies {
+                depends_on: configuration.pre_launch_task.into_iter().collect(),
+                ..TaskDependencies::default()
+            },
+        });
+    }
+
+    fn report_unsupported(&mut self, subject: &str, unsupported: Vec<String>) {
+        if !unsupported.is_empty() {
+            self.warnings
+                .push(format!("{subject}: unsupported {}", unsupported.join(", ")));
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TasksFile {
+    #[serde(default)]
+    tasks: Vec<VsCodeTask>,
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeTask {
+    label: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    command: Option<Arg>,
+    #[serde(default)]
+    args: Vec<Arg>,
+    script: Option<String>,
+    path: Option<String>,
+    options: Option<TaskOptions>,
+    depends_on: Option<OneOrMany<String>>,
+    depends_order: Option<String>,
+    presentation: Option<Presentation>,
+    problem_matcher: Option<OneOrMany<Value>>,
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct TaskOptions {
+    cwd: Option<String>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct Presentation {
+    reveal: Option<String>,
+    panel: Option<String>,
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct LaunchFile {
+    #[serde(default)]
+    configurations: Vec<LaunchConfiguration>,
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LaunchConfiguration {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    request: String,
+    program: Option<String>,
+    module: Option<String>,
+    python: Option<String>,
+    runtime_executable: Option<String>,
+    #[serde(default)]
+    runtime_args: Vec<Arg>,
+    #[serde(default)]
+    args: Vec<Arg>,
+    cwd: Option<String>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+    pre_launch_task: Option<String>,
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>,
+}
+
+/// A command or an argument, either a plain string or VS Code's `{ "value": ..., "quoting": ... }` object.
+struct Arg(String);
+
+impl<'de> Deserialize<'de> for Arg {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawArg {
+            Plain(String),
+            Quoted { value: String },
+        }
+
+        Ok(match RawArg::deserialize(deserializer)? {
+            RawArg::Plain(value) | RawArg::Quoted { value } => Self(value),
+        })
+    }
+}
+
+struct OneOrMany<T>(Vec<T>);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for OneOrMany<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw<T> {
+            One(T),
+            Many(Vec<T>),
+        }
+
+        Ok(match Raw::deserialize(deserializer)? {
+            Raw::One(value) => Self(vec![value]),
+            Raw::Many(values) => Self(values),
+        })
+    }
+}
+
+fn ignore(fields: BTreeMap<String, Value>, ignored: &[&str]) -> Vec<String> {
+    fields
+        .into_keys()
+        .filter(|field| !ignored.contains(&field.as_str()))
+        .collect()
+}
+
+fn prefixed(prefix: &str, fields: BTreeMap<String, Value>) -> Vec<String> {
+    fields
+        .into_keys()
+        .map(|field| format!("{prefix}.{field}"))
+        .collect()
+}
+
+fn built_in_problem_matcher(matcher: &Value) -> Option<ProblemMatcher> {
+    let matcher = match matcher.as_str()? {
+        "$rustc" => BuiltInProblemMatcher::Rustc,
+        "$tsc" => BuiltInProblemMatcher::Tsc,
+        "$gcc" => BuiltInProblemMatcher::Gcc,
+        "$eslint-compact" => BuiltInProblemMatcher::Eslint,
+        "$go" => BuiltInProblemMatcher::Go,
+        _ => return None,
+    };
+    Some(ProblemMatcher::BuiltIn(matcher))
+}
+
+/// Tasks get their arguments joined with spaces and run in a shell, so the arguments are turned
+/// into shell words: their variables are substituted first, each expanded variable being
+/// double-quoted so that its value stays a single word, and the text around them is quoted if it
+/// contains whitespace.
+fn shell_arg(arg: &str, unsupported: &mut Vec<String>) -> String {
+    split_variables(arg, unsupported)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => quote(&text),
+            Segment::Variable(name) => format!("\"${name}\""),
+        })
+        .collect()
+}
+
+fn quote(text: &str) -> String {
+    if !text.contains(char::is_whitespace) || text.starts_with(['"', '\'']) {
+        return text.to_string();
+    }
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        if matches!(c, '"' | '\\' | '`') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Replaces VS Code `${variables}` with the environment variables Zed sets for the tasks,
+/// reporting the ones with no Zed counterpart and leaving them as is.
+fn substitute_variables(text: &str, unsupported: &mut Vec<String>) -> String {
+    let segments = split_variables(text, unsupported);
+    let mut result = String::with_capacity(text.len());
+    for (ix, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Text(text) => result.push_str(text),
+            Segment::Variable(name) => {
+                let followed_by_name = matches!(
+                    segments.get(ix + 1),
+                    Some(Segment::Text(next))
+                        if next.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+                );
+                if followed_by_name {
+                    result.push_str(&format!("${{{name}}}"));
+                } else {
+                    result.push('$');
+                    result.push_str(name);
+                }
+            }
+        }
+    }
+    result
+}
+
+/// A part of a VS Code string, which is either text or one of its `${variables}`.
+enum Segment<'a> {
+    Text(Cow<'a, str>),
+    /// The environment variable a VS Code variable stands for.
+    Variable(&'a str),
+}
+
+fn split_variables<'a>(text: &'a str, unsupported: &mut Vec<String>) -> Vec<Segment<'a>> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        if start > 0 {
+            segments.push(Segment::Text(Cow::Borrowed(&rest[..start])));
+        }
+        let Some(len) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let variable = &rest[start + 2..start + len];
+        let text = &rest[start..start + len + 1];
+        rest = &rest[start + len + 1..];
+        let env_variable = match variable {
+            "workspaceFolder" | "workspaceRoot" => "ZED_WORKTREE_ROOT",
+            "file" => "ZED_FILE",
+            "lineNumber" => "ZED_ROW",
+            "selectedText" => "ZED_SELECTED_TEXT",
+            "pathSeparator" => {
+                let separator = std::path::MAIN_SEPARATOR.to_string();
+                segments.push(Segment::Text(Cow::Owned(separator)));
+                continue;
+            }
+            _ => match variable.strip_prefix("env:") {
+                Some(env_variable) => env_variable,
+                None => {
+                    if !unsupported.iter().any(|unsupported| unsupported == text) {
+                        unsupported.push(text.to_string());
+                    }
+                    segments.push(Segment::Text(Cow::Borrowed(text)));
+                    continue;
+                }
+            },
+        };
+        segments.push(Segment::Variable(env_variable));
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(Cow::Borrowed(rest)));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variable_substitution() {
+        let mut unsupported = Vec::new();
+        assert_eq!(
+            substitute_variables(
+                "${workspaceFolder}/src/${file}:${lineNumber}${env:HOME}_x ${fileDirname} ${fileDirname}",
+                &mut unsupported
+            ),
+            "$ZED_WORKTREE_ROOT/src/$ZED_FILE:$ZED_ROW${HOME}_x ${fileDirname} ${fileDirname}"
+        );
+        assert_eq!(unsupported, vec!["${fileDirname}"]);
+    }
+
+    #[test]
+    fn test_shell_args() {
+        let mut unsupported = Vec::new();
+        assert_eq!(
+            shell_arg("${workspaceFolder}/my dir/${file}", &mut unsupported),
+            "\"$ZED_WORKTREE_ROOT\"\"/my dir/\"\"$ZED_FILE\""
+        );
+        assert_eq!(
+            shell_arg("--name=${env:USER}_x", &mut unsupported),
+            "--name=\"$USER\"_x"
+        );
+        assert_eq!(shell_arg("a \"b\"", &mut unsupported), "\"a \\\"b\\\"\"");
+        assert!(unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_tasks_conversion() {
+        let converted = VsCodeFile::Tasks
+            .convert(
+                r#"{
+                    // See https://go.microsoft.com/fwlink/?LinkId=733558
+                    "version": "2.0.0",
+                    "tasks": [
+                        {
+                            "label": "build",
+                            "type": "shell",
+                            "command": "cargo build",
+                            "args": ["--manifest-path", "${workspaceFolder}/Cargo.toml"],
+                            "group": { "kind": "build", "isDefault": true },
+                            "presentation": { "reveal": "silent", "panel": "new", "focus": true },
+                            "problemMatcher": ["$rustc", "$msCompile"],
+                        },
+                        {
+                            "type": "npm",
+                            "script": "test",
+                            "options": { "cwd": "${workspaceFolder}/web", "env": { "CI": "1" } },
+                            "dependsOn": ["build", "lint"],
+                        },
+                        {
+                            "label": "lint",
+                            "command": "eslint",
+                            "args": [{ "value": "${file}", "quoting": "strong" }, "src dir"],
+                        },
+                        { "label": "gulp", "type": "gulp", "task": "default" },
+                    ],
+                }"#,
+            )
+            .unwrap();
+
+        let json: Value = serde_json_lenient::from_str(&converted.to_json()).unwrap();
+        let tasks = json.as_array().unwrap();
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0]["label"], "build");
+        assert_eq!(tasks[0]["command"], "cargo build");
+        assert_eq!(
+            tasks[0]["args"],
+            serde_json_lenient::json!(["--manifest-path", "\"$ZED_WORKTREE_ROOT\"/Cargo.toml"])
+        );
+        assert_eq!(tasks[0]["reveal"], "never");
+        assert_eq!(tasks[0]["use_new_terminal"], true);
+        assert_eq!(tasks[0]["problem_matcher"], "rustc");
+
+        assert_eq!(tasks[1]["label"], "npm: test");
+        assert_eq!(tasks[1]["command"], "npm");
+        assert_eq!(tasks[1]["args"], serde_json_lenient::json!(["run", "test"]));
+        assert_eq!(tasks[1]["cwd"], "$ZED_WORKTREE_ROOT/web");
+        assert_eq!(tasks[1]["env"]["CI"], "1");
+        assert_eq!(
+            tasks[1]["depends_on"],
+            serde_json_lenient::json!(["build", "lint"])
+        );
+        assert_eq!(tasks[1]["run_mode"], "parallel");
+
+        assert_eq!(
+            tasks[2]["args"],
+            serde_json_lenient::json!(["\"$ZED_FILE\"", "\"src dir\""])
+        );
+
+        assert_eq!(
+            converted.warnings,
+            vec![
+                "task \"build\": unsupported group, presentation.focus, problemMatcher ($msCompile)",
+                "task \"gulp\": tasks of type \"gulp\" are not supported",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_launch_configurations_conversion() {
+        let converted = VsCodeFile::Launch
+            .convert(
+                r#"{
+                    "version": "0.2.0",
+                    "configurations": [
+                        {
+                            "name": "Run server",
+                            "type": "node",
+                            "request": "launch",
+                            "program": "${workspaceFolder}/server.js",
+                            "args": ["--port", "8080"],
+                            "preLaunchTask": "build",
+                            "skipFiles": ["<node_internals>/**"],
+                        },
+                        {
+                            "name": "Tests",
+                            "type": "debugpy",
+                            "request": "launch",
+                            "module": "pytest",
+                        },
+                        { "name": "Attach", "type": "node", "request": "attach", "port": 9229 },
+                    ],
+                }"#,
+            )
+            .unwrap();
+
+        let json: Value = serde_json_lenient::from_str(&converted.to_json()).unwrap();
+        let tasks = json.as_array().unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0]["command"], "node");
+        assert_eq!(
+            tasks[0]["args"],
+            serde_json_lenient::json!(["\"$ZED_WORKTREE_ROOT\"/server.js", "--port", "8080"])
+        );
+        assert_eq!(tasks[0]["depends_on"], serde_json_lenient::json!(["build"]));
+        assert_eq!(tasks[1]["command"], "python3");
+        assert_eq!(
+            tasks[1]["args"],
+            serde_json_lenient::json!(["-m", "pytest"])
+        );
+
+        assert_eq!(
+            converted.warnings,
+            vec![
+                "launch configuration \"Run server\": unsupported skipFiles",
+                "launch configuration \"Attach\": \"attach\" requests are not supported",
+            ]
+        );
+    }
+}
@@ -1,15 +1,15 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{path::PathBuf, sync::Arc};
 
 use editor::Editor;
 use gpui::{AppContext, ViewContext, WindowContext};
-use language::Point;
 use modal::{Spawn, TasksModal};
-use project::{Location, WorktreeId};
+use runner::TaskRunner;
 use task::{Task, TaskContext};
 use util::ResultExt;
 use workspace::Workspace;
 
 mod modal;
+mod runner;
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(
@@ -31,7 +31,7 @@ pub fn init(cx: &mut AppContext) {
                             old_context
                         };
 
-                        schedule_task(workspace, task.as_ref(), task_context, cx)
+                        schedule_task(workspace, task, task_context, cx)
                     };
                 });
         },
@@ -70,7 +70,7 @@ fn spawn_task_with_name(name: String, cx: &mut ViewContext<Workspace>) {
                 let (_, target_task) = tasks.into_iter().find(|(_, task)| task.name() == name)?;
                 let cwd = task_cwd(this, cx).log_err().flatten();
                 let task_context = task_context(this, cwd, cx);
-                schedule_task(this, target_task.as_ref(), task_context, cx);
+                schedule_task(this, target_task, task_context, cx);
                 Some(())
             })
             .ok()
@@ -118,65 +118,7 @@ fn task_context(
                     .buffer_snapshot
                     .anchor_after(selection_range.end)
                     .text_anchor;
-                let Point { row, column } = snapshot
-                    .display_snapshot
-                    .buffer_snapshot
-                    .offset_to_point(selection_range.start);
-                let row = row + 1;
-                let column = column + 1;
-                let location = Location {
-                    buffer: buffer.clone(),
-                    range: start..end,
-                };
-
-                let current_file = location
-                    .buffer
-                    .read(cx)
-                    .file()
-                    .and_then(|file| file.as_local())
-                    .map(|file| file.abs_path(cx).to_string_lossy().to_string());
-                let worktree_id = location
-                    .buffer
-                    .read(cx)
-                    .file()
-                    .map(|file| WorktreeId::from_usize(file.worktree_id()));
-                let context = buffer
-                    .read(cx)
-                    .language()
-                    .and_then(|language| language.context_provider())
-                    .and_then(|provider| provider.build_context(location, cx).ok());
-
-                let worktree_path = worktree_id.and_then(|worktree_id| {
-                    workspace
-                        .project()
-                        .read(cx)
-                        .worktree_for_id(worktree_id, cx)
-                        .map(|worktree| worktree.read(cx).abs_path().to_string_lossy().to_string())
-                });
-
-                let selected_text = buffer.read(cx).chars_for_range(selection_range).collect();
-
-                let mut env = HashMap::from_iter([
-                    ("ZED_ROW".into(), row.to_string()),
-                    ("ZED_COLUMN".into(), column.to_string()),
-                    ("ZED_SELECTED_TEXT".into(), selected_text),
-                ]);
-                if let Some(path) = current_file {
-                    env.insert("ZED_FILE".into(), path);
-                }
-                if let Some(worktree_path) = worktree_path {
-                    env.insert("ZED_WORKTREE_ROOT".into(), worktree_path);
-                }
-                if let Some(language_context) = context {
-                    if let Some(symbol) = language_context.symbol {
-                        env.insert("ZED_SYMBOL".into(), symbol);
-                    }
-                }
-
-                Some(TaskContext {
-                    cwd: cwd.clone(),
-                    env,
-                })
+                Some(editor.task_context(&buffer, start..end, cwd.clone(), cx))
             })
         })()
         .unwrap_or_else(|| TaskContext {
@@ -192,19 +134,29 @@ fn task_context(
 }
 
 fn schedule_task(
-    workspace: &Workspace,
-    task: &dyn Task,
+    workspace: &mut Workspace,
+    task: Arc<dyn Task>,
     task_cx: TaskContext,
     cx: &mut ViewContext<'_, Workspace>,
 ) {
-    let spawn_in_terminal = task.exec(task_cx.clone());
-    if let Some(spawn_in_terminal) = spawn_in_terminal {
-        workspace.project().update(cx, |project, cx| {
-            project.task_inventory().update(cx, |inventory, _| {
-                inventory.task_scheduled(task.id().clone(), task_cx);
-            })
-        });
-        cx.emit(workspace::Event::SpawnTask(spawn_in_terminal));
+    let worktree = workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx))
+        .map(|path| path.worktree_id);
+    let inventory = workspace.project().read(cx).task_inventory().clone();
+    let resolved_task = inventory.update(cx, |inventory, cx| {
+        inventory.resolve_task(task.clone(), None, worktree, cx)
+    });
+    match resolved_task {
+        Ok(resolved_task) => {
+            inventory.update(cx, |inventory, _| {
+                inventory.task_scheduled(task.id().clone(), task_cx.clone());
+            });
+            let runner = TaskRunner::new(cx.view().downgrade(), inventory, task_cx);
+            cx.spawn(|_, cx| runner.run_resolved(resolved_task, cx))
+                .detach();
+        }
+        Err(error) => workspace.show_error(&error, cx),
     }
 }
 
@@ -11,8 +11,11 @@ use picker::{
     Picker, PickerDelegate,
 };
 use project::{Inventory, ProjectPath, TaskSourceKind};
-use task::{oneshot_source::OneshotSource, Task, TaskContext};
-use ui::{v_flex, ListItem, ListItemSpacing, RenderOnce, Selectable, WindowContext};
+use task::{oneshot_source::OneshotSource, Task, TaskContext, TaskStatus};
+use ui::{
+    v_flex, Color, Label, LabelCommon, LabelSize, ListItem, ListItemSpacing, RenderOnce,
+    Selectable, WindowContext,
+};
 use util::{paths::PathExt, ResultExt};
 use workspace::{ModalView, Workspace};
 
@@ -256,7 +259,7 @@ impl PickerDelegate for TasksModalDelegate {
 
         self.workspace
             .update(cx, |workspace, cx| {
-                schedule_task(workspace, task.as_ref(), self.task_context.clone(), cx);
+                schedule_task(workspace, task, self.task_context.clone(), cx);
             })
             .ok();
         cx.emit(DismissEvent);
@@ -273,12 +276,16 @@ impl PickerDelegate for TasksModalDelegate {
         cx: &mut ViewContext<picker::Picker<Self>>,
     ) -> Option<Self::ListItem> {
         let hit = &self.matches[ix];
-        let (source_kind, _) = &self.candidates[hit.candidate_id];
+        let (source_kind, task) = &self.candidates[hit.candidate_id];
         let details = match source_kind {
             TaskSourceKind::UserInput => "user input".to_string(),
+            TaskSourceKind::Runnables => "runnable".to_string(),
             TaskSourceKind::Worktree { abs_path, .. } | TaskSourceKind::AbsPath(abs_path) => {
                 abs_path.compact().to_string_lossy().to_string()
             }
+            TaskSourceKind::Discovered { abs_path, file, .. } => {
+                format!("{}: {}", file.tool(), abs_path.compact().to_string_lossy())
+            }
         };
 
         let highlighted_location = HighlightedMatchWithPaths {
@@ -293,12 +300,29 @@ impl PickerDelegate for TasksModalDelegate {
                 text: details,
             }],
         };
+        let status = self
+            .inventory
+            .read(cx)
+            .task_status(task.id())
+            .map(|status| {
+                let (text, color) = match status {
+                    TaskStatus::Running => ("running".to_string(), Color::Accent),
+                    TaskStatus::Succeeded => ("exit code 0".to_string(), Color::Success),
+                    TaskStatus::Failed {
+                        exit_code: Some(exit_code),
+                    } => (format!("exit code {exit_code}"), Color::Error),
+                    TaskStatus::Failed { exit_code: None } => ("failed".to_string(), Color::Error),
+                    TaskStatus::Skipped => ("skipped".to_string(), Color::Muted),
+                };
+                Label::new(text).size(LabelSize::Small).color(color)
+            });
         Some(
             ListItem::new(SharedString::from(format!("tasks-modal-{ix}")))
                 .inset(true)
                 .spacing(ListItemSpacing::Sparse)
                 .selected(selected)
-                .child(highlighted_location.render(cx)),
+                .child(highlighted_location.render(cx))
+                .end_slot(status),
         )
     }
 
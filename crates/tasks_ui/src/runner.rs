@@ -0,0 +1,129 @@
+//! Runs tasks after the tasks they depend on and follows them up with their `on_success` and `on_failure` tasks,
+//! keeping track of the status of every step in the task inventory.
+
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+use collections::HashMap;
+use futures::{
+    future::{self, LocalBoxFuture, Shared},
+    FutureExt,
+};
+use gpui::{AsyncWindowContext, Model, WeakView};
+use project::{Inventory, ResolvedTask};
+use task::{RunMode, Task, TaskContext, TaskId, TaskStatus};
+use workspace::Workspace;
+
+type TaskRun = Shared<LocalBoxFuture<'static, bool>>;
+
+pub(crate) struct TaskRunner {
+    workspace: WeakView<Workspace>,
+    inventory: Model<Inventory>,
+    task_context: TaskContext,
+    /// Every task runs at most once, even if several other tasks depend on it.
+    runs: RefCell<HashMap<TaskId, TaskRun>>,
+}
+
+impl TaskRunner {
+    pub(crate) fn new(
+        workspace: WeakView<Workspace>,
+        inventory: Model<Inventory>,
+        task_context: TaskContext,
+    ) -> Rc<Self> {
+        Rc::new(Self {
+            workspace,
+            inventory,
+            task_context,
+            runs: RefCell::default(),
+        })
+    }
+
+    /// Runs the task with all of its dependencies and follow-ups.
+    pub(crate) async fn run_resolved(self: Rc<Self>, task: ResolvedTask, cx: AsyncWindowContext) {
+        self.run(task, cx).await;
+        // Runs hold the runner, drop them to release it.
+        self.runs.borrow_mut().clear();
+    }
+
+    /// Runs the task after its dependencies, resolving to whether it succeeded.
+    fn run(self: &Rc<Self>, task: ResolvedTask, cx: AsyncWindowContext) -> TaskRun {
+        let id = task.task.id().clone();
+        if let Some(run) = self.runs.borrow().get(&id) {
+            return run.clone();
+        }
+        let run = self.clone().run_once(task, cx).boxed_local().shared();
+        self.runs.borrow_mut().insert(id, run.clone());
+        run
+    }
+
+    async fn run_once(self: Rc<Self>, task: ResolvedTask, mut cx: AsyncWindowContext) -> bool {
+        let id = task.task.id().clone();
+        self.set_status(id.clone(), TaskStatus::Running, &mut cx);
+        let dependencies_succeeded = match task.run_mode {
+            RunMode::Sequential => {
+                let mut succeeded = true;
+                for dependency in task.dependencies {
+                    if !self.run(dependency, cx.clone()).await {
+                        succeeded = false;
+                        break;
+                    }
+                }
+                succeeded
+            }
+            RunMode::Parallel => future::join_all(
+                task.dependencies
+                    .into_iter()
+                    .map(|dependency| self.run(dependency, cx.clone())),
+            )
+            .await
+            .into_iter()
+            .all(|succeeded| succeeded),
+        };
+        if !dependencies_succeeded {
+            self.set_status(id, TaskStatus::Skipped, &mut cx);
+            return false;
+        }
+
+        let status = self.spawn(&task.task, &mut cx).await;
+        self.set_status(id, status, &mut cx);
+        let succeeded = status == TaskStatus::Succeeded;
+        let follow_up = if succeeded {
+            task.on_success
+        } else {
+            task.on_failure
+        };
+        if let Some(follow_up) = follow_up {
+            self.run(*follow_up, cx).await;
+        }
+        succeeded
+    }
+
+    async fn spawn(&self, task: &Arc<dyn Task>, cx: &mut AsyncWindowContext) -> TaskStatus {
+        let completion = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                let spawn_in_terminal = task.exec(self.task_context.clone())?;
+                let completion = workspace.project().update(cx, |project, _| {
+                    project.task_completion(spawn_in_terminal.id.clone())
+                });
+                cx.emit(workspace::Event::SpawnTask(spawn_in_terminal));
+                Some(completion)
+            })
+            .ok()
+            .flatten();
+        let Some(completion) = completion else {
+            return TaskStatus::Failed { exit_code: None };
+        };
+        match completion.await.ok().flatten() {
+            Some(0) => TaskStatus::Succeeded,
+            exit_code => TaskStatus::Failed { exit_code },
+        }
+    }
+
+    fn set_status(&self, id: TaskId, status: TaskStatus, cx: &mut AsyncWindowContext) {
+        self.inventory
+            .update(cx, |inventory, cx| {
+                inventory.set_task_status(id, status, cx)
+            })
+            .ok();
+    }
+}
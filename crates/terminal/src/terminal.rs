@@ -39,7 +39,7 @@ use pty_info::PtyProcessInfo;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
 use smol::channel::{Receiver, Sender};
-use task::{static_source::RevealStrategy, TaskId};
+use task::{problem_matcher::ProblemMatcher, static_source::RevealStrategy, TaskId};
 use terminal_settings::{AlternateScroll, Shell, TerminalBlink, TerminalSettings};
 use theme::{ActiveTheme, Theme};
 use util::truncate_and_trailoff;
@@ -290,6 +290,7 @@ pub struct SpawnTask {
     pub args: Vec<String>,
     pub env: HashMap<String, String>,
     pub reveal: RevealStrategy,
+    pub problem_matcher: Option<ProblemMatcher>,
 }
 
 // https://github.com/alacritty/alacritty/blob/cb3a79dbf6472740daca8440d5166c1d4af5029e/extra/man/alacritty.5.scd?plain=1#L207-L213
@@ -594,6 +595,8 @@ pub struct TaskState {
     pub id: TaskId,
     pub label: String,
     pub completed: bool,
+    /// Exit code of the completed task, or `None` if it was terminated by a signal.
+    pub exit_code: Option<i32>,
     pub completion_rx: Receiver<()>,
 }
 
@@ -626,6 +629,12 @@ impl Terminal {
             AlacTermEvent::Bell => {
                 cx.emit(Event::Bell);
             }
+            // Sent before `Exit` when the child process exits normally.
+            AlacTermEvent::ChildExit(exit_code) => {
+                if let Some(task) = &mut self.task {
+                    task.exit_code = Some(*exit_code);
+                }
+            }
             AlacTermEvent::Exit => match &mut self.task {
                 Some(task) => {
                     task.completed = true;
@@ -933,6 +942,14 @@ impl Terminal {
         }
     }
 
+    /// The text of the whole terminal, including its scrollback history.
+    pub fn text(&self) -> String {
+        let term = self.term.lock();
+        let start = AlacPoint::new(term.topmost_line(), Column(0));
+        let end = AlacPoint::new(term.bottommost_line(), term.last_column());
+        term.bounds_to_string(start, end)
+    }
+
     pub fn select_all(&mut self) {
         let term = self.term.lock();
         let start = AlacPoint::new(term.topmost_line(), Column(0));
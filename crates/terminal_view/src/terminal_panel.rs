@@ -304,6 +304,7 @@ impl TerminalPanel {
             args: spawn_in_terminal.args.clone(),
             env: spawn_in_terminal.env.clone(),
             reveal: spawn_in_terminal.reveal,
+            problem_matcher: spawn_in_terminal.problem_matcher.clone(),
         };
         // Set up shell args unconditionally, as tasks are always spawned inside of a shell.
         let Some((shell, mut user_args)) = (match TerminalSettings::get_global(cx).shell.clone() {
@@ -311,6 +312,13 @@ impl TerminalPanel {
             Shell::Program(shell) => Some((shell, vec![])),
             Shell::WithArguments { program, args } => Some((program, args)),
         }) else {
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    workspace.project().update(cx, |project, _| {
+                        project.cancel_task_completion(&spawn_in_terminal.id)
+                    })
+                })
+                .ok();
             return;
         };
 
@@ -922,6 +922,8 @@ impl SearchableItem for TerminalView {
             word: false,
             regex: true,
             replacement: false,
+            fuzzy: false,
+            selection: false,
         }
     }
 
@@ -978,6 +980,7 @@ impl SearchableItem for TerminalView {
                 .unwrap()),
             ),
             SearchQuery::Regex { .. } => regex_search_for_query(&query),
+            SearchQuery::Structural { .. } | SearchQuery::Fuzzy { .. } => None,
         };
 
         if let Some(s) = searcher {
@@ -111,6 +111,7 @@ pub enum IconName {
     Split,
     Tab,
     Terminal,
+    TextSelect,
     Update,
     WholeWord,
     XCircle,
@@ -206,6 +207,7 @@ impl IconName {
             IconName::Split => "icons/split.svg",
             IconName::Tab => "icons/tab.svg",
             IconName::Terminal => "icons/terminal.svg",
+            IconName::TextSelect => "icons/text_select.svg",
             IconName::Update => "icons/update.svg",
             IconName::WholeWord => "icons/word_search.svg",
             IconName::XCircle => "icons/error.svg",
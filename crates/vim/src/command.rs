@@ -1,6 +1,6 @@
 use command_palette_hooks::CommandInterceptResult;
 use editor::actions::{SortLinesCaseInsensitive, SortLinesCaseSensitive};
-use gpui::{impl_actions, Action, AppContext, ViewContext};
+use gpui::{impl_actions, Action, AppContext, Keystroke, Modifiers, ViewContext};
 use serde_derive::Deserialize;
 use workspace::{SaveIntent, Workspace};
 
@@ -20,7 +20,13 @@ pub struct GoToLine {
     pub line: u32,
 }
 
-impl_actions!(vim, [GoToLine]);
+/// Runs keys as though they were typed in normal mode, as `:normal {keys}` does.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NormalCommand {
+    pub keys: String,
+}
+
+impl_actions!(vim, [GoToLine, NormalCommand]);
 
 pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     workspace.register_action(|_: &mut Workspace, action: &GoToLine, cx| {
@@ -29,6 +35,44 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
             move_cursor(vim, Motion::StartOfDocument, Some(action.line as usize), cx);
         });
     });
+
+    workspace.register_action(|_: &mut Workspace, action: &NormalCommand, cx| {
+        let keys = action.keys.clone();
+        // The keys are dispatched once the workspace is no longer being updated, since
+        // handling them may need to redraw the window.
+        cx.window_context().defer(move |cx| {
+            Vim::update(cx, |vim, cx| vim.switch_mode(Mode::Normal, false, cx));
+            for keystroke in keys.chars().map(keystroke_for_char) {
+                cx.dispatch_keystroke(keystroke);
+            }
+            // Like Vim, an unfinished command is aborted as though escape was typed.
+            let state = Vim::read(cx).state();
+            let unfinished = state.mode != Mode::Normal || !state.operator_stack.is_empty();
+            if unfinished {
+                cx.dispatch_keystroke(Keystroke {
+                    key: "escape".into(),
+                    ..Default::default()
+                });
+            }
+        });
+    });
+}
+
+fn keystroke_for_char(char: char) -> Keystroke {
+    let key = match char {
+        ' ' => "space".to_string(),
+        '\t' => "tab".to_string(),
+        '\n' => "enter".to_string(),
+        char => char.to_ascii_lowercase().to_string(),
+    };
+    Keystroke {
+        modifiers: Modifiers {
+            shift: char.is_ascii_uppercase(),
+            ..Default::default()
+        },
+        key,
+        ime_key: Some(char.to_string()),
+    }
 }
 
 pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandInterceptResult> {
@@ -340,6 +384,14 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
                 )
             } else if let Ok(line) = query.parse::<u32>() {
                 (query, GoToLine { line }.boxed_clone())
+            } else if let Some(keys) = normal_command_keys(query) {
+                (
+                    query,
+                    NormalCommand {
+                        keys: keys.to_string(),
+                    }
+                    .boxed_clone(),
+                )
             } else {
                 return None;
             }
@@ -356,6 +408,13 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
     })
 }
 
+/// Returns the keys of a `:normal {keys}` command, which can be shortened to `:norm`.
+fn normal_command_keys(query: &str) -> Option<&str> {
+    let (command, keys) = query.split_once(' ')?;
+    let command = command.strip_suffix('!').unwrap_or(command);
+    matches!(command, "norm" | "norma" | "normal").then_some(keys)
+}
+
 fn generate_positions(string: &str, query: &str) -> Vec<usize> {
     let mut positions = Vec::new();
     let mut chars = query.chars();
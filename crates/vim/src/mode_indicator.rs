@@ -6,6 +6,7 @@ use crate::{state::Mode, Vim};
 /// The ModeIndicator displays the current mode in the status bar.
 pub struct ModeIndicator {
     pub(crate) mode: Option<Mode>,
+    pub(crate) recording_register: Option<char>,
     _subscription: Subscription,
 }
 
@@ -15,6 +16,7 @@ impl ModeIndicator {
         let _subscription = cx.observe_global::<Vim>(|this, cx| this.update_mode(cx));
         let mut this = Self {
             mode: None,
+            recording_register: None,
             _subscription,
         };
         this.update_mode(cx);
@@ -29,8 +31,10 @@ impl ModeIndicator {
 
         if vim.enabled {
             self.mode = Some(vim.state().mode);
+            self.recording_register = vim.workspace_state.recording_register;
         } else {
             self.mode = None;
+            self.recording_register = None;
         }
     }
 }
@@ -41,7 +45,11 @@ impl Render for ModeIndicator {
             return div().into_any();
         };
 
-        Label::new(format!("-- {} --", mode))
+        let recording = self
+            .recording_register
+            .map(|register| format!(" recording @{register}"))
+            .unwrap_or_default();
+        Label::new(format!("-- {} --{}", mode, recording))
             .size(LabelSize::Small)
             .into_any_element()
     }
@@ -3,7 +3,7 @@ use editor::{
     movement::{
         self, find_boundary, find_preceding_boundary_display_point, FindRange, TextLayoutDetails,
     },
-    Bias, DisplayPoint, ToOffset,
+    Anchor, Bias, DisplayPoint, ToOffset,
 };
 use gpui::{actions, impl_actions, px, ViewContext, WindowContext};
 use language::{char_kind, CharKind, Point, Selection, SelectionGoal};
@@ -11,7 +11,7 @@ use serde::Deserialize;
 use workspace::Workspace;
 
 use crate::{
-    normal::normal_motion,
+    normal::{mark::record_jump, normal_motion},
     state::{Mode, Operator},
     utils::coerce_punctuation,
     visual::visual_motion,
@@ -94,6 +94,10 @@ pub enum Motion {
     WindowTop,
     WindowMiddle,
     WindowBottom,
+    Jump {
+        anchor: Anchor,
+        line: bool,
+    },
 }
 
 #[derive(Clone, Deserialize, PartialEq)]
@@ -386,6 +390,9 @@ pub(crate) fn motion(motion: Motion, cx: &mut WindowContext) {
 
     let count = Vim::update(cx, |vim, cx| vim.take_count(cx));
     let operator = Vim::read(cx).active_operator();
+    if operator.is_none() && motion.is_jump() {
+        Vim::update(cx, |vim, cx| record_jump(vim, cx));
+    }
     match Vim::read(cx).state().mode {
         Mode::Normal | Mode::Replace => normal_motion(motion, operator, count, cx),
         Mode::Visual | Mode::VisualLine | Mode::VisualBlock => visual_motion(motion, count, cx),
@@ -414,6 +421,7 @@ impl Motion {
             | WindowMiddle
             | WindowBottom
             | EndOfParagraph => true,
+            Jump { line, .. } => *line,
             EndOfLine { .. }
             | Matching
             | FindForward { .. }
@@ -442,7 +450,7 @@ impl Motion {
     pub fn infallible(&self) -> bool {
         use Motion::*;
         match self {
-            StartOfDocument | EndOfDocument | CurrentLine => true,
+            StartOfDocument | EndOfDocument | CurrentLine | Jump { .. } => true,
             Down { .. }
             | Up { .. }
             | EndOfLine { .. }
@@ -511,13 +519,31 @@ impl Motion {
             | NextSubwordStart { .. }
             | PreviousSubwordStart { .. }
             | FirstNonWhitespace { .. }
-            | FindBackward { .. } => false,
+            | FindBackward { .. }
+            | Jump { .. } => false,
             RepeatFind { last_find: motion } | RepeatFindReversed { last_find: motion } => {
                 motion.inclusive()
             }
         }
     }
 
+    /// Whether the motion is a jump, which records the position it starts from in the jump list.
+    pub fn is_jump(&self) -> bool {
+        use Motion::*;
+        matches!(
+            self,
+            StartOfDocument
+                | EndOfDocument
+                | Matching
+                | StartOfParagraph
+                | EndOfParagraph
+                | WindowTop
+                | WindowMiddle
+                | WindowBottom
+                | Jump { .. }
+        )
+    }
+
     pub fn move_point(
         &self,
         map: &DisplaySnapshot,
@@ -702,6 +728,14 @@ impl Motion {
             WindowTop => window_top(map, point, &text_layout_details, times - 1),
             WindowMiddle => window_middle(map, point, &text_layout_details),
             WindowBottom => window_bottom(map, point, &text_layout_details, times - 1),
+            Jump { anchor, line } => {
+                let point = anchor.to_display_point(map);
+                if *line {
+                    (first_non_whitespace(map, false, point), SelectionGoal::None)
+                } else {
+                    (point, SelectionGoal::None)
+                }
+            }
         };
 
         (new_point != point || infallible).then_some((new_point, goal))
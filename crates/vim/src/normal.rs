@@ -2,6 +2,7 @@ mod case;
 mod change;
 mod delete;
 mod increment;
+pub(crate) mod mark;
 mod paste;
 pub(crate) mod repeat;
 mod scroll;
@@ -158,6 +159,7 @@ pub(crate) fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace
         });
     });
 
+    mark::register(workspace, cx);
     paste::register(workspace, cx);
     repeat::register(workspace, cx);
     scroll::register(workspace, cx);
@@ -211,16 +213,28 @@ pub(crate) fn move_cursor(
     times: Option<usize>,
     cx: &mut WindowContext,
 ) {
+    let mut failed = false;
     vim.update_active_editor(cx, |_, editor, cx| {
         let text_layout_details = editor.text_layout_details(cx);
         editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
             s.move_cursors_with(|map, cursor, goal| {
                 motion
                     .move_point(map, cursor, goal, times, &text_layout_details)
-                    .unwrap_or((cursor, goal))
+                    .unwrap_or_else(|| {
+                        failed = true;
+                        (cursor, goal)
+                    })
             })
         })
     });
+
+    // Like in Vim, a failing motion ends the replay of a recording, which is what stops
+    // recursive recordings.
+    if failed {
+        if let Some(replayer) = vim.workspace_state.replayer.as_ref() {
+            replayer.stop();
+        }
+    }
 }
 
 fn insert_after(_: &mut Workspace, _: &InsertAfter, cx: &mut ViewContext<Workspace>) {
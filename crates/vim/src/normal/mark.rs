@@ -0,0 +1,797 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use collections::HashMap;
+use editor::{scroll::Autoscroll, Anchor, Bias, Editor};
+use gpui::{actions, AppContext, Model, ViewContext, WeakModel, WindowContext};
+use language::{Buffer, BufferSnapshot, Point, SelectionGoal, ToOffset, ToPoint};
+use workspace::{Workspace, WorkspaceId};
+
+use crate::{
+    motion::{self, first_non_whitespace, Motion},
+    persistence::DB,
+    state::Mode,
+    visual::visual_block_motion,
+    Vim,
+};
+
+actions!(
+    vim,
+    [
+        JumpBack,
+        JumpForward,
+        ChangeListOlder,
+        ChangeListNewer,
+        RestoreVisualSelection
+    ]
+);
+
+/// The number of positions kept in the jump list, and in the change list of each buffer.
+const MAX_LIST_LEN: usize = 100;
+
+/// Where a global mark, or an entry of the jump list, points to.
+#[derive(Clone)]
+pub enum MarkLocation {
+    /// A position in an open buffer, which follows the edits made around it.
+    Buffer {
+        buffer: WeakModel<Buffer>,
+        anchor: text::Anchor,
+    },
+    /// A position restored from the database, in a file that hasn't been opened since.
+    Path { path: Arc<Path>, point: Point },
+}
+
+/// The marks and the change list of a buffer.
+#[derive(Clone, Debug, Default)]
+pub struct BufferMarks {
+    pub marks: HashMap<char, text::Anchor>,
+    pub change_list: Vec<text::Anchor>,
+    pub change_list_position: Option<usize>,
+    pub last_visual_mode: Option<Mode>,
+    pub last_visual_reversed: bool,
+}
+
+pub(crate) fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    workspace.register_action(|_: &mut Workspace, _: &JumpBack, cx| {
+        Vim::update(cx, |vim, cx| {
+            let count = vim.take_count(cx).unwrap_or(1);
+            let position = match vim.workspace_state.jump_list_position {
+                Some(position) => position,
+                None => {
+                    // Like in Vim, going back from the newest jump first records the cursor
+                    // position, so that jumping forward returns to it.
+                    record_jump(vim, cx);
+                    vim.workspace_state.jump_list.len().saturating_sub(1)
+                }
+            };
+            let Some(target) = position.checked_sub(count) else {
+                return;
+            };
+            vim.workspace_state.jump_list_position = Some(target);
+            let location = vim.workspace_state.jump_list[target].clone();
+            go_to_location(vim, location, false, cx);
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &JumpForward, cx| {
+        Vim::update(cx, |vim, cx| {
+            let count = vim.take_count(cx).unwrap_or(1);
+            let Some(position) = vim.workspace_state.jump_list_position else {
+                return;
+            };
+            let target = position + count;
+            if target >= vim.workspace_state.jump_list.len() {
+                return;
+            }
+            vim.workspace_state.jump_list_position = Some(target);
+            let location = vim.workspace_state.jump_list[target].clone();
+            go_to_location(vim, location, false, cx);
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &ChangeListOlder, cx| {
+        Vim::update(cx, |vim, cx| {
+            let count = vim.take_count(cx).unwrap_or(1);
+            move_in_change_list(vim, -(count as isize), cx);
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &ChangeListNewer, cx| {
+        Vim::update(cx, |vim, cx| {
+            let count = vim.take_count(cx).unwrap_or(1);
+            move_in_change_list(vim, count as isize, cx);
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &RestoreVisualSelection, cx| {
+        Vim::update(cx, |vim, cx| restore_visual_selection(vim, cx))
+    });
+
+    restore_marks(workspace.database_id(), cx);
+}
+
+/// Sets a mark at the cursor position with `m`. Lowercase marks are local to the buffer,
+/// uppercase ones span files, and both are saved with the workspace.
+pub(crate) fn create_mark(vim: &mut Vim, name: char, cx: &mut WindowContext) {
+    vim.clear_operator(cx);
+    if !name.is_ascii_alphabetic() && !matches!(name, '`' | '\'' | '[' | ']' | '<' | '>') {
+        return;
+    }
+    let Some((buffer, anchor, workspace_id)) = vim
+        .update_active_editor(cx, |_, editor, cx| {
+            let (buffer, anchor) = cursor_position(editor, cx)?;
+            let workspace_id = editor
+                .workspace()
+                .map(|workspace| workspace.read(cx).database_id());
+            Some((buffer, anchor, workspace_id))
+        })
+        .flatten()
+    else {
+        return;
+    };
+
+    set_mark(vim, name, &buffer, anchor, cx);
+    if let Some(workspace_id) = workspace_id.filter(|_| name.is_ascii_alphabetic()) {
+        persist_mark(workspace_id, name, &buffer, anchor, cx);
+    }
+}
+
+/// Moves to a mark with `` ` `` or, when `line` is set, to the first non-blank character of
+/// its line with `'`. Marks in the active editor are motions, so they can be used with
+/// operators, while global marks in other files open them.
+pub(crate) fn jump_to_mark(name: char, line: bool, cx: &mut WindowContext) {
+    Vim::update(cx, |vim, cx| vim.pop_operator(cx));
+    let name = if name == '\'' { '`' } else { name };
+
+    let anchor = Vim::update(cx, |vim, cx| {
+        vim.update_active_editor(cx, |vim, editor, cx| mark_anchor(vim, editor, name, cx))
+            .flatten()
+    });
+    if let Some(anchor) = anchor {
+        motion::motion(Motion::Jump { anchor, line }, cx);
+        return;
+    }
+
+    Vim::update(cx, |vim, cx| {
+        let operator = vim.active_operator();
+        vim.clear_operator(cx);
+        if operator.is_some() || !name.is_ascii_uppercase() {
+            return;
+        }
+        if let Some(location) = vim.workspace_state.global_marks.get(&name).cloned() {
+            record_jump(vim, cx);
+            go_to_location(vim, location, line, cx);
+        }
+    });
+}
+
+/// Records the cursor position of the active editor in the jump list before a jump moves it,
+/// and sets the previous context mark to it.
+pub(crate) fn record_jump(vim: &mut Vim, cx: &mut WindowContext) {
+    let Some((buffer, anchor)) = vim
+        .update_active_editor(cx, |_, editor, cx| cursor_position(editor, cx))
+        .flatten()
+    else {
+        return;
+    };
+
+    let snapshot = buffer.read(cx).snapshot();
+    let row = anchor.to_point(&snapshot).row;
+    let state = &mut vim.workspace_state;
+    // Like in Vim, a jump replaces the earlier ones from the same line.
+    state.jump_list.retain(|location| match location {
+        MarkLocation::Buffer {
+            buffer: jump_buffer,
+            anchor,
+        } => jump_buffer.entity_id() != buffer.entity_id() || anchor.to_point(&snapshot).row != row,
+        MarkLocation::Path { .. } => true,
+    });
+    state.jump_list.push(MarkLocation::Buffer {
+        buffer: buffer.downgrade(),
+        anchor,
+    });
+    if state.jump_list.len() > MAX_LIST_LEN {
+        state.jump_list.remove(0);
+    }
+    state.jump_list_position = None;
+
+    buffer_marks(vim, &buffer, cx).marks.insert('`', anchor);
+}
+
+/// Remembers the version of the buffer under the cursor when a transaction begins, so that
+/// the `[` and `]` marks can be set to the text it changed.
+pub(crate) fn transaction_begun(vim: &mut Vim, cx: &mut WindowContext) {
+    if vim.workspace_state.change_base.is_some() {
+        return;
+    }
+    if let Some((buffer, _)) = vim
+        .update_active_editor(cx, |_, editor, cx| cursor_position(editor, cx))
+        .flatten()
+    {
+        let version = buffer.read(cx).version();
+        vim.workspace_state.change_base = Some((buffer.downgrade(), version));
+    }
+}
+
+/// Updates the `[`, `]` and `.` marks and the change list after the active editor made a
+/// change. A change that is typed in insert mode spans everything typed since entering it.
+pub(crate) fn buffer_edited(vim: &mut Vim, cx: &mut WindowContext) {
+    let change_base = if vim.state().mode == Mode::Insert {
+        vim.workspace_state.change_base.clone()
+    } else {
+        vim.workspace_state.change_base.take()
+    };
+    if let Some((buffer, version)) =
+        change_base.and_then(|(buffer, version)| Some((buffer.upgrade()?, version)))
+    {
+        let changed_range = buffer
+            .read(cx)
+            .edits_since::<usize>(&version)
+            .map(|edit| edit.new)
+            .reduce(|range, new| range.start.min(new.start)..range.end.max(new.end));
+        if let Some(changed_range) = changed_range {
+            set_range_marks(vim, &buffer, changed_range, cx);
+        }
+    }
+
+    let Some((buffer, cursor)) = vim
+        .update_active_editor(cx, |_, editor, cx| cursor_position(editor, cx))
+        .flatten()
+    else {
+        return;
+    };
+    let snapshot = buffer.read(cx).snapshot();
+    let mut point = cursor.to_point(&snapshot);
+    // While typing, the cursor is after the last change rather than on it.
+    if vim.state().mode == Mode::Insert && point.column > 0 {
+        point = snapshot.clip_point(Point::new(point.row, point.column - 1), Bias::Left);
+    }
+    let row = point.row;
+    let anchor = snapshot.anchor_before(point);
+    let marks = buffer_marks(vim, &buffer, cx);
+    // Changes made on the same line as the previous one replace it.
+    if marks
+        .change_list
+        .last()
+        .is_some_and(|last| last.to_point(&snapshot).row == row)
+    {
+        marks.change_list.pop();
+    }
+    marks.change_list.push(anchor);
+    if marks.change_list.len() > MAX_LIST_LEN {
+        marks.change_list.remove(0);
+    }
+    marks.change_list_position = None;
+    marks.marks.insert('.', anchor);
+}
+
+/// Sets the `[` and `]` marks to the first and last characters of a range of the buffer.
+pub(crate) fn set_range_marks(
+    vim: &mut Vim,
+    buffer: &Model<Buffer>,
+    range: Range<usize>,
+    cx: &AppContext,
+) {
+    let (first, last) = first_and_last_anchors(&buffer.read(cx).snapshot(), range);
+    let marks = &mut buffer_marks(vim, buffer, cx).marks;
+    marks.insert('[', first);
+    marks.insert(']', last);
+}
+
+/// Sets the `<` and `>` marks to the first and last characters of the visual selection being
+/// left, and remembers its mode so that `gv` can restore it.
+pub(crate) fn set_visual_marks(vim: &mut Vim, mode: Mode, cx: &mut WindowContext) {
+    let Some((buffer, tail, head)) = vim
+        .update_active_editor(cx, |_, editor, cx| {
+            let tail = editor.selections.oldest_anchor().tail();
+            let head = editor.selections.newest_anchor().head();
+            let multi_buffer = editor.buffer().read(cx);
+            let (buffer, tail) = multi_buffer.text_anchor_for_position(tail, cx)?;
+            let (head_buffer, head) = multi_buffer.text_anchor_for_position(head, cx)?;
+            (buffer == head_buffer).then_some((buffer, tail, head))
+        })
+        .flatten()
+    else {
+        return;
+    };
+
+    let snapshot = buffer.read(cx).snapshot();
+    let reversed = head.cmp(&tail, &snapshot).is_lt();
+    let (start, end) = if reversed { (head, tail) } else { (tail, head) };
+    let (first, last) = first_and_last_anchors(
+        &snapshot,
+        start.to_offset(&snapshot)..end.to_offset(&snapshot),
+    );
+    let marks = buffer_marks(vim, &buffer, cx);
+    marks.marks.insert('<', first);
+    marks.marks.insert('>', last);
+    marks.last_visual_mode = Some(mode);
+    marks.last_visual_reversed = reversed;
+}
+
+/// Attaches the marks restored from the database to the buffer under the cursor of a newly
+/// active editor.
+pub(crate) fn load_buffer_marks(vim: &mut Vim, editor: &Editor, cx: &AppContext) {
+    let head = editor.selections.newest_anchor().head();
+    if let Some((buffer, _)) = editor.buffer().read(cx).text_anchor_for_position(head, cx) {
+        buffer_marks(vim, &buffer, cx);
+    }
+}
+
+/// Saves the current positions of the marks in the buffer under the cursor, so that the
+/// database follows the edits made around them.
+pub(crate) fn persist_buffer_marks(vim: &mut Vim, cx: &mut WindowContext) {
+    let Some((buffer, workspace_id)) = vim
+        .update_active_editor(cx, |_, editor, cx| {
+            let (buffer, _) = cursor_position(editor, cx)?;
+            let workspace_id = editor.workspace()?.read(cx).database_id();
+            Some((buffer, workspace_id))
+        })
+        .flatten()
+    else {
+        return;
+    };
+
+    let local_marks = buffer_marks(vim, &buffer, cx)
+        .marks
+        .iter()
+        .filter(|(name, _)| name.is_ascii_lowercase())
+        .map(|(name, anchor)| (*name, *anchor))
+        .collect::<Vec<_>>();
+    let global_marks = vim
+        .workspace_state
+        .global_marks
+        .iter()
+        .filter_map(|(name, location)| match location {
+            MarkLocation::Buffer {
+                buffer: mark_buffer,
+                anchor,
+            } if mark_buffer.entity_id() == buffer.entity_id() => Some((*name, *anchor)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    for (name, anchor) in local_marks.into_iter().chain(global_marks) {
+        persist_mark(workspace_id, name, &buffer, anchor, cx);
+    }
+}
+
+fn set_mark(
+    vim: &mut Vim,
+    name: char,
+    buffer: &Model<Buffer>,
+    anchor: text::Anchor,
+    cx: &AppContext,
+) {
+    if name.is_ascii_uppercase() {
+        vim.workspace_state.global_marks.insert(
+            name,
+            MarkLocation::Buffer {
+                buffer: buffer.downgrade(),
+                anchor,
+            },
+        );
+    } else {
+        let name = if name == '\'' { '`' } else { name };
+        buffer_marks(vim, buffer, cx).marks.insert(name, anchor);
+    }
+}
+
+fn persist_mark(
+    workspace_id: WorkspaceId,
+    name: char,
+    buffer: &Model<Buffer>,
+    anchor: text::Anchor,
+    cx: &mut WindowContext,
+) {
+    let Some(path) = abs_path(buffer, cx) else {
+        return;
+    };
+    let point = anchor.to_point(&buffer.read(cx).snapshot());
+    cx.background_executor()
+        .spawn(async move {
+            // A global mark is only ever in a single file.
+            if name.is_ascii_uppercase() {
+                DB.delete_mark(workspace_id, name.to_string()).await?;
+            }
+            DB.save_mark(
+                workspace_id,
+                name.to_string(),
+                path,
+                point.row,
+                point.column,
+            )
+            .await
+        })
+        .detach_and_log_err(cx);
+}
+
+fn restore_marks(workspace_id: WorkspaceId, cx: &mut WindowContext) {
+    let marks = match DB.get_marks(workspace_id) {
+        Ok(marks) => marks,
+        Err(error) => {
+            log::error!("failed to restore vim marks: {error:?}");
+            return;
+        }
+    };
+    Vim::update(cx, |vim, _| {
+        let state = &mut vim.workspace_state;
+        for (name, path, row, column) in marks {
+            let Some(name) = name.chars().next() else {
+                continue;
+            };
+            let path: Arc<Path> = path.into();
+            let point = Point::new(row, column);
+            if name.is_ascii_uppercase() {
+                state
+                    .global_marks
+                    .entry(name)
+                    .or_insert(MarkLocation::Path { path, point });
+            } else {
+                state
+                    .saved_marks
+                    .entry(path)
+                    .or_default()
+                    .entry(name)
+                    .or_insert(point);
+            }
+        }
+    });
+}
+
+/// Returns the marks of a buffer. The first time a buffer is seen, the marks restored from the
+/// database for its file are anchored in it.
+fn buffer_marks<'a>(
+    vim: &'a mut Vim,
+    buffer: &Model<Buffer>,
+    cx: &AppContext,
+) -> &'a mut BufferMarks {
+    let state = &mut vim.workspace_state;
+    state
+        .buffer_marks
+        .entry(buffer.entity_id())
+        .or_insert_with(|| {
+            let mut marks = BufferMarks::default();
+            let Some(path) = abs_path(buffer, cx) else {
+                return marks;
+            };
+            let snapshot = buffer.read(cx).snapshot();
+            let anchor_at = |point| snapshot.anchor_before(snapshot.clip_point(point, Bias::Left));
+            for location in state.global_marks.values_mut() {
+                if let MarkLocation::Path {
+                    path: mark_path,
+                    point,
+                } = location
+                {
+                    if mark_path.as_ref() == path.as_path() {
+                        *location = MarkLocation::Buffer {
+                            buffer: buffer.downgrade(),
+                            anchor: anchor_at(*point),
+                        };
+                    }
+                }
+            }
+            if let Some(saved_marks) = state.saved_marks.remove(path.as_path()) {
+                marks.marks = saved_marks
+                    .into_iter()
+                    .map(|(name, point)| (name, anchor_at(point)))
+                    .collect();
+            }
+            marks
+        })
+}
+
+fn first_and_last_anchors(
+    snapshot: &BufferSnapshot,
+    range: Range<usize>,
+) -> (text::Anchor, text::Anchor) {
+    let last = if range.end > range.start {
+        snapshot.clip_offset(range.end - 1, Bias::Left)
+    } else {
+        range.start
+    };
+    (
+        snapshot.anchor_before(range.start),
+        snapshot.anchor_before(last),
+    )
+}
+
+fn abs_path(buffer: &Model<Buffer>, cx: &AppContext) -> Option<PathBuf> {
+    Some(buffer.read(cx).file()?.as_local()?.abs_path(cx))
+}
+
+/// Returns the buffer under the cursor, and the cursor position in it.
+fn cursor_position(editor: &Editor, cx: &AppContext) -> Option<(Model<Buffer>, text::Anchor)> {
+    let head = editor.selections.newest_anchor().head();
+    editor.buffer().read(cx).text_anchor_for_position(head, cx)
+}
+
+/// Returns the position of an anchor in the editor, if one of its excerpts shows it.
+fn editor_anchor(
+    editor: &Editor,
+    buffer: &Model<Buffer>,
+    anchor: text::Anchor,
+    cx: &AppContext,
+) -> Option<Anchor> {
+    let multi_buffer = editor.buffer().read(cx);
+    let snapshot = buffer.read(cx).snapshot();
+    let (excerpt_id, _) = multi_buffer
+        .excerpts_for_buffer(buffer, cx)
+        .into_iter()
+        .find(|(_, range)| {
+            range.context.start.cmp(&anchor, &snapshot).is_le()
+                && range.context.end.cmp(&anchor, &snapshot).is_ge()
+        })?;
+    multi_buffer
+        .snapshot(cx)
+        .anchor_in_excerpt(excerpt_id, anchor)
+}
+
+fn mark_anchor(vim: &mut Vim, editor: &Editor, name: char, cx: &AppContext) -> Option<Anchor> {
+    let (buffer, _) = cursor_position(editor, cx)?;
+    // This also anchors the restored global marks of the buffer.
+    let marks = buffer_marks(vim, &buffer, cx);
+    if !name.is_ascii_uppercase() {
+        let anchor = *marks.marks.get(&name)?;
+        return editor_anchor(editor, &buffer, anchor, cx);
+    }
+
+    match vim.workspace_state.global_marks.get(&name)? {
+        MarkLocation::Buffer {
+            buffer: mark_buffer,
+            anchor,
+        } => editor_anchor(editor, &mark_buffer.upgrade()?, *anchor, cx),
+        MarkLocation::Path { .. } => None,
+    }
+}
+
+/// Moves the cursor to a location, in the active editor if it shows it, and otherwise in the
+/// editor of its file, which is opened in the active pane.
+fn go_to_location(vim: &mut Vim, location: MarkLocation, line: bool, cx: &mut WindowContext) {
+    if let MarkLocation::Buffer { buffer, anchor } = &location {
+        let Some(buffer) = buffer.upgrade() else {
+            return;
+        };
+        let moved = vim
+            .update_active_editor(cx, |_, editor, cx| {
+                let anchor = editor_anchor(editor, &buffer, *anchor, cx)?;
+                select_jump_target(editor, anchor, line, cx);
+                Some(())
+            })
+            .flatten()
+            .is_some();
+        if moved {
+            return;
+        }
+    }
+
+    let Some(workspace) = vim
+        .update_active_editor(cx, |_, editor, _| editor.workspace())
+        .flatten()
+    else {
+        return;
+    };
+    vim.workspace_state.opening_jump = true;
+    match location {
+        MarkLocation::Buffer { buffer, anchor } => {
+            let Some(buffer) = buffer.upgrade() else {
+                return;
+            };
+            let editor = workspace.update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().clone();
+                workspace.open_project_item::<Editor>(pane, buffer.clone(), cx)
+            });
+            editor.update(cx, |editor, cx| {
+                if let Some(anchor) = editor_anchor(editor, &buffer, anchor, cx) {
+                    select_jump_target(editor, anchor, line, cx);
+                }
+            });
+        }
+        MarkLocation::Path { path, point } => {
+            let open = workspace.update(cx, |workspace, cx| {
+                workspace.open_abs_path(path.to_path_buf(), true, cx)
+            });
+            cx.spawn(|mut cx| async move {
+                let Some(editor) = open.await?.downcast::<Editor>() else {
+                    return Ok(());
+                };
+                editor.update(&mut cx, |editor, cx| {
+                    let anchor = editor.buffer().read(cx).snapshot(cx).anchor_before(point);
+                    select_jump_target(editor, anchor, line, cx);
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+}
+
+fn select_jump_target(
+    editor: &mut Editor,
+    anchor: Anchor,
+    line: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+        s.select_anchor_ranges([anchor..anchor]);
+        if line {
+            s.move_cursors_with(|map, cursor, _| {
+                (
+                    first_non_whitespace(map, false, cursor),
+                    SelectionGoal::None,
+                )
+            });
+        }
+    });
+}
+
+fn move_in_change_list(vim: &mut Vim, delta: isize, cx: &mut WindowContext) {
+    vim.update_active_editor(cx, |vim, editor, cx| {
+        let Some((buffer, _)) = cursor_position(editor, cx) else {
+            return;
+        };
+        let marks = buffer_marks(vim, &buffer, cx);
+        let len = marks.change_list.len() as isize;
+        let position = marks
+            .change_list_position
+            .map_or(len, |position| position as isize);
+        let target = position + delta;
+        if len == 0 || (target < 0 && position == 0) || (target >= len && position >= len - 1) {
+            return;
+        }
+        // Like in Vim, a count beyond either end of the list moves to that end.
+        let target = target.clamp(0, len - 1) as usize;
+        marks.change_list_position = Some(target);
+        let anchor = marks.change_list[target];
+        if let Some(anchor) = editor_anchor(editor, &buffer, anchor, cx) {
+            select_jump_target(editor, anchor, false, cx);
+        }
+    });
+}
+
+fn restore_visual_selection(vim: &mut Vim, cx: &mut WindowContext) {
+    let Some((mode, tail, head)) = vim
+        .update_active_editor(cx, |vim, editor, cx| {
+            let (buffer, _) = cursor_position(editor, cx)?;
+            let marks = buffer_marks(vim, &buffer, cx);
+            let mode = marks.last_visual_mode?;
+            let reversed = marks.last_visual_reversed;
+            let start = *marks.marks.get(&'<')?;
+            let last = *marks.marks.get(&'>')?;
+
+            // Selections end after the last character they contain.
+            let snapshot = buffer.read(cx).snapshot();
+            let last = last.to_offset(&snapshot);
+            let end = last + snapshot.chars_at(last).next().map_or(0, |c| c.len_utf8());
+            let start = editor_anchor(editor, &buffer, start, cx)?;
+            let end = editor_anchor(editor, &buffer, snapshot.anchor_after(end), cx)?;
+            Some(if reversed {
+                (mode, end, start)
+            } else {
+                (mode, start, end)
+            })
+        })
+        .flatten()
+    else {
+        return;
+    };
+
+    vim.switch_mode(mode, true, cx);
+    vim.update_active_editor(cx, |_, editor, cx| {
+        editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.select_anchor_ranges([tail..head]);
+        });
+        if mode == Mode::VisualBlock {
+            visual_block_motion(true, editor, cx, |_, point, goal| Some((point, goal)))
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use crate::test::NeovimBackedTestContext;
+
+    #[gpui::test]
+    async fn test_marks(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            one tˇwo
+              three
+            four"})
+            .await;
+        cx.simulate_shared_keystrokes(["m", "a", "j", "j", "`", "a"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            one tˇwo
+              three
+            four"})
+            .await;
+        cx.simulate_shared_keystrokes(["j", "m", "b", "g", "g", "'", "b"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            one two
+              ˇthree
+            four"})
+            .await;
+        cx.simulate_shared_keystrokes(["'", "'"]).await;
+        cx.assert_shared_state(indoc! {"
+            ˇone two
+              three
+            four"})
+            .await;
+        cx.simulate_shared_keystrokes(["d", "'", "b"]).await;
+        cx.assert_shared_state(indoc! {"
+        ˇfour"})
+            .await;
+    }
+
+    #[gpui::test]
+    async fn test_marks_follow_edits(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            one
+            tˇwo"})
+            .await;
+        cx.simulate_shared_keystrokes([
+            "m", "a", "g", "g", "shift-o", "z", "e", "r", "o", "escape", "`", "a",
+        ])
+        .await;
+        cx.assert_shared_state(indoc! {"
+            zero
+            one
+            tˇwo"})
+            .await;
+        cx.simulate_shared_keystrokes(["g", "g", "`", "."]).await;
+        cx.assert_shared_state(indoc! {"
+            zerˇo
+            one
+            two"})
+            .await;
+    }
+
+    #[gpui::test]
+    async fn test_change_list(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇone
+            two
+            three"})
+            .await;
+        cx.simulate_shared_keystrokes(["x", "j", "j", "x", "g", "g"])
+            .await;
+        cx.simulate_shared_keystrokes(["g", ";"]).await;
+        cx.assert_shared_state(indoc! {"
+            ne
+            two
+            ˇhree"})
+            .await;
+        cx.simulate_shared_keystrokes(["g", ";"]).await;
+        cx.assert_shared_state(indoc! {"
+            ˇne
+            two
+            hree"})
+            .await;
+        cx.simulate_shared_keystrokes(["g", ","]).await;
+        cx.assert_shared_state(indoc! {"
+            ne
+            two
+            ˇhree"})
+            .await;
+    }
+
+    #[gpui::test]
+    async fn test_restore_visual_selection(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state("ˇone two three").await;
+        cx.simulate_shared_keystrokes(["w", "v", "e", "escape", "0", "g", "v"])
+            .await;
+        cx.assert_shared_state("one «twoˇ» three").await;
+        cx.simulate_shared_keystrokes(["escape", "`", "<"]).await;
+        cx.assert_shared_state("one ˇtwo three").await;
+        cx.simulate_shared_keystrokes(["`", ">"]).await;
+        cx.assert_shared_state("one twˇo three").await;
+    }
+}
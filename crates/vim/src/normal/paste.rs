@@ -9,7 +9,11 @@ use serde::Deserialize;
 use settings::Settings;
 use workspace::Workspace;
 
-use crate::{state::Mode, utils::copy_selections_content, UseSystemClipboard, Vim, VimSettings};
+use crate::{
+    state::{Mode, Register},
+    utils::copy_selections_content,
+    UseSystemClipboard, Vim, VimSettings,
+};
 
 #[derive(Clone, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -28,7 +32,12 @@ pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>
 
 fn system_clipboard_is_newer(vim: &Vim, cx: &mut AppContext) -> bool {
     cx.read_from_clipboard().is_some_and(|item| {
-        if let Some(last_state) = vim.workspace_state.registers.get(".system.") {
+        if let Some(last_state) = vim
+            .workspace_state
+            .registers
+            .get(".system.")
+            .and_then(Register::text)
+        {
             last_state != item.text()
         } else {
             true
@@ -54,8 +63,9 @@ fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
                             vim.workspace_state
                                 .registers
                                 .get("\"")
-                                .cloned()
-                                .unwrap_or_else(|| "".to_string()),
+                                .and_then(Register::text)
+                                .unwrap_or_default()
+                                .to_string(),
                             None,
                         )
                     } else {
@@ -1,14 +1,21 @@
+use std::{cell::RefCell, mem, rc::Rc};
+
 use crate::{
     insert::NormalBefore,
     motion::Motion,
-    state::{Mode, RecordedSelection, ReplayableAction},
+    observe_action,
+    state::{Mode, Operator, RecordedSelection, Register, ReplayableAction},
     visual::visual_motion,
     Vim,
 };
 use gpui::{actions, Action, ViewContext, WindowContext};
 use workspace::Workspace;
 
-actions!(vim, [Repeat, EndRepeat]);
+actions!(vim, [Repeat, EndRepeat, ToggleRecord, ReplayLastRecording]);
+
+/// Recursive recordings end when one of their motions fails, and replaying gives up after
+/// this many actions for those that never fail.
+const MAX_REPLAYED_ACTIONS: usize = 10000;
 
 fn should_replay(action: &Box<dyn Action>) -> bool {
     // skip so that we don't leave the character palette open
@@ -48,6 +55,166 @@ pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>
     });
 
     workspace.register_action(|_: &mut Workspace, _: &Repeat, cx| repeat(cx, false));
+
+    workspace.register_action(|_: &mut Workspace, _: &ToggleRecord, cx| {
+        Vim::update(cx, |vim, cx| {
+            let state = &mut vim.workspace_state;
+            if let Some(register) = state.recording_register.take() {
+                let actions = mem::take(&mut state.pending_recording);
+                state
+                    .registers
+                    .insert(register.to_string(), Register::Recording(actions));
+                state.last_recorded_register = Some(register);
+            } else {
+                vim.push_operator(Operator::RecordRegister, cx);
+            }
+        })
+    });
+
+    workspace.register_action(|_: &mut Workspace, _: &ReplayLastRecording, cx| {
+        let Some(register) = Vim::read(cx).workspace_state.last_recorded_register else {
+            return;
+        };
+        replay_register(register, cx)
+    });
+}
+
+/// Replays the actions recorded into registers. Replaying a register from within a replay
+/// (as recursive macros do) inserts its actions at the current position, so that they run
+/// before the rest of the outer recording.
+#[derive(Clone, Default)]
+pub struct Replayer(Rc<RefCell<ReplayerState>>);
+
+#[derive(Default)]
+struct ReplayerState {
+    actions: Vec<ReplayableAction>,
+    running: bool,
+    ix: usize,
+}
+
+impl Replayer {
+    fn replay(&self, actions: Vec<ReplayableAction>, cx: &mut WindowContext) {
+        let mut state = self.0.borrow_mut();
+        let ix = state.ix;
+        state.actions.splice(ix..ix, actions);
+        if state.running {
+            return;
+        }
+        state.running = true;
+        let this = self.clone();
+        cx.defer(move |cx| this.next(cx));
+    }
+
+    /// Drops the actions that haven't been replayed yet.
+    pub(crate) fn stop(&self) {
+        self.0.borrow_mut().actions.clear();
+    }
+
+    fn next(self, cx: &mut WindowContext) {
+        let mut state = self.0.borrow_mut();
+        let action = if state.ix < MAX_REPLAYED_ACTIONS {
+            state.actions.get(state.ix).cloned()
+        } else {
+            log::error!("aborting replay after {MAX_REPLAYED_ACTIONS} actions");
+            None
+        };
+        state.ix += 1;
+        drop(state);
+
+        let Some(action) = action else {
+            Vim::update(cx, |vim, _| vim.workspace_state.replayer.take());
+            return;
+        };
+        match action {
+            ReplayableAction::Action(action) => {
+                if should_replay(&action) {
+                    cx.dispatch_action(action.boxed_clone());
+                    // Dispatched actions aren't observed like typed ones, but `.` should
+                    // still repeat the changes made by a recording.
+                    cx.defer(move |cx| observe_action(action, cx));
+                }
+            }
+            ReplayableAction::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => {
+                if let Some(editor) = Vim::read(cx)
+                    .active_editor
+                    .clone()
+                    .and_then(|editor| editor.upgrade())
+                {
+                    editor.update(cx, |editor, cx| {
+                        editor.replay_insert_event(&text, utf16_range_to_replace, cx)
+                    });
+                }
+            }
+        }
+        cx.defer(move |cx| self.next(cx));
+    }
+}
+
+/// Starts recording typed keys into a named register, as `q{register}` does. The register keeps
+/// its previous contents until recording stops, and uppercase registers append to the recording
+/// of their lowercase counterpart. The unnamed, numbered and read-only registers can't be
+/// recorded into.
+pub(crate) fn record_register(register: char, cx: &mut WindowContext) {
+    Vim::update(cx, |vim, cx| {
+        vim.clear_operator(cx);
+        if !register.is_ascii_alphabetic() {
+            return;
+        }
+        let state = &mut vim.workspace_state;
+        state.pending_recording = if register.is_ascii_uppercase() {
+            match state
+                .registers
+                .get(&register.to_ascii_lowercase().to_string())
+            {
+                Some(Register::Recording(actions)) => actions.clone(),
+                _ => Vec::new(),
+            }
+        } else {
+            Vec::new()
+        };
+        state.recording_register = Some(register.to_ascii_lowercase());
+    })
+}
+
+/// Replays a recorded register as many times as the count says, as `@{register}` does.
+/// `@@` replays the register that was replayed last.
+pub(crate) fn replay_register(register: char, cx: &mut WindowContext) {
+    Vim::update(cx, |vim, cx| {
+        let count = vim.take_count(cx).unwrap_or(1);
+        vim.clear_operator(cx);
+
+        let register = if register == '@' {
+            let Some(register) = vim.workspace_state.last_replayed_register else {
+                return;
+            };
+            register
+        } else {
+            register.to_ascii_lowercase()
+        };
+        let Some(Register::Recording(actions)) =
+            vim.workspace_state.registers.get(&register.to_string())
+        else {
+            return;
+        };
+        if actions.is_empty() {
+            return;
+        }
+        let actions = actions
+            .iter()
+            .cycle()
+            .take(actions.len() * count)
+            .cloned()
+            .collect();
+
+        vim.workspace_state.last_replayed_register = Some(register);
+        vim.workspace_state
+            .replayer
+            .get_or_insert_with(Replayer::default)
+            .replay(actions, cx);
+    })
 }
 
 pub(crate) fn repeat(cx: &mut WindowContext, from_insert_mode: bool) {
@@ -494,6 +661,96 @@ mod test {
         cx.assert_state("ˇjhello\n", Mode::Normal);
     }
 
+    #[gpui::test]
+    async fn test_record_replay(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state("ˇhello world").await;
+        cx.simulate_shared_keystrokes(["q", "w", "c", "w", "j", "escape", "q"])
+            .await;
+        cx.assert_shared_state("ˇj world").await;
+        cx.simulate_shared_keystrokes(["2", "l", "@", "w"]).await;
+        cx.assert_shared_state("j ˇj").await;
+    }
+
+    #[gpui::test]
+    async fn test_record_unsupported_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇhello\n", Mode::Normal);
+        cx.simulate_keystrokes(["q", "1", "x", "q", "escape", "@", "1"]);
+        cx.assert_state("ˇello\n", Mode::Normal);
+        cx.simulate_keystrokes(["q", ".", "x", "q", "escape", "@", "."]);
+        cx.assert_state("ˇllo\n", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_replay_count(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state("ˇhello world").await;
+        cx.simulate_shared_keystrokes(["q", "a", "x", "q"]).await;
+        cx.assert_shared_state("ˇello world").await;
+        cx.simulate_shared_keystrokes(["2", "@", "a"]).await;
+        cx.assert_shared_state("ˇlo world").await;
+        cx.simulate_shared_keystrokes(["@", "@"]).await;
+        cx.assert_shared_state("ˇo world").await;
+        cx.simulate_shared_keystrokes(["shift-q"]).await;
+        cx.assert_shared_state("ˇ world").await;
+    }
+
+    #[gpui::test]
+    async fn test_recursive_recording(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇa
+            b
+            c"})
+            .await;
+        // The register is cleared first, so that replaying it while recording does nothing.
+        cx.simulate_shared_keystrokes([
+            "q", "a", "q", "q", "a", "shift-a", "!", "escape", "j", "@", "a", "q",
+        ])
+        .await;
+        cx.assert_shared_state(indoc! {"
+            a!
+            ˇb
+            c"})
+            .await;
+        // Replaying stops once `j` fails on the last line.
+        cx.simulate_shared_keystrokes(["@", "a"]).await;
+        cx.assert_shared_state(indoc! {"
+            a!
+            b!
+            cˇ!"})
+            .await;
+    }
+
+    #[gpui::test]
+    async fn test_normal_command(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇone
+            two"})
+            .await;
+        cx.simulate_shared_keystrokes([":", "n", "o", "r", "m", "space", "shift-a", "x", "enter"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            oneˇx
+            two"})
+            .await;
+        cx.simulate_shared_keystrokes(["q", "a", "shift-a", "!", "escape", "q", "j"])
+            .await;
+        cx.simulate_shared_keystrokes([":", "n", "o", "r", "m", "space", "@", "a", "enter"])
+            .await;
+        cx.assert_shared_state(indoc! {"
+            onex!
+            twoˇ!"})
+            .await;
+    }
+
     #[gpui::test]
     async fn test_repeat_over_blur(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
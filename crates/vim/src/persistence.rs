@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use db::{define_connection, query, sqlez_macros::sql};
+use workspace::{WorkspaceDb, WorkspaceId};
+
+define_connection! {
+    // Current schema shape using pseudo-rust syntax:
+    // vim_marks(
+    //   workspace_id: usize,
+    //   mark_name: String,
+    //   path: PathBuf,
+    //   row: u32,
+    //   column: u32,
+    // )
+    pub static ref DB: VimDb<WorkspaceDb> =
+        &[sql!(
+            CREATE TABLE vim_marks(
+                workspace_id INTEGER NOT NULL,
+                mark_name TEXT NOT NULL,
+                path BLOB NOT NULL,
+                row INTEGER NOT NULL,
+                column INTEGER NOT NULL,
+                PRIMARY KEY(workspace_id, mark_name, path),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        )];
+}
+
+impl VimDb {
+    query! {
+        pub fn get_marks(workspace_id: WorkspaceId) -> Result<Vec<(String, PathBuf, u32, u32)>> {
+            SELECT mark_name, path, row, column FROM vim_marks
+            WHERE workspace_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_mark(
+            workspace_id: WorkspaceId,
+            mark_name: String,
+            path: PathBuf,
+            row: u32,
+            column: u32
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO vim_marks(workspace_id, mark_name, path, row, column)
+            VALUES (?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        pub async fn delete_mark(workspace_id: WorkspaceId, mark_name: String) -> Result<()> {
+            DELETE FROM vim_marks
+            WHERE workspace_id = ? AND mark_name = ?
+        }
+    }
+}
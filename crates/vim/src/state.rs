@@ -1,10 +1,16 @@
-use std::{fmt::Display, ops::Range, sync::Arc};
+use std::{fmt::Display, ops::Range, path::Path, sync::Arc};
 
-use crate::motion::Motion;
+use crate::{
+    motion::Motion,
+    normal::{
+        mark::{BufferMarks, MarkLocation},
+        repeat::Replayer,
+    },
+};
 use collections::HashMap;
 use editor::Anchor;
-use gpui::{Action, KeyContext};
-use language::{CursorShape, Selection, TransactionId};
+use gpui::{Action, EntityId, KeyContext, WeakModel};
+use language::{Buffer, CursorShape, Point, Selection, TransactionId};
 use serde::{Deserialize, Serialize};
 use workspace::searchable::Direction;
 
@@ -55,6 +61,10 @@ pub enum Operator {
     Object { around: bool },
     FindForward { before: bool },
     FindBackward { after: bool },
+    RecordRegister,
+    ReplayRegister,
+    Mark,
+    Jump { line: bool },
 }
 
 #[derive(Default, Clone)]
@@ -107,7 +117,42 @@ pub struct WorkspaceState {
     pub recorded_actions: Vec<ReplayableAction>,
     pub recorded_selection: RecordedSelection,
 
-    pub registers: HashMap<String, String>,
+    pub registers: HashMap<String, Register>,
+
+    pub recording_register: Option<char>,
+    pub pending_recording: Vec<ReplayableAction>,
+    pub last_recorded_register: Option<char>,
+    pub last_replayed_register: Option<char>,
+    pub replayer: Option<Replayer>,
+
+    pub buffer_marks: HashMap<EntityId, BufferMarks>,
+    pub global_marks: HashMap<char, MarkLocation>,
+    /// Marks restored from the database for files that haven't been opened yet.
+    pub saved_marks: HashMap<Arc<Path>, HashMap<char, Point>>,
+    pub jump_list: Vec<MarkLocation>,
+    pub jump_list_position: Option<usize>,
+    /// Set while the editor of a jump target is being opened, so that switching to it isn't
+    /// recorded as another jump.
+    pub opening_jump: bool,
+    /// The buffer being changed, and its version before the change began.
+    pub change_base: Option<(WeakModel<Buffer>, clock::Global)>,
+}
+
+/// The contents of a register: the text yanked or deleted into it, or the actions recorded into
+/// it with `q`.
+#[derive(Clone, Debug)]
+pub enum Register {
+    Text(String),
+    Recording(Vec<ReplayableAction>),
+}
+
+impl Register {
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Register::Text(text) => Some(text),
+            Register::Recording(_) => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -253,15 +298,24 @@ impl Operator {
             Operator::FindForward { before: true } => "t",
             Operator::FindBackward { after: false } => "F",
             Operator::FindBackward { after: true } => "T",
+            Operator::RecordRegister => "q",
+            Operator::ReplayRegister => "@",
+            Operator::Mark => "m",
+            Operator::Jump { line: false } => "`",
+            Operator::Jump { line: true } => "'",
         }
     }
 
     pub fn context_flags(&self) -> &'static [&'static str] {
         match self {
             Operator::Object { .. } => &["VimObject"],
-            Operator::FindForward { .. } | Operator::FindBackward { .. } | Operator::Replace => {
-                &["VimWaiting"]
-            }
+            Operator::FindForward { .. }
+            | Operator::FindBackward { .. }
+            | Operator::Replace
+            | Operator::RecordRegister
+            | Operator::ReplayRegister
+            | Operator::Mark
+            | Operator::Jump { .. } => &["VimWaiting"],
             _ => &[],
         }
     }
@@ -5,7 +5,11 @@ use gpui::{ClipboardItem, ViewContext};
 use language::{CharKind, Point};
 use settings::Settings;
 
-use crate::{state::Mode, UseSystemClipboard, Vim, VimSettings};
+use crate::{
+    normal::mark::set_range_marks,
+    state::{Mode, Register},
+    UseSystemClipboard, Vim, VimSettings,
+};
 
 pub struct HighlightOnYank;
 
@@ -83,21 +87,38 @@ fn copy_selections_content_internal(
         }
     }
 
+    if is_yank {
+        if let (Some(first), Some(last)) = (selections.first(), selections.last()) {
+            let multi_buffer = editor.buffer().read(cx);
+            let start = multi_buffer.point_to_buffer_offset(first.start, cx);
+            let end = multi_buffer.point_to_buffer_offset(last.end, cx);
+            if let Some(((start_buffer, start, _), (end_buffer, end, _))) = start.zip(end) {
+                if start_buffer == end_buffer {
+                    set_range_marks(vim, &start_buffer, start..end, cx);
+                }
+            }
+        }
+    }
+
     let setting = VimSettings::get_global(cx).use_system_clipboard;
     if setting == UseSystemClipboard::Always || setting == UseSystemClipboard::OnYank && is_yank {
         cx.write_to_clipboard(ClipboardItem::new(text.clone()).with_metadata(clipboard_selections));
         vim.workspace_state
             .registers
-            .insert(".system.".to_string(), text.clone());
+            .insert(".system.".to_string(), Register::Text(text.clone()));
     } else {
         vim.workspace_state.registers.insert(
             ".system.".to_string(),
-            cx.read_from_clipboard()
-                .map(|item| item.text().clone())
-                .unwrap_or_default(),
+            Register::Text(
+                cx.read_from_clipboard()
+                    .map(|item| item.text().clone())
+                    .unwrap_or_default(),
+            ),
         );
     }
-    vim.workspace_state.registers.insert("\"".to_string(), text);
+    vim.workspace_state
+        .registers
+        .insert("\"".to_string(), Register::Text(text));
     if !is_yank || vim.state().mode == Mode::Visual {
         return;
     }
@@ -10,6 +10,7 @@ mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod persistence;
 mod replace;
 mod state;
 mod utils;
@@ -29,7 +30,11 @@ use gpui::{
 use language::{CursorShape, Point, Selection, SelectionGoal, TransactionId};
 pub use mode_indicator::ModeIndicator;
 use motion::Motion;
-use normal::normal_replace;
+use normal::{
+    mark::{self, create_mark, jump_to_mark},
+    normal_replace,
+    repeat::{record_register, replay_register},
+};
 use replace::multi_replace;
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -148,17 +153,13 @@ fn observe_keystrokes(keystroke_event: &KeystrokeEvent, cx: &mut WindowContext)
         .map(|action| action.boxed_clone())
     {
         Vim::update(cx, |vim, _| {
-            if vim.workspace_state.recording {
+            if vim.workspace_state.recording_register.is_some() {
                 vim.workspace_state
-                    .recorded_actions
+                    .pending_recording
                     .push(ReplayableAction::Action(action.boxed_clone()));
-
-                if vim.workspace_state.stop_recording_after_next_action {
-                    vim.workspace_state.recording = false;
-                    vim.workspace_state.stop_recording_after_next_action = false;
-                }
             }
         });
+        observe_action(action.boxed_clone(), cx);
 
         // Keystroke is handled by the vim system, so continue forward
         if action.name().starts_with("vim::") {
@@ -169,7 +170,15 @@ fn observe_keystrokes(keystroke_event: &KeystrokeEvent, cx: &mut WindowContext)
     }
 
     Vim::update(cx, |vim, cx| match vim.active_operator() {
-        Some(Operator::FindForward { .. } | Operator::FindBackward { .. } | Operator::Replace) => {}
+        Some(
+            Operator::FindForward { .. }
+            | Operator::FindBackward { .. }
+            | Operator::Replace
+            | Operator::RecordRegister
+            | Operator::ReplayRegister
+            | Operator::Mark
+            | Operator::Jump { .. },
+        ) => {}
         Some(_) => {
             vim.clear_operator(cx);
         }
@@ -177,6 +186,22 @@ fn observe_keystrokes(keystroke_event: &KeystrokeEvent, cx: &mut WindowContext)
     });
 }
 
+/// Records an action for `.` while a change is being recorded.
+pub(crate) fn observe_action(action: Box<dyn Action>, cx: &mut WindowContext) {
+    Vim::update(cx, |vim, _| {
+        if vim.workspace_state.recording {
+            vim.workspace_state
+                .recorded_actions
+                .push(ReplayableAction::Action(action));
+
+            if vim.workspace_state.stop_recording_after_next_action {
+                vim.workspace_state.recording = false;
+                vim.workspace_state.stop_recording_after_next_action = false;
+            }
+        }
+    });
+}
+
 /// The state pertaining to Vim mode.
 #[derive(Default)]
 struct Vim {
@@ -210,6 +235,17 @@ impl Vim {
             return;
         }
 
+        // Switching to another editor is a jump, unless it was opened by one.
+        if self
+            .active_editor
+            .as_ref()
+            .is_some_and(|previous| previous.entity_id() != editor.entity_id())
+            && !std::mem::take(&mut self.workspace_state.opening_jump)
+        {
+            mark::record_jump(self, cx);
+        }
+        mark::load_buffer_marks(self, editor.read(cx), cx);
+
         self.active_editor = Some(editor.clone().downgrade());
         self.editor_subscription = Some(cx.subscribe(&editor, |editor, event, cx| match event {
             EditorEvent::SelectionsChanged { local: true } => {
@@ -223,16 +259,25 @@ impl Vim {
                 }
             }
             EditorEvent::InputIgnored { text } => {
+                // Recorded before the input is handled, so that the register a recording
+                // starts with isn't part of it.
+                Vim::record_register_insertion(text, None, cx);
                 Vim::active_editor_input_ignored(text.clone(), cx);
                 Vim::record_insertion(text, None, cx)
             }
             EditorEvent::InputHandled {
                 text,
                 utf16_range_to_replace: range_to_replace,
-            } => Vim::record_insertion(text, range_to_replace.clone(), cx),
+            } => {
+                Vim::record_register_insertion(text, range_to_replace.clone(), cx);
+                Vim::record_insertion(text, range_to_replace.clone(), cx)
+            }
             EditorEvent::TransactionBegun { transaction_id } => Vim::update(cx, |vim, cx| {
                 vim.transaction_begun(*transaction_id, cx);
+                mark::transaction_begun(vim, cx);
             }),
+            EditorEvent::Edited => Vim::update(cx, |vim, cx| mark::buffer_edited(vim, cx)),
+            EditorEvent::Saved => Vim::update(cx, |vim, cx| mark::persist_buffer_marks(vim, cx)),
             EditorEvent::TransactionUndone { transaction_id } => Vim::update(cx, |vim, cx| {
                 vim.transaction_undone(transaction_id, cx);
             }),
@@ -276,6 +321,27 @@ impl Vim {
         });
     }
 
+    /// Records typed text into the register being recorded. Text inserted while replaying
+    /// isn't recorded, since the keys that started the replay already were.
+    fn record_register_insertion(
+        text: &Arc<str>,
+        range_to_replace: Option<Range<isize>>,
+        cx: &mut WindowContext,
+    ) {
+        Vim::update(cx, |vim, _| {
+            let state = &mut vim.workspace_state;
+            if state.replaying || state.replayer.is_some() {
+                return;
+            }
+            if state.recording_register.is_some() {
+                state.pending_recording.push(ReplayableAction::Insertion {
+                    text: text.clone(),
+                    utf16_range_to_replace: range_to_replace,
+                });
+            }
+        });
+    }
+
     fn update_active_editor<S>(
         &mut self,
         cx: &mut WindowContext,
@@ -365,6 +431,12 @@ impl Vim {
         let last_mode = state.mode;
         let prior_mode = state.last_mode;
         let prior_tx = state.current_tx;
+        if last_mode.is_visual() && !mode.is_visual() {
+            mark::set_visual_marks(self, last_mode, cx);
+        }
+        if last_mode == Mode::Insert {
+            self.workspace_state.change_base = None;
+        }
         self.update_state(|state| {
             state.last_mode = last_mode;
             state.mode = mode;
@@ -614,6 +686,12 @@ impl Vim {
                 });
                 motion::motion(find, cx)
             }
+            Some(Operator::RecordRegister) => record_register(text.chars().next().unwrap(), cx),
+            Some(Operator::ReplayRegister) => replay_register(text.chars().next().unwrap(), cx),
+            Some(Operator::Mark) => Vim::update(cx, |vim, cx| {
+                create_mark(vim, text.chars().next().unwrap(), cx)
+            }),
+            Some(Operator::Jump { line }) => jump_to_mark(text.chars().next().unwrap(), line, cx),
             Some(Operator::Replace) => match Vim::read(cx).state().mode {
                 Mode::Normal => normal_replace(text, cx),
                 Mode::Visual | Mode::VisualLine | Mode::VisualBlock => visual_replace(text, cx),
@@ -30,6 +30,10 @@ pub struct SearchOptions {
     pub regex: bool,
     /// Specifies whether the item supports search & replace.
     pub replacement: bool,
+    /// Specifies whether the item supports fuzzy search, ranking its lines by how well they match the query.
+    pub fuzzy: bool,
+    /// Specifies whether the item supports restricting the search to its selections.
+    pub selection: bool,
 }
 
 pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
@@ -41,6 +45,8 @@ pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
             word: true,
             regex: true,
             replacement: true,
+            fuzzy: true,
+            selection: true,
         }
     }
 
@@ -55,6 +61,8 @@ pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
     );
     fn select_matches(&mut self, matches: Vec<Self::Match>, cx: &mut ViewContext<Self>);
     fn replace(&mut self, _: &Self::Match, _: &SearchQuery, _: &mut ViewContext<Self>);
+    /// Restricts the search to the current selections, following them as the item is edited, or lifts the restriction.
+    fn toggle_filtered_search_ranges(&mut self, _enabled: bool, _cx: &mut ViewContext<Self>) {}
     fn match_index_for_direction(
         &mut self,
         matches: &Vec<Self::Match>,
@@ -107,6 +115,7 @@ pub trait SearchableItemHandle: ItemHandle {
     );
     fn select_matches(&self, matches: &Vec<Box<dyn Any + Send>>, cx: &mut WindowContext);
     fn replace(&self, _: &Box<dyn Any + Send>, _: &SearchQuery, _: &mut WindowContext);
+    fn toggle_filtered_search_ranges(&self, enabled: bool, cx: &mut WindowContext);
     fn match_index_for_direction(
         &self,
         matches: &Vec<Box<dyn Any + Send>>,
@@ -213,6 +222,12 @@ impl<T: SearchableItem> SearchableItemHandle for View<T> {
         let matches = matches.downcast_ref().unwrap();
         self.update(cx, |this, cx| this.replace(matches, query, cx))
     }
+
+    fn toggle_filtered_search_ranges(&self, enabled: bool, cx: &mut WindowContext) {
+        self.update(cx, |this, cx| {
+            this.toggle_filtered_search_ranges(enabled, cx)
+        })
+    }
 }
 
 fn downcast_matches<T: Any + Clone>(matches: &Vec<Box<dyn Any + Send>>) -> Vec<T> {
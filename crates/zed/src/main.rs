@@ -164,6 +164,7 @@ fn main() {
         language::init(cx);
         editor::init(cx);
         diagnostics::init(cx);
+        project_diff::init(cx);
         copilot::init(
             copilot_language_server_id,
             http.clone(),
@@ -183,6 +184,7 @@ fn main() {
 
         load_user_themes_in_background(fs.clone(), cx);
         watch_themes(fs.clone(), cx);
+        snippet_provider::init(fs.clone(), cx);
 
         watch_file_types(fs.clone(), cx);
 
@@ -237,9 +239,11 @@ fn main() {
 
         go_to_line::init(cx);
         file_finder::init(cx);
+        file_history::init(cx);
         outline::init(cx);
         project_symbols::init(cx);
         project_panel::init(Assets, cx);
+        git_panel::init(cx);
         tasks_ui::init(cx);
         channel::init(&client, user_store.clone(), cx);
         search::init(cx);
@@ -18,7 +18,8 @@ pub use open_listener::*;
 use anyhow::Context as _;
 use assets::Assets;
 use futures::{channel::mpsc, select_biased, StreamExt};
-use project::TaskSourceKind;
+use git_panel::GitPanel;
+use project::{runnables_source::RunnablesSource, TaskSourceKind};
 use project_panel::ProjectPanel;
 use quick_action_bar::QuickActionBar;
 use release_channel::{AppCommitSha, ReleaseChannel};
@@ -29,7 +30,11 @@ use settings::{
     SettingsStore, DEFAULT_KEYMAP_PATH,
 };
 use std::{borrow::Cow, ops::Deref, path::Path, sync::Arc};
-use task::{oneshot_source::OneshotSource, static_source::StaticSource};
+use task::{
+    oneshot_source::OneshotSource,
+    static_source::StaticSource,
+    vscode_format::{ConvertedTasks, VsCodeFile},
+};
 use terminal_view::terminal_panel::{self, TerminalPanel};
 use util::{
     asset_str,
@@ -54,6 +59,7 @@ actions!(
         DecreaseBufferFontSize,
         Hide,
         HideOthers,
+        ImportVsCodeTasks,
         IncreaseBufferFontSize,
         Minimize,
         OpenDefaultKeymap,
@@ -158,6 +164,11 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                         |cx| OneshotSource::new(cx),
                         cx,
                     );
+                    inventory.add_source(
+                        TaskSourceKind::Runnables,
+                        |cx| RunnablesSource::new(cx),
+                        cx,
+                    );
                     inventory.add_source(
                         TaskSourceKind::AbsPath(paths::TASKS.clone()),
                         |cx| {
@@ -175,6 +186,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
         }
         cx.spawn(|workspace_handle, mut cx| async move {
             let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
+            let git_panel = GitPanel::load(workspace_handle.clone(), cx.clone());
             let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
             let assistant_panel = AssistantPanel::load(workspace_handle.clone(), cx.clone());
             let channels_panel =
@@ -187,6 +199,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             );
             let (
                 project_panel,
+                git_panel,
                 terminal_panel,
                 assistant_panel,
                 channels_panel,
@@ -194,6 +207,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 notification_panel,
             ) = futures::try_join!(
                 project_panel,
+                git_panel,
                 terminal_panel,
                 assistant_panel,
                 channels_panel,
@@ -203,6 +217,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
 
             workspace_handle.update(&mut cx, |workspace, cx| {
                 workspace.add_panel(project_panel, cx);
+                workspace.add_panel(git_panel, cx);
                 workspace.add_panel(terminal_panel, cx);
                 workspace.add_panel(assistant_panel, cx);
                 workspace.add_panel(channels_panel, cx);
@@ -325,6 +340,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             )
             .register_action(open_local_settings_file)
             .register_action(open_local_tasks_file)
+            .register_action(import_vscode_tasks)
             .register_action(
                 move |workspace: &mut Workspace,
                       _: &OpenDefaultKeymap,
@@ -428,6 +444,8 @@ fn initialize_pane(workspace: &mut Workspace, pane: &View<Pane>, cx: &mut ViewCo
             toolbar.add_item(quick_action_bar, cx);
             let diagnostic_editor_controls = cx.new_view(|_| diagnostics::ToolbarControls::new());
             toolbar.add_item(diagnostic_editor_controls, cx);
+            let project_diff_controls = cx.new_view(|_| project_diff::ToolbarControls::new());
+            toolbar.add_item(project_diff_controls, cx);
             let project_search_bar = cx.new_view(|_| ProjectSearchBar::new());
             toolbar.add_item(project_search_bar, cx);
             let lsp_log_item = cx.new_view(|_| language_tools::LspLogToolbarItemView::new());
@@ -670,6 +688,73 @@ fn open_local_tasks_file(
     )
 }
 
+fn import_vscode_tasks(
+    workspace: &mut Workspace,
+    _: &ImportVsCodeTasks,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let worktree_root = workspace
+        .project()
+        .read(cx)
+        .visible_worktrees(cx)
+        .find_map(|tree| {
+            let tree = tree.read(cx);
+            tree.root_entry()?.is_dir().then(|| tree.abs_path())
+        });
+    let Some(worktree_root) = worktree_root else {
+        workspace.show_notification(0, cx, |cx| {
+            cx.new_view(|_| MessageNotification::new("This project has no folders open."))
+        });
+        return;
+    };
+    let fs = workspace.app_state().fs.clone();
+    cx.spawn(|workspace, mut cx| async move {
+        let tasks_abs_path = worktree_root.join(&*LOCAL_TASKS_RELATIVE_PATH);
+        anyhow::ensure!(
+            !fs.is_file(&tasks_abs_path).await,
+            "{} already exists",
+            LOCAL_TASKS_RELATIVE_PATH.display()
+        );
+
+        let mut converted = ConvertedTasks::default();
+        for vscode_file in [VsCodeFile::Tasks, VsCodeFile::Launch] {
+            let abs_path = worktree_root.join(vscode_file.relative_path());
+            if fs.is_file(&abs_path).await {
+                let contents = fs.load(&abs_path).await?;
+                converted.extend(vscode_file.convert(&contents)?);
+            }
+        }
+        anyhow::ensure!(
+            !converted.is_empty(),
+            "No VS Code tasks or launch configurations to import"
+        );
+
+        if let Some(tasks_dir) = tasks_abs_path.parent() {
+            fs.create_dir(tasks_dir).await?;
+        }
+        fs.atomic_write(tasks_abs_path.clone(), converted.to_json())
+            .await?;
+        workspace
+            .update(&mut cx, |workspace, cx| {
+                if !converted.warnings.is_empty() {
+                    let message = format!(
+                        "Some of the VS Code tasks were not imported completely:\n{}",
+                        converted.warnings.join("\n")
+                    );
+                    workspace.show_notification(0, cx, |cx| {
+                        cx.new_view(|_| MessageNotification::new(message))
+                    });
+                }
+                workspace.open_abs_path(tasks_abs_path, true, cx)
+            })?
+            .await?;
+        Ok(())
+    })
+    .detach_and_prompt_err("Failed to import VS Code tasks", cx, |e, _| {
+        Some(e.to_string())
+    });
+}
+
 fn open_local_file(
     workspace: &mut Workspace,
     settings_relative_path: &'static Path,
@@ -3059,6 +3144,7 @@ mod tests {
             project_panel::init_settings(cx);
             collab_ui::init(&app_state, cx);
             project_panel::init((), cx);
+            git_panel::init(cx);
             terminal_view::init(cx);
             assistant::init(cx);
             initialize_workspace(app_state.clone(), cx);